@@ -199,13 +199,20 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("# Do not edit manually\n\n");
 
     // Imports
+    pyi.push_str("from datetime import datetime\n");
+    pyi.push_str("from os import PathLike\n");
     pyi.push_str("from typing import (\n");
     pyi.push_str("    Any,\n");
+    pyi.push_str("    Callable,\n");
     pyi.push_str("    Dict,\n");
+    pyi.push_str("    Iterable,\n");
     pyi.push_str("    Iterator,\n");
     pyi.push_str("    List,\n");
+    pyi.push_str("    Literal,\n");
     pyi.push_str("    Optional,\n");
+    pyi.push_str("    Pattern,\n");
     pyi.push_str("    Protocol,\n");
+    pyi.push_str("    Tuple,\n");
     pyi.push_str("    Union,\n");
     pyi.push_str(")\n\n");
 
@@ -227,6 +234,10 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("    writing, or validation of teehistorian files.\n");
     pyi.push_str("    \"\"\"\n\n");
     pyi.push_str("    def __init__(self, message: str) -> None: ...\n\n");
+    pyi.push_str("class TeehistorianWarning(UserWarning):\n");
+    pyi.push_str(
+        "    \"\"\"Warning category for non-fatal anomalies found while parsing.\"\"\"\n\n",
+    );
 
     // Parser class
     pyi.push_str(
@@ -242,19 +253,56 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("    Rust backend. It supports iteration over chunks and custom UUID handler\n");
     pyi.push_str("    registration for extensibility.\n");
     pyi.push_str("    \"\"\"\n\n");
-    pyi.push_str("    def __init__(self, data: bytes) -> None:\n");
+    pyi.push_str("    def __init__(\n");
+    pyi.push_str("        self,\n");
+    pyi.push_str("        data: bytes,\n");
+    pyi.push_str("        *,\n");
+    pyi.push_str("        skip_inputs: bool = False,\n");
+    pyi.push_str("        exclude_categories: Optional[List[str]] = None,\n");
+    pyi.push_str("        emit_warnings: bool = False,\n");
+    pyi.push_str("        resolve_inputs: bool = False,\n");
+    pyi.push_str("        resolve_positions: bool = False,\n");
+    pyi.push_str(
+        "        unknown_uuid_policy: Literal[\"yield\", \"skip\", \"error\"] = \"yield\",\n",
+    );
+    pyi.push_str("    ) -> None:\n");
     pyi.push_str("        \"\"\"Initialize parser with raw teehistorian data.\n\n");
     pyi.push_str("        Args:\n");
-    pyi.push_str("            data: Raw bytes from a teehistorian file\n\n");
+    pyi.push_str("            data: Raw bytes from a teehistorian file\n");
+    pyi.push_str("            skip_inputs: Discard InputNew/InputDiff chunks while decoding\n");
+    pyi.push_str("            exclude_categories: Chunk categories to discard while decoding\n");
+    pyi.push_str(
+        "            emit_warnings: Also issue parser warnings through the warnings module\n",
+    );
+    pyi.push_str(
+        "            resolve_inputs: Return every InputDiff as InputNew with the absolute input\n",
+    );
+    pyi.push_str(
+        "            resolve_positions: Return PlayerNew/PlayerDiff as PlayerPosition chunks\n",
+    );
+    pyi.push_str(
+        "            unknown_uuid_policy: Yield, skip or raise on chunks of unregistered UUIDs\n\n",
+    );
     pyi.push_str("        Raises:\n");
     pyi.push_str("            TeehistorianError: If data is empty or invalid\n");
     pyi.push_str("        \"\"\"\n\n");
-    pyi.push_str("    def register_custom_uuid(self, uuid_string: str) -> None:\n");
-    pyi.push_str("        \"\"\"Register a custom UUID handler for chunk parsing.\n\n");
-    pyi.push_str("        Args:\n");
+    pyi.push_str("    def register_custom_uuid(\n");
+    pyi.push_str("        self,\n");
+    pyi.push_str("        uuid_string: str,\n");
     pyi.push_str(
-        "            uuid_string: UUID in format XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX\n\n",
+        "        decoder: Optional[Union[Callable[[str, bytes], Any], ExtensionHandler]] = None,\n",
     );
+    pyi.push_str("        *,\n");
+    pyi.push_str("        name: Optional[str] = None,\n");
+    pyi.push_str("        fields: Optional[List[Tuple[str, str]]] = None,\n");
+    pyi.push_str("    ) -> None:\n");
+    pyi.push_str("        \"\"\"Register a custom UUID handler for chunk parsing.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            uuid_string: UUID in format XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX\n");
+    pyi.push_str("            decoder: Called with the UUID and payload, its result is yielded\n");
+    pyi.push_str("                instead of a CustomChunk, or an ExtensionHandler\n");
+    pyi.push_str("            name: Name of the extension, used as CustomChunk.handler_name\n");
+    pyi.push_str("            fields: (name, format) pairs the payload is decoded into\n\n");
     pyi.push_str("        Raises:\n");
     pyi.push_str("            TeehistorianError: If UUID format is invalid\n");
     pyi.push_str("        \"\"\"\n\n");
@@ -266,17 +314,239 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("    def get_header_str(self) -> str:\n");
     pyi.push_str("        \"\"\"Get the teehistorian header as a JSON string.\n\n");
     pyi.push_str("        Returns:\n");
+    pyi.push_str("            Header data as JSON string\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def header_json(self) -> Dict[str, Any]:\n");
+    pyi.push_str("        \"\"\"Get the teehistorian header parsed as JSON.\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Dictionary of all header fields\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def peek(self) -> Optional[Any]:\n");
+    pyi.push_str("        \"\"\"Get the next chunk without consuming it.\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            The chunk the next iteration step will return, or None at EOF\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def reset(self) -> None:\n");
     pyi.push_str(
-        "            Header data as JSON string (must be called before iterating chunks)\n",
+        "        \"\"\"Rewind to the first chunk so the parser can be iterated again.\"\"\"\n\n",
     );
+    pyi.push_str("    def raw(self) -> Optional[bytes]:\n");
+    pyi.push_str("        \"\"\"Get the encoded bytes of the most recently returned chunk.\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            The bytes the decoder read, or None before the first chunk\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def next_batch(self, size: int) -> List[Any]:\n");
+    pyi.push_str("        \"\"\"Read up to size chunks at once, decoding without the GIL.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            size: Maximum number of chunks to return\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            List of chunks, empty at end of stream\n");
     pyi.push_str("        \"\"\"\n\n");
     pyi.push_str("    @property\n");
     pyi.push_str("    def chunk_count(self) -> int:\n");
     pyi.push_str("        \"\"\"Number of chunks processed so far.\"\"\"\n\n");
-    pyi.push_str("    def get_registered_uuids(self) -> List[str]:\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def tick(self) -> int:\n");
+    pyi.push_str("        \"\"\"Tick of the most recently read chunk.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def header_info(self) -> 'Header':\n");
+    pyi.push_str(
+        "        \"\"\"Header with the well-known fields parsed from the JSON header.\"\"\"\n\n",
+    );
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def format_version(self) -> Optional[int]:\n");
+    pyi.push_str("        \"\"\"Format version named in the header.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def header_span(self) -> Tuple[int, int]:\n");
+    pyi.push_str(
+        "        \"\"\"Byte range of the JSON header, without its NUL terminator.\"\"\"\n\n",
+    );
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def body_offset(self) -> int:\n");
+    pyi.push_str("        \"\"\"Byte offset of the first chunk.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def uuid_registrations(self) -> List['UuidRegistration']:\n");
+    pyi.push_str("        \"\"\"Extension UUIDs registered in the header.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def warnings(self) -> List['ParserWarning']:\n");
+    pyi.push_str("        \"\"\"Non-fatal anomalies found in the chunks read so far.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def truncated(self) -> bool:\n");
+    pyi.push_str("        \"\"\"Whether iteration stopped at a chunk cut off by the end of the data.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def last_complete_offset(self) -> Optional[int]:\n");
+    pyi.push_str(
+        "        \"\"\"Byte offset after the last complete chunk of a truncated file.\"\"\"\n\n",
+    );
+    pyi.push_str(
+        "    def iter_ticks(self, start: int, end: Optional[int] = None) -> 'ChunkIterator':\n",
+    );
+    pyi.push_str("        \"\"\"Iterate over the chunks within a tick range.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            start: First tick to yield chunks for\n");
+    pyi.push_str("            end: Last tick to yield chunks for (inclusive), or None\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Iterator sharing this parser's position\n\n");
+    pyi.push_str("        Raises:\n");
+    pyi.push_str("            ValidationError: If end is before start\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def iter_player(self, cid: int) -> 'ChunkIterator':\n");
+    pyi.push_str("        \"\"\"Iterate over the chunks concerning a single player.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            cid: Client ID to yield chunks for\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Iterator sharing this parser's position\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def iter_players(self, cids: List[int]) -> 'ChunkIterator':\n");
+    pyi.push_str("        \"\"\"Iterate over the chunks concerning any of the given players.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            cids: Client IDs to yield chunks for\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Iterator sharing this parser's position\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def filter(self, types: List[Union[type, str]]) -> 'ChunkIterator':\n");
+    pyi.push_str("        \"\"\"Iterate over the chunks of the given types only.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            types: Chunk classes or chunk type names\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Iterator sharing this parser's position\n\n");
+    pyi.push_str("        Raises:\n");
+    pyi.push_str("            ValidationError: If a chunk type is unknown\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def take_until(\n");
+    pyi.push_str("        self,\n");
+    pyi.push_str("        *,\n");
+    pyi.push_str("        chunk_type: Optional[Union[type, str]] = None,\n");
+    pyi.push_str("        tick: Optional[int] = None,\n");
+    pyi.push_str("    ) -> Optional[Any]:\n");
+    pyi.push_str("        \"\"\"Skip ahead to the first chunk matching all given conditions.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            chunk_type: Chunk class or type name to stop at\n");
+    pyi.push_str("            tick: Stop at the first chunk at or after this tick\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            The matching chunk, or None if the stream ends first\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def iter_with_ticks(self) -> Iterator[Tuple[int, Any]]:\n");
+    pyi.push_str("        \"\"\"Iterate over (tick, chunk) tuples.\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Iterator sharing this parser's position\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def iter_sampled(self, every_n_ticks: int) -> 'ChunkIterator':\n");
+    pyi.push_str(
+        "        \"\"\"Iterate with movement and input chunks thinned out to a tick stride.\n\n",
+    );
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            every_n_ticks: Width of the sampling window in ticks\n\n");
+    pyi.push_str("        Raises:\n");
+    pyi.push_str("            ValidationError: If every_n_ticks is not positive\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def stats(self) -> 'ParserStats':\n");
+    pyi.push_str(
+        "        \"\"\"Summarize the whole file without creating chunk objects.\"\"\"\n\n",
+    );
+    pyi.push_str("    @staticmethod\n");
+    pyi.push_str("    def validate(data: bytes) -> 'ValidationReport':\n");
+    pyi.push_str("        \"\"\"Check a whole file and report every problem found.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            data: Raw or compressed teehistorian file data\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def count(self, chunk_type: Union[type, str]) -> int:\n");
+    pyi.push_str("        \"\"\"Count the chunks of one type in the whole file.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            chunk_type: Chunk class or type name\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def save_state(self) -> 'ParserState':\n");
+    pyi.push_str("        \"\"\"Snapshot the parser position for a later resume.\"\"\"\n\n");
+    pyi.push_str("    @staticmethod\n");
+    pyi.push_str("    def resume(\n");
+    pyi.push_str("        data: bytes,\n");
+    pyi.push_str("        state: 'ParserState',\n");
+    pyi.push_str("        *,\n");
+    pyi.push_str("        skip_inputs: bool = False,\n");
+    pyi.push_str("        exclude_categories: Optional[List[str]] = None,\n");
+    pyi.push_str("        emit_warnings: bool = False,\n");
+    pyi.push_str("        resolve_inputs: bool = False,\n");
+    pyi.push_str("        resolve_positions: bool = False,\n");
+    pyi.push_str(
+        "        unknown_uuid_policy: Literal[\"yield\", \"skip\", \"error\"] = \"yield\",\n",
+    );
+    pyi.push_str("    ) -> 'Teehistorian':\n");
+    pyi.push_str("        \"\"\"Create a parser that continues where a saved state left off.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            data: The same teehistorian data the state was saved from\n");
+    pyi.push_str("            state: State returned by save_state()\n");
+    pyi.push_str("            skip_inputs, exclude_categories, emit_warnings, resolve_inputs,\n");
+    pyi.push_str(
+        "                resolve_positions, unknown_uuid_policy: Same as for the constructor\n\n",
+    );
+    pyi.push_str("        Raises:\n");
+    pyi.push_str(
+        "            ValidationError: If the state does not belong to the data or was saved\n",
+    );
+    pyi.push_str("                without a resolve option set for this parser\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    @staticmethod\n");
+    pyi.push_str("    def from_offset(\n");
+    pyi.push_str("        data: bytes,\n");
+    pyi.push_str("        offset: int,\n");
+    pyi.push_str("        state: Optional['ParserState'] = None,\n");
+    pyi.push_str("        *,\n");
+    pyi.push_str("        skip_inputs: bool = False,\n");
+    pyi.push_str("        exclude_categories: Optional[List[str]] = None,\n");
+    pyi.push_str("        emit_warnings: bool = False,\n");
+    pyi.push_str("        resolve_inputs: bool = False,\n");
+    pyi.push_str("        resolve_positions: bool = False,\n");
+    pyi.push_str(
+        "        unknown_uuid_policy: Literal[\"yield\", \"skip\", \"error\"] = \"yield\",\n",
+    );
+    pyi.push_str("    ) -> 'Teehistorian':\n");
+    pyi.push_str("        \"\"\"Create a parser that starts at a known chunk boundary.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            data: Teehistorian file data the offset refers to\n");
+    pyi.push_str("            offset: Byte offset of a chunk\n");
+    pyi.push_str(
+        "            state: Optional state whose ticks, counts, UUIDs and resolvers to restore\n",
+    );
+    pyi.push_str("            skip_inputs, exclude_categories, emit_warnings, resolve_inputs,\n");
+    pyi.push_str(
+        "                resolve_positions, unknown_uuid_policy: Same as for the constructor\n\n",
+    );
+    pyi.push_str("        Raises:\n");
+    pyi.push_str("            ValidationError: If the offset is not a chunk boundary\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    @staticmethod\n");
+    pyi.push_str("    def follow(\n");
+    pyi.push_str("        path: Union[str, PathLike[str]],\n");
+    pyi.push_str("        poll_interval: float = 0.5,\n");
+    pyi.push_str("        *,\n");
+    pyi.push_str("        skip_inputs: bool = False,\n");
+    pyi.push_str("        exclude_categories: Optional[List[str]] = None,\n");
+    pyi.push_str("        emit_warnings: bool = False,\n");
+    pyi.push_str("        resolve_inputs: bool = False,\n");
+    pyi.push_str("        resolve_positions: bool = False,\n");
+    pyi.push_str(
+        "        unknown_uuid_policy: Literal[\"yield\", \"skip\", \"error\"] = \"yield\",\n",
+    );
+    pyi.push_str("    ) -> 'FollowIterator':\n");
+    pyi.push_str("        \"\"\"Follow a recording while the server is still writing it.\n\n");
+    pyi.push_str("        Waits for new data at the end of the file and stops after `Eos`.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            path: Path of the recording being written\n");
+    pyi.push_str("            poll_interval: Seconds to wait before checking for new data\n");
+    pyi.push_str("            skip_inputs, exclude_categories, emit_warnings, resolve_inputs,\n");
+    pyi.push_str(
+        "                resolve_positions, unknown_uuid_policy: Same as for the constructor\n",
+    );
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def unregister_custom_uuid(self, uuid_string: str) -> bool:\n");
+    pyi.push_str("        \"\"\"Remove the handler of a custom UUID.\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Whether a handler was registered\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def get_registered_uuids(self) -> List[RegisteredUuid]:\n");
     pyi.push_str("        \"\"\"Get all registered custom UUID handlers.\n\n");
     pyi.push_str("        Returns:\n");
-    pyi.push_str("            List of registered UUID strings\n");
+    pyi.push_str("            Registered UUIDs with name and decoder kind, sorted by UUID\n");
     pyi.push_str("        \"\"\"\n\n");
     pyi.push_str("    def __iter__(self) -> Iterator[Any]:\n");
     pyi.push_str("        \"\"\"Iterate over chunks in the teehistorian.\"\"\"\n\n");
@@ -292,6 +562,414 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> bool:\n");
     pyi.push_str("        \"\"\"Context manager exit.\"\"\"\n\n");
 
+    pyi.push_str("class ChunkIterator:\n");
+    pyi.push_str("    \"\"\"Filtered view of a parser's chunks.\n\n");
+    pyi.push_str("    Shares the position of the parser it was created from.\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("    def __iter__(self) -> Iterator[Any]: ...\n");
+    pyi.push_str("    def __next__(self) -> Any: ...\n\n");
+
+    pyi.push_str("class FollowIterator:\n");
+    pyi.push_str("    \"\"\"Chunks of a recording that is still being written.\"\"\"\n\n");
+    pyi.push_str("    def __iter__(self) -> Iterator[Any]: ...\n");
+    pyi.push_str("    def __next__(self) -> Any: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def tick(self) -> int: ...\n\n");
+
+    pyi.push_str("class ParserState:\n");
+    pyi.push_str("    \"\"\"Opaque snapshot of a parser's position.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def offset(self) -> int: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def tick(self) -> int: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def chunk_count(self) -> int: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def registered_uuids(self) -> List[str]: ...\n");
+    pyi.push_str("    def to_bytes(self) -> bytes: ...\n");
+    pyi.push_str("    @staticmethod\n");
+    pyi.push_str("    def from_bytes(data: bytes) -> 'ParserState': ...\n\n");
+
+    pyi.push_str("class ParserStats:\n");
+    pyi.push_str("    \"\"\"Summary of a whole teehistorian file.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def chunk_counts(self) -> Dict[str, int]: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def total_chunks(self) -> int: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def first_tick(self) -> Optional[int]: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def last_tick(self) -> Optional[int]: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def player_ids(self) -> List[int]: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def duration(self) -> float: ...\n\n");
+
+    pyi.push_str("class Header:\n");
+    pyi.push_str("    \"\"\"Typed view of the JSON header.\"\"\"\n\n");
+    for (name, ty) in [
+        ("map_name", "Optional[str]"),
+        ("map_sha256", "Optional[str]"),
+        ("map_crc", "Optional[int]"),
+        ("server_version", "Optional[str]"),
+        ("start_time", "Optional[str]"),
+        ("start_datetime", "Optional[datetime]"),
+        ("game_uuid", "Optional[str]"),
+        ("prng_description", "Optional[str]"),
+        ("prng_seed", "Optional[Tuple[int, int]]"),
+        ("config", "Dict[str, str]"),
+        ("tuning", "'Tuning'"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push_str("    def tick_to_datetime(self, tick: int) -> Optional[datetime]: ...\n");
+    pyi.push_str("    def validate(self) -> List[str]: ...\n");
+    pyi.push_str("    def compatible_with(self, other: 'Header') -> bool: ...\n");
+    pyi.push_str("    def diff(self, other: 'Header') -> Dict[str, Tuple[Any, Any]]: ...\n\n");
+
+    pyi.push_str("class UuidRegistration:\n");
+    pyi.push_str("    \"\"\"Extension UUID registered in the header.\"\"\"\n\n");
+    pyi.push_str("    def __init__(self, uuid: str, name: str) -> None: ...\n");
+    for name in ["uuid", "name"] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> str: ...\n", name));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class Tuning:\n");
+    pyi.push_str("    \"\"\"Physics tuning recorded in the header.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def changed(self) -> Dict[str, float]: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def is_default(self) -> bool: ...\n");
+    for name in [
+        "ground_control_speed",
+        "ground_control_accel",
+        "ground_friction",
+        "ground_jump_impulse",
+        "air_jump_impulse",
+        "air_control_speed",
+        "air_control_accel",
+        "air_friction",
+        "hook_length",
+        "hook_fire_speed",
+        "hook_drag_accel",
+        "hook_drag_speed",
+        "gravity",
+        "velramp_start",
+        "velramp_range",
+        "velramp_curvature",
+        "gun_curvature",
+        "gun_speed",
+        "gun_lifetime",
+        "shotgun_curvature",
+        "shotgun_speed",
+        "shotgun_speeddiff",
+        "shotgun_lifetime",
+        "grenade_curvature",
+        "grenade_speed",
+        "grenade_lifetime",
+        "laser_reach",
+        "laser_bounce_delay",
+        "laser_bounce_num",
+        "laser_bounce_cost",
+        "laser_damage",
+        "player_collision",
+        "player_hooking",
+        "jetpack_strength",
+        "shotgun_strength",
+        "explosion_strength",
+        "hammer_strength",
+        "hook_duration",
+        "hammer_fire_delay",
+        "gun_fire_delay",
+        "shotgun_fire_delay",
+        "grenade_fire_delay",
+        "laser_fire_delay",
+        "ninja_fire_delay",
+        "hammer_hit_fire_delay",
+        "ground_elasticity_x",
+        "ground_elasticity_y",
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> float: ...\n", name));
+    }
+    pyi.push_str("    def to_dict(self) -> Dict[str, float]: ...\n\n");
+
+    pyi.push_str("class AntibotEvent:\n");
+    pyi.push_str("    \"\"\"Antibot payload split into its fields.\"\"\"\n\n");
+    for (name, ty) in [
+        ("event", "Optional[str]"),
+        ("args", "List[str]"),
+        ("params", "Dict[str, str]"),
+        ("raw", "bytes"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class PlayerInput:\n");
+    pyi.push_str("    \"\"\"Player input with named fields, in wire order.\"\"\"\n\n");
+    pyi.push_str("    FIELDS: List[str]\n");
+    let input_fields = [
+        "direction",
+        "target_x",
+        "target_y",
+        "jump",
+        "fire",
+        "hook",
+        "player_flags",
+        "wanted_weapon",
+        "next_weapon",
+        "prev_weapon",
+    ];
+    for name in input_fields {
+        pyi.push_str(&format!("    {}: int\n", name));
+    }
+    pyi.push_str("    def __init__(self, *");
+    for name in input_fields {
+        pyi.push_str(&format!(", {}: int = 0", name));
+    }
+    pyi.push_str(") -> None: ...\n");
+    pyi.push_str("    @staticmethod\n");
+    pyi.push_str("    def from_list(input: List[int]) -> 'PlayerInput': ...\n");
+    pyi.push_str("    def to_list(self) -> List[int]: ...\n");
+    pyi.push_str("    def to_dict(self) -> Dict[str, int]: ...\n\n");
+
+    // Game messages returned by NetMessage.decode()
+    for (class, fields) in [
+        (
+            "ChatMessage",
+            &[("mode", "str"), ("target", "int"), ("message", "str")][..],
+        ),
+        ("KillMessage", &[][..]),
+        ("Emoticon", &[("emoticon", "int"), ("name", "str")][..]),
+        (
+            "VoteCall",
+            &[
+                ("vote_type", "str"),
+                ("value", "str"),
+                ("reason", "str"),
+                ("force", "bool"),
+            ][..],
+        ),
+        ("Vote", &[("vote", "int")][..]),
+        ("SetTeam", &[("team", "int")][..]),
+        ("SetSpectatorMode", &[("spectator_id", "int")][..]),
+        (
+            "ClientCommand",
+            &[("name", "str"), ("arguments", "str")][..],
+        ),
+    ] {
+        pyi.push_str(&format!("class {}:\n", class));
+        for (name, ty) in fields {
+            pyi.push_str(&format!("    {}: {}\n", name, ty));
+        }
+        pyi.push_str("    def message_type(self) -> str: ...\n");
+        pyi.push_str("    def to_dict(self) -> Dict[str, Any]: ...\n\n");
+    }
+    pyi.push_str("GameMessage = Union[\n");
+    for class in [
+        "ChatMessage",
+        "KillMessage",
+        "Emoticon",
+        "VoteCall",
+        "Vote",
+        "SetTeam",
+        "SetSpectatorMode",
+        "ClientCommand",
+    ] {
+        pyi.push_str(&format!("    {},\n", class));
+    }
+    pyi.push_str("]\n\n");
+
+    pyi.push_str("class ExtensionHandler:\n");
+    pyi.push_str("    \"\"\"Base class of stateful handlers for a custom UUID.\"\"\"\n\n");
+    pyi.push_str("    def on_chunk(self, chunk: Any, tick: int) -> Any: ...\n");
+    pyi.push_str("    def reset(self) -> None: ...\n\n");
+    pyi.push_str("class RegisteredUuid:\n");
+    pyi.push_str("    \"\"\"Custom UUID registered with a parser.\"\"\"\n\n");
+    for name in ["uuid", "name", "kind"] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> str: ...\n", name));
+    }
+    pyi.push('\n');
+    pyi.push_str("class ParserWarning:\n");
+    pyi.push_str("    \"\"\"Non-fatal anomaly found while parsing.\"\"\"\n\n");
+    for (name, ty) in [
+        ("kind", "str"),
+        ("message", "str"),
+        ("offset", "int"),
+        ("tick", "int"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class ValidationReport:\n");
+    pyi.push_str("    \"\"\"Result of Teehistorian.validate().\"\"\"\n\n");
+    for (name, ty) in [
+        ("is_valid", "bool"),
+        ("header_ok", "bool"),
+        ("header_error", "Optional[str]"),
+        ("eos_present", "bool"),
+        ("unknown_uuids", "List[str]"),
+        ("error_offset", "Optional[int]"),
+        ("error", "Optional[str]"),
+        ("truncated", "bool"),
+        ("ticks_monotonic", "bool"),
+        ("chunk_counts", "Dict[str, int]"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class RecordingDiff:\n");
+    pyi.push_str("    \"\"\"Result of diff().\"\"\"\n\n");
+    for (name, ty) in [
+        ("identical", "bool"),
+        ("header", "Dict[str, Tuple[Any, Any]]"),
+        ("chunk_counts", "Dict[str, int]"),
+        ("first_index", "Optional[int]"),
+        ("offsets", "Optional[Tuple[Optional[int], Optional[int]]]"),
+        ("ticks", "Optional[Tuple[Optional[int], Optional[int]]]"),
+        (
+            "chunks",
+            "Optional[Tuple[Optional[Chunk], Optional[Chunk]]]",
+        ),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class RepairReport:\n");
+    pyi.push_str("    \"\"\"Result of repair().\"\"\"\n\n");
+    for (name, ty) in [
+        ("repaired", "bool"),
+        ("kept_chunks", "int"),
+        ("dropped_bytes", "int"),
+        ("error_offset", "Optional[int]"),
+        ("error", "Optional[str]"),
+        ("truncated", "bool"),
+        ("eos_added", "bool"),
+        ("last_tick", "int"),
+        ("decompress_error", "Optional[str]"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class RecompressReport:\n");
+    pyi.push_str("    \"\"\"Result of recompress().\"\"\"\n\n");
+    for (name, ty) in [
+        ("original_bytes", "int"),
+        ("recompressed_bytes", "int"),
+        ("saved_bytes", "int"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class PlayerTracker:\n");
+    pyi.push_str("    \"\"\"Positions of every character over a recording.\"\"\"\n\n");
+    pyi.push_str("    def __init__(self, data: bytes) -> None:\n");
+    pyi.push_str("        \"\"\"Track the characters of a recording.\n\n");
+    pyi.push_str("        Raises:\n");
+    pyi.push_str("            TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def positions_at(self, tick: int) -> Dict[int, Tuple[int, int]]: ...\n");
+    pyi.push_str("    def trajectory(self, cid: int) -> List[Tuple[int, int, int]]: ...\n");
+    pyi.push_str(
+        "    def kinematics(self, cid: int) -> List[Tuple[int, int, int, int, int]]: ...\n",
+    );
+    pyi.push_str("    def snapshots(self) -> 'SnapshotIterator': ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def client_ids(self) -> List[int]: ...\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def ticks(self) -> List[int]: ...\n\n");
+
+    pyi.push_str("class SnapshotIterator:\n");
+    pyi.push_str("    \"\"\"World snapshots of a PlayerTracker.\"\"\"\n\n");
+    pyi.push_str(
+        "    def __iter__(self) -> Iterator[Tuple[int, Dict[int, Tuple[int, int]]]]: ...\n",
+    );
+    pyi.push_str("    def __next__(self) -> Tuple[int, Dict[int, Tuple[int, int]]]: ...\n\n");
+
+    pyi.push_str("class PlayerSession:\n");
+    pyi.push_str("    \"\"\"One connection of a player.\"\"\"\n\n");
+    for (name, ty) in [
+        ("cid", "int"),
+        ("name", "Optional[str]"),
+        ("join_tick", "int"),
+        ("drop_tick", "Optional[int]"),
+        ("drop_reason", "Optional[str]"),
+        ("duration", "float"),
+        ("ddnet_version", "Optional[int]"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class Anomaly:\n");
+    pyi.push_str("    \"\"\"Something a player did that looks inhuman.\"\"\"\n\n");
+    for (name, ty) in [
+        (
+            "kind",
+            "Literal[\"teleport\", \"input_rate\", \"periodic_input\"]",
+        ),
+        ("cid", "int"),
+        ("tick", "int"),
+        ("end_tick", "int"),
+        ("score", "float"),
+        ("details", "str"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class Finish:\n");
+    pyi.push_str("    \"\"\"A finished race.\"\"\"\n\n");
+    for (name, ty) in [
+        ("kind", "Literal[\"player\", \"team\"]"),
+        ("cids", "List[int]"),
+        ("names", "List[Optional[str]]"),
+        ("team", "int"),
+        ("tick", "int"),
+        ("start_tick", "int"),
+        ("time", "int"),
+        ("seconds", "float"),
+        ("practice", "bool"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
+    pyi.push_str("class RunVerification:\n");
+    pyi.push_str("    \"\"\"Result of verify_run().\"\"\"\n\n");
+    for (name, ty) in [
+        ("verified", "bool"),
+        ("cid", "int"),
+        ("claimed_time", "float"),
+        ("recorded_time", "Optional[float]"),
+        ("start_tick", "Optional[int]"),
+        ("finish_tick", "Optional[int]"),
+        ("discrepancies", "List[str]"),
+    ] {
+        pyi.push_str("    @property\n");
+        pyi.push_str(&format!("    def {}(self) -> {}: ...\n", name, ty));
+    }
+    pyi.push('\n');
+
     // Writer class
     pyi.push_str(
         "# ============================================================================\n",
@@ -306,18 +984,71 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("    by writing chunks and configuring headers. Supports method chaining\n");
     pyi.push_str("    and context manager protocol for clean resource management.\n");
     pyi.push_str("    \"\"\"\n\n");
-    pyi.push_str("    def __init__(self, file: Optional[Any] = None) -> None:\n");
+    pyi.push_str("    def __init__(\n");
+    pyi.push_str("        self,\n");
+    pyi.push_str("        file: Optional[Any] = None,\n");
+    pyi.push_str("        *,\n");
+    pyi.push_str("        path: Optional[Union[str, PathLike[str]]] = None,\n");
+    pyi.push_str("        buffer_size: int = 65536,\n");
+    pyi.push_str("        strict: bool = False,\n");
+    pyi.push_str("        delta_encode: bool = False,\n");
+    pyi.push_str("        spill_threshold: Optional[int] = None,\n");
+    pyi.push_str("        extensions: Optional[Dict[str, str]] = None,\n");
+    pyi.push_str("    ) -> None:\n");
     pyi.push_str("        \"\"\"Initialize a new teehistorian writer.\n\n");
     pyi.push_str("        Args:\n");
-    pyi.push_str("            file: Optional file-like object (for future use)\n");
+    pyi.push_str("            file: Optional file-like object to stream chunks to\n");
+    pyi.push_str("            path: Optional file to stream chunks to instead of memory\n");
+    pyi.push_str(
+        "            buffer_size: Buffered bytes after which a streaming writer flushes\n",
+    );
+    pyi.push_str("            strict: Reject chunks in an order no server writes\n");
+    pyi.push_str("            delta_encode: Write absolute positions and inputs as diffs\n");
+    pyi.push_str("            spill_threshold: Bytes after which data moves to a temporary file\n");
+    pyi.push_str("            extensions: Custom extension UUIDs to register, mapped to names\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    @staticmethod\n");
+    pyi.push_str("    def append(\n");
+    pyi.push_str("        source: Union[bytes, str, PathLike[str]], *, strict: bool = False\n");
+    pyi.push_str("    ) -> 'TeehistorianWriter':\n");
+    pyi.push_str("        \"\"\"Continue an existing recording, dropping its final Eos.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            source: Path or bytes of the recording\n");
+    pyi.push_str("            strict: Check chunk order against the recorded clients\n");
     pyi.push_str("        \"\"\"\n\n");
-    pyi.push_str("    def write(self, chunk: Any) -> 'TeehistorianWriter':\n");
+    pyi.push_str(
+        "    def write(self, chunk: Any, tick: Optional[int] = None) -> 'TeehistorianWriter':\n",
+    );
     pyi.push_str("        \"\"\"Write a chunk to the teehistorian.\n\n");
     pyi.push_str("        Args:\n");
-    pyi.push_str("            chunk: A chunk object to write\n\n");
+    pyi.push_str("            chunk: A chunk object to write\n");
+    pyi.push_str("            tick: Optional absolute tick to write the chunk at\n\n");
     pyi.push_str("        Returns:\n");
     pyi.push_str("            Self for method chaining\n");
     pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def write_join(\n");
+    pyi.push_str("        self,\n");
+    pyi.push_str("        client_id: int,\n");
+    pyi.push_str("        protocol: Optional[int] = None,\n");
+    pyi.push_str("        *,\n");
+    pyi.push_str("        version: Any = None,\n");
+    pyi.push_str("        tick: Optional[int] = None,\n");
+    pyi.push_str("    ) -> None:\n");
+    pyi.push_str("        \"\"\"Write the chunks a server records when a client connects.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            client_id: ID of the connecting client\n");
+    pyi.push_str("            protocol: 6 or 7 to write JoinVer6/JoinVer7 before the Join\n");
+    pyi.push_str("            version: DdnetVersion or DdnetVersionOld written after the Join\n");
+    pyi.push_str("            tick: Optional absolute tick to write the chunks at\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def advance_to_tick(self, tick: int) -> None:\n");
+    pyi.push_str("        \"\"\"Emit the TickSkip that makes `tick` the current tick.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            tick: Absolute tick, not before the current one\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def tick(self) -> int:\n");
+    pyi.push_str("        \"\"\"Tick of the most recently written chunk\"\"\"\n\n");
     pyi.push_str("    def write_all(self, chunks: List[Any]) -> 'TeehistorianWriter':\n");
     pyi.push_str("        \"\"\"Write multiple chunks at once.\n\n");
     pyi.push_str("        Args:\n");
@@ -325,15 +1056,30 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("        Returns:\n");
     pyi.push_str("            Self for method chaining\n");
     pyi.push_str("        \"\"\"\n\n");
-    pyi.push_str("    def set_header(self, key: str, value: str) -> 'TeehistorianWriter':\n");
+    pyi.push_str(
+        "    def write_from(self, chunks: Iterable[Any], batch_size: int = 1024) -> int:\n",
+    );
+    pyi.push_str("        \"\"\"Write chunks pulled from an iterable, e.g. a parser.\n\n");
+    pyi.push_str("        Chunks are serialized in batches with the GIL released.\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Number of chunks written\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def set_header(self, key: str, value: Any) -> 'TeehistorianWriter':\n");
     pyi.push_str("        \"\"\"Set a header field value.\n\n");
     pyi.push_str("        Args:\n");
     pyi.push_str("            key: Header field name\n");
-    pyi.push_str("            value: Header field value\n\n");
+    pyi.push_str("            value: Header field value, str or any JSON-compatible value\n\n");
     pyi.push_str("        Returns:\n");
     pyi.push_str("            Self for method chaining\n");
     pyi.push_str("        \"\"\"\n\n");
-    pyi.push_str("    def get_header(self, key: str) -> Optional[str]:\n");
+    pyi.push_str("    def remove_header(self, key: str) -> 'TeehistorianWriter':\n");
+    pyi.push_str("        \"\"\"Remove a header field.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            key: Header field name\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Self for method chaining\n");
+    pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def get_header(self, key: str) -> Any:\n");
     pyi.push_str("        \"\"\"Get a header field value.\n\n");
     pyi.push_str("        Args:\n");
     pyi.push_str("            key: Header field name\n\n");
@@ -341,7 +1087,7 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("            Header value or None if not set\n");
     pyi.push_str("        \"\"\"\n\n");
     pyi.push_str(
-        "    def update_headers(self, headers: Dict[str, str]) -> 'TeehistorianWriter':\n",
+        "    def update_headers(self, headers: Dict[str, Any]) -> 'TeehistorianWriter':\n",
     );
     pyi.push_str("        \"\"\"Update multiple header fields from a dictionary.\n\n");
     pyi.push_str("        Args:\n");
@@ -349,6 +1095,19 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("        Returns:\n");
     pyi.push_str("            Self for method chaining\n");
     pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def copy_header_from(\n");
+    pyi.push_str(
+        "        self, source: Union['Teehistorian', 'Header', Dict[str, Any], str, bytes]\n",
+    );
+    pyi.push_str("    ) -> 'TeehistorianWriter':\n");
+    pyi.push_str("        \"\"\"Replace the header with the one of another recording.\n\n");
+    pyi.push_str("        Args:\n");
+    pyi.push_str(
+        "            source: A parser, a Header, a dict of header fields or the header JSON\n\n",
+    );
+    pyi.push_str("        Returns:\n");
+    pyi.push_str("            Self for method chaining\n");
+    pyi.push_str("        \"\"\"\n\n");
     pyi.push_str("    def getvalue(self) -> bytes:\n");
     pyi.push_str("        \"\"\"Get all written data as bytes.\n\n");
     pyi.push_str("        Returns:\n");
@@ -364,6 +1123,15 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("        Args:\n");
     pyi.push_str("            file: File-like object with write() method\n");
     pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    def flush(self) -> None:\n");
+    pyi.push_str(
+        "        \"\"\"Write buffered chunks to the file or file object of a streaming writer.\"\"\"\n\n",
+    );
+    pyi.push_str("    def finalize(self) -> None:\n");
+    pyi.push_str("        \"\"\"Write Eos if missing, flush and refuse further chunks.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def finished(self) -> bool:\n");
+    pyi.push_str("        \"\"\"Whether finalize() was called\"\"\"\n\n");
     pyi.push_str("    def size(self) -> int:\n");
     pyi.push_str("        \"\"\"Get current buffer size in bytes.\n\n");
     pyi.push_str("        Returns:\n");
@@ -373,6 +1141,22 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("        \"\"\"Reset the writer to initial empty state.\n\n");
     pyi.push_str("        Clears all written data and resets headers to defaults.\n");
     pyi.push_str("        \"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def spilled(self) -> bool:\n");
+    pyi.push_str("        \"\"\"Whether the data was moved to a temporary file\"\"\"\n\n");
+    pyi.push_str("    def reserve(self, n_bytes: int) -> None:\n");
+    pyi.push_str("        \"\"\"Preallocate memory for n_bytes more bytes.\"\"\"\n\n");
+    pyi.push_str("    def estimate_size(self, chunks: Iterable[Any]) -> int:\n");
+    pyi.push_str(
+        "        \"\"\"Count the bytes writing chunks would add, without writing them.\n\n",
+    );
+    pyi.push_str("        Args:\n");
+    pyi.push_str("            chunks: Iterable of chunk objects\n\n");
+    pyi.push_str("        Returns:\n");
+    pyi.push_str(
+        "            Number of bytes the chunks would add, header included if unwritten\n",
+    );
+    pyi.push_str("        \"\"\"\n\n");
     pyi.push_str("    def is_empty(self) -> bool:\n");
     pyi.push_str("        \"\"\"Check if any data has been written.\n\n");
     pyi.push_str("        Returns:\n");
@@ -390,6 +1174,212 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("    def __repr__(self) -> str:\n");
     pyi.push_str("        \"\"\"Get string representation.\"\"\"\n\n");
 
+    // Module-level functions
+    pyi.push_str("def detect_format(data: bytes) -> Optional[int]:\n");
+    pyi.push_str(
+        "    \"\"\"Get the format version of teehistorian data without parsing its chunks.\n\n",
+    );
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        ValidationError: If the data is not a teehistorian file\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str(
+        "def rewrite_header(data: bytes, new_fields: Dict[str, Optional[str]]) -> bytes:\n",
+    );
+    pyi.push_str(
+        "    \"\"\"Replace fields of the JSON header, keeping the chunks byte for byte.\n\n",
+    );
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        ValidationError: If the data is not a teehistorian file\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def transform(\n");
+    pyi.push_str("    data: bytes,\n");
+    pyi.push_str("    *,\n");
+    pyi.push_str("    keep: Optional[Iterable[Union[type, str]]] = None,\n");
+    pyi.push_str("    drop: Optional[Iterable[Union[type, str]]] = None,\n");
+    pyi.push_str("    map_fn: Optional[Callable[[Chunk], Any]] = None,\n");
+    pyi.push_str("    map_types: Optional[Iterable[Union[type, str]]] = None,\n");
+    pyi.push_str(") -> bytes:\n");
+    pyi.push_str(
+        "    \"\"\"Rewrite a recording in Rust, keeping, dropping or mapping chunks by type.\n\n",
+    );
+    pyi.push_str("    map_fn returns the chunk to write instead, a list of chunks or None.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If a chunk type is unknown or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def patch(\n");
+    pyi.push_str("    data: bytes,\n");
+    pyi.push_str("    predicate: Callable[[Chunk], bool],\n");
+    pyi.push_str("    replacement_fn: Callable[[Chunk], Any],\n");
+    pyi.push_str(") -> bytes:\n");
+    pyi.push_str("    \"\"\"Replace the chunks a predicate selects, keeping every other byte.\n\n");
+    pyi.push_str(
+        "    replacement_fn returns the chunk to write instead, a list of chunks or None.\n\n",
+    );
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def anonymize(\n");
+    pyi.push_str("    data: bytes,\n");
+    pyi.push_str("    *,\n");
+    pyi.push_str("    names: bool = True,\n");
+    pyi.push_str("    ips: bool = True,\n");
+    pyi.push_str("    auth: bool = True,\n");
+    pyi.push_str("    chat: bool = False,\n");
+    pyi.push_str(") -> bytes:\n");
+    pyi.push_str(
+        "    \"\"\"Replace player names, account names and IP addresses with stable pseudonyms.\n\n",
+    );
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def slice_ticks(data: bytes, start: int, end: Optional[int] = None) -> bytes:\n");
+    pyi.push_str("    \"\"\"Cut a tick range out of a recording as a file of its own.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If end is before start or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def split(\n");
+    pyi.push_str(
+        "    data: bytes, every_ticks: Optional[int] = None, *, every_minutes: Optional[float] = None\n",
+    );
+    pyi.push_str(") -> List[bytes]:\n");
+    pyi.push_str(
+        "    \"\"\"Split a recording into recordings covering a fixed number of ticks each.\n\n",
+    );
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If the part length is missing or not positive, or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def merge(files: List[bytes]) -> bytes:\n");
+    pyi.push_str("    \"\"\"Merge the recordings of consecutive server runs into one.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If the headers are not compatible or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def redact_chat(\n");
+    pyi.push_str(
+        "    data: bytes, matcher: Union[str, Pattern[str], Iterable[str]], *, remove: bool = False\n",
+    );
+    pyi.push_str(") -> bytes:\n");
+    pyi.push_str("    \"\"\"Blank or remove the chat messages that match.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If the word list is empty or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def extract_player(data: bytes, cid_or_name: Union[int, str]) -> bytes:\n");
+    pyi.push_str("    \"\"\"Extract the chunks of one player into a recording of its own.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If the player is not found, the name is ambiguous or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def diff(a: bytes, b: bytes) -> RecordingDiff:\n");
+    pyi.push_str("    \"\"\"Compare what two recordings mean, chunk by chunk.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If either recording cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def normalize(\n");
+    pyi.push_str("    data: bytes, *, expand_diffs: bool = False, recompress_diffs: bool = True\n");
+    pyi.push_str(") -> bytes:\n");
+    pyi.push_str("    \"\"\"Re-encode a recording canonically.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def shift_ticks(data: bytes, delta: int) -> bytes:\n");
+    pyi.push_str("    \"\"\"Move every chunk of a recording by a number of ticks.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If a tick would become negative or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def remap_cids(data: bytes, mapping: Dict[int, int]) -> bytes:\n");
+    pyi.push_str("    \"\"\"Give the clients of a recording new client IDs.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If two clients would get the same ID or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def strip(data: bytes, categories: List[str] = [\"Input\"]) -> bytes:\n");
+    pyi.push_str("    \"\"\"Remove whole categories of chunks from a recording.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If a category is unknown or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def repair(data: bytes) -> Tuple[bytes, RepairReport]:\n");
+    pyi.push_str("    \"\"\"Salvage the readable part of a damaged recording.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If the header cannot be read, even after decompressing what is left\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def recompress(\n");
+    pyi.push_str("    data: bytes, profile: Literal[\"small\", \"fast\"] = \"small\"\n");
+    pyi.push_str(") -> Tuple[bytes, RecompressReport]:\n");
+    pyi.push_str("    \"\"\"Rewrite a recording to take less space.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If the profile is unknown or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def extract_sessions(data: bytes) -> List[PlayerSession]:\n");
+    pyi.push_str("    \"\"\"Every session of every player in a recording.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def input_stats(data: bytes) -> Dict[int, Dict[str, Union[int, float]]]:\n");
+    pyi.push_str("    \"\"\"Input statistics of every player in a recording.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def detect_anomalies(\n");
+    pyi.push_str("    data: bytes,\n");
+    pyi.push_str("    *,\n");
+    pyi.push_str("    max_speed: float = 128.0,\n");
+    pyi.push_str("    max_presses_per_second: float = 20.0,\n");
+    pyi.push_str("    min_periodic_presses: int = 10,\n");
+    pyi.push_str(") -> List[Anomaly]:\n");
+    pyi.push_str("    \"\"\"Look for movement and input a human player cannot produce.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str(
+        "        TeehistorianError: If a threshold is not positive or the data cannot be parsed\n",
+    );
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def extract_finishes(data: bytes) -> List[Finish]:\n");
+    pyi.push_str("    \"\"\"Every race finish in a recording.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def verify_run(\n");
+    pyi.push_str("    data: bytes, cid: int, claimed_time: float, *, tolerance: float = 0.02\n");
+    pyi.push_str(") -> RunVerification:\n");
+    pyi.push_str("    \"\"\"Check a claimed race time against the recording of the run.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        TeehistorianError: If the data cannot be parsed\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def make_chunk(chunk_type: str, **fields: Any) -> Chunk:\n");
+    pyi.push_str("    \"\"\"Create a chunk from its type name and constructor arguments.\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        ValidationError: If the chunk type is unknown\n");
+    pyi.push_str("    \"\"\"\n\n");
+    pyi.push_str("def chunk_types() -> Dict[str, type]:\n");
+    pyi.push_str("    \"\"\"Get the class of every chunk type, keyed by type name.\"\"\"\n\n");
+    pyi.push_str("def categories() -> Dict[str, List[str]]:\n");
+    pyi.push_str("    \"\"\"Get the chunk type names of every category.\"\"\"\n\n");
+    pyi.push_str(
+        "def parse_chunk(data: bytes, uuid_registry: Optional[List[str]] = None) -> Chunk:\n",
+    );
+    pyi.push_str("    \"\"\"Decode a single encoded chunk, the inverse of write_to_buffer().\n\n");
+    pyi.push_str("    Raises:\n");
+    pyi.push_str("        ParseError: If the data is not exactly one chunk\n");
+    pyi.push_str("    \"\"\"\n\n");
+
     // Base Chunk class
     pyi.push_str(
         "# ============================================================================\n",
@@ -405,6 +1395,11 @@ fn generate_pyi(chunks: &[ChunkInfo]) -> String {
     pyi.push_str("    \"\"\"\n\n");
     pyi.push_str("    def chunk_type(self) -> str:\n");
     pyi.push_str("        \"\"\"Get the chunk type identifier.\"\"\"\n\n");
+    pyi.push_str("    @property\n");
+    pyi.push_str("    def category(self) -> str:\n");
+    pyi.push_str(
+        "        \"\"\"Category of the chunk type, e.g. \\\"PlayerLifecycle\\\".\"\"\"\n\n",
+    );
     pyi.push_str("    def __repr__(self) -> str:\n");
     pyi.push_str("        \"\"\"Get string representation for debugging.\"\"\"\n\n");
     pyi.push_str("    def __str__(self) -> str:\n");
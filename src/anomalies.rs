@@ -0,0 +1,355 @@
+//! Movement and input anomalies
+//!
+//! Cheats leave traces a human player cannot: characters that move further
+//! in a tick than the physics allow, more button presses per second than
+//! fingers manage and presses at exactly the same interval over and over.
+//! Each finding is scored, so reviewers can start with the most suspicious
+//! ones; a finding is a hint, not proof.
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+use crate::errors::TeehistorianParseError;
+use crate::input_stats::Presses;
+use crate::player_input::PyPlayerInput;
+use crate::resolve::{InputResolver, PositionResolver};
+use crate::ticks::TICK_SPEED;
+use crate::walk_recording;
+
+/// Something a player did that looks inhuman
+#[pyclass(name = "Anomaly", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyAnomaly {
+    /// `"teleport"`, `"input_rate"` or `"periodic_input"`
+    #[pyo3(get)]
+    kind: &'static str,
+    /// Client ID
+    #[pyo3(get)]
+    cid: i32,
+    /// First tick of the anomaly
+    #[pyo3(get)]
+    tick: i32,
+    /// Last tick of the anomaly
+    #[pyo3(get)]
+    end_tick: i32,
+    /// How far beyond the threshold the anomaly is, from 0.5 at the
+    /// threshold towards 1
+    #[pyo3(get)]
+    score: f64,
+    /// What was measured
+    #[pyo3(get)]
+    details: String,
+}
+
+impl PyAnomaly {
+    fn new(kind: &'static str, cid: i32, ticks: (i32, i32), value: f64, threshold: f64) -> Self {
+        Self {
+            kind,
+            cid,
+            tick: ticks.0,
+            end_tick: ticks.1,
+            score: 1.0 - threshold / (2.0 * value),
+            details: String::new(),
+        }
+    }
+
+    fn details(mut self, details: String) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+#[pymethods]
+impl PyAnomaly {
+    fn __repr__(&self) -> String {
+        format!(
+            "Anomaly(kind={:?}, cid={}, tick={}, score={:.2})",
+            self.kind, self.cid, self.tick, self.score
+        )
+    }
+}
+
+/// Limits beyond which behavior is flagged
+#[derive(Debug, Clone, Copy)]
+struct Thresholds {
+    /// World units per tick
+    max_speed: f64,
+    max_presses_per_second: f64,
+    /// Equal intervals in a row
+    min_periodic_presses: u32,
+}
+
+impl Thresholds {
+    /// Finding for a burst of presses
+    fn burst(&self, cid: i32, burst: &Burst) -> PyAnomaly {
+        let Burst { start, end, rate } = *burst;
+        let limit = self.max_presses_per_second;
+        PyAnomaly::new("input_rate", cid, (start, end), rate, limit)
+            .details(format!("{} presses within one second", rate))
+    }
+
+    /// Finding for a rhythm of a button, None if it is not long enough
+    fn rhythm(&self, cid: i32, button: usize, rhythm: &Rhythm) -> Option<PyAnomaly> {
+        let min = self.min_periodic_presses;
+        if rhythm.interval <= 1 || rhythm.repeats < min {
+            return None;
+        }
+        let name = ["jump", "hook", "fire"][button];
+        let end = rhythm.start + rhythm.interval * rhythm.repeats as i32;
+        let anomaly = PyAnomaly::new(
+            "periodic_input",
+            cid,
+            (rhythm.start, end),
+            f64::from(rhythm.repeats),
+            f64::from(min),
+        );
+        Some(anomaly.details(format!(
+            "Pressed {} {} times in a row exactly {} ticks apart",
+            name,
+            rhythm.repeats + 1,
+            rhythm.interval
+        )))
+    }
+}
+
+/// Ticks in which the presses within a second were above the limit
+#[derive(Debug, Clone, Copy)]
+struct Burst {
+    start: i32,
+    end: i32,
+    /// Highest number of presses within a second
+    rate: f64,
+}
+
+/// Presses of one button at the same interval
+#[derive(Debug, Clone, Copy, Default)]
+struct Rhythm {
+    last: Option<i32>,
+    interval: i32,
+    /// Tick of the first press at `interval`
+    start: i32,
+    /// Equal intervals in a row
+    repeats: u32,
+}
+
+/// What is known about one client
+#[derive(Debug, Default)]
+struct ClientState {
+    /// Ticks of the presses within the last second
+    recent: VecDeque<i32>,
+    burst: Option<Burst>,
+    /// Jump, hook and fire rhythm
+    rhythms: [Rhythm; 3],
+}
+
+#[derive(Debug)]
+struct Detector {
+    thresholds: Thresholds,
+    positions: PositionResolver,
+    /// Last position of every spawned character
+    last_moves: HashMap<i32, [i32; 2]>,
+    inputs: InputResolver,
+    clients: BTreeMap<i32, ClientState>,
+    found: Vec<PyAnomaly>,
+}
+
+impl Detector {
+    fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            positions: PositionResolver::new(),
+            last_moves: HashMap::new(),
+            inputs: InputResolver::new(),
+            clients: BTreeMap::new(),
+            found: Vec::new(),
+        }
+    }
+
+    fn observe(&mut self, chunk: Chunk, tick: i32) {
+        match chunk {
+            Chunk::PlayerNew(_) | Chunk::PlayerDiff(_) => self.moved(&chunk, tick),
+            Chunk::PlayerOld { cid } => {
+                self.positions.resolve(&chunk);
+                self.last_moves.remove(&cid);
+            }
+            Chunk::InputNew(ref new) => {
+                let cid = new.cid;
+                let old = self.inputs.input(cid).copied();
+                self.inputs.resolve(chunk);
+                self.input(cid, old, tick);
+            }
+            Chunk::InputDiff(ref diff) => {
+                let cid = diff.cid;
+                let old = self.inputs.input(cid).copied();
+                self.inputs.resolve(chunk);
+                self.input(cid, old, tick);
+            }
+            Chunk::Drop(ref drop) => {
+                let cid = drop.cid;
+                self.positions.resolve(&Chunk::PlayerOld { cid });
+                self.last_moves.remove(&cid);
+                self.end_client(cid);
+                self.inputs.resolve(chunk);
+            }
+            _ => {}
+        }
+    }
+
+    fn moved(&mut self, chunk: &Chunk, tick: i32) {
+        let Some((cid, x, y)) = self.positions.resolve(chunk) else {
+            return;
+        };
+        let Some([last_x, last_y]) = self.last_moves.insert(cid, [x, y]) else {
+            return;
+        };
+        // Positions are only recorded when they change, so the character
+        // stood still until the previous tick and covered the whole distance
+        // in this one, as in `PlayerTracker.kinematics()`
+        let speed = (f64::from(x) - f64::from(last_x)).hypot(f64::from(y) - f64::from(last_y));
+        let max_speed = self.thresholds.max_speed;
+        if speed > max_speed {
+            let anomaly = PyAnomaly::new("teleport", cid, (tick - 1, tick), speed, max_speed);
+            self.found.push(anomaly.details(format!(
+                "Moved {:.0} units from ({}, {}) to ({}, {}) in one tick",
+                speed, last_x, last_y, x, y
+            )));
+        }
+    }
+
+    fn input(&mut self, cid: i32, old: Option<[i32; 10]>, tick: i32) {
+        let (Some(old), Some(new)) = (old, self.inputs.input(cid)) else {
+            return;
+        };
+        let presses = Presses::between(
+            &PyPlayerInput::from_slice(&old),
+            &PyPlayerInput::from_slice(new),
+        );
+        let thresholds = self.thresholds;
+        let client = self.clients.entry(cid).or_default();
+
+        // Presses within the last second
+        client
+            .recent
+            .extend(std::iter::repeat_n(tick, presses.total() as usize));
+        while client
+            .recent
+            .front()
+            .is_some_and(|&t| t <= tick - TICK_SPEED)
+        {
+            client.recent.pop_front();
+        }
+        let rate = client.recent.len() as f64;
+        if rate > thresholds.max_presses_per_second {
+            let burst = client.burst.get_or_insert(Burst {
+                start: tick,
+                end: tick,
+                rate,
+            });
+            burst.end = tick;
+            burst.rate = burst.rate.max(rate);
+        } else if let Some(burst) = client.burst.take() {
+            self.found.push(thresholds.burst(cid, &burst));
+        }
+
+        let pressed = [presses.jumps, presses.hooks, presses.fires];
+        for (button, rhythm) in client.rhythms.iter_mut().enumerate() {
+            if pressed[button] == 0 {
+                continue;
+            }
+            let interval = rhythm.last.map_or(0, |last| tick - last);
+            if interval > 1 && interval == rhythm.interval {
+                rhythm.repeats += 1;
+            } else {
+                self.found.extend(thresholds.rhythm(cid, button, rhythm));
+                *rhythm = Rhythm {
+                    last: None,
+                    interval,
+                    start: tick - interval,
+                    repeats: 1,
+                };
+            }
+            rhythm.last = Some(tick);
+        }
+    }
+
+    /// Report what is still going on for a client that leaves
+    fn end_client(&mut self, cid: i32) {
+        let Some(client) = self.clients.remove(&cid) else {
+            return;
+        };
+        if let Some(burst) = client.burst {
+            self.found.push(self.thresholds.burst(cid, &burst));
+        }
+        for (button, rhythm) in client.rhythms.iter().enumerate() {
+            self.found
+                .extend(self.thresholds.rhythm(cid, button, rhythm));
+        }
+    }
+
+    /// Forget the clients of the previous stream
+    fn end_stream(&mut self) {
+        let cids: Vec<i32> = self.clients.keys().copied().collect();
+        for cid in cids {
+            self.end_client(cid);
+        }
+        self.positions = PositionResolver::new();
+        self.last_moves.clear();
+        self.inputs = InputResolver::new();
+    }
+}
+
+/// Look for movement and input a human player cannot produce
+///
+/// Flags characters moving faster than `max_speed` within a tick,
+/// bursts of more than `max_presses_per_second` button presses and direction
+/// changes within a second, and jump, hook or fire presses repeated at
+/// exactly the same interval `min_periodic_presses` times in a row.
+/// Teleporters and speedups of the map are flagged as well, so compare
+/// findings with the map before acting on them.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `max_speed` - World units per tick, 32 per tile
+/// * `max_presses_per_second` - Presses and direction changes per second
+/// * `min_periodic_presses` - Equal intervals in a row
+///
+/// # Returns
+/// A list of `Anomaly`, ordered by tick and client ID
+///
+/// # Example
+/// ```python
+/// for anomaly in th.detect_anomalies(data):
+///     if anomaly.score > 0.8:
+///         print(anomaly.tick, anomaly.cid, anomaly.details)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, *, max_speed=128.0, max_presses_per_second=20.0, min_periodic_presses=10))]
+pub fn detect_anomalies(
+    py: Python<'_>,
+    data: &[u8],
+    max_speed: f64,
+    max_presses_per_second: f64,
+    min_periodic_presses: u32,
+) -> PyResult<Vec<PyAnomaly>> {
+    if max_speed <= 0.0 || max_presses_per_second <= 0.0 || min_periodic_presses == 0 {
+        return Err(
+            TeehistorianParseError::Validation("Thresholds must be positive".to_string()).into(),
+        );
+    }
+    let thresholds = Thresholds {
+        max_speed,
+        max_presses_per_second,
+        min_periodic_presses,
+    };
+    let mut detector = Detector::new(thresholds);
+    walk_recording(py, data, |chunk, tick| match chunk {
+        Some(chunk) => detector.observe(chunk, tick),
+        None => detector.end_stream(),
+    })?;
+
+    detector.end_stream();
+    let mut found = detector.found;
+    found.sort_by_key(|anomaly| (anomaly.tick, anomaly.cid));
+    Ok(found)
+}
@@ -0,0 +1,380 @@
+//! Removing personal data from recordings before sharing them
+//!
+//! Recordings hold player names, IP addresses, account names and chat.
+//! Anonymizing replaces each of them with a pseudonym that stays the same
+//! for the whole recording, so the game still reads the same. Redacting chat
+//! blanks or drops only the messages that match. Every other chunk is copied
+//! as it is.
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use serde_json::Value;
+use teehistorian::Chunk;
+use teehistorian::chunks::{Auth, ConsoleCommand, DdnetVersion, NetMessage, PlayerName};
+use uuid::Uuid;
+
+use crate::errors::TeehistorianParseError;
+use crate::net_msg::{ClientVersions, PlayerText, replace_player_texts};
+use crate::transform::{Item, rewrite};
+
+/// Stable pseudonyms, numbered in order of first appearance
+struct Pseudonyms {
+    prefix: &'static str,
+    known: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Pseudonyms {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            known: HashMap::new(),
+        }
+    }
+
+    /// Pseudonym of `original`, empty texts stay empty
+    fn get(&mut self, original: &[u8]) -> Vec<u8> {
+        if original.is_empty() {
+            return Vec::new();
+        }
+        let next = self.known.len() + 1;
+        self.known
+            .entry(original.to_vec())
+            .or_insert_with(|| format!("{}-{}", self.prefix, next).into_bytes())
+            .clone()
+    }
+
+    /// Pseudonym of `original` if it was seen before
+    fn known(&self, original: &[u8]) -> Option<Vec<u8>> {
+        self.known.get(original).cloned()
+    }
+}
+
+/// Which identifiers to replace
+#[derive(Clone, Copy)]
+struct Options {
+    names: bool,
+    ips: bool,
+    auth: bool,
+    chat: bool,
+}
+
+/// Replaces identifying data chunk by chunk
+struct Anonymizer {
+    options: Options,
+    names: Pseudonyms,
+    accounts: Pseudonyms,
+    ips: HashMap<IpAddr, IpAddr>,
+    connections: HashMap<Uuid, Uuid>,
+    versions: ClientVersions,
+}
+
+impl Anonymizer {
+    fn new(options: Options) -> Self {
+        Self {
+            options,
+            names: Pseudonyms::new("player"),
+            accounts: Pseudonyms::new("account"),
+            ips: HashMap::new(),
+            connections: HashMap::new(),
+            versions: ClientVersions::default(),
+        }
+    }
+
+    /// Private address standing in for `ip`
+    fn ip(&mut self, ip: IpAddr) -> IpAddr {
+        let next = self.ips.len() as u32 + 1;
+        *self.ips.entry(ip).or_insert_with(|| match ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(0x0a00_0000 | next)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(0xfd00_u128 << 112 | next as u128)),
+        })
+    }
+
+    /// Replace the IP addresses in a text, None if there are none
+    ///
+    /// Addresses are recognized as runs of hex digits, dots and colons, so a
+    /// port after an IPv4 address is kept as it is.
+    fn replace_ips(&mut self, text: &[u8]) -> Option<Vec<u8>> {
+        let is_ip_byte = |b: &u8| b.is_ascii_hexdigit() || *b == b'.' || *b == b':';
+        let mut out = Vec::new();
+        let mut copied = 0;
+        let mut start = 0;
+        while start < text.len() {
+            let len = text[start..].iter().take_while(|b| is_ip_byte(b)).count();
+            if len == 0 {
+                start += 1;
+                continue;
+            }
+            let run = std::str::from_utf8(&text[start..start + len]).unwrap_or_default();
+            let address = run.trim_end_matches(['.', ':']);
+            let address = match address.parse::<IpAddr>() {
+                Ok(_) => address,
+                Err(_) => address.split_once(':').map_or("", |(ip, _)| ip),
+            };
+            if let Ok(ip) = address.parse::<IpAddr>()
+                && !ip.is_unspecified()
+            {
+                let ip = self.ip(ip);
+                out.extend_from_slice(&text[copied..start]);
+                out.extend_from_slice(ip.to_string().as_bytes());
+                copied = start + address.len();
+            }
+            start += len;
+        }
+        if copied == 0 {
+            return None;
+        }
+        out.extend_from_slice(&text[copied..]);
+        Some(out)
+    }
+
+    /// Replace the IP addresses in all strings of a header
+    fn header(&mut self, value: &mut Value) {
+        match value {
+            Value::String(text) if self.options.ips => {
+                if let Some(replaced) = self.replace_ips(text.as_bytes()) {
+                    *text = String::from_utf8_lossy(&replaced).into_owned();
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.header(value)),
+            Value::Object(fields) => fields.values_mut().for_each(|value| self.header(value)),
+            _ => {}
+        }
+    }
+
+    /// Replacement for a console command argument, None to keep it
+    fn argument(&mut self, arg: &[u8]) -> Option<Vec<u8>> {
+        let name = self.options.names.then(|| self.names.known(arg)).flatten();
+        let account = self
+            .options
+            .auth
+            .then(|| self.accounts.known(arg))
+            .flatten();
+        name.or(account)
+            .or_else(|| self.options.ips.then(|| self.replace_ips(arg)).flatten())
+    }
+
+    /// Replacement for a text of a client message, None to keep it
+    fn player_text(&mut self, kind: PlayerText, text: &[u8]) -> Option<Vec<u8>> {
+        match kind {
+            PlayerText::Name if self.options.names => Some(self.names.get(text)),
+            PlayerText::Clan if self.options.names && !text.is_empty() => Some(Vec::new()),
+            PlayerText::Chat if self.options.chat && !text.is_empty() => Some(Vec::new()),
+            PlayerText::Chat if self.options.ips => self.replace_ips(text),
+            _ => None,
+        }
+    }
+
+    /// Write the replacement for a chunk, returns false to keep it as it is
+    ///
+    /// The replacement borrows from locals, so it is handed to `write`.
+    fn chunk(
+        &mut self,
+        chunk: Chunk,
+        write: impl FnOnce(&Chunk) -> PyResult<()>,
+    ) -> PyResult<bool> {
+        self.versions.observe(&chunk);
+        match chunk {
+            Chunk::PlayerName(PlayerName { cid, name }) if self.options.names => {
+                let name = self.names.get(name);
+                write(&Chunk::PlayerName(PlayerName { cid, name: &name }))?;
+                return Ok(true);
+            }
+            Chunk::AuthInit(auth) if self.options.auth => {
+                let auth_name = self.accounts.get(auth.auth_name);
+                write(&Chunk::AuthInit(Auth {
+                    auth_name: &auth_name,
+                    ..auth
+                }))?;
+                return Ok(true);
+            }
+            Chunk::AuthLogin(auth) if self.options.auth => {
+                let auth_name = self.accounts.get(auth.auth_name);
+                write(&Chunk::AuthLogin(Auth {
+                    auth_name: &auth_name,
+                    ..auth
+                }))?;
+                return Ok(true);
+            }
+            Chunk::DdnetVersion(version) if self.options.ips => {
+                let next = self.connections.len() as u128 + 1;
+                let connection_id = *self
+                    .connections
+                    .entry(version.connection_id)
+                    .or_insert_with(|| Uuid::from_u128(next));
+                write(&Chunk::DdnetVersion(DdnetVersion {
+                    connection_id,
+                    ..version
+                }))?;
+                return Ok(true);
+            }
+            Chunk::ConsoleCommand(command) => {
+                let replaced: Vec<_> = command.args.iter().map(|arg| self.argument(arg)).collect();
+                if replaced.iter().any(Option::is_some) {
+                    let args = replaced
+                        .iter()
+                        .zip(&command.args)
+                        .map(|(new, old)| new.as_deref().unwrap_or(old))
+                        .collect();
+                    write(&Chunk::ConsoleCommand(ConsoleCommand { args, ..command }))?;
+                    return Ok(true);
+                }
+            }
+            Chunk::NetMessage(NetMessage { cid, msg }) => {
+                let version = self.versions.get(cid);
+                if let Some(msg) =
+                    replace_player_texts(msg, version, |kind, text| self.player_text(kind, text))
+                {
+                    write(&Chunk::NetMessage(NetMessage { cid, msg: &msg }))?;
+                    return Ok(true);
+                }
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+}
+
+/// Replace identifying data of a recording with stable pseudonyms
+#[pyfunction]
+#[pyo3(signature = (data, *, names=true, ips=true, auth=true, chat=false))]
+pub fn anonymize<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    names: bool,
+    ips: bool,
+    auth: bool,
+    chat: bool,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut anonymizer = Anonymizer::new(Options {
+        names,
+        ips,
+        auth,
+        chat,
+    });
+    rewrite(py, data, |out, item, encoded, _| match item {
+        Item::Header(header) => {
+            anonymizer.header(header);
+            Ok(())
+        }
+        Item::End => Ok(()),
+        Item::Segment(header) => {
+            let mut header = serde_json::from_slice(header).map_err(|e| {
+                TeehistorianParseError::Parse(format!("Failed to parse header: {}", e))
+            })?;
+            anonymizer.header(&mut header);
+            out.write_segment(&header)
+        }
+        Item::Chunk(chunk, tick) => {
+            if anonymizer.chunk(chunk, |chunk| out.write(chunk, tick))? {
+                Ok(())
+            } else {
+                out.copy(encoded, Some(tick))
+            }
+        }
+    })
+}
+
+/// Python matcher for chat messages, from a pattern, compiled regex or words
+fn chat_matcher<'py>(py: Python<'py>, matcher: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let re = py.import("re")?;
+    if matcher.is_instance_of::<PyString>() {
+        return re.call_method1("compile", (matcher,));
+    }
+    if matcher.hasattr("search")? {
+        return Ok(matcher.clone());
+    }
+    let words = matcher
+        .try_iter()?
+        .map(|word| re.call_method1("escape", (word?,))?.extract::<String>())
+        .collect::<PyResult<Vec<_>>>()?;
+    if words.is_empty() {
+        return Err(TeehistorianParseError::Validation("No words to redact".to_string()).into());
+    }
+    let pattern = format!(r"(?<!\w)(?:{})(?!\w)", words.join("|"));
+    re.call_method1("compile", (pattern, re.getattr("IGNORECASE")?))
+}
+
+/// Blank or remove the chat messages of a recording that match
+#[pyfunction]
+#[pyo3(signature = (data, matcher, *, remove=false))]
+pub fn redact_chat<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    matcher: &Bound<'py, PyAny>,
+    remove: bool,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let matcher = chat_matcher(py, matcher)?;
+    let mut versions = ClientVersions::default();
+    rewrite(py, data, |out, item, encoded, _| {
+        let (chunk, tick) = match item {
+            Item::Header(_) | Item::End => return Ok(()),
+            Item::Segment(_) => return out.copy(encoded, None),
+            Item::Chunk(chunk, tick) => (chunk, tick),
+        };
+        versions.observe(&chunk);
+        let Chunk::NetMessage(NetMessage { cid, msg }) = chunk else {
+            return out.copy(encoded, Some(tick));
+        };
+        let mut error = None;
+        let blanked = replace_player_texts(msg, versions.get(cid), |kind, text| {
+            if kind != PlayerText::Chat || error.is_some() {
+                return None;
+            }
+            let found = matcher
+                .call_method1("search", (String::from_utf8_lossy(text),))
+                .and_then(|found| found.is_truthy());
+            match found {
+                Ok(true) => Some(Vec::new()),
+                Ok(false) => None,
+                Err(err) => {
+                    error = Some(err);
+                    None
+                }
+            }
+        });
+        if let Some(err) = error {
+            return Err(err);
+        }
+        match blanked {
+            Some(_) if remove => Ok(()),
+            Some(msg) => out.write(&Chunk::NetMessage(NetMessage { cid, msg: &msg }), tick),
+            None => out.copy(encoded, Some(tick)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_ips() {
+        let mut anonymizer = Anonymizer::new(Options {
+            names: true,
+            ips: true,
+            auth: true,
+            chat: false,
+        });
+        assert_eq!(
+            anonymizer.replace_ips(b"ban 192.168.1.7:8303 and 8.8.8.8."),
+            Some(b"ban 10.0.0.1:8303 and 10.0.0.2.".to_vec())
+        );
+        assert_eq!(
+            anonymizer.replace_ips(b"[2001:db8::1]:8303 192.168.1.7"),
+            Some(b"[fd00::3]:8303 10.0.0.1".to_vec())
+        );
+        assert_eq!(anonymizer.replace_ips(b"version 0.6.4 :: cafe"), None);
+    }
+
+    #[test]
+    fn test_pseudonyms() {
+        let mut names = Pseudonyms::new("player");
+        assert_eq!(names.get(b"nameless tee"), b"player-1");
+        assert_eq!(names.get(b"brainless tee"), b"player-2");
+        assert_eq!(names.get(b"nameless tee"), b"player-1");
+        assert_eq!(names.get(b""), b"");
+        assert_eq!(names.known(b"someone"), None);
+    }
+}
@@ -0,0 +1,105 @@
+//! Decoding of antibot payloads
+//!
+//! The antibot module hands the server an opaque payload that ends up in
+//! the `teehistorian-antibot@ddnet.org` chunk. Text payloads are an event
+//! name followed by whitespace-separated parameters, either positional or
+//! `key=value`. Anything else is kept as raw bytes only.
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Antibot payload split into its fields
+///
+/// Obtained from `AntiBot.decode()`.
+#[pyclass(name = "AntibotEvent", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyAntibotEvent {
+    /// Event name, or None if the payload is not text
+    #[pyo3(get)]
+    pub event: Option<String>,
+    /// Positional parameters
+    #[pyo3(get)]
+    pub args: Vec<String>,
+    /// `key=value` parameters
+    #[pyo3(get)]
+    pub params: HashMap<String, String>,
+    raw: Vec<u8>,
+}
+
+impl PyAntibotEvent {
+    /// Split an antibot payload into event name and parameters
+    pub fn decode(data: &[u8]) -> Self {
+        let mut decoded = Self {
+            event: None,
+            args: Vec::new(),
+            params: HashMap::new(),
+            raw: data.to_vec(),
+        };
+
+        let Ok(text) = std::str::from_utf8(data) else {
+            return decoded;
+        };
+        let text = text.trim_end_matches('\0');
+        if text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+            return decoded;
+        }
+
+        let mut tokens: Vec<&str> = text.split_whitespace().collect();
+        // A payload starting with a parameter has no event name
+        if tokens.first().is_some_and(|first| !first.contains('=')) {
+            decoded.event = Some(tokens.remove(0).to_string());
+        }
+        for token in tokens {
+            match token.split_once('=') {
+                Some((key, value)) => {
+                    decoded.params.insert(key.to_string(), value.to_string());
+                }
+                None => decoded.args.push(token.to_string()),
+            }
+        }
+        decoded
+    }
+}
+
+#[pymethods]
+impl PyAntibotEvent {
+    /// The undecoded payload
+    #[getter]
+    fn raw<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.raw)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "AntibotEvent(event={:?}, args={:?}, params={:?})",
+            self.event, self.args, self.params
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_text() {
+        let event = PyAntibotEvent::decode(b"aimbot_suspect 3 score=0.92 weapon=laser");
+        assert_eq!(event.event.as_deref(), Some("aimbot_suspect"));
+        assert_eq!(event.args, vec!["3"]);
+        assert_eq!(event.params["score"], "0.92");
+        assert_eq!(event.params["weapon"], "laser");
+    }
+
+    #[test]
+    fn test_decode_fallback() {
+        let event = PyAntibotEvent::decode(&[0xff, 0x00, 0x13]);
+        assert_eq!(event.event, None);
+        assert!(event.args.is_empty());
+        assert_eq!(event.raw, vec![0xff, 0x00, 0x13]);
+
+        let event = PyAntibotEvent::decode(b"cid=3");
+        assert_eq!(event.event, None);
+        assert_eq!(event.params["cid"], "3");
+    }
+}
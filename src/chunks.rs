@@ -1,9 +1,14 @@
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict};
 use std::any::type_name;
 use std::io::Cursor;
 use teehistorian::Chunk;
 
+use crate::antibot::PyAntibotEvent;
+use crate::errors::TeehistorianParseError;
+use crate::net_msg::NetVersion;
+use crate::player_input::PyPlayerInput;
+use crate::registry::{FieldFormat, FieldValue};
 // Import macros from the macros module
 use crate::{define_chunk, define_chunk_custom, define_inline_chunk, define_zero_field_chunk};
 
@@ -24,20 +29,141 @@ pub trait TeehistorianChunk {
             .unwrap_or(full_name)
     }
 
+    /// Encoding read from a file that the fields cannot reproduce
+    ///
+    /// Text fields replace invalid UTF-8, so chunks with such text keep the
+    /// bytes they were read from to be written back unchanged.
+    fn recorded(&self) -> Option<&[u8]> {
+        None
+    }
+
     /// Serialize this chunk to bytes
-    /// Always re-serializes chunks to support modification
+    /// Re-serializes the fields unless the chunk keeps a recorded encoding
     fn write_to_buffer(&self) -> PyResult<Vec<u8>> {
-        // Always re-serialize to support chunk modification
-        // This ensures that if a chunk was modified in Python, it gets properly re-encoded
-        let chunk = self.to_teehistorian_chunk();
-        let mut cursor = Cursor::new(Vec::new());
-        teehistorian::serialize_into(&mut cursor, &chunk).map_err(|e| {
-            pyo3::PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                "Failed to serialize chunk: {}",
-                e
-            ))
-        })?;
-        Ok(cursor.into_inner())
+        if let Some(recorded) = self.recorded() {
+            return Ok(recorded.to_vec());
+        }
+        encode(&self.to_teehistorian_chunk())
+    }
+}
+
+/// Serialize a chunk to bytes
+pub fn encode(chunk: &Chunk) -> PyResult<Vec<u8>> {
+    let mut cursor = Cursor::new(Vec::new());
+    teehistorian::serialize_into(&mut cursor, chunk).map_err(|e| {
+        pyo3::PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Failed to serialize chunk: {}",
+            e
+        ))
+    })?;
+    Ok(cursor.into_inner())
+}
+
+/// Copy a chunk with every client ID passed through `map`
+pub fn map_cids<'a>(chunk: &Chunk<'a>, mut map: impl FnMut(i32) -> i32) -> Chunk<'a> {
+    use teehistorian::chunks::{
+        Antibot, Auth, ConsoleCommand, DdnetVersion, DdnetVersionOld, Drop, InputDiff, InputNew,
+        NetMessage, PlayerDiff, PlayerName, PlayerNew, TeamSave, UnknownEx,
+    };
+
+    match *chunk {
+        Chunk::PlayerDiff(ref p) => Chunk::PlayerDiff(PlayerDiff {
+            cid: map(p.cid),
+            dx: p.dx,
+            dy: p.dy,
+        }),
+        Chunk::Eos => Chunk::Eos,
+        Chunk::TickSkip { dt } => Chunk::TickSkip { dt },
+        Chunk::PlayerNew(ref p) => Chunk::PlayerNew(PlayerNew {
+            cid: map(p.cid),
+            x: p.x,
+            y: p.y,
+        }),
+        Chunk::PlayerOld { cid } => Chunk::PlayerOld { cid: map(cid) },
+        Chunk::InputDiff(ref i) => Chunk::InputDiff(InputDiff {
+            cid: map(i.cid),
+            dinput: i.dinput,
+        }),
+        Chunk::InputNew(ref i) => Chunk::InputNew(InputNew {
+            cid: map(i.cid),
+            input: i.input,
+        }),
+        Chunk::NetMessage(ref m) => Chunk::NetMessage(NetMessage {
+            cid: map(m.cid),
+            msg: m.msg,
+        }),
+        Chunk::Join { cid } => Chunk::Join { cid: map(cid) },
+        Chunk::Drop(ref d) => Chunk::Drop(Drop {
+            cid: map(d.cid),
+            reason: d.reason,
+        }),
+        Chunk::ConsoleCommand(ref c) => Chunk::ConsoleCommand(ConsoleCommand {
+            cid: map(c.cid),
+            flags: c.flags,
+            cmd: c.cmd,
+            args: c.args.clone(),
+        }),
+        Chunk::UnknownEx(ref u) => Chunk::UnknownEx(UnknownEx {
+            uuid: u.uuid,
+            data: u.data,
+        }),
+        Chunk::Test => Chunk::Test,
+        Chunk::DdnetVersionOld(ref v) => Chunk::DdnetVersionOld(DdnetVersionOld {
+            cid: map(v.cid),
+            version: v.version,
+        }),
+        Chunk::DdnetVersion(ref v) => Chunk::DdnetVersion(DdnetVersion {
+            cid: map(v.cid),
+            connection_id: v.connection_id,
+            version: v.version,
+            version_str: v.version_str,
+        }),
+        Chunk::AuthInit(ref a) => Chunk::AuthInit(Auth {
+            cid: map(a.cid),
+            level: a.level,
+            auth_name: a.auth_name,
+        }),
+        Chunk::AuthLogin(ref a) => Chunk::AuthLogin(Auth {
+            cid: map(a.cid),
+            level: a.level,
+            auth_name: a.auth_name,
+        }),
+        Chunk::AuthLogout { cid } => Chunk::AuthLogout { cid: map(cid) },
+        Chunk::JoinVer6 { cid } => Chunk::JoinVer6 { cid: map(cid) },
+        Chunk::JoinVer7 { cid } => Chunk::JoinVer7 { cid: map(cid) },
+        Chunk::RejoinVer6 { cid } => Chunk::RejoinVer6 { cid: map(cid) },
+        Chunk::TeamSaveSuccess(ref t) => Chunk::TeamSaveSuccess(TeamSave {
+            team: t.team,
+            save_id: t.save_id,
+            save: t.save,
+        }),
+        Chunk::TeamSaveFailure { team } => Chunk::TeamSaveFailure { team },
+        Chunk::TeamLoadSuccess(ref t) => Chunk::TeamLoadSuccess(TeamSave {
+            team: t.team,
+            save_id: t.save_id,
+            save: t.save,
+        }),
+        Chunk::TeamLoadFailure { team } => Chunk::TeamLoadFailure { team },
+        Chunk::PlayerTeam { cid, team } => Chunk::PlayerTeam {
+            cid: map(cid),
+            team,
+        },
+        Chunk::TeamPractice { team, practice } => Chunk::TeamPractice { team, practice },
+        Chunk::PlayerReady { cid } => Chunk::PlayerReady { cid: map(cid) },
+        Chunk::PlayerSwap { cid1, cid2 } => Chunk::PlayerSwap {
+            cid1: map(cid1),
+            cid2: map(cid2),
+        },
+        Chunk::Antibot(ref a) => Chunk::Antibot(Antibot { data: a.data }),
+        Chunk::PlayerName(ref p) => Chunk::PlayerName(PlayerName {
+            cid: map(p.cid),
+            name: p.name,
+        }),
+        Chunk::PlayerFinish { cid, time } => Chunk::PlayerFinish {
+            cid: map(cid),
+            time,
+        },
+        Chunk::TeamFinish { team, time } => Chunk::TeamFinish { team, time },
     }
 }
 
@@ -89,17 +215,102 @@ define_inline_chunk! {
 }
 
 define_inline_chunk! {
-    /// Player rejoins with version 6 protocol
-    RejoinVer6 {
+    /// Player re-enters the game world after a map change
+    ///
+    /// Written as the `teehistorian-rejoinver6@ddnet.org` extension chunk.
+    PlayerRejoin(RejoinVer6) {
         client_id: i32 => cid,
     }
 }
 
-define_chunk! {
-    /// Player disconnects from server
-    Drop(Drop) {
-        client_id: i32 => cid,
-        reason: String => reason,
+/// Player disconnects from server
+/// Category: PlayerLifecycle
+#[pyclass(module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyDrop {
+    #[pyo3(get)]
+    pub client_id: i32,
+    /// Reason as text, invalid UTF-8 replaced with U+FFFD
+    #[pyo3(get)]
+    pub reason: String,
+    // Kick reasons set by mods may be any bytes, kept as-is for writing
+    reason_bytes: Vec<u8>,
+}
+
+/// A reason given as text or as raw bytes
+#[derive(FromPyObject)]
+pub enum ReasonArg {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl PyDrop {
+    pub fn new(client_id: i32, reason_bytes: Vec<u8>) -> Self {
+        Self {
+            client_id,
+            reason: String::from_utf8_lossy(&reason_bytes).into_owned(),
+            reason_bytes,
+        }
+    }
+}
+
+impl TeehistorianChunk for PyDrop {
+    fn to_teehistorian_chunk(&self) -> Chunk<'_> {
+        Chunk::Drop(teehistorian::chunks::Drop {
+            cid: self.client_id,
+            reason: &self.reason_bytes,
+        })
+    }
+}
+
+#[pymethods]
+impl PyDrop {
+    #[new]
+    fn py_new(client_id: i32, reason: ReasonArg) -> Self {
+        let reason = match reason {
+            ReasonArg::Text(text) => text.into_bytes(),
+            ReasonArg::Bytes(bytes) => bytes,
+        };
+        Self::new(client_id, reason)
+    }
+
+    /// The reason exactly as recorded
+    #[getter]
+    fn reason_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.reason_bytes)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PyDrop {{ client_id: {}, reason: {:?} }}",
+            self.client_id, self.reason
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn chunk_type(&self) -> &'static str {
+        self.py_chunk_type()
+    }
+
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("type", self.chunk_type())?;
+        dict.set_item("client_id", self.client_id)?;
+        dict.set_item("reason", &self.reason)?;
+        Ok(dict.into())
+    }
+
+    fn write_to_buffer(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        self.py_write_to_buffer(py)
     }
 }
 
@@ -149,6 +360,12 @@ impl PyPlayerReady {
         "PlayerReady"
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
@@ -227,6 +444,12 @@ impl PyPlayerTeam {
         "PlayerTeam"
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
@@ -257,14 +480,114 @@ define_chunk! {
     }
 }
 
+/// Absolute player position, produced by `resolve_positions=True`
+///
+/// Written as a `PlayerNew` chunk.
+/// Category: PlayerState
+#[pyclass(module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyPlayerPosition {
+    #[pyo3(get)]
+    pub client_id: i32,
+    #[pyo3(get)]
+    pub tick: i32,
+    #[pyo3(get)]
+    pub x: i32,
+    #[pyo3(get)]
+    pub y: i32,
+}
+
+impl PyPlayerPosition {
+    pub fn new(client_id: i32, tick: i32, x: i32, y: i32) -> Self {
+        Self {
+            client_id,
+            tick,
+            x,
+            y,
+        }
+    }
+}
+
+impl TeehistorianChunk for PyPlayerPosition {
+    fn to_teehistorian_chunk(&self) -> Chunk<'_> {
+        Chunk::PlayerNew(teehistorian::chunks::PlayerNew {
+            cid: self.client_id,
+            x: self.x,
+            y: self.y,
+        })
+    }
+}
+
+#[pymethods]
+impl PyPlayerPosition {
+    #[new]
+    fn py_new(client_id: i32, tick: i32, x: i32, y: i32) -> Self {
+        Self::new(client_id, tick, x, y)
+    }
+
+    fn __repr__(&self) -> String {
+        self.py_repr()
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn chunk_type(&self) -> &'static str {
+        self.py_chunk_type()
+    }
+
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("type", self.chunk_type())?;
+        dict.set_item("client_id", self.client_id)?;
+        dict.set_item("tick", self.tick)?;
+        dict.set_item("x", self.x)?;
+        dict.set_item("y", self.y)?;
+        Ok(dict.into())
+    }
+
+    fn write_to_buffer(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        self.py_write_to_buffer(py)
+    }
+}
+
 define_inline_chunk! {
     /// Player finished a race
+    ///
+    /// Written as the `teehistorian-player-finish@ddnet.org` extension chunk.
+    /// `time` is the race time in ticks.
     PlayerFinish {
         client_id: i32 => cid,
         time: i32 => time,
     }
 }
 
+define_inline_chunk! {
+    /// Team finished a race
+    ///
+    /// Written as the `teehistorian-team-finish@ddnet.org` extension chunk.
+    /// `time` is the race time in ticks.
+    TeamFinish {
+        team: i32 => team,
+        time: i32 => time,
+    }
+}
+
+define_inline_chunk! {
+    /// Two players swapped their positions in the race (`/swap`)
+    PlayerSwap {
+        client_id1: i32 => cid1,
+        client_id2: i32 => cid2,
+    }
+}
+
 // Input Chunks - These have fixed-size arrays [i32; 10]
 // ----------------------------------------------------------------------------
 
@@ -305,6 +628,12 @@ impl PyInputNew {
         Self::new(client_id, input)
     }
 
+    /// The input with named fields
+    #[getter]
+    fn player_input(&self) -> PyPlayerInput {
+        PyPlayerInput::from_slice(&self.input)
+    }
+
     fn __repr__(&self) -> String {
         self.py_repr()
     }
@@ -317,6 +646,12 @@ impl PyInputNew {
         self.py_chunk_type()
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
@@ -367,6 +702,12 @@ impl PyInputDiff {
         Self::new(client_id, input)
     }
 
+    /// The input with named fields
+    #[getter]
+    fn player_input(&self) -> PyPlayerInput {
+        PyPlayerInput::from_slice(&self.input)
+    }
+
     fn __repr__(&self) -> String {
         self.py_repr()
     }
@@ -379,6 +720,12 @@ impl PyInputDiff {
         self.py_chunk_type()
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
@@ -395,11 +742,129 @@ impl PyInputDiff {
 // Communication Chunks
 // ----------------------------------------------------------------------------
 
-define_chunk_custom! {
-    /// Network message from/to player
-    NetMessage(NetMessage) {
-        client_id: i32 => cid,
-        msg: Vec<u8> => msg [as_slice],
+/// Network message from/to player
+#[pyclass(module = "teehistorian_py", frozen)]
+#[derive(Clone)]
+pub struct PyNetMessage {
+    #[pyo3(get)]
+    pub client_id: i32,
+    #[pyo3(get)]
+    pub msg: Vec<u8>,
+    /// Protocol of the sender, as far as the parser knows it
+    pub protocol: NetVersion,
+}
+
+// `protocol` is context from the parser and stays out of the repr
+impl std::fmt::Debug for PyNetMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PyNetMessage")
+            .field("client_id", &self.client_id)
+            .field("msg", &self.msg)
+            .finish()
+    }
+}
+
+impl PyNetMessage {
+    pub fn new(client_id: i32, msg: Vec<u8>) -> Self {
+        Self {
+            client_id,
+            msg,
+            protocol: NetVersion::Unknown,
+        }
+    }
+
+    /// Message of a client known to use `protocol`
+    pub fn with_protocol(mut self, protocol: NetVersion) -> Self {
+        self.protocol = protocol;
+        self
+    }
+}
+
+impl TeehistorianChunk for PyNetMessage {
+    fn to_teehistorian_chunk(&self) -> Chunk<'_> {
+        Chunk::NetMessage(teehistorian::chunks::NetMessage {
+            cid: self.client_id,
+            msg: self.msg.as_slice(),
+        })
+    }
+}
+
+#[pymethods]
+impl PyNetMessage {
+    #[new]
+    fn py_new(client_id: i32, msg: Vec<u8>) -> Self {
+        Self::new(client_id, msg)
+    }
+
+    /// Chat message as sent by a DDNet client
+    ///
+    /// `NetMessage.chat(0, "hi").decode()` returns the `ChatMessage` again.
+    #[staticmethod]
+    #[pyo3(signature = (client_id, message, team=false))]
+    fn chat(client_id: i32, message: &str, team: bool) -> Self {
+        Self::new(
+            client_id,
+            crate::net_msg::encode_chat_message(message, team),
+        )
+    }
+
+    /// Protocol of the sender, `"0.6"` or `"0.7"`
+    ///
+    /// Known for messages read by a parser that saw the client's
+    /// `JoinVer6`/`JoinVer7`, None otherwise.
+    #[getter]
+    fn protocol(&self) -> Option<&'static str> {
+        self.protocol.name()
+    }
+
+    /// Decode the packed payload into a typed game message
+    ///
+    /// `protocol` (`"0.6"` or `"0.7"`) overrides the sender's protocol. A
+    /// message of an unknown protocol is tried as 0.6 first. Returns None if
+    /// the message cannot be parsed or is not one of the supported message
+    /// types.
+    #[pyo3(signature = (protocol=None))]
+    fn decode(&self, py: Python<'_>, protocol: Option<&str>) -> PyResult<Option<Py<PyAny>>> {
+        let version = match protocol {
+            Some(name) => NetVersion::from_name(name).ok_or_else(|| {
+                TeehistorianParseError::Validation(format!(
+                    "Unknown protocol {:?}, expected \"0.6\" or \"0.7\"",
+                    name
+                ))
+            })?,
+            None => self.protocol,
+        };
+        crate::game_msg::decode(py, &self.msg, version)
+    }
+
+    fn __repr__(&self) -> String {
+        self.py_repr()
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn chunk_type(&self) -> &'static str {
+        self.py_chunk_type()
+    }
+
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("type", self.chunk_type())?;
+        dict.set_item("client_id", self.client_id)?;
+        dict.set_item("msg", &self.msg)?;
+        Ok(dict.into())
+    }
+
+    fn write_to_buffer(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        self.py_write_to_buffer(py)
     }
 }
 
@@ -561,6 +1026,12 @@ impl PyNetMessagePlayerInfo {
         "NetMessagePlayerInfo"
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
@@ -591,6 +1062,15 @@ define_chunk_custom! {
 // Authentication & Version Chunks
 // ----------------------------------------------------------------------------
 
+define_chunk_custom! {
+    /// Player already logged in when the recording started
+    AuthInit(AuthInit::Auth) {
+        client_id: i32 => cid,
+        level: i32 => level,
+        auth_name: String => auth_name [as_bytes],
+    }
+}
+
 define_chunk_custom! {
     /// Player authentication/login
     AuthLogin(AuthLogin::Auth) {
@@ -600,6 +1080,13 @@ define_chunk_custom! {
     }
 }
 
+define_inline_chunk! {
+    /// Player logged out of their account
+    AuthLogout {
+        client_id: i32 => cid,
+    }
+}
+
 define_chunk_custom! {
     /// DDNet client version information
     DdnetVersion(DdnetVersion) {
@@ -654,6 +1141,12 @@ impl PyDdnetVersionOld {
         "DdnetVersionOld"
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
@@ -694,9 +1187,108 @@ define_inline_chunk! {
 }
 
 define_chunk_custom! {
-    /// Anti-bot system event
-    AntiBot(Antibot) {
-        data: String => data [as_bytes],
+    /// Team saved successfully
+    TeamSaveSuccess(TeamSaveSuccess::TeamSave) {
+        team: i32 => team,
+        save_id: String => save_id [as_uuid],
+        save: String => save [as_bytes],
+    }
+}
+
+define_inline_chunk! {
+    /// Team save failed
+    TeamSaveFailure {
+        team: i32 => team,
+    }
+}
+
+define_inline_chunk! {
+    /// Team practice mode changed, `practice` is 1 when enabled
+    TeamPractice {
+        team: i32 => team,
+        practice: i32 => practice,
+    }
+}
+
+/// Anti-bot system event
+#[pyclass(module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyAntiBot {
+    /// Payload as text, invalid UTF-8 replaced with U+FFFD
+    #[pyo3(get)]
+    pub data: String,
+    // Antibot modules may record any bytes, kept as-is for writing
+    data_bytes: Vec<u8>,
+}
+
+impl PyAntiBot {
+    pub fn new(data_bytes: Vec<u8>) -> Self {
+        Self {
+            data: String::from_utf8_lossy(&data_bytes).into_owned(),
+            data_bytes,
+        }
+    }
+}
+
+impl TeehistorianChunk for PyAntiBot {
+    fn to_teehistorian_chunk(&self) -> Chunk<'_> {
+        Chunk::Antibot(teehistorian::chunks::Antibot {
+            data: &self.data_bytes,
+        })
+    }
+}
+
+#[pymethods]
+impl PyAntiBot {
+    #[new]
+    fn py_new(data: ReasonArg) -> Self {
+        let data = match data {
+            ReasonArg::Text(text) => text.into_bytes(),
+            ReasonArg::Bytes(bytes) => bytes,
+        };
+        Self::new(data)
+    }
+
+    /// The payload exactly as recorded
+    #[getter]
+    fn data_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.data_bytes)
+    }
+
+    /// Split the payload into event name and parameters
+    ///
+    /// Payloads that are not text only carry the raw bytes.
+    fn decode(&self) -> PyAntibotEvent {
+        PyAntibotEvent::decode(&self.data_bytes)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PyAntiBot {{ data: {:?} }}", self.data)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn chunk_type(&self) -> &'static str {
+        self.py_chunk_type()
+    }
+
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("type", self.chunk_type())?;
+        dict.set_item("data", &self.data)?;
+        Ok(dict.into())
+    }
+
+    fn write_to_buffer(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        self.py_write_to_buffer(py)
     }
 }
 
@@ -708,6 +1300,11 @@ define_zero_field_chunk! {
     Eos(Eos)
 }
 
+define_zero_field_chunk! {
+    /// Test chunk written by DDNet's teehistorian self-test
+    TestChunk(Test)
+}
+
 // ----------------------------------------------------------------------------
 // Special Chunks with Custom Implementations
 // ----------------------------------------------------------------------------
@@ -765,6 +1362,12 @@ impl PyUnknown {
         self.py_chunk_type()
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
@@ -777,6 +1380,33 @@ impl PyUnknown {
         self.py_write_to_buffer(py)
     }
 
+    /// Name of the extension if the UUID is a known one, e.g.
+    /// "teehistorian-player-name@ddnet.org"
+    #[getter]
+    fn uuid_name(&self) -> Option<&'static str> {
+        let uuid = uuid::Uuid::parse_str(&self.uuid).ok()?;
+        crate::extensions::lookup(&uuid).map(|ext| ext.name)
+    }
+
+    /// Decode the payload with the decoder of the known extension
+    ///
+    /// Returns None if the UUID is unknown or the payload is invalid.
+    fn decode(&self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        if self.uuid_name().is_none() {
+            return Ok(None);
+        }
+        let data = TeehistorianChunk::write_to_buffer(self)?;
+        let Ok((_, chunk)) = teehistorian::chunks::chunk(&data) else {
+            return Ok(None);
+        };
+        let handlers = std::sync::Arc::new(std::collections::HashMap::new());
+        let converter = crate::handlers::ChunkConverter::new(&handlers);
+        if converter.type_name(&chunk) == "Unknown" {
+            return Ok(None);
+        }
+        converter.convert(py, chunk, 0).map(Some)
+    }
+
     fn data_preview(&self) -> String {
         let preview_len = self.data.len().min(32);
         let hex: String = self.data[..preview_len]
@@ -802,6 +1432,8 @@ pub struct PyCustomChunk {
     pub data: Vec<u8>,
     #[pyo3(get)]
     pub handler_name: String,
+    /// Payload decoded by the field list the UUID was registered with
+    pub fields: Option<Vec<(String, FieldValue)>>,
 }
 
 impl PyCustomChunk {
@@ -810,8 +1442,27 @@ impl PyCustomChunk {
             uuid,
             data,
             handler_name,
+            fields: None,
         }
     }
+
+    pub fn with_fields(mut self, fields: Vec<(String, FieldValue)>) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    /// Read one value at `offset` of the payload, with the offset after it
+    fn read_at(&self, format: FieldFormat, offset: usize) -> PyResult<(FieldValue, usize)> {
+        format.read_at(&self.data, offset).ok_or_else(|| {
+            TeehistorianParseError::Validation(format!(
+                "No {:?} value at offset {} of the {}-byte payload",
+                format,
+                offset,
+                self.data.len()
+            ))
+            .into()
+        })
+    }
 }
 
 impl TeehistorianChunk for PyCustomChunk {
@@ -839,31 +1490,114 @@ impl PyCustomChunk {
         Ok(Self::new(uuid, data, handler_name))
     }
 
-    fn __repr__(&self) -> String {
-        self.py_repr()
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "PyCustomChunk {{ uuid: {:?}, data: {:?}, handler_name: {:?}, fields: {} }}",
+            self.uuid,
+            self.data,
+            self.handler_name,
+            self.fields(py)?.into_pyobject(py)?.repr()?
+        ))
     }
 
-    fn __str__(&self) -> String {
-        self.__repr__()
+    fn __str__(&self, py: Python<'_>) -> PyResult<String> {
+        self.__repr__(py)
     }
 
     fn chunk_type(&self) -> &'static str {
         self.py_chunk_type()
     }
 
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
         dict.set_item("uuid", &self.uuid)?;
         dict.set_item("data", &self.data)?;
         dict.set_item("handler_name", &self.handler_name)?;
+        for (name, value) in self.fields.iter().flatten() {
+            dict.set_item(name, value.clone())?;
+        }
         Ok(dict.into())
     }
 
+    /// Decoded fields in registration order, None without a field list or
+    /// if the payload did not match it
+    #[getter]
+    fn fields<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let Some(fields) = &self.fields else {
+            return Ok(None);
+        };
+        let dict = PyDict::new(py);
+        for (name, value) in fields {
+            dict.set_item(name, value.clone())?;
+        }
+        Ok(Some(dict))
+    }
+
+    fn __getattr__(&self, py: Python<'_>, name: &str) -> PyResult<Py<PyAny>> {
+        self.fields
+            .iter()
+            .flatten()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.clone().into_pyobject(py).map(|v| v.unbind()))
+            .unwrap_or_else(|| {
+                Err(pyo3::exceptions::PyAttributeError::new_err(format!(
+                    "'CustomChunk' object has no attribute '{}'",
+                    name
+                )))
+            })
+    }
+
     fn write_to_buffer(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         self.py_write_to_buffer(py)
     }
 
+    /// Read a teeworlds varint at `offset` of the payload
+    ///
+    /// Returns the value and the offset after it, so reads can be chained.
+    #[pyo3(signature = (offset=0))]
+    fn read_int(&self, offset: usize) -> PyResult<(FieldValue, usize)> {
+        self.read_at(FieldFormat::Varint, offset)
+    }
+
+    /// Read a NUL-terminated string at `offset` of the payload
+    ///
+    /// Returns the string and the offset after its terminator.
+    #[pyo3(signature = (offset=0))]
+    fn read_string(&self, offset: usize) -> PyResult<(FieldValue, usize)> {
+        self.read_at(FieldFormat::String, offset)
+    }
+
+    /// Decode the whole payload as one value per format name
+    ///
+    /// Takes the formats of `register_custom_uuid(fields=...)`, e.g.
+    /// `["int", "int", "str"]`, and fails unless they cover the payload
+    /// exactly.
+    fn unpack(&self, spec: Vec<String>) -> PyResult<Vec<FieldValue>> {
+        let formats = spec
+            .iter()
+            .map(|name| {
+                FieldFormat::from_name(name).ok_or_else(|| {
+                    TeehistorianParseError::Validation(format!("Unknown field format '{}'", name))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        FieldFormat::read_all(&formats, &self.data).ok_or_else(|| {
+            TeehistorianParseError::Validation(format!(
+                "Payload of {} bytes does not match {:?}",
+                self.data.len(),
+                spec
+            ))
+            .into()
+        })
+    }
+
     fn data_preview(&self) -> String {
         let preview_len = self.data.len().min(32);
         let hex: String = self.data[..preview_len]
@@ -879,39 +1613,129 @@ impl PyCustomChunk {
     }
 }
 
-/// Generic/fallback chunk type
-#[pyclass(name = "Generic", module = "teehistorian_py", frozen)]
+/// Chunk kept as its encoded bytes
+///
+/// `type_id` is the chunk tag: the client id for position diffs, a negative
+/// built-in tag, or -11 for extension chunks whose payload starts with the
+/// UUID. Writing it emits the tag followed by the payload unchanged.
+/// Category: Special
+#[pyclass(name = "RawChunk", module = "teehistorian_py", frozen)]
 #[derive(Debug, Clone)]
-pub struct PyGeneric {
+pub struct PyRawChunk {
     #[pyo3(get)]
-    pub data: String,
+    pub type_id: i32,
+    /// Everything after the tag
+    #[pyo3(get)]
+    pub payload: Vec<u8>,
 }
 
-impl PyGeneric {
-    pub fn new(data: String) -> Self {
-        Self { data }
+impl PyRawChunk {
+    pub fn new(type_id: i32, payload: Vec<u8>) -> Self {
+        Self { type_id, payload }
+    }
+
+    /// Split one encoded chunk into tag and payload
+    pub fn from_encoded(data: &[u8]) -> crate::errors::Result<Self> {
+        let invalid = |msg: String| crate::errors::TeehistorianParseError::Parse(msg);
+        let (_, end) = crate::scan::scan_chunk(data, 0)
+            .map_err(|e| invalid(format!("invalid chunk: {}", e)))?
+            .ok_or_else(|| invalid("chunk is truncated".to_string()))?;
+        if end != data.len() {
+            return Err(invalid(format!(
+                "{} bytes after the end of the chunk",
+                data.len() - end
+            )));
+        }
+        let (type_id, tag_len) =
+            crate::scan::read_tag(data).ok_or_else(|| invalid("chunk is truncated".to_string()))?;
+        Ok(Self::new(type_id, data[tag_len..].to_vec()))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        // Packed ints take at most 5 bytes
+        let mut buf = Vec::with_capacity(5 + self.payload.len());
+        libtw2_packer::with_packer(&mut buf, |mut p| p.write_int(self.type_id))
+            .expect("buffer has room for the tag");
+        buf.extend_from_slice(&self.payload);
+        buf
     }
 }
 
-impl TeehistorianChunk for PyGeneric {
-    fn to_teehistorian_chunk(&self) -> Chunk<'_> {
-        // Generic chunks use NetMessage as fallback
-        Chunk::NetMessage(teehistorian::chunks::NetMessage {
-            cid: -1,
-            msg: self.data.as_bytes(),
-        })
+#[pymethods]
+impl PyRawChunk {
+    #[new]
+    fn py_new(type_id: i32, payload: Vec<u8>) -> Self {
+        Self::new(type_id, payload)
+    }
+
+    /// Build from the bytes of exactly one chunk, e.g. `Teehistorian.raw()`
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        Ok(Self::from_encoded(data)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn chunk_type(&self) -> &'static str {
+        "RawChunk"
+    }
+
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("type", self.chunk_type())?;
+        dict.set_item("type_id", self.type_id)?;
+        dict.set_item("payload", PyBytes::new(py, &self.payload))?;
+        Ok(dict.into())
+    }
+
+    fn write_to_buffer(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(PyBytes::new(py, &self.encode()).into())
+    }
+}
+
+/// Start of another teehistorian stream concatenated after the previous one
+///
+/// Yielded when a new header follows the previous stream's end. Writing it
+/// emits that header again, so concatenated files round-trip.
+#[pyclass(name = "SegmentStart", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone)]
+pub struct PySegmentStart {
+    /// Byte offset of the stream's magic UUID
+    #[pyo3(get)]
+    pub offset: usize,
+    /// JSON header of the new stream
+    #[pyo3(get)]
+    pub header: String,
+}
+
+impl PySegmentStart {
+    pub fn new(offset: usize, header: String) -> Self {
+        Self { offset, header }
     }
 }
 
 #[pymethods]
-impl PyGeneric {
+impl PySegmentStart {
     #[new]
-    fn py_new(data: String) -> Self {
-        Self::new(data)
+    #[pyo3(signature = (header, offset=0))]
+    fn py_new(header: String, offset: usize) -> Self {
+        Self::new(offset, header)
     }
 
     fn __repr__(&self) -> String {
-        self.py_repr()
+        format!("{:?}", self)
     }
 
     fn __str__(&self) -> String {
@@ -919,17 +1743,28 @@ impl PyGeneric {
     }
 
     fn chunk_type(&self) -> &'static str {
-        self.py_chunk_type()
+        "SegmentStart"
+    }
+
+    /// Category of the chunk type, e.g. "PlayerLifecycle"
+    #[getter]
+    fn category(&self) -> &'static str {
+        crate::handlers::chunk_category(self.chunk_type())
     }
 
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
         let dict = pyo3::types::PyDict::new(py);
         dict.set_item("type", self.chunk_type())?;
-        dict.set_item("data", &self.data)?;
+        dict.set_item("offset", self.offset)?;
+        dict.set_item("header", &self.header)?;
         Ok(dict.into())
     }
 
     fn write_to_buffer(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
-        self.py_write_to_buffer(py)
+        let mut buf = Vec::with_capacity(crate::MAGIC.len() + self.header.len() + 1);
+        buf.extend_from_slice(&crate::MAGIC);
+        buf.extend_from_slice(self.header.as_bytes());
+        buf.push(0);
+        Ok(PyBytes::new(py, &buf).into())
     }
 }
@@ -0,0 +1,371 @@
+//! Semantic comparison of two recordings
+//!
+//! Recordings that mean the same can differ in their bytes: one writer
+//! delta-encodes positions and inputs, another writes them in full, and
+//! `TickSkip` chunks only encode ticks. Chunks are therefore compared by
+//! their tick and absolute value.
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::Value;
+use teehistorian::Chunk;
+use teehistorian::chunks::{InputNew, PlayerNew};
+
+use crate::chunks::PySegmentStart;
+use crate::errors::TeehistorianParseError;
+use crate::handlers::ChunkConverter;
+use crate::header::{PyHeader, json_to_py};
+use crate::net_msg::ClientVersions;
+use crate::resolve::DeltaEncoder;
+use crate::ticks::TickTracker;
+use crate::{MAGIC, PyTeehistorian, TeehistorianParserInner};
+
+/// Item of a recording as it is compared
+#[derive(Debug)]
+enum Entry<'a> {
+    /// Header of a concatenated stream
+    Segment { offset: usize, header: &'a [u8] },
+    Chunk {
+        offset: usize,
+        tick: i32,
+        chunk: Chunk<'a>,
+        /// Absolute value of a diff
+        absolute: Option<Chunk<'static>>,
+    },
+}
+
+impl Entry<'_> {
+    /// Whether two entries have the same tick and absolute value
+    fn means_same(&self, other: &Entry) -> bool {
+        match (self, other) {
+            (Entry::Segment { header: a, .. }, Entry::Segment { header: b, .. }) => a == b,
+            (
+                Entry::Chunk {
+                    tick: tick_a,
+                    chunk: a,
+                    absolute: absolute_a,
+                    ..
+                },
+                Entry::Chunk {
+                    tick: tick_b,
+                    chunk: b,
+                    absolute: absolute_b,
+                    ..
+                },
+            ) => {
+                tick_a == tick_b
+                    && absolute_a.as_ref().unwrap_or(a) == absolute_b.as_ref().unwrap_or(b)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Position in a recording being compared
+struct Cursor<'a> {
+    data: &'a [u8],
+    offset: usize,
+    ticks: TickTracker,
+    values: DeltaEncoder,
+    /// Protocol of every client, to tell the types of its messages
+    versions: ClientVersions,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(parser: &'a PyTeehistorian) -> Self {
+        Self {
+            data: &parser.inner.data,
+            offset: parser.inner.chunks_start,
+            ticks: TickTracker::new(),
+            values: DeltaEncoder::new(),
+            versions: ClientVersions::default(),
+        }
+    }
+
+    /// Next entry, None at the end
+    fn next(&mut self) -> PyResult<Option<Entry<'a>>> {
+        loop {
+            let start = self.offset;
+            let segment = TeehistorianParserInner::segment_at(self.data, start).map_err(|e| {
+                TeehistorianParseError::Parse(format!("Invalid concatenated stream header: {}", e))
+            })?;
+            if let Some(end) = segment {
+                self.offset = end;
+                self.ticks.restart();
+                self.values = DeltaEncoder::new();
+                self.versions = ClientVersions::default();
+                let header = &self.data[start + MAGIC.len()..end - 1];
+                return Ok(Some(Entry::Segment {
+                    offset: start,
+                    header,
+                }));
+            }
+
+            let decoded = TeehistorianParserInner::decode_at(self.data, start).map_err(|e| {
+                TeehistorianParseError::Parse(format!(
+                    "Failed to parse chunk at offset {}: {}",
+                    start, e
+                ))
+            })?;
+            let Some((chunk, end)) = decoded else {
+                return Ok(None);
+            };
+            self.offset = end;
+            let tick = self.ticks.observe(&chunk);
+            if matches!(chunk, Chunk::TickSkip { .. }) {
+                continue;
+            }
+            self.values.observe(&chunk);
+            self.versions.observe(&chunk);
+            let absolute = match chunk {
+                Chunk::PlayerDiff(ref diff) => {
+                    let [x, y] = self.values.position(diff.cid).unwrap_or_default();
+                    Some(Chunk::PlayerNew(PlayerNew {
+                        cid: diff.cid,
+                        x,
+                        y,
+                    }))
+                }
+                Chunk::InputDiff(ref diff) => Some(Chunk::InputNew(InputNew {
+                    cid: diff.cid,
+                    input: self.values.input(diff.cid).copied().unwrap_or_default(),
+                })),
+                _ => None,
+            };
+            return Ok(Some(Entry::Chunk {
+                offset: start,
+                tick,
+                chunk,
+                absolute,
+            }));
+        }
+    }
+}
+
+/// Chunk objects of `a` and `b`
+type ChunkPair = (Option<Py<PyAny>>, Option<Py<PyAny>>);
+
+/// Where two recordings stop meaning the same
+#[derive(Debug)]
+struct Divergence {
+    index: usize,
+    offsets: [Option<usize>; 2],
+    ticks: [Option<i32>; 2],
+    chunks: [Option<Py<PyAny>>; 2],
+}
+
+/// Result of `diff()`
+#[pyclass(name = "RecordingDiff", module = "teehistorian_py", frozen)]
+#[derive(Debug)]
+pub struct PyRecordingDiff {
+    header: BTreeMap<String, [Value; 2]>,
+    counts: BTreeMap<&'static str, i64>,
+    divergence: Option<Divergence>,
+}
+
+#[pymethods]
+impl PyRecordingDiff {
+    /// Whether the headers and all chunks mean the same
+    #[getter]
+    fn identical(&self) -> bool {
+        self.header.is_empty() && self.divergence.is_none()
+    }
+
+    /// Header fields that differ, mapped to an `(a, b)` tuple
+    ///
+    /// A field missing from one header is None on that side.
+    #[getter]
+    fn header<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, [a, b]) in &self.header {
+            dict.set_item(key, (json_to_py(py, a)?, json_to_py(py, b)?))?;
+        }
+        Ok(dict)
+    }
+
+    /// Number of chunks per `chunk_type()` name in `b` minus those in `a`
+    ///
+    /// Only types whose count differs are included. Chunks are counted as
+    /// written, so diffs and absolute values count as different types.
+    #[getter]
+    fn chunk_counts(&self) -> BTreeMap<&'static str, i64> {
+        self.counts.clone()
+    }
+
+    /// Index of the first chunk that differs, not counting `TickSkip`
+    #[getter]
+    fn first_index(&self) -> Option<usize> {
+        self.divergence.as_ref().map(|d| d.index)
+    }
+
+    /// Byte offsets of the first differing chunk in `a` and `b`
+    ///
+    /// An offset is None if that recording ended before.
+    #[getter]
+    fn offsets(&self) -> Option<(Option<usize>, Option<usize>)> {
+        self.divergence
+            .as_ref()
+            .map(|d| (d.offsets[0], d.offsets[1]))
+    }
+
+    /// Ticks of the first differing chunk in `a` and `b`
+    #[getter]
+    fn ticks(&self) -> Option<(Option<i32>, Option<i32>)> {
+        self.divergence.as_ref().map(|d| (d.ticks[0], d.ticks[1]))
+    }
+
+    /// The first differing chunk of `a` and `b`, as they were written
+    #[getter]
+    fn chunks(&self, py: Python<'_>) -> Option<ChunkPair> {
+        self.divergence.as_ref().map(|d| {
+            let [a, b] = &d.chunks;
+            (
+                a.as_ref().map(|a| a.clone_ref(py)),
+                b.as_ref().map(|b| b.clone_ref(py)),
+            )
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RecordingDiff(identical={}, header={:?}, first_index={})",
+            self.identical(),
+            self.header.keys().collect::<Vec<_>>(),
+            self.first_index()
+                .map_or("None".to_string(), |index| index.to_string())
+        )
+    }
+}
+
+/// Header fields whose values differ
+fn header_diff(a: &Value, b: &Value) -> BTreeMap<String, [Value; 2]> {
+    let (Value::Object(a), Value::Object(b)) = (a, b) else {
+        return BTreeMap::new();
+    };
+    a.keys()
+        .chain(b.keys())
+        .filter_map(|key| {
+            let values = [
+                a.get(key).cloned().unwrap_or_default(),
+                b.get(key).cloned().unwrap_or_default(),
+            ];
+            (a.get(key) != b.get(key)).then(|| (key.clone(), values))
+        })
+        .collect()
+}
+
+/// Offset, tick and Python object of an entry where the recordings diverge
+type Side = (Option<usize>, Option<i32>, Option<Py<PyAny>>);
+
+fn describe(py: Python<'_>, entry: Option<Entry>, converter: &ChunkConverter) -> PyResult<Side> {
+    match entry {
+        None => Ok((None, None, None)),
+        Some(Entry::Segment { offset, header }) => {
+            let header = String::from_utf8_lossy(header).into_owned();
+            let segment = Py::new(py, PySegmentStart::new(offset, header))?.into_any();
+            Ok((Some(offset), None, Some(segment)))
+        }
+        Some(Entry::Chunk {
+            offset,
+            tick,
+            chunk,
+            ..
+        }) => Ok((
+            Some(offset),
+            Some(tick),
+            Some(converter.convert(py, chunk, tick)?),
+        )),
+    }
+}
+
+/// Compare what two recordings mean
+///
+/// Chunks are compared in order by their tick and absolute value, so
+/// `PlayerDiff`/`InputDiff` match the `PlayerNew`/`InputNew` they result in.
+/// `TickSkip` chunks are not compared or counted.
+///
+/// # Arguments
+/// * `a` - Raw or compressed teehistorian data
+/// * `b` - Raw or compressed teehistorian data to compare with
+///
+/// # Example
+/// ```python
+/// result = th.diff(original, th.transform(original))
+/// assert result.identical, result
+/// ```
+#[pyfunction]
+pub fn diff(py: Python<'_>, a: &[u8], b: &[u8]) -> PyResult<PyRecordingDiff> {
+    let parse = |data| PyTeehistorian::new(data, false, None, false, false, false, "yield");
+    let parsers = [parse(a)?, parse(b)?];
+    let headers = parsers
+        .iter()
+        .map(|parser| Ok(PyHeader::parse(parser.inner.get_header())?.to_json()))
+        .collect::<PyResult<Vec<_>>>()?;
+    let handlers = [
+        Arc::clone(&parsers[0].handlers),
+        Arc::clone(&parsers[1].handlers),
+    ];
+
+    let mut cursors = [Cursor::new(&parsers[0]), Cursor::new(&parsers[1])];
+    let mut counts: BTreeMap<&'static str, i64> = BTreeMap::new();
+    let mut divergence = None;
+    let mut index = 0;
+    loop {
+        let entries = [cursors[0].next()?, cursors[1].next()?];
+        if entries.iter().all(Option::is_none) {
+            break;
+        }
+        let converters = [
+            ChunkConverter::with_versions(&handlers[0], &cursors[0].versions),
+            ChunkConverter::with_versions(&handlers[1], &cursors[1].versions),
+        ];
+        for (sign, (entry, converter)) in [-1, 1].into_iter().zip(entries.iter().zip(&converters)) {
+            let name = match entry {
+                Some(Entry::Chunk { chunk, .. }) => converter.type_name(chunk),
+                Some(Entry::Segment { .. }) => "SegmentStart",
+                None => continue,
+            };
+            *counts.entry(name).or_default() += sign;
+        }
+
+        let same = match &entries {
+            [Some(a), Some(b)] => a.means_same(b),
+            _ => false,
+        };
+        if !same && divergence.is_none() {
+            let [a, b] = entries;
+            let (offset_a, tick_a, chunk_a) = describe(py, a, &converters[0])?;
+            let (offset_b, tick_b, chunk_b) = describe(py, b, &converters[1])?;
+            divergence = Some(Divergence {
+                index,
+                offsets: [offset_a, offset_b],
+                ticks: [tick_a, tick_b],
+                chunks: [chunk_a, chunk_b],
+            });
+        }
+        index += 1;
+    }
+    counts.retain(|_, delta| *delta != 0);
+
+    Ok(PyRecordingDiff {
+        header: header_diff(&headers[0], &headers[1]),
+        counts,
+        divergence,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_diff() {
+        let a = serde_json::json!({"map_name": "Kobra", "version": "2"});
+        let b = serde_json::json!({"map_name": "Kobra", "map_crc": "abc"});
+        let diff = header_diff(&a, &b);
+        assert_eq!(diff.keys().collect::<Vec<_>>(), ["map_crc", "version"]);
+        assert_eq!(diff["version"], [Value::from("2"), Value::Null]);
+    }
+}
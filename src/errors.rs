@@ -1,5 +1,5 @@
 use pyo3::create_exception;
-use pyo3::exceptions::PyException;
+use pyo3::exceptions::{PyException, PyUserWarning};
 use pyo3::prelude::*;
 use thiserror::Error;
 
@@ -38,6 +38,13 @@ create_exception!(
     "Exception for writing errors"
 );
 
+create_exception!(
+    teehistorian_py,
+    TeehistorianWarning,
+    PyUserWarning,
+    "Warning category for non-fatal anomalies found while parsing"
+);
+
 /// Error enum for all possible errors in the library
 #[derive(Debug, Error)]
 pub enum TeehistorianParseError {
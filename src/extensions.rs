@@ -0,0 +1,139 @@
+//! Known teehistorian extension chunks
+//!
+//! Extension chunks are tagged with a UUID derived from their name, e.g.
+//! `teehistorian-player-name@ddnet.org`. The teehistorian crate decodes all
+//! extensions DDNet writes; this table names them, so an extension chunk that
+//! reaches Python undecoded can still be recognized by its UUID.
+use teehistorian::chunks::{
+    TH_ANTIBOT, TH_AUTH_INIT, TH_AUTH_LOGIN, TH_AUTH_LOGOUT, TH_DDNETVER, TH_DDNETVER_OLD,
+    TH_JOINVER6, TH_JOINVER7, TH_LOAD_FAILURE, TH_LOAD_SUCCESS, TH_PLAYER_FINISH, TH_PLAYER_NAME,
+    TH_PLAYER_READY, TH_PLAYER_SWAP, TH_PLAYER_TEAM, TH_REJOINVER6, TH_SAVE_FAILURE,
+    TH_SAVE_SUCCESS, TH_TEAM_FINISH, TH_TEAM_PRACTICE, TH_TEST,
+};
+use uuid::Uuid;
+
+/// Extension chunk with a known UUID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extension {
+    pub uuid: Uuid,
+    /// Name the UUID is derived from
+    pub name: &'static str,
+    /// `chunk_type()` of the decoded chunk
+    pub chunk_type: &'static str,
+}
+
+const fn ext(uuid: Uuid, name: &'static str, chunk_type: &'static str) -> Extension {
+    Extension {
+        uuid,
+        name,
+        chunk_type,
+    }
+}
+
+/// Every known extension, DDNet's own followed by ours
+pub const EXTENSIONS: &[Extension] = &[
+    ext(TH_TEST, "teehistorian-test@ddnet.tw", "TestChunk"),
+    ext(
+        TH_DDNETVER_OLD,
+        "teehistorian-ddnetver-old@ddnet.tw",
+        "DdnetVersionOld",
+    ),
+    ext(
+        TH_DDNETVER,
+        "teehistorian-ddnetver@ddnet.tw",
+        "DdnetVersion",
+    ),
+    ext(TH_AUTH_INIT, "teehistorian-auth-init@ddnet.tw", "AuthInit"),
+    ext(
+        TH_AUTH_LOGIN,
+        "teehistorian-auth-login@ddnet.tw",
+        "AuthLogin",
+    ),
+    ext(
+        TH_AUTH_LOGOUT,
+        "teehistorian-auth-logout@ddnet.tw",
+        "AuthLogout",
+    ),
+    ext(TH_JOINVER6, "teehistorian-joinver6@ddnet.tw", "JoinVer6"),
+    ext(TH_JOINVER7, "teehistorian-joinver7@ddnet.tw", "JoinVer7"),
+    ext(
+        TH_REJOINVER6,
+        "teehistorian-rejoinver6@ddnet.org",
+        "PlayerRejoin",
+    ),
+    ext(
+        TH_SAVE_SUCCESS,
+        "teehistorian-save-success@ddnet.tw",
+        "TeamSaveSuccess",
+    ),
+    ext(
+        TH_SAVE_FAILURE,
+        "teehistorian-save-failure@ddnet.tw",
+        "TeamSaveFailure",
+    ),
+    ext(
+        TH_LOAD_SUCCESS,
+        "teehistorian-load-success@ddnet.tw",
+        "TeamLoadSuccess",
+    ),
+    ext(
+        TH_LOAD_FAILURE,
+        "teehistorian-load-failure@ddnet.tw",
+        "TeamLoadFailure",
+    ),
+    ext(
+        TH_PLAYER_TEAM,
+        "teehistorian-player-team@ddnet.tw",
+        "PlayerTeam",
+    ),
+    ext(
+        TH_TEAM_PRACTICE,
+        "teehistorian-team-practice@ddnet.tw",
+        "TeamPractice",
+    ),
+    ext(
+        TH_PLAYER_READY,
+        "teehistorian-player-ready@ddnet.tw",
+        "PlayerReady",
+    ),
+    ext(
+        TH_PLAYER_SWAP,
+        "teehistorian-player-swap@ddnet.tw",
+        "PlayerSwap",
+    ),
+    ext(TH_ANTIBOT, "teehistorian-antibot@ddnet.org", "AntiBot"),
+    ext(
+        TH_PLAYER_NAME,
+        "teehistorian-player-name@ddnet.org",
+        "PlayerName",
+    ),
+    ext(
+        TH_PLAYER_FINISH,
+        "teehistorian-player-finish@ddnet.org",
+        "PlayerFinish",
+    ),
+    ext(
+        TH_TEAM_FINISH,
+        "teehistorian-team-finish@ddnet.org",
+        "TeamFinish",
+    ),
+];
+
+/// Find the extension a UUID belongs to
+pub fn lookup(uuid: &Uuid) -> Option<&'static Extension> {
+    EXTENSIONS.iter().find(|ext| ext.uuid == *uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        for ext in EXTENSIONS {
+            assert_eq!(lookup(&ext.uuid), Some(ext));
+        }
+        assert_eq!(lookup(&TH_PLAYER_NAME).unwrap().chunk_type, "PlayerName");
+        assert_eq!(lookup(&Uuid::nil()), None);
+    }
+}
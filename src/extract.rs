@@ -0,0 +1,148 @@
+//! Extracting the activity of a single player
+//!
+//! Every chunk about a client carries its client ID, and the delta-encoded
+//! ones only build on chunks of the same client. Keeping all chunks of one
+//! client therefore leaves a recording that replays on its own.
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use teehistorian::Chunk;
+
+use crate::errors::TeehistorianParseError;
+use crate::transform::{Item, Rewriter, rewrite};
+
+/// A client given by its ID or by its name
+#[derive(FromPyObject)]
+pub enum ClientArg {
+    Id(i32),
+    Name(String),
+}
+
+/// Chunks of the sessions to extract
+struct Extractor {
+    client: ClientArg,
+    /// Client ID of the session being extracted
+    active: Option<i32>,
+    /// Encoded chunks with their ticks of every session that did not use
+    /// the name yet
+    pending: HashMap<i32, Vec<(Vec<u8>, i32)>>,
+    /// Tick of the last chunk written
+    last_tick: i32,
+    found: bool,
+}
+
+impl Extractor {
+    fn new(client: ClientArg) -> Self {
+        let active = match client {
+            ClientArg::Id(cid) => Some(cid),
+            ClientArg::Name(_) => None,
+        };
+        Self {
+            client,
+            active,
+            pending: HashMap::new(),
+            last_tick: 0,
+            found: false,
+        }
+    }
+
+    fn write(&mut self, out: &mut Rewriter, encoded: &[u8], tick: i32) -> PyResult<()> {
+        self.found = true;
+        self.last_tick = tick;
+        out.copy(encoded, Some(tick))
+    }
+
+    /// Write the chunk if it belongs to the extracted sessions
+    fn chunk(
+        &mut self,
+        out: &mut Rewriter,
+        chunk: &Chunk,
+        encoded: &[u8],
+        tick: i32,
+    ) -> PyResult<()> {
+        let Some(cid) = chunk.cid() else {
+            return Ok(());
+        };
+        let ClientArg::Name(ref name) = self.client else {
+            return if self.active == Some(cid) {
+                self.write(out, encoded, tick)
+            } else {
+                Ok(())
+            };
+        };
+
+        if matches!(chunk, Chunk::JoinVer6 { .. } | Chunk::JoinVer7 { .. }) {
+            self.pending.insert(cid, Vec::new());
+        }
+        if let Chunk::PlayerName(player) = chunk
+            && player.name == name.as_bytes()
+            && self.active != Some(cid)
+        {
+            let pending = self.pending.remove(&cid).unwrap_or_default();
+            if self.active.is_some() || pending.first().is_some_and(|&(_, t)| t < self.last_tick) {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Several clients are named {:?} at tick {}, pass the client ID instead",
+                    name, tick
+                ))
+                .into());
+            }
+            for (encoded, tick) in pending {
+                self.write(out, &encoded, tick)?;
+            }
+            self.active = Some(cid);
+        }
+
+        if self.active == Some(cid) {
+            self.write(out, encoded, tick)?;
+        } else {
+            self.pending
+                .entry(cid)
+                .or_default()
+                .push((encoded.to_vec(), tick));
+        }
+        if let Chunk::Drop(drop) = chunk {
+            self.pending.remove(&drop.cid);
+            if self.active == Some(drop.cid) {
+                self.active = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forget the clients of the previous stream
+    fn restart(&mut self) {
+        self.pending.clear();
+        if let ClientArg::Name(_) = self.client {
+            self.active = None;
+        }
+    }
+}
+
+/// Extract the chunks of one player into a recording of its own
+#[pyfunction]
+pub fn extract_player<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    cid_or_name: ClientArg,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut extractor = Extractor::new(cid_or_name);
+    let out = rewrite(py, data, |out, item, encoded, _| match item {
+        Item::Header(_) => Ok(()),
+        Item::Segment(_) => {
+            extractor.restart();
+            out.copy(encoded, None)
+        }
+        Item::Chunk(Chunk::Eos, _) => out.copy(encoded, None),
+        Item::Chunk(chunk, tick) => extractor.chunk(out, &chunk, encoded, tick),
+        Item::End => Ok(()),
+    })?;
+    if !extractor.found {
+        let message = match extractor.client {
+            ClientArg::Id(cid) => format!("No chunks of client {}", cid),
+            ClientArg::Name(name) => format!("No client named {:?}", name),
+        };
+        return Err(TeehistorianParseError::Validation(message).into());
+    }
+    Ok(out)
+}
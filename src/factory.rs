@@ -0,0 +1,113 @@
+//! Chunk classes looked up by type name
+//!
+//! Data-driven tools, e.g. converters from JSON back to teehistorian, only
+//! know a chunk by its `chunk_type()` name. The registry maps those names to
+//! the Python classes, so such tools need no dispatch over every class.
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyType};
+
+use crate::chunks::*;
+use crate::errors::TeehistorianParseError;
+
+macro_rules! chunk_classes {
+    ($($name:ident),* $(,)?) => {
+        $crate::macros::paste! {
+            /// Type names of every class in the registry
+            const CLASS_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+            /// Look up the Python class of a chunk type name
+            pub fn chunk_class<'py>(py: Python<'py>, name: &str) -> Option<Bound<'py, PyType>> {
+                match name {
+                    $(stringify!($name) => Some(py.get_type::<[<Py $name>]>()),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+chunk_classes! {
+    Join,
+    JoinVer6,
+    JoinVer7,
+    Drop,
+    PlayerReady,
+    PlayerRejoin,
+    PlayerNew,
+    PlayerOld,
+    PlayerTeam,
+    PlayerName,
+    PlayerDiff,
+    PlayerPosition,
+    PlayerFinish,
+    PlayerSwap,
+    InputNew,
+    InputDiff,
+    NetMessage,
+    NetMessagePlayerInfo,
+    ConsoleCommand,
+    AuthInit,
+    AuthLogin,
+    AuthLogout,
+    DdnetVersion,
+    DdnetVersionOld,
+    TickSkip,
+    TeamSaveSuccess,
+    TeamSaveFailure,
+    TeamLoadSuccess,
+    TeamLoadFailure,
+    TeamPractice,
+    TeamFinish,
+    AntiBot,
+    Eos,
+    TestChunk,
+    Unknown,
+    CustomChunk,
+    RawChunk,
+    SegmentStart,
+}
+
+/// Create a chunk from its type name and constructor arguments
+///
+/// `make_chunk("PlayerNew", client_id=3, x=100, y=200)` is the same as
+/// `PlayerNew(client_id=3, x=100, y=200)`.
+#[pyfunction]
+#[pyo3(signature = (chunk_type, **fields))]
+pub fn make_chunk<'py>(
+    py: Python<'py>,
+    chunk_type: &str,
+    fields: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let class = chunk_class(py, chunk_type).ok_or_else(|| {
+        TeehistorianParseError::Validation(format!("Unknown chunk type: {}", chunk_type))
+    })?;
+    class.call((), fields)
+}
+
+/// Get the class of every chunk type, keyed by type name
+///
+/// Plugins can use this to build dispatch tables without importing every
+/// class by hand.
+#[pyfunction]
+pub fn chunk_types(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let types = PyDict::new(py);
+    for name in CLASS_NAMES {
+        types.set_item(name, chunk_class(py, name))?;
+    }
+    Ok(types)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::CHUNK_TYPE_NAMES;
+
+    #[test]
+    fn test_registry_covers_all_types() {
+        let mut registered = CLASS_NAMES.to_vec();
+        registered.sort_unstable();
+        let mut known = CHUNK_TYPE_NAMES.to_vec();
+        known.sort_unstable();
+        assert_eq!(registered, known);
+    }
+}
@@ -0,0 +1,216 @@
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+use teehistorian::Chunk;
+
+use crate::errors::TeehistorianParseError;
+use crate::handlers::{ChunkConverter, category_chunk_types, known_chunk_type};
+
+/// Selection applied to decoded chunks before they are converted to Python
+///
+/// Chunks rejected by a filter are decoded and skipped entirely in Rust, so
+/// they never cost a Python allocation.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkFilter {
+    start_tick: Option<i32>,
+    end_tick: Option<i32>,
+    cids: Option<Vec<i32>>,
+    types: Option<Vec<&'static str>>,
+    skip_inputs: bool,
+    excluded: Vec<&'static str>,
+}
+
+impl ChunkFilter {
+    /// Only accept chunks in the inclusive tick range `start..=end`
+    pub fn tick_range(start: i32, end: Option<i32>) -> Self {
+        Self {
+            start_tick: Some(start),
+            end_tick: end,
+            ..Self::default()
+        }
+    }
+
+    /// Only accept chunks that concern one of the given client IDs
+    pub fn players(mut cids: Vec<i32>) -> Self {
+        cids.sort_unstable();
+        cids.dedup();
+        Self {
+            cids: Some(cids),
+            ..Self::default()
+        }
+    }
+
+    /// Only accept chunks of the given types, by `chunk_type()` name
+    pub fn types(types: Vec<&'static str>) -> Self {
+        Self::default().with_types(types)
+    }
+
+    /// Additionally require one of the given types, by `chunk_type()` name
+    pub fn with_types(mut self, types: Vec<&'static str>) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    /// Drop `InputNew` and `InputDiff` chunks
+    pub fn skip_inputs(mut self, skip: bool) -> Self {
+        self.skip_inputs = skip;
+        self
+    }
+
+    /// Drop every chunk type in the given categories
+    pub fn exclude_categories(mut self, categories: &[String]) -> PyResult<Self> {
+        for category in categories {
+            let types = category_chunk_types(category).ok_or_else(|| {
+                TeehistorianParseError::Validation(format!("Unknown chunk category: {}", category))
+            })?;
+            self.excluded.extend_from_slice(types);
+        }
+        Ok(self)
+    }
+
+    /// Whether `chunk` at `tick` should be handed to Python
+    pub fn accepts(&self, chunk: &Chunk, tick: i32, converter: &ChunkConverter) -> bool {
+        !(self.skip_inputs && matches!(chunk, Chunk::InputNew(_) | Chunk::InputDiff(_)))
+            && (self.excluded.is_empty() || !self.excluded.contains(&converter.type_name(chunk)))
+            && self.start_tick.is_none_or(|start| tick >= start)
+            && self.cids.as_ref().is_none_or(|cids| {
+                chunk_cids(chunk)
+                    .into_iter()
+                    .flatten()
+                    .any(|cid| cids.binary_search(&cid).is_ok())
+            })
+            && self
+                .types
+                .as_ref()
+                .is_none_or(|types| types.contains(&converter.type_name(chunk)))
+    }
+
+    /// Whether `SegmentStart` markers should be handed to Python
+    ///
+    /// Markers carry no tick or client ID, so they only pass filters that do
+    /// not select players and either name no types or include the marker.
+    pub fn accepts_segment(&self) -> bool {
+        self.cids.is_none()
+            && !self.excluded.contains(&"SegmentStart")
+            && self
+                .types
+                .as_ref()
+                .is_none_or(|types| types.contains(&"SegmentStart"))
+    }
+
+    /// Whether no chunk at or after `tick` can be accepted anymore
+    pub fn is_exhausted(&self, tick: i32) -> bool {
+        self.end_tick.is_some_and(|end| tick > end)
+    }
+}
+
+/// Resolve a chunk type given as a class (`th.Join`) or a name (`"Join"`)
+pub fn resolve_chunk_type(obj: &Bound<'_, PyAny>) -> PyResult<&'static str> {
+    let name = match obj.cast::<PyType>() {
+        Ok(ty) => ty.name()?.to_string(),
+        Err(_) => obj.extract::<String>()?,
+    };
+    let name = name.strip_prefix("Py").unwrap_or(&name);
+
+    known_chunk_type(name).ok_or_else(|| {
+        TeehistorianParseError::Validation(format!("Unknown chunk type: {}", name)).into()
+    })
+}
+
+/// Client IDs a chunk is about
+///
+/// Most player chunks carry a single client ID; `PlayerSwap` concerns two.
+pub fn chunk_cids(chunk: &Chunk) -> [Option<i32>; 2] {
+    match *chunk {
+        Chunk::PlayerSwap { cid1, cid2 } => [Some(cid1), Some(cid2)],
+        _ => [chunk.cid(), None],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::net_msg::ClientVersions;
+
+    fn accepts(filter: &ChunkFilter, chunk: &Chunk, tick: i32) -> bool {
+        let handlers = Arc::new(HashMap::new());
+        let versions = ClientVersions::default();
+        filter.accepts(
+            chunk,
+            tick,
+            &ChunkConverter::with_versions(&handlers, &versions),
+        )
+    }
+
+    #[test]
+    fn test_tick_range() {
+        let filter = ChunkFilter::tick_range(10, Some(20));
+        assert!(!accepts(&filter, &Chunk::Eos, 9));
+        assert!(accepts(&filter, &Chunk::Eos, 10));
+        assert!(accepts(&filter, &Chunk::Eos, 20));
+        assert!(!filter.is_exhausted(20));
+        assert!(filter.is_exhausted(21));
+
+        let open_ended = ChunkFilter::tick_range(10, None);
+        assert!(!open_ended.is_exhausted(i32::MAX));
+    }
+
+    #[test]
+    fn test_players() {
+        let filter = ChunkFilter::players(vec![7, 3, 7]);
+        assert!(accepts(&filter, &Chunk::Join { cid: 3 }, 0));
+        assert!(accepts(&filter, &Chunk::PlayerOld { cid: 7 }, 0));
+        assert!(!accepts(&filter, &Chunk::Join { cid: 4 }, 0));
+        assert!(accepts(&filter, &Chunk::PlayerSwap { cid1: 1, cid2: 7 }, 0));
+        // Chunks without a client ID never match
+        assert!(!accepts(&filter, &Chunk::TickSkip { dt: 0 }, 0));
+        assert!(!accepts(&filter, &Chunk::Eos, 0));
+    }
+
+    #[test]
+    fn test_types() {
+        let filter = ChunkFilter::types(vec!["Join", "Eos"]);
+        assert!(accepts(&filter, &Chunk::Join { cid: 3 }, 0));
+        assert!(accepts(&filter, &Chunk::Eos, 0));
+        assert!(!accepts(&filter, &Chunk::PlayerOld { cid: 3 }, 0));
+        assert!(!filter.accepts_segment());
+        assert!(ChunkFilter::types(vec!["SegmentStart"]).accepts_segment());
+        assert!(ChunkFilter::default().accepts_segment());
+        assert!(!ChunkFilter::players(vec![0]).accepts_segment());
+    }
+
+    #[test]
+    fn test_combined() {
+        let filter = ChunkFilter::tick_range(10, None).with_types(vec!["Join"]);
+        assert!(!accepts(&filter, &Chunk::Join { cid: 0 }, 9));
+        assert!(!accepts(&filter, &Chunk::Eos, 10));
+        assert!(accepts(&filter, &Chunk::Join { cid: 0 }, 10));
+    }
+
+    #[test]
+    fn test_skip_inputs() {
+        use teehistorian::chunks::InputDiff;
+
+        let input = Chunk::InputDiff(InputDiff {
+            cid: 0,
+            dinput: [0; 10],
+        });
+        assert!(accepts(&ChunkFilter::default(), &input, 0));
+
+        let filter = ChunkFilter::default().skip_inputs(true);
+        assert!(!accepts(&filter, &input, 0));
+        assert!(accepts(&filter, &Chunk::Join { cid: 0 }, 0));
+    }
+
+    #[test]
+    fn test_exclude_categories() {
+        let filter = ChunkFilter::default()
+            .exclude_categories(&["GameEvent".to_string(), "Special".to_string()])
+            .unwrap();
+        assert!(!accepts(&filter, &Chunk::TickSkip { dt: 0 }, 0));
+        assert!(!accepts(&filter, &Chunk::Eos, 0));
+        assert!(accepts(&filter, &Chunk::Join { cid: 0 }, 0));
+    }
+}
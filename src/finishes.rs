@@ -0,0 +1,166 @@
+//! Race finishes of a recording
+//!
+//! DDNet writes an extension chunk with the race time whenever a player or
+//! a team finishes. Who was in the team, the names of the players and
+//! whether the team was practicing come from the chunks before it, so the
+//! finishes are collected in one pass over the whole recording.
+use std::collections::{BTreeSet, HashMap};
+
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+use crate::ticks::TICK_SPEED;
+use crate::walk_recording;
+
+/// A finished race, from `extract_finishes()`
+#[pyclass(name = "Finish", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyFinish {
+    /// `"player"` for a `PlayerFinish`, `"team"` for a `TeamFinish`
+    #[pyo3(get)]
+    pub kind: &'static str,
+    /// Client IDs of the players who finished
+    #[pyo3(get)]
+    pub cids: Vec<i32>,
+    /// Names of the players, None for a player who never set one
+    #[pyo3(get)]
+    pub names: Vec<Option<String>>,
+    /// Team, 0 for players without a team
+    #[pyo3(get)]
+    pub team: i32,
+    /// Tick of the finish
+    #[pyo3(get)]
+    pub tick: i32,
+    /// Race time in ticks
+    #[pyo3(get)]
+    pub time: i32,
+    /// Whether the team was in practice mode, so the time does not count
+    #[pyo3(get)]
+    pub practice: bool,
+}
+
+#[pymethods]
+impl PyFinish {
+    /// Race time in seconds
+    #[getter]
+    pub fn seconds(&self) -> f64 {
+        f64::from(self.time) / f64::from(TICK_SPEED)
+    }
+
+    /// Tick the race started at
+    #[getter]
+    pub fn start_tick(&self) -> i32 {
+        self.tick - self.time
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Finish(kind={:?}, cids={:?}, team={}, tick={}, seconds={})",
+            self.kind,
+            self.cids,
+            self.team,
+            self.tick,
+            self.seconds()
+        )
+    }
+}
+
+/// Players and teams as far as the recording got
+#[derive(Debug, Default)]
+pub struct Races {
+    names: HashMap<i32, String>,
+    teams: HashMap<i32, i32>,
+    practicing: BTreeSet<i32>,
+    pub finishes: Vec<PyFinish>,
+}
+
+impl Races {
+    fn finish(&mut self, kind: &'static str, cids: Vec<i32>, team: i32, tick: i32, time: i32) {
+        let names = cids
+            .iter()
+            .map(|cid| self.names.get(cid).cloned())
+            .collect();
+        self.finishes.push(PyFinish {
+            kind,
+            cids,
+            names,
+            team,
+            tick,
+            time,
+            practice: team != 0 && self.practicing.contains(&team),
+        });
+    }
+
+    pub fn observe(&mut self, chunk: &Chunk, tick: i32) {
+        match *chunk {
+            Chunk::PlayerName(ref player) => {
+                let name = String::from_utf8_lossy(player.name).into_owned();
+                self.names.insert(player.cid, name);
+            }
+            Chunk::PlayerTeam { cid, team } => {
+                self.teams.insert(cid, team);
+            }
+            Chunk::TeamPractice { team, practice } => {
+                if practice != 0 {
+                    self.practicing.insert(team);
+                } else {
+                    self.practicing.remove(&team);
+                }
+            }
+            Chunk::Drop(ref drop) => {
+                self.names.remove(&drop.cid);
+                self.teams.remove(&drop.cid);
+            }
+            Chunk::PlayerFinish { cid, time } => {
+                let team = self.teams.get(&cid).copied().unwrap_or(0);
+                self.finish("player", vec![cid], team, tick, time);
+            }
+            Chunk::TeamFinish { team, time } => {
+                let mut members: Vec<i32> = self
+                    .teams
+                    .iter()
+                    .filter(|&(_, &t)| t == team)
+                    .map(|(&cid, _)| cid)
+                    .collect();
+                members.sort_unstable();
+                self.finish("team", members, team, tick, time);
+            }
+            _ => {}
+        }
+    }
+
+    /// Forget the players of the previous stream
+    pub fn restart(&mut self) {
+        self.names.clear();
+        self.teams.clear();
+        self.practicing.clear();
+    }
+}
+
+/// Every race finish in a recording
+///
+/// Finishes come from the `PlayerFinish` and `TeamFinish` extension chunks,
+/// with the team members, names and practice mode at the time of the
+/// finish. DDNet does not record checkpoint times, so there are no splits.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+///
+/// # Returns
+/// A list of `Finish` in the order they happened
+///
+/// # Example
+/// ```python
+/// for finish in th.extract_finishes(data):
+///     if not finish.practice:
+///         print(finish.names, finish.seconds)
+/// ```
+#[pyfunction]
+pub fn extract_finishes(py: Python<'_>, data: &[u8]) -> PyResult<Vec<PyFinish>> {
+    let mut races = Races::default();
+    walk_recording(py, data, |chunk, tick| match chunk {
+        Some(chunk) => races.observe(&chunk, tick),
+        None => races.restart(),
+    })?;
+    Ok(races.finishes)
+}
@@ -0,0 +1,151 @@
+//! Following a recording while the server writes it
+//!
+//! The server appends chunks to the active recording as the game runs. The
+//! follow iterator keeps the file open and, whenever the parser runs out of
+//! complete chunks, reads whatever was appended since and tries again.
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use pyo3::prelude::*;
+
+use crate::chunks::PyEos;
+use crate::errors::TeehistorianParseError;
+use crate::filter::ChunkFilter;
+use crate::handlers::UnknownUuidPolicy;
+use crate::{MAGIC, MAGIC_LEN, PyTeehistorian};
+
+/// Iterator returned by `Teehistorian.follow()`
+#[pyclass(name = "FollowIterator", module = "teehistorian_py")]
+pub struct PyFollowIterator {
+    file: File,
+    poll_interval: Duration,
+    skip_inputs: bool,
+    exclude_categories: Vec<String>,
+    emit_warnings: bool,
+    resolve_inputs: bool,
+    resolve_positions: bool,
+    unknown_uuid_policy: String,
+    /// Data read before the header was complete
+    pending: Vec<u8>,
+    /// Created as soon as the whole header has been written
+    parser: Option<PyTeehistorian>,
+    /// Set once the `Eos` chunk was returned
+    finished: bool,
+}
+
+impl PyFollowIterator {
+    #[allow(clippy::too_many_arguments)]
+    pub fn open(
+        path: &Path,
+        poll_interval: f64,
+        skip_inputs: bool,
+        exclude_categories: Vec<String>,
+        emit_warnings: bool,
+        resolve_inputs: bool,
+        resolve_positions: bool,
+        unknown_uuid_policy: &str,
+    ) -> PyResult<Self> {
+        let poll_interval = Duration::try_from_secs_f64(poll_interval).map_err(|_| {
+            TeehistorianParseError::Validation(format!("Invalid poll interval: {}", poll_interval))
+        })?;
+        // Reject unknown categories and policies right away rather than on the first chunk
+        ChunkFilter::default().exclude_categories(&exclude_categories)?;
+        UnknownUuidPolicy::from_name(unknown_uuid_policy)?;
+
+        Ok(Self {
+            file: File::open(path)?,
+            poll_interval,
+            skip_inputs,
+            exclude_categories,
+            emit_warnings,
+            resolve_inputs,
+            resolve_positions,
+            unknown_uuid_policy: unknown_uuid_policy.to_string(),
+            pending: Vec::new(),
+            parser: None,
+            finished: false,
+        })
+    }
+
+    /// Read everything appended to the file since the last call
+    ///
+    /// Returns whether any new data was read.
+    fn read_appended(&mut self) -> PyResult<bool> {
+        let mut more = Vec::new();
+        self.file.read_to_end(&mut more)?;
+        match &mut self.parser {
+            Some(parser) => parser.inner.extend(&more),
+            None => self.pending.extend_from_slice(&more),
+        }
+        Ok(!more.is_empty())
+    }
+
+    /// Create the parser once the header has been written completely
+    fn start_parser(&mut self) -> PyResult<()> {
+        let magic_len = self.pending.len().min(MAGIC_LEN);
+        if self.pending[..magic_len] != MAGIC[..magic_len] {
+            return Err(TeehistorianParseError::Validation(
+                "Not a teehistorian file: data does not start with the teehistorian magic UUID"
+                    .to_string(),
+            )
+            .into());
+        }
+
+        let header_complete =
+            self.pending.len() > MAGIC_LEN && self.pending[MAGIC_LEN..].contains(&0);
+        if header_complete {
+            let data = std::mem::take(&mut self.pending);
+            self.parser = Some(PyTeehistorian::new(
+                &data,
+                self.skip_inputs,
+                Some(self.exclude_categories.clone()),
+                self.emit_warnings,
+                self.resolve_inputs,
+                self.resolve_positions,
+                &self.unknown_uuid_policy,
+            )?);
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyFollowIterator {
+    /// Python iterator protocol support
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    /// Get the next chunk, waiting until the server has written it
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        while !self.finished {
+            if let Some(parser) = &mut self.parser {
+                if let Some(chunk) = parser.next_matching(py, &ChunkFilter::default())? {
+                    self.finished = chunk.bind(py).is_instance_of::<PyEos>();
+                    return Ok(Some(chunk));
+                }
+            } else {
+                self.start_parser()?;
+                if self.parser.is_some() {
+                    continue;
+                }
+            }
+
+            if !self.read_appended()? {
+                let interval = self.poll_interval;
+                py.detach(|| std::thread::sleep(interval));
+                // Let Ctrl+C interrupt a recording that never ends
+                py.check_signals()?;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Tick of the most recently read chunk
+    #[getter]
+    fn tick(&self) -> i32 {
+        self.parser.as_ref().map_or(0, |parser| parser.ticks.tick())
+    }
+}
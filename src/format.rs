@@ -0,0 +1,77 @@
+//! Teehistorian format detection
+//!
+//! Every stream starts with the teehistorian magic UUID followed by a JSON
+//! header whose `version` field names the format revision. Checking both is
+//! enough to tell recordings apart from arbitrary files before parsing.
+use pyo3::prelude::*;
+use serde_json::Value;
+use teehistorian::Th;
+
+use crate::errors::TeehistorianParseError;
+use crate::{MAGIC, MAGIC_LEN, input};
+
+/// Get the format version named by a JSON header
+///
+/// DDNet writes the version as a string (`"version": "2"`), but plain
+/// numbers are accepted as well.
+pub fn header_version(header: &[u8]) -> PyResult<Option<i32>> {
+    let json: Value = serde_json::from_slice(header).map_err(|e| {
+        TeehistorianParseError::Header(format!("Failed to parse header JSON: {}", e))
+    })?;
+
+    let version = match json.get("version") {
+        Some(Value::String(version)) => version.trim().parse().ok(),
+        Some(Value::Number(version)) => version.as_i64().and_then(|v| v.try_into().ok()),
+        _ => None,
+    };
+    Ok(version)
+}
+
+/// Check that data starts with the teehistorian magic UUID
+pub fn check_magic(data: &[u8]) -> PyResult<()> {
+    if !data.starts_with(&MAGIC) {
+        return Err(TeehistorianParseError::Validation(
+            "Not a teehistorian file: data does not start with the teehistorian magic UUID"
+                .to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Get the JSON header of a teehistorian stream
+pub fn read_header(data: &[u8]) -> PyResult<&[u8]> {
+    let mut th = Th::parse(data)
+        .map_err(|e| TeehistorianParseError::Header(format!("Failed to read header: {}", e)))?;
+    let header_len = th
+        .header()
+        .map_err(|e| TeehistorianParseError::Header(format!("Failed to read header: {}", e)))?
+        .len();
+    Ok(&data[MAGIC_LEN..MAGIC_LEN + header_len])
+}
+
+/// Get the format version of teehistorian data without parsing its chunks
+///
+/// Compressed data is decompressed first. Returns None for recordings whose
+/// header does not name a version.
+///
+/// Raises ValidationError if the data is not a teehistorian file.
+#[pyfunction]
+pub fn detect_format(data: &[u8]) -> PyResult<Option<i32>> {
+    let data = input::decode_input(data)?;
+    check_magic(&data)?;
+    header_version(read_header(&data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_version() {
+        assert_eq!(header_version(br#"{"version": "2"}"#).unwrap(), Some(2));
+        assert_eq!(header_version(br#"{"version": 1}"#).unwrap(), Some(1));
+        assert_eq!(header_version(br#"{"version": "x"}"#).unwrap(), None);
+        assert_eq!(header_version(b"{}").unwrap(), None);
+    }
+}
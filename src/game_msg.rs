@@ -0,0 +1,188 @@
+//! Typed game messages decoded from `NetMessage` payloads
+//!
+//! Teehistorian records the packed game messages clients send to the
+//! server. `NetMessage.decode()` unpacks them with libtw2 into one of the
+//! classes below. Player info messages are already turned into
+//! `NetMessagePlayerInfo` chunks by the parser and are not repeated here.
+use pyo3::prelude::*;
+
+use crate::net_msg::{Chat, ClNetMessage, NetVersion, parse_net_msg};
+
+macro_rules! define_game_msg {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident: $field_ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $crate::macros::paste! {
+            $(#[$meta])*
+            #[pyclass(module = "teehistorian_py", frozen, get_all)]
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct [<Py $name>] {
+                $(
+                    $(#[$field_meta])*
+                    pub $field: $field_ty,
+                )*
+            }
+
+            #[pymethods]
+            impl [<Py $name>] {
+                /// Name of the message type
+                fn message_type(&self) -> &'static str {
+                    stringify!($name)
+                }
+
+                fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+                    let dict = pyo3::types::PyDict::new(py);
+                    dict.set_item("type", self.message_type())?;
+                    $(
+                        dict.set_item(stringify!($field), &self.$field)?;
+                    )*
+                    Ok(dict.into())
+                }
+
+                fn __repr__(&self) -> String {
+                    format!("{:?}", self).replacen("Py", "", 1)
+                }
+            }
+        }
+    };
+}
+
+define_game_msg! {
+    /// Chat message (`Cl_Say`)
+    ChatMessage {
+        /// "all", "team", "whisper" or "none"
+        mode: String,
+        /// Whisper recipient, -1 otherwise
+        target: i32,
+        message: String,
+    }
+}
+
+define_game_msg! {
+    /// Self-kill request (`Cl_Kill`)
+    KillMessage {}
+}
+
+define_game_msg! {
+    /// Emoticon shown above the tee (`Cl_Emoticon`)
+    Emoticon {
+        emoticon: i32,
+        /// Lowercase emoticon name, e.g. "hearts"
+        name: String,
+    }
+}
+
+define_game_msg! {
+    /// Vote started by the client (`Cl_CallVote`)
+    VoteCall {
+        /// "option", "kick" or "spectate"
+        vote_type: String,
+        value: String,
+        reason: String,
+        /// Forced vote, only sent by 0.7 clients
+        force: bool,
+    }
+}
+
+define_game_msg! {
+    /// Vote cast (`Cl_Vote`), 1 for yes and -1 for no
+    Vote {
+        vote: i32,
+    }
+}
+
+define_game_msg! {
+    /// Team change request (`Cl_SetTeam`), -1 for spectators
+    SetTeam {
+        team: i32,
+    }
+}
+
+define_game_msg! {
+    /// Spectator target change (`Cl_SetSpectatorMode`), -1 for free view
+    SetSpectatorMode {
+        spectator_id: i32,
+    }
+}
+
+define_game_msg! {
+    /// Console command sent by a 0.7 client (`Cl_Command`)
+    ClientCommand {
+        name: String,
+        arguments: String,
+    }
+}
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Decode a packed game message into one of the message classes
+///
+/// A message of a client with an unknown protocol is tried as 0.6 first.
+/// Returns None for messages that cannot be parsed or have no class.
+pub fn decode(
+    py: Python<'_>,
+    msg: &[u8],
+    mut net_version: NetVersion,
+) -> PyResult<Option<Py<PyAny>>> {
+    let Ok(msg) = parse_net_msg(msg, &mut net_version) else {
+        return Ok(None);
+    };
+    let obj = match msg {
+        ClNetMessage::ClSay(say) => {
+            let mode = match say.mode {
+                Chat::None => "none",
+                Chat::All => "all",
+                Chat::Team => "team",
+                Chat::Whisper => "whisper",
+            };
+            let msg = PyChatMessage {
+                mode: mode.to_string(),
+                target: say.target,
+                message: lossy(say.message),
+            };
+            Py::new(py, msg)?.into_any()
+        }
+        ClNetMessage::ClKill => Py::new(py, PyKillMessage {})?.into_any(),
+        ClNetMessage::ClEmoticon(emoticon) => {
+            let msg = PyEmoticon {
+                emoticon: emoticon as i32,
+                name: format!("{:?}", emoticon).to_lowercase(),
+            };
+            Py::new(py, msg)?.into_any()
+        }
+        ClNetMessage::ClCallVote(vote) => {
+            let msg = PyVoteCall {
+                vote_type: lossy(vote.type_),
+                value: lossy(vote.value),
+                reason: lossy(vote.reason),
+                force: vote.force,
+            };
+            Py::new(py, msg)?.into_any()
+        }
+        ClNetMessage::ClVote(vote) => Py::new(py, PyVote { vote })?.into_any(),
+        ClNetMessage::ClSetTeam(team) => Py::new(py, PySetTeam { team: team as i32 })?.into_any(),
+        ClNetMessage::ClSetSpectatorMode(mode) => {
+            let msg = PySetSpectatorMode {
+                spectator_id: mode.spectator_id,
+            };
+            Py::new(py, msg)?.into_any()
+        }
+        ClNetMessage::ClCommand(command) => {
+            let msg = PyClientCommand {
+                name: lossy(command.name),
+                arguments: lossy(command.arguments),
+            };
+            Py::new(py, msg)?.into_any()
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(obj))
+}
@@ -1,4 +1,3 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -7,13 +6,214 @@ use teehistorian::Chunk;
 
 use crate::chunks::*;
 use crate::errors::{Result, TeehistorianParseError};
-use crate::net_msg::{ClNetMessage, NetVersion, parse_net_msg};
+use crate::net_msg::{ClNetMessage, ClientVersions, NetVersion, parse_net_msg, parse_net_msg_with};
+use crate::registry::ChunkDef;
+
+/// Names of every chunk type the converter can produce
+///
+/// These match `chunk_type()` on the resulting Python objects.
+pub const CHUNK_TYPE_NAMES: &[&str] = &[
+    "Join",
+    "JoinVer6",
+    "JoinVer7",
+    "Drop",
+    "PlayerReady",
+    "PlayerRejoin",
+    "PlayerNew",
+    "PlayerOld",
+    "PlayerTeam",
+    "PlayerName",
+    "PlayerDiff",
+    "PlayerPosition",
+    "InputNew",
+    "InputDiff",
+    "NetMessage",
+    "NetMessagePlayerInfo",
+    "ConsoleCommand",
+    "AuthInit",
+    "AuthLogin",
+    "AuthLogout",
+    "DdnetVersion",
+    "DdnetVersionOld",
+    "PlayerFinish",
+    "PlayerSwap",
+    "TickSkip",
+    "TeamSaveSuccess",
+    "TeamSaveFailure",
+    "TeamLoadSuccess",
+    "TeamLoadFailure",
+    "TeamPractice",
+    "TeamFinish",
+    "AntiBot",
+    "Eos",
+    "TestChunk",
+    "Unknown",
+    "CustomChunk",
+    "RawChunk",
+    "SegmentStart",
+];
+
+/// Chunk type names grouped by category
+///
+/// The categories match the `Category:` annotations in `chunks.rs` and the
+/// `*Chunk` type aliases in the Python stubs.
+pub const CHUNK_CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "PlayerLifecycle",
+        &[
+            "Join",
+            "JoinVer6",
+            "JoinVer7",
+            "Drop",
+            "PlayerReady",
+            "PlayerRejoin",
+        ],
+    ),
+    (
+        "PlayerState",
+        &[
+            "PlayerNew",
+            "PlayerOld",
+            "PlayerTeam",
+            "PlayerName",
+            "PlayerDiff",
+            "PlayerPosition",
+            "PlayerFinish",
+            "PlayerSwap",
+        ],
+    ),
+    ("Input", &["InputNew", "InputDiff"]),
+    (
+        "Communication",
+        &["NetMessage", "NetMessagePlayerInfo", "ConsoleCommand"],
+    ),
+    (
+        "AuthVersion",
+        &[
+            "AuthInit",
+            "AuthLogin",
+            "AuthLogout",
+            "DdnetVersion",
+            "DdnetVersionOld",
+        ],
+    ),
+    (
+        "GameEvent",
+        &[
+            "TickSkip",
+            "TeamSaveSuccess",
+            "TeamSaveFailure",
+            "TeamLoadSuccess",
+            "TeamLoadFailure",
+            "TeamPractice",
+            "TeamFinish",
+            "AntiBot",
+        ],
+    ),
+    (
+        "Special",
+        &[
+            "Eos",
+            "TestChunk",
+            "Unknown",
+            "CustomChunk",
+            "RawChunk",
+            "SegmentStart",
+        ],
+    ),
+];
+
+/// Look up the chunk type names of a category, if it is known
+pub fn category_chunk_types(category: &str) -> Option<&'static [&'static str]> {
+    CHUNK_CATEGORIES
+        .iter()
+        .find(|(name, _)| *name == category)
+        .map(|(_, types)| *types)
+}
+
+/// Get the category a chunk type belongs to
+///
+/// Every name in `CHUNK_TYPE_NAMES` is in exactly one category.
+pub fn chunk_category(chunk_type: &str) -> &'static str {
+    CHUNK_CATEGORIES
+        .iter()
+        .find(|(_, types)| types.contains(&chunk_type))
+        .map(|(name, _)| *name)
+        .expect("every chunk type has a category")
+}
+
+/// Get the chunk type names of every category
+#[pyfunction]
+pub fn categories() -> HashMap<&'static str, Vec<&'static str>> {
+    CHUNK_CATEGORIES
+        .iter()
+        .map(|(name, types)| (*name, types.to_vec()))
+        .collect()
+}
+
+/// Look up the static name of a chunk type, if it is known
+pub fn known_chunk_type(name: &str) -> Option<&'static str> {
+    CHUNK_TYPE_NAMES
+        .iter()
+        .copied()
+        .find(|known| *known == name)
+}
+
+/// What the parser does with extension chunks nobody registered a UUID for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownUuidPolicy {
+    /// Yield them as `Unknown` chunks
+    #[default]
+    Yield,
+    /// Drop them silently
+    Skip,
+    /// Fail with a parse error
+    Error,
+}
+
+impl UnknownUuidPolicy {
+    /// Parse a policy from its name, "yield", "skip" or "error"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "yield" => Ok(Self::Yield),
+            "skip" => Ok(Self::Skip),
+            "error" => Ok(Self::Error),
+            _ => Err(TeehistorianParseError::Validation(format!(
+                "Unknown UUID policy: {} (expected \"yield\", \"skip\" or \"error\")",
+                name
+            ))),
+        }
+    }
+
+    /// Whether `chunk` is kept, failing on unregistered UUIDs under `Error`
+    pub fn accepts(self, chunk: &Chunk, converter: &ChunkConverter) -> Result<bool> {
+        let Chunk::UnknownEx(unknown) = chunk else {
+            return Ok(true);
+        };
+        if self == Self::Yield || converter.type_name(chunk) != "Unknown" {
+            return Ok(true);
+        }
+        match self {
+            Self::Error => Err(TeehistorianParseError::Parse(format!(
+                "Unknown extension UUID {}",
+                unknown.uuid
+            ))),
+            _ => Ok(false),
+        }
+    }
+}
 
 /// Handler for custom UUID chunks
 #[derive(Debug, Clone)]
 pub struct UuidHandler {
     uuid: String,
     name: String,
+    /// Python callable `(uuid, payload) -> object` replacing `CustomChunk`
+    decoder: Option<Arc<Py<PyAny>>>,
+    /// Fields the payload is decoded into
+    fields: Option<ChunkDef>,
+    /// `ExtensionHandler` seeing every `CustomChunk` of this UUID
+    handler: Option<Arc<Py<PyAny>>>,
 }
 
 impl UuidHandler {
@@ -27,9 +227,50 @@ impl UuidHandler {
         Ok(Self {
             name: uuid.clone(),
             uuid,
+            decoder: None,
+            fields: None,
+            handler: None,
         })
     }
 
+    /// Pass chunks of this UUID to a stateful `ExtensionHandler`
+    pub fn with_handler(mut self, handler: Py<PyAny>) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// The stateful handler, if any
+    pub fn handler(&self) -> Option<&Py<PyAny>> {
+        self.handler.as_deref()
+    }
+
+    /// A copy that yields plain `CustomChunk`s instead of calling the
+    /// stateful handler
+    pub fn without_handler(&self) -> Self {
+        Self {
+            handler: None,
+            ..self.clone()
+        }
+    }
+
+    /// Name the extension, e.g. "kog-checkpoint"
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Decode chunks of this UUID into the fields of `def`
+    pub fn with_fields(mut self, def: ChunkDef) -> Self {
+        self.fields = Some(def);
+        self
+    }
+
+    /// Decode chunks of this UUID with a Python callable
+    pub fn with_decoder(mut self, decoder: Py<PyAny>) -> Self {
+        self.decoder = Some(Arc::new(decoder));
+        self
+    }
+
     /// Get the UUID string
     pub fn uuid(&self) -> &str {
         &self.uuid
@@ -39,11 +280,97 @@ impl UuidHandler {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// How chunks of this UUID are decoded
+    ///
+    /// "handler" for a stateful `ExtensionHandler`, "callback" for a decoder
+    /// callable, "fields" for declared fields and "none" for plain
+    /// `CustomChunk`s.
+    pub fn kind(&self) -> &'static str {
+        if self.handler.is_some() {
+            "handler"
+        } else if self.decoder.is_some() {
+            "callback"
+        } else if self.fields.is_some() {
+            "fields"
+        } else {
+            "none"
+        }
+    }
+}
+
+/// Custom UUID registered with a parser
+///
+/// Returned by `Teehistorian.get_registered_uuids()`.
+#[pyclass(
+    name = "RegisteredUuid",
+    module = "teehistorian_py",
+    frozen,
+    get_all,
+    eq
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyRegisteredUuid {
+    pub uuid: String,
+    /// `CustomChunk.handler_name` of its chunks
+    pub name: String,
+    /// "none", "callback", "fields" or "handler"
+    pub kind: &'static str,
+}
+
+impl From<&UuidHandler> for PyRegisteredUuid {
+    fn from(handler: &UuidHandler) -> Self {
+        Self {
+            uuid: handler.uuid().to_string(),
+            name: handler.name().to_string(),
+            kind: handler.kind(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyRegisteredUuid {
+    fn __repr__(&self) -> String {
+        format!("{:?}", self).replacen("Py", "", 1)
+    }
+}
+
+/// Base class of stateful handlers for a custom UUID
+///
+/// Subclass it and register an instance with `register_custom_uuid()` to see
+/// every chunk of the UUID in order, e.g. to accumulate checkpoint times and
+/// emit a derived event once a run is complete.
+#[pyclass(name = "ExtensionHandler", module = "teehistorian_py", subclass)]
+pub struct PyExtensionHandler;
+
+#[pymethods]
+impl PyExtensionHandler {
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(_args: &Bound<'_, PyAny>, _kwargs: Option<&Bound<'_, PyAny>>) -> Self {
+        Self
+    }
+
+    /// Called with every `CustomChunk` of the UUID and its tick
+    ///
+    /// Returns the object to yield instead of the chunk, or None to yield the
+    /// chunk itself.
+    fn on_chunk(&self, chunk: Py<PyAny>, tick: i32) -> Option<Py<PyAny>> {
+        let _ = (chunk, tick);
+        None
+    }
+
+    /// Called when the parser is reset, drop accumulated state here
+    fn reset(&self) {}
 }
 
 /// Build a PyNetMessagePlayerInfo from parsed player info fields
+///
+/// `msg` is the recorded message, written back instead of re-encoding the
+/// fields, which cannot represent every message (e.g. 0.7 skins).
 fn build_player_info_chunk(
     cid: i32,
+    msg: &[u8],
     message_type: &str,
     name: &[u8],
     clan: &[u8],
@@ -63,7 +390,7 @@ fn build_player_info_chunk(
         crate::net_msg::Skin::V7(_) => ("default".to_string(), false, 0, 0),
     };
 
-    PyNetMessagePlayerInfo::new(
+    PyNetMessagePlayerInfo::with_raw_bytes(
         cid,
         message_type.to_string(),
         name,
@@ -73,31 +400,121 @@ fn build_player_info_chunk(
         use_custom_color,
         color_body,
         color_feet,
+        msg.to_vec(),
     )
 }
 
+/// Keep the recorded encoding of `chunk` if `obj` does not reproduce it
+///
+/// Text fields replace invalid UTF-8, so such chunks would change when
+/// written back otherwise.
+fn keep_recorded<T: TeehistorianChunk>(
+    obj: T,
+    chunk: &Chunk,
+    with_recorded: fn(T, Vec<u8>) -> T,
+) -> PyResult<T> {
+    let recorded = encode(chunk)?;
+    if obj.write_to_buffer()? == recorded {
+        Ok(obj)
+    } else {
+        Ok(with_recorded(obj, recorded))
+    }
+}
+
 /// Chunk converter that transforms Rust chunks to Python objects
 pub struct ChunkConverter<'a> {
     handlers: &'a Arc<HashMap<String, UuidHandler>>,
-    net_version: RefCell<NetVersion>,
+    /// Protocol of every client, to parse its messages with
+    versions: Option<&'a ClientVersions>,
 }
 
 impl<'a> ChunkConverter<'a> {
-    /// Create a new chunk converter
+    /// Chunk converter for chunks taken out of a recording
+    ///
+    /// Messages are parsed without knowing the protocol of their client, use
+    /// `with_versions()` while going through a recording.
     pub fn new(handlers: &'a Arc<HashMap<String, UuidHandler>>) -> Self {
         Self {
             handlers,
-            net_version: RefCell::new(NetVersion::Unknown),
+            versions: None,
+        }
+    }
+
+    /// Chunk converter parsing messages with the protocol of their client
+    pub fn with_versions(
+        handlers: &'a Arc<HashMap<String, UuidHandler>>,
+        versions: &'a ClientVersions,
+    ) -> Self {
+        Self {
+            handlers,
+            versions: Some(versions),
+        }
+    }
+
+    /// Protocol of a client, Unknown without versions
+    fn net_version(&self, cid: i32) -> NetVersion {
+        self.versions
+            .map_or(NetVersion::Unknown, |versions| versions.get(cid))
+    }
+
+    /// Get the chunk type name `convert` would produce, without creating it
+    pub fn type_name(&self, chunk: &Chunk) -> &'static str {
+        // No wildcard arm here or in `convert`: a variant added upstream has
+        // to be mapped before the crate builds again
+        match chunk {
+            Chunk::Join { .. } => "Join",
+            Chunk::JoinVer6 { .. } => "JoinVer6",
+            Chunk::JoinVer7 { .. } => "JoinVer7",
+            Chunk::RejoinVer6 { .. } => "PlayerRejoin",
+            Chunk::Drop(_) => "Drop",
+            Chunk::PlayerReady { .. } => "PlayerReady",
+            Chunk::PlayerNew(_) => "PlayerNew",
+            Chunk::PlayerOld { .. } => "PlayerOld",
+            Chunk::PlayerTeam { .. } => "PlayerTeam",
+            Chunk::PlayerName(_) => "PlayerName",
+            Chunk::PlayerDiff(_) => "PlayerDiff",
+            Chunk::InputNew(_) => "InputNew",
+            Chunk::InputDiff(_) => "InputDiff",
+            Chunk::NetMessage(msg) => {
+                let mut net_ver = self.net_version(msg.cid);
+                // Only the type is needed, scans and filters must not print
+                match parse_net_msg_with(msg.msg, &mut net_ver, &mut warn::Ignore) {
+                    Ok(ClNetMessage::ClStartInfo(_) | ClNetMessage::ClChangeInfo(_)) => {
+                        "NetMessagePlayerInfo"
+                    }
+                    _ => "NetMessage",
+                }
+            }
+            Chunk::ConsoleCommand(_) => "ConsoleCommand",
+            Chunk::AuthInit(_) => "AuthInit",
+            Chunk::AuthLogin(_) => "AuthLogin",
+            Chunk::AuthLogout { .. } => "AuthLogout",
+            Chunk::DdnetVersion(_) => "DdnetVersion",
+            Chunk::DdnetVersionOld(_) => "DdnetVersionOld",
+            Chunk::PlayerFinish { .. } => "PlayerFinish",
+            Chunk::PlayerSwap { .. } => "PlayerSwap",
+            Chunk::TickSkip { .. } => "TickSkip",
+            Chunk::TeamSaveSuccess(_) => "TeamSaveSuccess",
+            Chunk::TeamSaveFailure { .. } => "TeamSaveFailure",
+            Chunk::TeamLoadSuccess(_) => "TeamLoadSuccess",
+            Chunk::TeamLoadFailure { .. } => "TeamLoadFailure",
+            Chunk::TeamPractice { .. } => "TeamPractice",
+            Chunk::TeamFinish { .. } => "TeamFinish",
+            Chunk::Antibot(_) => "AntiBot",
+            Chunk::Eos => "Eos",
+            Chunk::Test => "TestChunk",
+            Chunk::UnknownEx(unknown) => {
+                if self.handlers.contains_key(&unknown.uuid.to_string()) {
+                    "CustomChunk"
+                } else {
+                    "Unknown"
+                }
+            }
         }
     }
 
     /// Convert a Rust chunk to a Python object, preserving original serialized bytes
-    pub fn convert(
-        &self,
-        py: Python<'_>,
-        chunk: Chunk,
-        _chunk_number: usize,
-    ) -> PyResult<Py<PyAny>> {
+    pub fn convert(&self, py: Python<'_>, chunk: Chunk, tick: i32) -> PyResult<Py<PyAny>> {
         // Serialize the chunk immediately to preserve original bytes
         // This allows us to avoid re-serialization when writing unmodified chunks
         match chunk {
@@ -118,13 +535,12 @@ impl<'a> ChunkConverter<'a> {
             }
 
             Chunk::RejoinVer6 { cid } => {
-                let obj = PyRejoinVer6::new(cid);
+                let obj = PyPlayerRejoin::new(cid);
                 Ok(Py::new(py, obj)?.into())
             }
 
             Chunk::Drop(drop_data) => {
-                let reason = String::from_utf8_lossy(drop_data.reason).to_string();
-                let obj = PyDrop::new(drop_data.cid, reason);
+                let obj = PyDrop::new(drop_data.cid, drop_data.reason.to_vec());
                 Ok(Py::new(py, obj)?.into())
             }
 
@@ -154,6 +570,8 @@ impl<'a> ChunkConverter<'a> {
                     .trim_end_matches('\0')
                     .to_string();
                 let obj = PyPlayerName::new(player_name.cid, name);
+                let chunk = Chunk::PlayerName(player_name);
+                let obj = keep_recorded(obj, &chunk, PyPlayerName::with_recorded)?;
                 Ok(Py::new(py, obj)?.into())
             }
 
@@ -180,11 +598,13 @@ impl<'a> ChunkConverter<'a> {
                 let message_bytes = msg.msg;
 
                 // Try to parse the network message to extract player info
-                let mut net_ver = self.net_version.borrow_mut();
+                let version = self.net_version(msg.cid);
+                let mut net_ver = version;
                 match parse_net_msg(message_bytes, &mut net_ver) {
                     Ok(ClNetMessage::ClStartInfo(player_info)) => {
                         let obj = build_player_info_chunk(
                             msg.cid,
+                            message_bytes,
                             "ClStartInfo",
                             player_info.name,
                             player_info.clan,
@@ -196,6 +616,7 @@ impl<'a> ChunkConverter<'a> {
                     Ok(ClNetMessage::ClChangeInfo(player_info)) => {
                         let obj = build_player_info_chunk(
                             msg.cid,
+                            message_bytes,
                             "ClChangeInfo",
                             player_info.name,
                             player_info.clan,
@@ -206,7 +627,8 @@ impl<'a> ChunkConverter<'a> {
                     }
                     _ => {
                         // Fall back to regular NetMessage if parsing fails or it's not a player info message
-                        let obj = PyNetMessage::new(msg.cid, message_bytes.to_vec());
+                        let obj = PyNetMessage::new(msg.cid, message_bytes.to_vec())
+                            .with_protocol(version);
                         Ok(Py::new(py, obj)?.into())
                     }
                 }
@@ -220,15 +642,32 @@ impl<'a> ChunkConverter<'a> {
                     .map(|arg| String::from_utf8_lossy(arg).to_string())
                     .collect::<Vec<_>>();
                 let obj = PyConsoleCommand::new(console_cmd.cid, console_cmd.flags, command, args);
+                let chunk = Chunk::ConsoleCommand(console_cmd);
+                let obj = keep_recorded(obj, &chunk, PyConsoleCommand::with_recorded)?;
                 Ok(Py::new(py, obj)?.into())
             }
 
             // Authentication & version events
+            Chunk::AuthInit(auth) => {
+                let auth_name = String::from_utf8_lossy(auth.auth_name)
+                    .trim_end_matches('\0')
+                    .to_string();
+                let obj = PyAuthInit::new(auth.cid, auth.level, auth_name);
+                let obj = keep_recorded(obj, &Chunk::AuthInit(auth), PyAuthInit::with_recorded)?;
+                Ok(Py::new(py, obj)?.into())
+            }
+
             Chunk::AuthLogin(auth) => {
                 let auth_name = String::from_utf8_lossy(auth.auth_name)
                     .trim_end_matches('\0')
                     .to_string();
                 let obj = PyAuthLogin::new(auth.cid, auth.level, auth_name);
+                let obj = keep_recorded(obj, &Chunk::AuthLogin(auth), PyAuthLogin::with_recorded)?;
+                Ok(Py::new(py, obj)?.into())
+            }
+
+            Chunk::AuthLogout { cid } => {
+                let obj = PyAuthLogout::new(cid);
                 Ok(Py::new(py, obj)?.into())
             }
 
@@ -256,12 +695,30 @@ impl<'a> ChunkConverter<'a> {
                 Ok(Py::new(py, obj)?.into())
             }
 
+            Chunk::TeamSaveSuccess(team_save) => {
+                let save_id_str = team_save.save_id.to_string();
+                let save_str = String::from_utf8_lossy(team_save.save)
+                    .trim_end_matches('\0')
+                    .to_string();
+                let obj = PyTeamSaveSuccess::new(team_save.team, save_id_str, save_str);
+                let chunk = Chunk::TeamSaveSuccess(team_save);
+                let obj = keep_recorded(obj, &chunk, PyTeamSaveSuccess::with_recorded)?;
+                Ok(Py::new(py, obj)?.into())
+            }
+
+            Chunk::TeamSaveFailure { team } => {
+                let obj = PyTeamSaveFailure::new(team);
+                Ok(Py::new(py, obj)?.into())
+            }
+
             Chunk::TeamLoadSuccess(team_load) => {
                 let save_id_str = team_load.save_id.to_string();
                 let save_str = String::from_utf8_lossy(team_load.save)
                     .trim_end_matches('\0')
                     .to_string();
                 let obj = PyTeamLoadSuccess::new(team_load.team, save_id_str, save_str);
+                let chunk = Chunk::TeamLoadSuccess(team_load);
+                let obj = keep_recorded(obj, &chunk, PyTeamLoadSuccess::with_recorded)?;
                 Ok(Py::new(py, obj)?.into())
             }
 
@@ -270,10 +727,23 @@ impl<'a> ChunkConverter<'a> {
                 Ok(Py::new(py, obj)?.into())
             }
 
+            Chunk::TeamPractice { team, practice } => {
+                let obj = PyTeamPractice::new(team, practice);
+                Ok(Py::new(py, obj)?.into())
+            }
+
+            Chunk::TeamFinish { team, time } => {
+                let obj = PyTeamFinish::new(team, time);
+                Ok(Py::new(py, obj)?.into())
+            }
+
+            Chunk::PlayerSwap { cid1, cid2 } => {
+                let obj = PyPlayerSwap::new(cid1, cid2);
+                Ok(Py::new(py, obj)?.into())
+            }
+
             Chunk::Antibot(data) => {
-                // Convert bytes to String (lossy for non-UTF8)
-                let data_str = String::from_utf8_lossy(data.data).to_string();
-                let obj = PyAntiBot::new(data_str);
+                let obj = PyAntiBot::new(data.data.to_vec());
                 Ok(Py::new(py, obj)?.into())
             }
 
@@ -283,37 +753,46 @@ impl<'a> ChunkConverter<'a> {
                 Ok(Py::new(py, obj)?.into())
             }
 
+            Chunk::Test => {
+                let obj = PyTestChunk::new();
+                Ok(Py::new(py, obj)?.into())
+            }
+
             Chunk::UnknownEx(unknown_data) => {
                 let uuid_str = unknown_data.uuid.to_string();
                 let data = unknown_data.data.to_vec();
 
                 // Check if we have a registered handler for this UUID
                 if let Some(handler) = self.handlers.get(&uuid_str) {
-                    let obj = PyCustomChunk::new(
+                    if let Some(decoder) = &handler.decoder {
+                        let payload = pyo3::types::PyBytes::new(py, &data);
+                        return decoder.call1(py, (uuid_str, payload));
+                    }
+                    let mut obj = PyCustomChunk::new(
                         handler.uuid().to_string(),
                         data,
                         handler.name().to_string(),
                     );
-                    Ok(Py::new(py, obj)?.into())
+                    if let Some(fields) = handler
+                        .fields
+                        .as_ref()
+                        .and_then(|def| def.decode(&obj.data))
+                    {
+                        obj = obj.with_fields(fields);
+                    }
+                    let obj = Py::new(py, obj)?.into_any();
+                    match &handler.handler {
+                        Some(stateful) => {
+                            let derived = stateful.call_method1(py, "on_chunk", (&obj, tick))?;
+                            Ok(if derived.is_none(py) { obj } else { derived })
+                        }
+                        None => Ok(obj),
+                    }
                 } else {
                     let obj = PyUnknown::new(uuid_str, data);
                     Ok(Py::new(py, obj)?.into())
                 }
             }
-
-            // Fallback for any unhandled chunk types
-            _ => {
-                // For unhandled chunks, create a Generic chunk with debug representation
-                // This is a temporary fallback - ideally all chunks should have explicit handlers
-                let chunk_str = format!("{:?}", chunk);
-                log::warn!(
-                    "Unhandled chunk type encountered: {}. Using Generic fallback. \
-                     This chunk may not roundtrip correctly.",
-                    chunk_str
-                );
-                let obj = PyGeneric::new(chunk_str);
-                Ok(Py::new(py, obj)?.into())
-            }
         }
     }
 }
@@ -330,9 +809,55 @@ mod tests {
         let handler = handler.unwrap();
         assert_eq!(handler.uuid(), "test-uuid");
         assert_eq!(handler.name(), "test-uuid");
+        assert_eq!(handler.kind(), "none");
 
         // Invalid handler (empty UUID)
         let handler = UuidHandler::new("".to_string());
         assert!(handler.is_err());
     }
+
+    #[test]
+    fn test_type_name() {
+        let handlers = Arc::new(HashMap::new());
+        let converter = ChunkConverter::new(&handlers);
+        assert_eq!(converter.type_name(&Chunk::Join { cid: 0 }), "Join");
+        assert_eq!(
+            converter.type_name(&Chunk::Antibot(teehistorian::chunks::Antibot { data: b"" })),
+            "AntiBot"
+        );
+        assert_eq!(
+            converter.type_name(&Chunk::TeamPractice {
+                team: 0,
+                practice: 1
+            }),
+            "TeamPractice"
+        );
+
+        for chunk in [Chunk::Eos, Chunk::TickSkip { dt: 0 }, Chunk::Test] {
+            assert!(known_chunk_type(converter.type_name(&chunk)).is_some());
+        }
+        assert_eq!(known_chunk_type("Nope"), None);
+    }
+
+    #[test]
+    fn test_categories_cover_all_types() {
+        let mut categorized: Vec<&str> = CHUNK_CATEGORIES
+            .iter()
+            .flat_map(|(_, types)| types.iter().copied())
+            .collect();
+        categorized.sort_unstable();
+
+        let mut known = CHUNK_TYPE_NAMES.to_vec();
+        known.sort_unstable();
+        assert_eq!(categorized, known);
+
+        assert_eq!(chunk_category("InputDiff"), "Input");
+        assert_eq!(chunk_category("AntiBot"), "GameEvent");
+
+        assert_eq!(
+            category_chunk_types("Input"),
+            Some(&["InputNew", "InputDiff"][..])
+        );
+        assert_eq!(category_chunk_types("Nope"), None);
+    }
 }
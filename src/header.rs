@@ -0,0 +1,627 @@
+//! Typed view of the JSON header
+//!
+//! DDNet writes every header value as a JSON string, numbers included.
+//! `Header` converts the well-known fields to proper Python types; fields the
+//! server did not write are `None`.
+use std::collections::HashMap;
+
+use pyo3::IntoPyObjectExt;
+use pyo3::prelude::*;
+use pyo3::types::{
+    PyBool, PyBytes, PyDateTime, PyDelta, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple,
+    PyTzInfo,
+};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::errors::TeehistorianParseError;
+use crate::ticks::TICK_SPEED;
+use crate::tuning::PyTuning;
+use crate::writer::PyTeehistorianWriter;
+use crate::{MAGIC, format, input};
+
+/// Start of a recording as written by DDNet, e.g. `2024-05-01T18:30:00+0200`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StartTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    /// Offset from UTC in minutes
+    offset_minutes: i32,
+}
+
+impl StartTime {
+    /// Parse `YYYY-MM-DDTHH:MM:SS` followed by `+HHMM`, `+HH:MM` or `Z`
+    fn parse(s: &str) -> Option<Self> {
+        let number = |range: std::ops::Range<usize>| -> Option<u32> {
+            let digits = s.get(range)?;
+            digits
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| digits.parse().ok())?
+        };
+        let separators = [(4, b'-'), (7, b'-'), (10, b'T'), (13, b':'), (16, b':')];
+        if s.len() < 19 || separators.iter().any(|&(i, c)| s.as_bytes()[i] != c) {
+            return None;
+        }
+
+        let time = Self {
+            year: number(0..4)? as i32,
+            month: number(5..7)? as u8,
+            day: number(8..10)? as u8,
+            hour: number(11..13)? as u8,
+            minute: number(14..16)? as u8,
+            second: number(17..19)? as u8,
+            offset_minutes: match s.get(19..)? {
+                "Z" => 0,
+                offset => {
+                    let sign = match offset.as_bytes().first()? {
+                        b'+' => 1,
+                        b'-' => -1,
+                        _ => return None,
+                    };
+                    let (hours, minutes) = match offset.len() {
+                        5 => (number(20..22)?, number(22..24)?),
+                        6 if offset.as_bytes()[3] == b':' => (number(20..22)?, number(23..25)?),
+                        _ => return None,
+                    };
+                    sign * (hours * 60 + minutes) as i32
+                }
+            },
+        };
+
+        let valid = (1..=12).contains(&time.month)
+            && (1..=31).contains(&time.day)
+            && time.hour < 24
+            && time.minute < 60
+            && time.second < 61
+            && time.offset_minutes.abs() < 24 * 60;
+        valid.then_some(time)
+    }
+}
+
+/// Extension UUID registered in the header of a recording
+///
+/// `TeehistorianWriter` records the custom chunks it knows about in the
+/// `__teehistorian_py` header field so parsers can decode them. Returned by
+/// `Teehistorian.uuid_registrations`.
+#[pyclass(
+    name = "UuidRegistration",
+    module = "teehistorian_py",
+    frozen,
+    get_all,
+    eq
+)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyUuidRegistration {
+    pub uuid: String,
+    pub name: String,
+}
+
+#[pymethods]
+impl PyUuidRegistration {
+    #[new]
+    fn new(uuid: String, name: String) -> Self {
+        Self { uuid, name }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self).replacen("Py", "", 1)
+    }
+}
+
+/// Header of a teehistorian file
+///
+/// Obtained from `Teehistorian.header_info`.
+#[pyclass(name = "Header", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyHeader {
+    fields: Map<String, Value>,
+}
+
+impl PyHeader {
+    /// Parse the JSON header between the magic UUID and the first chunk
+    pub fn parse(header: &[u8]) -> PyResult<Self> {
+        let json: Value = serde_json::from_slice(header).map_err(|e| {
+            TeehistorianParseError::Header(format!("Failed to parse header JSON: {}", e))
+        })?;
+        Self::from_json(json)
+    }
+
+    /// Header with the fields of a JSON object
+    pub fn from_json(json: Value) -> PyResult<Self> {
+        match json {
+            Value::Object(fields) => Ok(Self { fields }),
+            _ => Err(
+                TeehistorianParseError::Header("Header is not a JSON object".to_string()).into(),
+            ),
+        }
+    }
+
+    /// All header fields as a JSON object
+    pub fn to_json(&self) -> Value {
+        Value::Object(self.fields.clone())
+    }
+
+    /// Convert all header fields to a Python dict
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, value) in &self.fields {
+            dict.set_item(key, json_to_py(py, value)?)?;
+        }
+        Ok(dict)
+    }
+
+    /// Extension UUIDs registered in the `__teehistorian_py` header field
+    pub fn uuid_registrations(&self) -> Vec<PyUuidRegistration> {
+        let chunks = self
+            .fields
+            .get("__teehistorian_py")
+            .and_then(|meta| meta.get("chunks"))
+            .and_then(Value::as_object);
+        chunks
+            .into_iter()
+            .flatten()
+            .map(|(uuid, def)| {
+                let name = def.get("name").and_then(Value::as_str).unwrap_or_default();
+                PyUuidRegistration::new(uuid.clone(), name.to_string())
+            })
+            .collect()
+    }
+
+    fn str_field(&self, key: &str) -> Option<&str> {
+        self.fields.get(key)?.as_str()
+    }
+
+    /// Get a nested object field with its values as strings
+    ///
+    /// Values that are not strings are converted to their JSON text.
+    fn str_map(&self, key: &str) -> HashMap<String, String> {
+        let Some(Value::Object(map)) = self.fields.get(key) else {
+            return HashMap::new();
+        };
+        map.iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    Value::String(value) => value.clone(),
+                    other => other.to_string(),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+/// Convert a JSON value to the Python object `json.loads` would return
+pub(crate) fn json_to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        Value::Null => Ok(py.None().into_bound(py)),
+        Value::Bool(b) => b.into_bound_py_any(py),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_bound_py_any(py),
+            (None, Some(u)) => u.into_bound_py_any(py),
+            _ => n.as_f64().into_bound_py_any(py),
+        },
+        Value::String(s) => s.into_bound_py_any(py),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| json_to_py(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new(py, items)?.into_any())
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, value) in fields {
+                dict.set_item(key, json_to_py(py, value)?)?;
+            }
+            Ok(dict.into_any())
+        }
+    }
+}
+
+/// Convert a Python value to JSON the way `json.dumps` would
+///
+/// Takes `None`, bools, ints, floats, strings, lists, tuples and dicts with
+/// string keys.
+pub(crate) fn py_to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let unsupported = |what: String| TeehistorianParseError::Validation(what).into();
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = value.cast::<PyBool>() {
+        Ok(Value::Bool(b.is_true()))
+    } else if let Ok(i) = value.cast::<PyInt>() {
+        match i.extract::<i64>() {
+            Ok(i) => Ok(i.into()),
+            Err(_) => Ok(i.extract::<u64>()?.into()),
+        }
+    } else if let Ok(f) = value.cast::<PyFloat>() {
+        serde_json::Number::from_f64(f.value())
+            .map(Value::Number)
+            .ok_or_else(|| unsupported(format!("{} cannot be stored in JSON", f.value())))
+    } else if let Ok(s) = value.cast::<PyString>() {
+        Ok(Value::String(s.to_str()?.to_string()))
+    } else if let Ok(dict) = value.cast::<PyDict>() {
+        let mut fields = Map::new();
+        for (key, item) in dict.iter() {
+            let key = key.cast_into::<PyString>().map_err(|e| {
+                unsupported(format!(
+                    "Header keys must be str, not {}",
+                    e.into_inner().get_type()
+                ))
+            })?;
+            fields.insert(key.to_str()?.to_string(), py_to_json(&item)?);
+        }
+        Ok(Value::Object(fields))
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        value
+            .try_iter()?
+            .map(|item| py_to_json(&item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array)
+    } else {
+        Err(unsupported(format!(
+            "{} cannot be stored in the header",
+            value.get_type()
+        )))
+    }
+}
+
+#[pymethods]
+impl PyHeader {
+    /// Name of the map the recording was made on
+    #[getter]
+    fn map_name(&self) -> Option<&str> {
+        self.str_field("map_name")
+    }
+
+    /// SHA-256 of the map file as a hex string
+    #[getter]
+    fn map_sha256(&self) -> Option<&str> {
+        self.str_field("map_sha256")
+    }
+
+    /// CRC32 of the map file
+    #[getter]
+    fn map_crc(&self) -> Option<u32> {
+        u32::from_str_radix(self.str_field("map_crc")?, 16).ok()
+    }
+
+    /// Version string of the recording server
+    #[getter]
+    fn server_version(&self) -> Option<&str> {
+        self.str_field("server_version")
+    }
+
+    /// Start of the recording as written by the server
+    ///
+    /// DDNet uses the format `2024-05-01T18:30:00+0200`.
+    #[getter]
+    fn start_time(&self) -> Option<&str> {
+        self.str_field("start_time")
+    }
+
+    /// Start of the recording as a timezone-aware datetime
+    ///
+    /// Carries the UTC offset the server wrote. None if the start time is
+    /// missing or not in DDNet's format.
+    #[getter]
+    fn start_datetime<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDateTime>>> {
+        let Some(time) = self.start_time().and_then(StartTime::parse) else {
+            return Ok(None);
+        };
+        let offset = PyDelta::new(py, 0, time.offset_minutes * 60, 0, true)?;
+        let tzinfo = PyTzInfo::fixed_offset(py, offset)?;
+        let datetime = PyDateTime::new(
+            py,
+            time.year,
+            time.month,
+            time.day,
+            time.hour,
+            time.minute,
+            time.second,
+            0,
+            Some(&tzinfo),
+        )?;
+        Ok(Some(datetime))
+    }
+
+    /// Convert a tick to wall-clock time
+    ///
+    /// # Arguments
+    /// * `tick` - Tick counted from the start of the recording, as reported
+    ///   by the parser
+    ///
+    /// # Returns
+    /// `start_datetime` plus the tick's offset at the server tick rate, or
+    /// None if the header has no usable start time
+    fn tick_to_datetime<'py>(
+        &self,
+        py: Python<'py>,
+        tick: i32,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let Some(start) = self.start_datetime(py)? else {
+            return Ok(None);
+        };
+        let elapsed = PyDelta::new(
+            py,
+            0,
+            tick.div_euclid(TICK_SPEED),
+            tick.rem_euclid(TICK_SPEED) * (1_000_000 / TICK_SPEED),
+            true,
+        )?;
+        Ok(Some(start.add(elapsed)?))
+    }
+
+    /// UUID of the recorded game
+    #[getter]
+    fn game_uuid(&self) -> Option<&str> {
+        self.str_field("game_uuid")
+    }
+
+    /// Description of the server's random number generator
+    ///
+    /// DDNet writes the algorithm followed by the two 64-bit seed words, e.g.
+    /// `pcg-xsh-rr:0123456789abcdef:fedcba9876543210`.
+    #[getter]
+    fn prng_description(&self) -> Option<&str> {
+        self.str_field("prng_description")
+    }
+
+    /// Seed of the server's random number generator
+    ///
+    /// The `(state, increment)` words from `prng_description`, or None if the
+    /// description does not name them.
+    #[getter]
+    fn prng_seed(&self) -> Option<(u64, u64)> {
+        let mut parts = self.prng_description()?.split(':').skip(1);
+        let state = u64::from_str_radix(parts.next()?, 16).ok()?;
+        let increment = u64::from_str_radix(parts.next()?, 16).ok()?;
+        Some((state, increment))
+    }
+
+    /// Server config the recording was made under
+    ///
+    /// Maps setting names such as `sv_motd` to their values. Empty if the
+    /// header holds no config.
+    #[getter]
+    fn config(&self) -> HashMap<String, String> {
+        self.str_map("config")
+    }
+
+    /// Physics tuning the recording was made with
+    #[getter]
+    fn tuning(&self) -> PyTuning {
+        PyTuning::from_header(&self.str_map("tuning"))
+    }
+
+    /// Check whether two recordings come from equivalent server setups
+    ///
+    /// True if the map hashes, the server version and the tuning match.
+    fn compatible_with(&self, py: Python<'_>, other: &PyHeader) -> PyResult<bool> {
+        Ok(self.diff(py, other)?.is_empty())
+    }
+
+    /// Check the header against the fields DDNet always writes
+    ///
+    /// # Returns
+    /// Descriptions of the missing or malformed fields; empty if the header
+    /// conforms
+    fn validate(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        let mut check =
+            |key: &str, expected: &str, well_formed: fn(&str) -> bool| match self.fields.get(key) {
+                None => violations.push(format!("Missing field '{}'", key)),
+                Some(Value::String(value)) if well_formed(value) => {}
+                Some(value) => {
+                    violations.push(format!("Field '{}' is not {}: {}", key, expected, value))
+                }
+            };
+
+        check("version", "an integer", |v| v.trim().parse::<i32>().is_ok());
+        check("game_uuid", "a UUID", |v| Uuid::parse_str(v).is_ok());
+        check("start_time", "a timestamp", |v| {
+            StartTime::parse(v).is_some()
+        });
+        check("map_name", "a non-empty string", |v| !v.is_empty());
+        check("map_sha256", "a SHA-256 hex digest", |v| {
+            v.len() == 64 && v.bytes().all(|b| b.is_ascii_hexdigit())
+        });
+        violations
+    }
+
+    /// Get the differences in server setup to another recording
+    ///
+    /// Compares the map hashes, the server version and every tuning
+    /// parameter. Differing tuning parameters are named `tuning.<name>`.
+    ///
+    /// # Returns
+    /// Dictionary mapping each differing field to a `(self, other)` tuple
+    pub(crate) fn diff<'py>(
+        &self,
+        py: Python<'py>,
+        other: &PyHeader,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for key in ["map_sha256", "map_crc", "server_version"] {
+            let (ours, theirs) = (self.str_field(key), other.str_field(key));
+            if ours != theirs {
+                dict.set_item(key, (ours, theirs))?;
+            }
+        }
+
+        let (ours, theirs) = (self.tuning(), other.tuning());
+        for name in PyTuning::NAMES {
+            let (ours, theirs) = (ours.get(name), theirs.get(name));
+            if ours != theirs {
+                dict.set_item(format!("tuning.{}", name), (ours, theirs))?;
+            }
+        }
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Header(map_name='{}', server_version='{}', start_time='{}')",
+            self.map_name().unwrap_or_default(),
+            self.server_version().unwrap_or_default(),
+            self.start_time().unwrap_or_default()
+        )
+    }
+}
+
+/// Apply a JSON merge patch (RFC 7386) to `target`
+///
+/// Objects are merged key by key, `null` removes a key and anything else
+/// replaces the target value.
+fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge_patch(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Replace fields of the JSON header, keeping the chunks byte for byte
+///
+/// Values are handled like `TeehistorianWriter.set_header()`: JSON objects
+/// and arrays are parsed, everything else is stored as a string. Objects
+/// are merged into the existing value, with `null` removing a nested key,
+/// and a value of None removes the field. Compressed data is decompressed;
+/// the result is always uncompressed.
+///
+/// Raises ValidationError if the data is not a teehistorian file.
+#[pyfunction]
+pub fn rewrite_header<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    new_fields: HashMap<String, Option<String>>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let data = input::decode_input(data)?;
+    format::check_magic(&data)?;
+    let header = format::read_header(&data)?;
+    let chunks = &data[MAGIC.len() + header.len() + 1..];
+
+    let mut fields = Value::Object(PyHeader::parse(header)?.fields);
+    for (key, value) in new_fields {
+        let patch = match value {
+            Some(value) => PyTeehistorianWriter::parse_header_value(&value),
+            None => Value::Null,
+        };
+        merge_patch(&mut fields, Value::Object(Map::from_iter([(key, patch)])));
+    }
+
+    let header = serde_json::to_vec(&fields).map_err(|e| {
+        TeehistorianParseError::Header(format!("Failed to serialize header: {}", e))
+    })?;
+    let mut out = Vec::with_capacity(MAGIC.len() + header.len() + 1 + chunks.len());
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&header);
+    out.push(0);
+    out.extend_from_slice(chunks);
+    Ok(PyBytes::new(py, &out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_fields() {
+        let header = PyHeader::parse(
+            br#"{"map_name": "Kobra 4", "map_crc": "0a1b2c3d", "server_version": "0.6.4, 18.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(header.map_name(), Some("Kobra 4"));
+        assert_eq!(header.map_crc(), Some(0x0a1b2c3d));
+        assert_eq!(header.server_version(), Some("0.6.4, 18.0"));
+        assert_eq!(header.map_sha256(), None);
+        assert_eq!(header.game_uuid(), None);
+        assert!(header.config().is_empty());
+        assert_eq!(header.prng_seed(), None);
+    }
+
+    #[test]
+    fn test_start_time() {
+        let time = StartTime::parse("2024-05-01T18:30:00+0200").unwrap();
+        assert_eq!((time.year, time.month, time.day), (2024, 5, 1));
+        assert_eq!((time.hour, time.minute, time.second), (18, 30, 0));
+        assert_eq!(time.offset_minutes, 120);
+        assert_eq!(
+            StartTime::parse("2024-05-01T18:30:00-05:30")
+                .unwrap()
+                .offset_minutes,
+            -330
+        );
+        assert_eq!(
+            StartTime::parse("2024-05-01T18:30:00Z")
+                .unwrap()
+                .offset_minutes,
+            0
+        );
+        assert_eq!(StartTime::parse("2024-13-01T18:30:00+0200"), None);
+        assert_eq!(StartTime::parse("2024-05-01 18:30:00"), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        let header = PyHeader::parse(br#"{"version": "2", "map_sha256": "abc"}"#).unwrap();
+        let violations = header.validate();
+        assert_eq!(violations.len(), 4);
+        assert!(violations.contains(&"Missing field 'game_uuid'".to_string()));
+        assert!(violations.iter().any(|v| v.contains("map_sha256")));
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target =
+            serde_json::json!({"a": "1", "config": {"sv_motd": "hi", "sv_rcon_password": "x"}});
+        merge_patch(
+            &mut target,
+            serde_json::json!({"a": "2", "config": {"sv_rcon_password": null}}),
+        );
+        assert_eq!(
+            target,
+            serde_json::json!({"a": "2", "config": {"sv_motd": "hi"}})
+        );
+    }
+
+    #[test]
+    fn test_prng_seed() {
+        let header = PyHeader::parse(
+            br#"{"prng_description": "pcg-xsh-rr:0123456789abcdef:fedcba9876543210"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            header.prng_seed(),
+            Some((0x0123456789abcdef, 0xfedcba9876543210))
+        );
+
+        let header = PyHeader::parse(br#"{"prng_description": "pcg-xsh-rr"}"#).unwrap();
+        assert_eq!(header.prng_seed(), None);
+    }
+
+    #[test]
+    fn test_config() {
+        let header =
+            PyHeader::parse(br#"{"config": {"sv_motd": "Welcome", "sv_max_clients": 64}}"#)
+                .unwrap();
+        let config = header.config();
+        assert_eq!(config["sv_motd"], "Welcome");
+        assert_eq!(config["sv_max_clients"], "64");
+    }
+}
@@ -0,0 +1,122 @@
+//! Input decoding
+//!
+//! Teehistorian archives are commonly stored compressed. This module detects
+//! the container format from its magic bytes and decompresses it so the
+//! parser always works on the raw teehistorian stream.
+use std::io::Read;
+
+use flate2::read::MultiGzDecoder;
+use ruzstd::decoding::StreamingDecoder;
+
+use crate::errors::{Result, TeehistorianParseError};
+
+/// Magic bytes at the start of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Magic bytes at the start of a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Return the raw teehistorian stream contained in `data`
+///
+/// Compressed input is decompressed straight into the returned buffer;
+/// anything else is copied as-is.
+pub fn decode_input(data: &[u8]) -> Result<Vec<u8>> {
+    match decode_input_partial(data) {
+        (out, None) => Ok(out),
+        (_, Some(e)) => Err(TeehistorianParseError::Parse(e)),
+    }
+}
+
+/// Like `decode_input()`, but keep what was decompressed before an error
+///
+/// A truncated or damaged archive gives the data up to the damage together
+/// with a description of the decompression error, so it can still be salvaged.
+pub fn decode_input_partial(data: &[u8]) -> (Vec<u8>, Option<String>) {
+    let mut out = Vec::with_capacity(data.len().saturating_mul(4));
+    let error = if data.starts_with(&GZIP_MAGIC) {
+        MultiGzDecoder::new(data)
+            .read_to_end(&mut out)
+            .err()
+            .map(|e| format!("Failed to decompress gzip data: {}", e))
+    } else if data.starts_with(&ZSTD_MAGIC) {
+        decode_zstd(data, &mut out).err()
+    } else {
+        out.extend_from_slice(data);
+        None
+    };
+    (out, error)
+}
+
+/// Decompress every zstd frame in `data` into `out`
+///
+/// Frames are decoded through a streaming decoder, which only keeps the
+/// frame's window (capped at 100 MiB by `ruzstd`) besides the output.
+fn decode_zstd(data: &[u8], out: &mut Vec<u8>) -> std::result::Result<(), String> {
+    let error = |e: &dyn std::fmt::Display| format!("Failed to decompress zstd data: {}", e);
+
+    let mut input = data;
+    while !input.is_empty() {
+        let mut decoder = StreamingDecoder::new(&mut input).map_err(|e| error(&e))?;
+        decoder.read_to_end(out).map_err(|e| error(&e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    use super::*;
+
+    #[test]
+    fn test_plain_input_is_copied() {
+        assert_eq!(decode_input(b"plain").unwrap(), b"plain");
+    }
+
+    #[test]
+    fn test_gzip_input_is_decompressed() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"teehistorian").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decode_input(&compressed).unwrap(), b"teehistorian");
+    }
+
+    #[test]
+    fn test_zstd_input_is_decompressed() {
+        use ruzstd::encoding::{CompressionLevel, compress_to_vec};
+
+        let mut compressed = compress_to_vec(&b"tee"[..], CompressionLevel::Fastest);
+        compressed.extend(compress_to_vec(
+            &b"historian"[..],
+            CompressionLevel::Fastest,
+        ));
+
+        assert_eq!(decode_input(&compressed).unwrap(), b"teehistorian");
+    }
+
+    #[test]
+    fn test_corrupt_zstd_is_rejected() {
+        assert!(decode_input(&[0x28, 0xb5, 0x2f, 0xfd, 0xff]).is_err());
+    }
+
+    #[test]
+    fn test_truncated_gzip_keeps_decompressed_data() {
+        let data = vec![7u8; 100_000];
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::none());
+        encoder.write_all(&data).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let (out, error) = decode_input_partial(&compressed[..compressed.len() / 2]);
+        assert!(error.is_some());
+        assert!(!out.is_empty() && data.starts_with(&out));
+    }
+
+    #[test]
+    fn test_corrupt_gzip_is_rejected() {
+        assert!(decode_input(&[0x1f, 0x8b, 0x00, 0x01]).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Input statistics per player
+//!
+//! Inputs are written whenever they change, so comparing each input with
+//! the previous one of the same client tells which buttons were pressed and
+//! how far the mouse moved. The counts are aggregated in Rust and only the
+//! totals become Python objects.
+use std::collections::{BTreeMap, HashMap};
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use teehistorian::Chunk;
+
+use crate::player_input::PyPlayerInput;
+use crate::resolve::InputResolver;
+use crate::ticks::TICK_SPEED;
+use crate::walk_recording;
+
+/// DDNet counts fire presses modulo 64
+const FIRE_COUNTER_MASK: i32 = 0x3f;
+
+/// Number of fire presses between two values of the fire counter
+///
+/// The counter is incremented on every press and on every release, so it
+/// is odd while fire is held.
+fn fire_presses(old: i32, new: i32) -> u64 {
+    let steps = new.wrapping_sub(old) & FIRE_COUNTER_MASK;
+    let presses = if old & 1 == 0 { steps + 1 } else { steps };
+    (presses / 2) as u64
+}
+
+/// Button presses between two inputs of a client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Presses {
+    pub jumps: u64,
+    pub hooks: u64,
+    pub fires: u64,
+    pub direction_changes: u64,
+}
+
+impl Presses {
+    pub fn between(old: &PyPlayerInput, new: &PyPlayerInput) -> Self {
+        Self {
+            jumps: u64::from(old.jump == 0 && new.jump != 0),
+            hooks: u64::from(old.hook == 0 && new.hook != 0),
+            fires: fire_presses(old.fire, new.fire),
+            direction_changes: u64::from(old.direction != new.direction),
+        }
+    }
+
+    /// All presses and direction changes
+    pub fn total(&self) -> u64 {
+        self.jumps + self.hooks + self.fires + self.direction_changes
+    }
+}
+
+/// Totals of one client
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PlayerStats {
+    inputs: u64,
+    jumps: u64,
+    hooks: u64,
+    fires: u64,
+    direction_changes: u64,
+    mouse_distance: f64,
+    /// Ticks from the first input of each session to its end
+    active_ticks: i64,
+}
+
+impl PlayerStats {
+    /// Account for a change from input `old` to `new`
+    fn observe(&mut self, old: &PyPlayerInput, new: &PyPlayerInput) {
+        let presses = Presses::between(old, new);
+        self.inputs += 1;
+        self.jumps += presses.jumps;
+        self.hooks += presses.hooks;
+        self.fires += presses.fires;
+        self.direction_changes += presses.direction_changes;
+        // Targets come from the client and can be anything, even i32 extremes
+        let dx = f64::from(new.target_x) - f64::from(old.target_x);
+        let dy = f64::from(new.target_y) - f64::from(old.target_y);
+        self.mouse_distance += dx.hypot(dy);
+    }
+
+    fn active_seconds(&self) -> f64 {
+        self.active_ticks as f64 / f64::from(TICK_SPEED)
+    }
+
+    /// Per minute of activity, 0 without activity
+    fn per_minute(&self, count: u64) -> f64 {
+        if self.active_ticks > 0 {
+            (count * 60) as f64 * f64::from(TICK_SPEED) / self.active_ticks as f64
+        } else {
+            0.0
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let actions = self.jumps + self.hooks + self.fires + self.direction_changes;
+        let dict = PyDict::new(py);
+        dict.set_item("inputs", self.inputs)?;
+        dict.set_item("jumps", self.jumps)?;
+        dict.set_item("hooks", self.hooks)?;
+        dict.set_item("fires", self.fires)?;
+        dict.set_item("direction_changes", self.direction_changes)?;
+        dict.set_item("mouse_distance", self.mouse_distance)?;
+        dict.set_item("active_seconds", self.active_seconds())?;
+        dict.set_item("apm", self.per_minute(actions))?;
+        dict.set_item(
+            "direction_changes_per_minute",
+            self.per_minute(self.direction_changes),
+        )?;
+        Ok(dict)
+    }
+}
+
+/// Input statistics of every client seen so far
+#[derive(Debug, Default)]
+struct InputStats {
+    players: BTreeMap<i32, PlayerStats>,
+    /// Input of every client, by the InputResolver's rules
+    inputs: InputResolver,
+    /// Tick of the first input of every client's current session
+    active_since: HashMap<i32, i32>,
+}
+
+impl InputStats {
+    fn observe(&mut self, chunk: Chunk, tick: i32) {
+        let cid = match chunk {
+            Chunk::InputNew(ref new) => new.cid,
+            Chunk::InputDiff(ref diff) => diff.cid,
+            Chunk::Drop(ref drop) => {
+                self.end_session(drop.cid, tick);
+                self.inputs.resolve(chunk);
+                return;
+            }
+            _ => return,
+        };
+        let old = self.inputs.input(cid).copied();
+        let Chunk::InputNew(new) = self.inputs.resolve(chunk) else {
+            return;
+        };
+        let stats = self.players.entry(cid).or_default();
+        self.active_since.entry(cid).or_insert(tick);
+        // The first input of a session only sets where the changes start
+        if let Some(old) = old {
+            stats.observe(
+                &PyPlayerInput::from_slice(&old),
+                &PyPlayerInput::from_slice(&new.input),
+            );
+        }
+    }
+
+    fn end_session(&mut self, cid: i32, tick: i32) {
+        if let Some(since) = self.active_since.remove(&cid) {
+            self.players.entry(cid).or_default().active_ticks += i64::from(tick - since);
+        }
+    }
+
+    /// End every session at the end of a stream
+    fn end_stream(&mut self, tick: i32) {
+        let cids: Vec<i32> = self.active_since.keys().copied().collect();
+        for cid in cids {
+            self.end_session(cid, tick);
+        }
+        self.inputs = InputResolver::new();
+    }
+}
+
+/// Input statistics of every player in a recording
+///
+/// Compares every input with the previous one of the same client, counting
+/// jump, hook and fire presses and direction changes and adding up how far
+/// the aim moved. Rates are per minute of activity, from the first input of
+/// a session to its `Drop` or the end of the stream.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+///
+/// # Returns
+/// A dict mapping each client ID to a dict of its statistics
+///
+/// # Example
+/// ```python
+/// stats = th.input_stats(data)
+/// df = pandas.DataFrame.from_dict(stats, orient="index")
+/// ```
+#[pyfunction]
+pub fn input_stats<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyDict>> {
+    let mut stats = InputStats::default();
+    let tick = walk_recording(py, data, |chunk, tick| match chunk {
+        Some(chunk) => stats.observe(chunk, tick),
+        None => stats.end_stream(tick),
+    })?;
+    stats.end_stream(tick);
+
+    let dict = PyDict::new(py);
+    for (cid, stats) in stats.players {
+        dict.set_item(cid, stats.to_dict(py)?)?;
+    }
+    Ok(dict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fire_presses() {
+        assert_eq!(fire_presses(0, 0), 0);
+        assert_eq!(fire_presses(0, 1), 1);
+        assert_eq!(fire_presses(1, 2), 0);
+        assert_eq!(fire_presses(0, 2), 1);
+        assert_eq!(fire_presses(1, 4), 1);
+        assert_eq!(fire_presses(2, 7), 3);
+        // The counter wraps around
+        assert_eq!(fire_presses(63, 65), 1);
+    }
+}
@@ -1,75 +1,282 @@
 use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use ouroboros::self_referencing;
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict};
 use teehistorian::{Chunk, Th};
 
+mod anomalies;
+mod anonymize;
+mod antibot;
 mod chunks;
+mod diff;
 mod encoding;
 mod errors;
+mod extensions;
+mod extract;
+mod factory;
+mod filter;
+mod finishes;
+mod follow;
+mod format;
+mod game_msg;
 mod handlers;
+mod header;
+mod input;
+mod input_stats;
 mod macros;
+mod merge;
 mod net_msg;
+mod normalize;
+mod order;
+mod player_input;
 mod registry;
+mod remap;
+mod repair;
+mod resolve;
+mod sample;
+mod scan;
+mod sessions;
+mod slice;
+mod state;
+mod stats;
+mod ticks;
+mod tracker;
+mod transform;
+mod tuning;
+mod validate;
+mod verify;
+mod warnings;
 mod writer;
 
+use anomalies::PyAnomaly;
 use chunks::*;
+use diff::PyRecordingDiff;
 use errors::TeehistorianParseError;
+use filter::{ChunkFilter, resolve_chunk_type};
+use finishes::PyFinish;
+use follow::PyFollowIterator;
 use handlers::*;
+use header::{PyHeader, PyUuidRegistration};
+use net_msg::ClientVersions;
+use normalize::PyRecompressReport;
 use registry::{ChunkDef, FieldFormat, FieldSpec};
+use repair::PyRepairReport;
+use resolve::{InputResolver, PositionResolver};
+use sample::Sampler;
+use sessions::PyPlayerSession;
+use state::PyParserState;
+use stats::PyParserStats;
+use ticks::TickTracker;
+use tracker::{PyPlayerTracker, PySnapshotIterator};
+use tuning::PyTuning;
+use validate::PyValidationReport;
+use verify::PyRunVerification;
+use warnings::{PyParserWarning, WarningLog};
 use writer::*;
 
 /// Type alias for thread-safe handler storage
 type HandlerMap = Arc<HashMap<String, UuidHandler>>;
 
-/// Safe self-referential parser using `ouroboros`
+/// Magic UUID that starts every teehistorian stream
+pub const MAGIC: [u8; 16] = [
+    0x69, 0x9d, 0xb1, 0x7b, 0x8e, 0xfb, 0x34, 0xff, 0xb1, 0xd8, 0xda, 0x6f, 0x60, 0xc1, 0x5d, 0xd1,
+];
+
+/// Length of the magic UUID that starts every teehistorian file
+const MAGIC_LEN: usize = MAGIC.len();
+
+/// Cursor over an in-memory teehistorian file
 ///
-/// This structure safely holds data and a parser that references it, with zero
-/// runtime overhead and no memory leaks. The `ouroboros` crate provides compile-time
-/// guarantees that references remain valid.
-#[self_referencing]
+/// The whole file is kept in one owned buffer and chunks are decoded directly
+/// out of it, so the parser position is a plain byte offset that can be
+/// remembered and rewound without any self-referential borrows.
 struct TeehistorianParserInner {
-    data: Box<[u8]>,
-    #[borrows(data)]
-    #[covariant]
-    parser: Th<&'this [u8]>,
+    data: Vec<u8>,
+    /// Offset of the first chunk, right after the header's NUL terminator
+    chunks_start: usize,
+    offset: usize,
 }
 
 impl TeehistorianParserInner {
     /// Create a new parser from data
     ///
-    /// This is 100% safe with zero memory leaks and no unsafe code.
+    /// The magic UUID and header are validated by the `teehistorian` crate.
     fn from_data(data: Vec<u8>) -> Result<Self, teehistorian::Error> {
-        let data_box = data.into_boxed_slice();
+        let header_len = Th::parse(&data[..])?.header()?.len();
+        let chunks_start = MAGIC_LEN + header_len + 1;
 
-        // Try to build the self-referencing struct
-        #[allow(clippy::borrowed_box)]
-        {
-            TeehistorianParserInnerTryBuilder {
-                data: data_box,
-                parser_builder: |data: &Box<[u8]>| Th::parse(data.as_ref()),
+        Ok(Self {
+            data,
+            chunks_start,
+            offset: chunks_start,
+        })
+    }
+
+    /// Decode the chunk at `offset`, returning it with the offset after it
+    fn decode_at(
+        data: &[u8],
+        offset: usize,
+    ) -> Result<Option<(Chunk<'_>, usize)>, teehistorian::Error> {
+        match teehistorian::chunks::chunk(&data[offset..]) {
+            Ok((rest, chunk)) => Ok(Some((chunk, data.len() - rest.len()))),
+            Err(nom::Err::Incomplete(_)) => Ok(None),
+            Err(nom::Err::Error(kind) | nom::Err::Failure(kind)) => Err(kind.into()),
+        }
+    }
+
+    /// Get the offset of the first chunk of a stream concatenated at `offset`
+    ///
+    /// Some setups append several recordings into one file. The magic UUID
+    /// never decodes as a valid chunk tag, so a header at a chunk boundary is
+    /// unambiguous.
+    fn segment_at(data: &[u8], offset: usize) -> Result<Option<usize>, teehistorian::Error> {
+        if !data[offset..].starts_with(&MAGIC) {
+            return Ok(None);
+        }
+        let header_len = Th::parse(&data[offset..])?.header()?.len();
+        Ok(Some(offset + MAGIC_LEN + header_len + 1))
+    }
+
+    /// Decode every chunk from `start` to the end of the data
+    ///
+    /// `visit` receives each chunk, or `None` for the header of a
+    /// concatenated stream. Returns the offset where the data ran out, or the
+    /// offset and error of the first chunk that failed to decode.
+    fn walk<'a>(
+        data: &'a [u8],
+        start: usize,
+        mut visit: impl FnMut(Option<Chunk<'a>>),
+    ) -> Result<usize, (usize, teehistorian::Error)> {
+        let mut offset = start;
+        loop {
+            let segment = Self::segment_at(data, offset).map_err(|e| (offset, e))?;
+            if let Some(chunks_start) = segment {
+                visit(None);
+                offset = chunks_start;
+                continue;
+            }
+            match Self::decode_at(data, offset).map_err(|e| (offset, e))? {
+                Some((chunk, end)) => {
+                    visit(Some(chunk));
+                    offset = end;
+                }
+                None => return Ok(offset),
             }
-            .try_build()
         }
     }
 
-    /// Get the next chunk from the parser
-    fn next_chunk(&mut self) -> Result<Option<Chunk<'_>>, teehistorian::Error> {
-        self.with_parser_mut(|parser| match parser.next_chunk() {
-            Ok(chunk) => Ok(Some(chunk)),
-            Err(e) if e.is_eof() => Ok(None),
-            Err(e) => Err(e),
-        })
+    /// Move past a concatenated stream header at the cursor, returning the header
+    fn next_segment(&mut self) -> Result<Option<String>, teehistorian::Error> {
+        let Some(chunks_start) = Self::segment_at(&self.data, self.offset)? else {
+            return Ok(None);
+        };
+        let header = &self.data[self.offset + MAGIC_LEN..chunks_start - 1];
+        self.offset = chunks_start;
+        Ok(Some(String::from_utf8_lossy(header).into_owned()))
+    }
+
+    /// Get the next chunk from the parser, with the offset after it
+    fn next_chunk(&mut self) -> Result<Option<(Chunk<'_>, usize)>, teehistorian::Error> {
+        match Self::decode_at(&self.data, self.offset)? {
+            Some((chunk, end)) => {
+                self.offset = end;
+                Ok(Some((chunk, end)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Decode up to `max` chunks from the cursor without moving it
+    ///
+    /// Each chunk is paired with the offset after it. Decoding stops early at
+    /// the end of the data, at the start of a concatenated stream or at the
+    /// first error, which is returned alongside the chunks decoded before it.
+    #[allow(clippy::type_complexity)]
+    fn decode_batch(&self, max: usize) -> (Vec<(Chunk<'_>, usize)>, Option<teehistorian::Error>) {
+        let mut batch = Vec::with_capacity(max.min(4096));
+        let mut offset = self.offset;
+        while batch.len() < max && !self.data[offset..].starts_with(&MAGIC) {
+            match Self::decode_at(&self.data, offset) {
+                Ok(Some((chunk, end))) => {
+                    offset = end;
+                    batch.push((chunk, end));
+                }
+                Ok(None) => break,
+                Err(e) => return (batch, Some(e)),
+            }
+        }
+        (batch, None)
+    }
+
+    /// Whether the cursor sits at a chunk cut off by the end of the data
+    fn is_truncated(&self) -> bool {
+        self.offset < self.data.len()
+            && matches!(Self::decode_at(&self.data, self.offset), Ok(None))
+    }
+
+    /// Append data written after the parser was created
+    ///
+    /// Only used while following a live recording; a chunk that was cut off
+    /// at the old end of the data becomes readable once it is complete.
+    fn extend(&mut self, more: &[u8]) {
+        self.data.extend_from_slice(more);
     }
 
     /// Get header data
-    fn get_header(&mut self) -> Result<Vec<u8>, teehistorian::Error> {
-        self.with_parser_mut(|parser| Ok(parser.header()?.to_vec()))
+    fn get_header(&self) -> &[u8] {
+        &self.data[MAGIC_LEN..self.chunks_start - 1]
+    }
+
+    /// Current byte offset into the file
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Move the cursor to a byte offset previously returned by `offset()`
+    fn seek(&mut self, offset: usize) {
+        self.offset = offset;
     }
 }
 
+/// Decode every chunk of a recording with the GIL released
+///
+/// `visit` receives each chunk with its tick, or `None` with the last tick
+/// before the header of a concatenated stream, where the ticks start over.
+/// Returns the tick of the last chunk.
+fn walk_recording(
+    py: Python<'_>,
+    data: &[u8],
+    mut visit: impl FnMut(Option<Chunk>, i32) + Send,
+) -> PyResult<i32> {
+    let parser = PyTeehistorian::new(data, false, None, false, false, false, "yield")?;
+    let data = &parser.inner.data[..];
+    let start = parser.inner.chunks_start;
+
+    let result = py.detach(|| {
+        let mut ticks = TickTracker::new();
+        let mut tick = 0;
+        TeehistorianParserInner::walk(data, start, |chunk| match chunk {
+            Some(chunk) => {
+                tick = ticks.observe(&chunk);
+                visit(Some(chunk), tick);
+            }
+            None => {
+                visit(None, tick);
+                ticks.restart();
+            }
+        })
+        .map(|_| tick)
+    });
+
+    result.map_err(|(offset, e)| {
+        TeehistorianParseError::Parse(format!("Failed to parse chunk at offset {}: {}", offset, e))
+            .into()
+    })
+}
+
 /// Main Teehistorian parser
 ///
 /// This struct provides a safe, efficient interface for parsing
@@ -79,14 +286,43 @@ pub struct PyTeehistorian {
     inner: TeehistorianParserInner,
     handlers: HandlerMap,
     chunk_count: usize,
+    ticks: TickTracker,
+    /// Filter applied to every chunk read from this parser
+    base_filter: ChunkFilter,
+    /// Byte range of the most recently returned chunk
+    last_raw: Option<Range<usize>>,
+    /// Input state for turning `InputDiff` into absolute `InputNew` chunks
+    inputs: Option<InputResolver>,
+    /// Character positions for turning movement into `PlayerPosition` chunks
+    positions: Option<PositionResolver>,
+    /// What to do with extension chunks of unregistered UUIDs
+    unknown_uuids: UnknownUuidPolicy,
+    warnings: WarningLog,
+    /// Protocol of every client, for parsing its messages
+    versions: ClientVersions,
 }
 
 #[pymethods]
 impl PyTeehistorian {
     /// Create a new Teehistorian parser from raw bytes
     ///
+    /// Gzip (`.teehistorian.gz`) and zstd (`.teehistorian.zst`) compressed
+    /// data is detected by its magic bytes and decompressed transparently.
+    ///
     /// # Arguments
-    /// * `data` - Raw teehistorian file data
+    /// * `data` - Raw or compressed teehistorian file data
+    /// * `skip_inputs` - Discard `InputNew`/`InputDiff` chunks while decoding
+    /// * `exclude_categories` - Discard every chunk in these categories
+    ///   (`"Input"`, `"PlayerState"`, ...) while decoding
+    /// * `emit_warnings` - Also issue each entry of `warnings` through
+    ///   Python's `warnings` module
+    /// * `resolve_inputs` - Apply every `InputDiff` to the client's last input
+    ///   and return it as an `InputNew` with the absolute input
+    /// * `resolve_positions` - Return every `PlayerNew` and `PlayerDiff` as a
+    ///   `PlayerPosition` with the absolute position
+    /// * `unknown_uuid_policy` - What to do with extension chunks of
+    ///   unregistered UUIDs: `"yield"` them as `Unknown`, `"skip"` them or
+    ///   raise an `"error"`
     ///
     /// # Returns
     /// A new parser instance or an error
@@ -98,7 +334,22 @@ impl PyTeehistorian {
     /// parser = Teehistorian(data)
     /// ```
     #[new]
-    fn new(data: &[u8]) -> PyResult<Self> {
+    #[pyo3(signature = (data, *, skip_inputs=false, exclude_categories=None, emit_warnings=false, resolve_inputs=false, resolve_positions=false, unknown_uuid_policy="yield"))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        data: &[u8],
+        skip_inputs: bool,
+        exclude_categories: Option<Vec<String>>,
+        emit_warnings: bool,
+        resolve_inputs: bool,
+        resolve_positions: bool,
+        unknown_uuid_policy: &str,
+    ) -> PyResult<Self> {
+        let unknown_uuids = UnknownUuidPolicy::from_name(unknown_uuid_policy)?;
+        let base_filter = ChunkFilter::default()
+            .skip_inputs(skip_inputs)
+            .exclude_categories(&exclude_categories.unwrap_or_default())?;
+
         // Basic validation
         if data.is_empty() {
             return Err(
@@ -106,6 +357,8 @@ impl PyTeehistorian {
             );
         }
 
+        let data = input::decode_input(data)?;
+
         // Validate minimum file size (teehistorian files have a header)
         if data.len() < 16 {
             return Err(TeehistorianParseError::Validation(
@@ -114,7 +367,7 @@ impl PyTeehistorian {
             .into());
         }
 
-        let parser = TeehistorianParserInner::from_data(data.to_vec()).map_err(|e| {
+        let parser = TeehistorianParserInner::from_data(data).map_err(|e| {
             TeehistorianParseError::Parse(format!("Failed to initialize parser: {}", e))
         })?;
 
@@ -122,6 +375,14 @@ impl PyTeehistorian {
             inner: parser,
             handlers: Arc::new(HashMap::new()),
             chunk_count: 0,
+            ticks: TickTracker::new(),
+            base_filter,
+            last_raw: None,
+            warnings: WarningLog::new(emit_warnings),
+            inputs: resolve_inputs.then(InputResolver::new),
+            positions: resolve_positions.then(PositionResolver::new),
+            unknown_uuids,
+            versions: ClientVersions::default(),
         };
 
         // Parse header metadata and auto-register custom chunks
@@ -134,10 +395,25 @@ impl PyTeehistorian {
     ///
     /// # Arguments
     /// * `uuid_string` - The UUID string to register
+    /// * `decoder` - Optional callable `(uuid, payload) -> object` whose result
+    ///   is yielded instead of a `CustomChunk`, or an `ExtensionHandler` that
+    ///   sees every `CustomChunk` of the UUID
+    /// * `name` - Name of the extension, used as `CustomChunk.handler_name`
+    /// * `fields` - Optional `(name, format)` pairs the payload is decoded into,
+    ///   exposed as attributes of the `CustomChunk`
     ///
     /// # Returns
     /// Ok(()) on success, error on failure
-    fn register_custom_uuid(&mut self, uuid_string: String) -> PyResult<()> {
+    #[pyo3(signature = (uuid_string, decoder=None, *, name=None, fields=None))]
+    fn register_custom_uuid(
+        &mut self,
+        uuid_string: String,
+        decoder: Option<Bound<'_, PyAny>>,
+        name: Option<String>,
+        fields: Option<Vec<(String, String)>>,
+    ) -> PyResult<()> {
+        let mut stateful = None;
+
         // Basic validation only
         if uuid_string.is_empty() {
             return Err(TeehistorianParseError::Validation(
@@ -155,9 +431,63 @@ impl PyTeehistorian {
             .into());
         }
 
+        let decoder = match decoder {
+            Some(handler) if handler.is_instance_of::<PyExtensionHandler>() => {
+                stateful = Some(handler);
+                None
+            }
+            decoder => decoder,
+        };
+        if let Some(decoder) = &decoder
+            && !decoder.is_callable()
+        {
+            return Err(
+                TeehistorianParseError::Validation("decoder must be callable".to_string()).into(),
+            );
+        }
+
         // Create new handler
-        let handler = UuidHandler::new(uuid_string.clone())
+        let mut handler = UuidHandler::new(uuid_string.clone())
             .map_err(|e| TeehistorianParseError::Handler(e.to_string()))?;
+        if let Some(stateful) = stateful {
+            handler = handler.with_handler(stateful.unbind());
+        }
+        if let Some(decoder) = decoder {
+            if fields.is_some() {
+                return Err(TeehistorianParseError::Validation(
+                    "decoder and fields cannot be combined".to_string(),
+                )
+                .into());
+            }
+            handler = handler.with_decoder(decoder.unbind());
+        }
+        if let Some(name) = name {
+            handler = handler.with_name(name);
+        }
+        if let Some(fields) = fields {
+            let fields = fields
+                .into_iter()
+                .map(|(name, format)| {
+                    let format = FieldFormat::from_name(&format).ok_or_else(|| {
+                        TeehistorianParseError::Validation(format!(
+                            "Unknown field format '{}' for field '{}'",
+                            format, name
+                        ))
+                    })?;
+                    Ok(FieldSpec {
+                        name,
+                        format,
+                        description: None,
+                    })
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            let def = ChunkDef {
+                uuid: uuid_string.clone(),
+                name: handler.name().to_string(),
+                fields,
+            };
+            handler = handler.with_fields(def);
+        }
 
         // Use Arc::make_mut for efficient copy-on-write
         let handlers = Arc::make_mut(&mut self.handlers);
@@ -170,35 +500,44 @@ impl PyTeehistorian {
     ///
     /// # Returns
     /// Header bytes or error
-    fn header(&mut self, py: Python<'_>) -> PyResult<Py<PyAny>> {
-        let header_bytes = self
-            .inner
-            .get_header()
-            .map_err(|e| TeehistorianParseError::Header(e.to_string()))?;
-
-        Ok(PyBytes::new(py, &header_bytes).into())
+    fn header(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        Ok(PyBytes::new(py, self.inner.get_header()).into())
     }
 
     /// Get the header data as a JSON string
     ///
     /// # Returns
     /// Header as JSON string or error
-    fn get_header_str(&mut self) -> PyResult<String> {
-        let header_bytes = self
-            .inner
-            .get_header()
-            .map_err(|e| TeehistorianParseError::Header(e.to_string()))?;
-
-        // Parse the header to extract the JSON string
-        // The teehistorian header format is: [compressed header][null terminator][chunks...]
-        // We need to decompress and parse it
-        let header_str = String::from_utf8(header_bytes).map_err(|e| {
+    fn get_header_str(&self) -> PyResult<String> {
+        // The teehistorian header format is: [magic uuid][json header][null terminator][chunks...]
+        let header_str = String::from_utf8(self.inner.get_header().to_vec()).map_err(|e| {
             TeehistorianParseError::Header(format!("Invalid UTF-8 in header: {}", e))
         })?;
 
         Ok(header_str)
     }
 
+    /// Get the header parsed as JSON
+    ///
+    /// # Returns
+    /// Dictionary of all header fields, as `json.loads` would return it
+    ///
+    /// # Errors
+    /// ParseError with the position of the problem if the header is not a
+    /// JSON object
+    fn header_json<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        PyHeader::parse(self.inner.get_header())?.to_dict(py)
+    }
+
+    /// Get the header as a typed `Header` object
+    ///
+    /// # Returns
+    /// Header with the well-known fields parsed from the JSON header
+    #[getter]
+    fn header_info(&self) -> PyResult<PyHeader> {
+        PyHeader::parse(self.inner.get_header())
+    }
+
     /// Python iterator protocol support
     fn __iter__(slf: Py<Self>) -> Py<Self> {
         slf
@@ -209,20 +548,7 @@ impl PyTeehistorian {
     /// # Returns
     /// Next chunk as Python object or None at EOF
     fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
-        match self.inner.next_chunk() {
-            Ok(Some(chunk)) => {
-                self.chunk_count += 1;
-                let converter = ChunkConverter::new(&self.handlers);
-                let py_chunk = converter.convert(py, chunk, self.chunk_count)?;
-                Ok(Some(py_chunk))
-            }
-            Ok(None) => Ok(None),
-            Err(e) => Err(TeehistorianParseError::Parse(format!(
-                "Failed to parse chunk {}: {}",
-                self.chunk_count, e
-            ))
-            .into()),
-        }
+        self.next_matching(py, &ChunkFilter::default())
     }
 
     /// Get the next chunk from the parser (for backward compatibility)
@@ -236,15 +562,749 @@ impl PyTeehistorian {
         self.__next__(py)
     }
 
+    /// Get the chunk the next `__next__` call will return, without consuming it
+    ///
+    /// # Returns
+    /// Next chunk as Python object or None at EOF
+    fn peek(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        let offset = self.inner.offset();
+        let ticks = self.ticks;
+        let chunk_count = self.chunk_count;
+        let last_raw = self.last_raw.clone();
+        let inputs = self.inputs.clone();
+        let positions = self.positions.clone();
+        let versions = self.versions.clone();
+        // The chunk is checked again when it is actually read
+        let warnings = self.warnings.clone();
+        self.warnings.emit = false;
+        // ...and handed to stateful handlers then
+        let handlers = Arc::clone(&self.handlers);
+        self.handlers = Arc::new(
+            handlers
+                .iter()
+                .map(|(uuid, handler)| (uuid.clone(), handler.without_handler()))
+                .collect(),
+        );
+
+        let chunk = self.next_matching(py, &ChunkFilter::default());
+
+        self.inner.seek(offset);
+        self.ticks = ticks;
+        self.chunk_count = chunk_count;
+        self.last_raw = last_raw;
+        self.inputs = inputs;
+        self.positions = positions;
+        self.versions = versions;
+        self.warnings = warnings;
+        self.handlers = handlers;
+        chunk
+    }
+
+    /// Rewind to the first chunk so the parser can be iterated again
+    ///
+    /// The data is neither copied nor validated again. Registered custom
+    /// UUIDs are kept; the tick and chunk count start over and
+    /// `ExtensionHandler.reset()` is called on registered handlers.
+    fn reset(&mut self, py: Python<'_>) -> PyResult<()> {
+        for handler in self.handlers.values().filter_map(UuidHandler::handler) {
+            handler.call_method0(py, "reset")?;
+        }
+        self.inner.seek(self.inner.chunks_start);
+        self.ticks = TickTracker::new();
+        self.chunk_count = 0;
+        self.last_raw = None;
+        self.warnings.clear();
+        self.versions = ClientVersions::default();
+        if let Some(inputs) = &mut self.inputs {
+            *inputs = InputResolver::new();
+        }
+        if let Some(positions) = &mut self.positions {
+            *positions = PositionResolver::new();
+        }
+        Ok(())
+    }
+
+    /// Read up to `size` chunks at once
+    ///
+    /// The chunks are decoded with the GIL released, so other Python threads
+    /// keep running while a large batch is parsed; only the conversion to
+    /// Python objects holds the GIL.
+    ///
+    /// # Arguments
+    /// * `size` - Maximum number of chunks to return
+    ///
+    /// # Returns
+    /// List of chunks, empty at EOF
+    ///
+    /// # Example
+    /// ```python
+    /// while batch := parser.next_batch(10_000):
+    ///     process(batch)
+    /// ```
+    fn next_batch(&mut self, py: Python<'_>, size: usize) -> PyResult<Vec<Py<PyAny>>> {
+        let mut chunks = Vec::new();
+        let mut start = self.inner.offset();
+        while chunks.len() < size
+            && let Some(segment) = self.enter_segment()?
+        {
+            if self.base_filter.accepts_segment() {
+                chunks.push(Py::new(py, segment)?.into_any());
+                self.last_raw = Some(start..self.inner.offset());
+            }
+            start = self.inner.offset();
+        }
+
+        let inner = &self.inner;
+        let remaining = size - chunks.len();
+        let (batch, error) = py.detach(|| inner.decode_batch(remaining));
+
+        if batch.is_empty()
+            && chunks.is_empty()
+            && let Some(e) = error
+        {
+            return Err(TeehistorianParseError::Parse(format!(
+                "Failed to parse chunk {}: {}",
+                self.chunk_count, e
+            ))
+            .into());
+        }
+
+        // A decode error after some chunks is reported by the next call
+        chunks.reserve(batch.len());
+        let mut end_offset = None;
+        for (chunk, end) in batch {
+            let tick = self.ticks.observe(&chunk);
+            self.versions.observe(&chunk);
+            let converter = ChunkConverter::with_versions(&self.handlers, &self.versions);
+            self.chunk_count += 1;
+            end_offset = Some(end);
+            self.warnings
+                .check(py, &chunk, converter.type_name(&chunk), start..end, tick)?;
+            let chunk = match &mut self.inputs {
+                Some(inputs) => inputs.resolve(chunk),
+                None => chunk,
+            };
+            match self.unknown_uuids.accepts(&chunk, &converter) {
+                Ok(true) => {}
+                Ok(false) => {
+                    start = end;
+                    continue;
+                }
+                Err(e) => {
+                    self.inner.seek(end);
+                    return Err(e.into());
+                }
+            }
+            let position = self.positions.as_mut().and_then(|p| p.resolve(&chunk));
+            if self.base_filter.accepts(&chunk, tick, &converter) {
+                chunks.push(match position {
+                    Some((cid, x, y)) => {
+                        Py::new(py, PyPlayerPosition::new(cid, tick, x, y))?.into_any()
+                    }
+                    None => converter.convert(py, chunk, tick)?,
+                });
+                self.last_raw = Some(start..end);
+            }
+            start = end;
+        }
+
+        if let Some(end) = end_offset {
+            self.inner.seek(end);
+        }
+        Ok(chunks)
+    }
+
     /// Get the current chunk count
     #[getter]
     fn chunk_count(&self) -> usize {
         self.chunk_count
     }
 
-    /// Get registered handler UUIDs
-    fn get_registered_uuids(&self) -> Vec<String> {
-        self.handlers.keys().cloned().collect()
+    /// Get the tick of the most recently read chunk
+    ///
+    /// Ticks are derived from `TickSkip` chunks and the implicit tick advance
+    /// of player chunks, counted from the start of the recording.
+    #[getter]
+    fn tick(&self) -> i32 {
+        self.ticks.tick()
+    }
+
+    /// Get the encoded bytes of the most recently returned chunk
+    ///
+    /// The bytes are exactly what the decoder read, so they can be written
+    /// back unchanged or compared against a re-encoded chunk. For a
+    /// `SegmentStart` they are the magic UUID and header of the stream.
+    /// Compressed input is returned decompressed.
+    ///
+    /// # Returns
+    /// Bytes of the last chunk, or None if no chunk was returned yet
+    fn raw<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        let range = self.last_raw.clone()?;
+        Some(PyBytes::new(py, &self.inner.data[range]))
+    }
+
+    /// Get the format version named in the header
+    ///
+    /// DDNet currently writes version 2. None if the header names no version.
+    #[getter]
+    fn format_version(&self) -> PyResult<Option<i32>> {
+        format::header_version(self.inner.get_header())
+    }
+
+    /// Get the extension UUIDs registered in the header
+    ///
+    /// Files written by `TeehistorianWriter` record the custom chunks they
+    /// use, so consumers can discover which extensions a file carries
+    /// without reading its chunks.
+    #[getter]
+    fn uuid_registrations(&self) -> PyResult<Vec<PyUuidRegistration>> {
+        Ok(PyHeader::parse(self.inner.get_header())?.uuid_registrations())
+    }
+
+    /// Get the byte range of the JSON header
+    ///
+    /// The header starts right after the magic UUID and ends at its NUL
+    /// terminator, which is not included. Offsets refer to the decompressed
+    /// data.
+    #[getter]
+    fn header_span(&self) -> (usize, usize) {
+        (MAGIC_LEN, self.inner.chunks_start - 1)
+    }
+
+    /// Get the byte offset of the first chunk, right after the header
+    #[getter]
+    fn body_offset(&self) -> usize {
+        self.inner.chunks_start
+    }
+
+    /// Get the non-fatal anomalies found in the chunks read so far
+    ///
+    /// Unknown extension UUIDs (reported once each), suspicious tick jumps
+    /// and oversized chunks are recorded here instead of being ignored. With
+    /// `emit_warnings=True` each one is also issued as a `TeehistorianWarning`
+    /// through Python's `warnings` module.
+    #[getter]
+    fn warnings(&self) -> Vec<PyParserWarning> {
+        self.warnings.records().to_vec()
+    }
+
+    /// Whether iteration stopped at a chunk cut off by the end of the data
+    ///
+    /// Servers that crash mid-write leave such files behind. Iteration ends
+    /// cleanly before the partial chunk instead of raising.
+    #[getter]
+    fn truncated(&self) -> bool {
+        self.inner.is_truncated()
+    }
+
+    /// Byte offset after the last complete chunk of a truncated file
+    ///
+    /// `None` unless `truncated` is set. The (decompressed) data up to this
+    /// offset is a readable recording without the partial chunk.
+    #[getter]
+    fn last_complete_offset(&self) -> Option<usize> {
+        self.inner.is_truncated().then(|| self.inner.offset())
+    }
+
+    /// Iterate over the chunks within a tick range
+    ///
+    /// Chunks before `start` are skipped in Rust without creating Python
+    /// objects. Iteration stops before the first chunk after `end`, which is
+    /// left unread on the parser. Ticks already passed cannot be revisited.
+    ///
+    /// # Arguments
+    /// * `start` - First tick to yield chunks for
+    /// * `end` - Last tick to yield chunks for (inclusive), or None for no limit
+    ///
+    /// # Example
+    /// ```python
+    /// for chunk in parser.iter_ticks(1500, 3000):
+    ///     print(parser.tick, chunk)
+    /// ```
+    #[pyo3(signature = (start, end=None))]
+    fn iter_ticks(slf: Py<Self>, start: i32, end: Option<i32>) -> PyResult<PyChunkIterator> {
+        if end.is_some_and(|end| end < start) {
+            return Err(TeehistorianParseError::Validation(format!(
+                "Tick range end {} is before start {}",
+                end.unwrap_or_default(),
+                start
+            ))
+            .into());
+        }
+
+        Ok(PyChunkIterator::new(
+            slf,
+            ChunkFilter::tick_range(start, end),
+        ))
+    }
+
+    /// Iterate over the chunks concerning a single player
+    ///
+    /// Only chunks carrying the given client ID are converted to Python
+    /// objects; everything else is skipped in Rust. Chunks without a client
+    /// ID (`TickSkip`, team events, `Eos`, ...) are skipped as well, use the
+    /// `tick` property to follow the timeline.
+    ///
+    /// # Arguments
+    /// * `cid` - Client ID to yield chunks for
+    ///
+    /// # Example
+    /// ```python
+    /// for chunk in parser.iter_player(3):
+    ///     print(parser.tick, chunk)
+    /// ```
+    fn iter_player(slf: Py<Self>, cid: i32) -> PyChunkIterator {
+        PyChunkIterator::new(slf, ChunkFilter::players(vec![cid]))
+    }
+
+    /// Iterate over the chunks concerning any of the given players
+    ///
+    /// Same as `iter_player`, but for a set of client IDs. `PlayerSwap`
+    /// chunks match if either of the swapped players is selected.
+    ///
+    /// # Arguments
+    /// * `cids` - Client IDs to yield chunks for
+    fn iter_players(slf: Py<Self>, cids: Vec<i32>) -> PyChunkIterator {
+        PyChunkIterator::new(slf, ChunkFilter::players(cids))
+    }
+
+    /// Iterate over the chunks of the given types only
+    ///
+    /// Chunks of other types are skipped in Rust without creating Python
+    /// objects.
+    ///
+    /// # Arguments
+    /// * `types` - Chunk classes (`InputDiff`) or type names (`"InputDiff"`)
+    ///
+    /// # Example
+    /// ```python
+    /// for chunk in parser.filter(types=[th.Join, th.Drop]):
+    ///     print(chunk)
+    /// ```
+    fn filter(slf: Py<Self>, types: Vec<Bound<'_, PyAny>>) -> PyResult<PyChunkIterator> {
+        let types = types
+            .iter()
+            .map(resolve_chunk_type)
+            .collect::<PyResult<Vec<_>>>()?;
+
+        Ok(PyChunkIterator::new(slf, ChunkFilter::types(types)))
+    }
+
+    /// Skip ahead to the first chunk matching all given conditions
+    ///
+    /// The chunks before it are decoded and dropped in Rust without creating
+    /// Python objects. The parser is left right after the returned chunk, so
+    /// calling this again finds the next match.
+    ///
+    /// # Arguments
+    /// * `chunk_type` - Chunk class (`AuthLogin`) or type name to stop at
+    /// * `tick` - Stop at the first chunk at or after this tick
+    ///
+    /// # Returns
+    /// The matching chunk, or None if the stream ends first
+    ///
+    /// # Example
+    /// ```python
+    /// while (login := parser.take_until(chunk_type=th.AuthLogin)) is not None:
+    ///     if login.auth_name == "admin":
+    ///         break
+    /// ```
+    #[pyo3(signature = (*, chunk_type=None, tick=None))]
+    fn take_until(
+        &mut self,
+        py: Python<'_>,
+        chunk_type: Option<Bound<'_, PyAny>>,
+        tick: Option<i32>,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        let mut filter = match tick {
+            Some(tick) => ChunkFilter::tick_range(tick, None),
+            None => ChunkFilter::default(),
+        };
+        match chunk_type {
+            Some(chunk_type) => filter = filter.with_types(vec![resolve_chunk_type(&chunk_type)?]),
+            None if tick.is_none() => {
+                return Err(TeehistorianParseError::Validation(
+                    "take_until() needs a chunk_type or a tick".to_string(),
+                )
+                .into());
+            }
+            None => {}
+        }
+        self.next_matching(py, &filter)
+    }
+
+    /// Iterate over `(tick, chunk)` tuples
+    ///
+    /// Same chunks as iterating the parser itself, each paired with the
+    /// absolute tick it belongs to, so timing does not have to be rebuilt
+    /// from `TickSkip` chunks in Python.
+    ///
+    /// # Example
+    /// ```python
+    /// for tick, chunk in parser.iter_with_ticks():
+    ///     print(tick / 50, chunk)
+    /// ```
+    fn iter_with_ticks(slf: Py<Self>) -> PyChunkIterator {
+        PyChunkIterator {
+            with_ticks: true,
+            ..PyChunkIterator::new(slf, ChunkFilter::default())
+        }
+    }
+
+    /// Iterate with movement and input chunks thinned out to a tick stride
+    ///
+    /// Per player, at most one `PlayerDiff` and one `InputDiff` is yielded
+    /// in every window of `every_n_ticks` ticks. The diffs skipped in between
+    /// are added into the next yielded one, so summing the yielded diffs still
+    /// gives exact positions and inputs. All other chunks pass through.
+    ///
+    /// # Arguments
+    /// * `every_n_ticks` - Width of the sampling window in ticks
+    ///
+    /// # Example
+    /// ```python
+    /// # One position per player and second
+    /// for chunk in parser.iter_sampled(every_n_ticks=50):
+    ///     plot(chunk)
+    /// ```
+    fn iter_sampled(slf: Py<Self>, every_n_ticks: i32) -> PyResult<PyChunkIterator> {
+        Ok(PyChunkIterator {
+            sampler: Some(Sampler::new(every_n_ticks)?),
+            ..PyChunkIterator::new(slf, ChunkFilter::default())
+        })
+    }
+
+    /// Summarize the whole file without creating chunk objects
+    ///
+    /// Every chunk is decoded in Rust with the GIL released. The scan is
+    /// independent of the iteration position and of `skip_inputs` or
+    /// `exclude_categories`, so the parser can be used normally afterwards.
+    ///
+    /// # Example
+    /// ```python
+    /// stats = parser.stats()
+    /// print(stats.chunk_counts["PlayerDiff"], stats.duration)
+    /// ```
+    fn stats(&self, py: Python<'_>) -> PyResult<PyParserStats> {
+        let data = &self.inner.data[..];
+        let start = self.inner.chunks_start;
+        let handlers = &self.handlers;
+
+        let result = py.detach(|| {
+            let mut ticks = TickTracker::new();
+            let mut versions = ClientVersions::default();
+            let mut stats = PyParserStats::default();
+            TeehistorianParserInner::walk(data, start, |chunk| match chunk {
+                Some(chunk) => {
+                    let tick = ticks.observe(&chunk);
+                    versions.observe(&chunk);
+                    let converter = ChunkConverter::with_versions(handlers, &versions);
+                    stats.observe(&chunk, tick, converter.type_name(&chunk));
+                }
+                None => {
+                    ticks.restart();
+                    versions = ClientVersions::default();
+                    stats.observe_segment();
+                }
+            })
+            .map(|_| stats)
+        });
+
+        result.map_err(|(_, e)| {
+            TeehistorianParseError::Parse(format!("Failed to scan chunks: {}", e)).into()
+        })
+    }
+
+    /// Check a whole file and report every problem found
+    ///
+    /// Never raises for malformed data; the report tells whether the header
+    /// could be read, whether every stream ends with `Eos`, which extension
+    /// UUIDs have no handler, where the first undecodable chunk is, the chunk
+    /// counts and whether ticks only move forward. Chunks are decoded with the
+    /// GIL released.
+    ///
+    /// # Arguments
+    /// * `data` - Raw or compressed teehistorian file data
+    ///
+    /// # Example
+    /// ```python
+    /// report = Teehistorian.validate(data)
+    /// if not report.is_valid:
+    ///     print(report.error_offset, report.error)
+    /// ```
+    #[staticmethod]
+    fn validate(py: Python<'_>, data: &[u8]) -> PyValidationReport {
+        let parser = match Self::new(data, false, None, false, false, false, "yield") {
+            Ok(parser) => parser,
+            Err(e) => return PyValidationReport::header_failure(e.value(py).to_string()),
+        };
+
+        let data = &parser.inner.data[..];
+        let start = parser.inner.chunks_start;
+        let handlers = &parser.handlers;
+
+        py.detach(|| {
+            let mut ticks = TickTracker::new();
+            let mut versions = ClientVersions::default();
+            let mut report = PyValidationReport::default();
+            let end = TeehistorianParserInner::walk(data, start, |chunk| match chunk {
+                Some(chunk) => {
+                    let tick = ticks.observe(&chunk);
+                    versions.observe(&chunk);
+                    let converter = ChunkConverter::with_versions(handlers, &versions);
+                    report.observe(&chunk, tick, converter.type_name(&chunk));
+                }
+                None => {
+                    ticks.restart();
+                    versions = ClientVersions::default();
+                    report.observe_segment();
+                }
+            });
+
+            match end {
+                Ok(end) if end < data.len() => report.truncate(end),
+                Ok(_) => {}
+                Err((offset, e)) => report.fail(offset, e.to_string()),
+            }
+            report
+        })
+    }
+
+    /// Count the chunks of one type in the whole file
+    ///
+    /// Chunks are skipped by reading only their headers, so only chunks whose
+    /// type depends on the payload (`NetMessage`, extension chunks with
+    /// unknown UUIDs) are fully decoded. Like `stats()`, the count covers the
+    /// whole file and leaves the parser position unchanged.
+    ///
+    /// # Arguments
+    /// * `chunk_type` - Chunk class (`ConsoleCommand`) or type name
+    ///
+    /// # Example
+    /// ```python
+    /// commands = parser.count(th.ConsoleCommand)
+    /// ```
+    fn count(&self, py: Python<'_>, chunk_type: &Bound<'_, PyAny>) -> PyResult<usize> {
+        let name = resolve_chunk_type(chunk_type)?;
+        let data = &self.inner.data[..];
+        let start = self.inner.chunks_start;
+        let handlers = &self.handlers;
+
+        let result = py.detach(|| {
+            let mut versions = ClientVersions::default();
+            let mut count = 0;
+            let mut offset = start;
+            loop {
+                if let Some(chunks_start) = TeehistorianParserInner::segment_at(data, offset)? {
+                    count += usize::from(name == "SegmentStart");
+                    versions = ClientVersions::default();
+                    offset = chunks_start;
+                    continue;
+                }
+                let Some((tag, end)) = scan::scan_chunk(data, offset)? else {
+                    break;
+                };
+                let matches = match tag.type_name() {
+                    // Decoded as well to follow the protocol of every client
+                    None | Some("JoinVer6" | "JoinVer7" | "Drop") => {
+                        TeehistorianParserInner::decode_at(data, offset)?.is_some_and(
+                            |(chunk, _)| {
+                                versions.observe(&chunk);
+                                let converter = ChunkConverter::with_versions(handlers, &versions);
+                                converter.type_name(&chunk) == name
+                            },
+                        )
+                    }
+                    Some(type_name) => type_name == name,
+                };
+                count += usize::from(matches);
+                offset = end;
+            }
+            Ok::<_, teehistorian::Error>(count)
+        });
+
+        result.map_err(|e| {
+            TeehistorianParseError::Parse(format!("Failed to scan chunks: {}", e)).into()
+        })
+    }
+
+    /// Snapshot the parser position for a later `resume`
+    ///
+    /// The state records the byte offset of the next chunk, the tick
+    /// bookkeeping, the protocol of every client, the registered custom UUIDs
+    /// and, with `resolve_inputs` or `resolve_positions`, the last input or
+    /// position of every player.
+    ///
+    /// # Example
+    /// ```python
+    /// state = parser.save_state()
+    /// Path("job.state").write_bytes(state.to_bytes())
+    /// ```
+    fn save_state(&self) -> PyParserState {
+        let mut uuids: Vec<String> = self.handlers.keys().cloned().collect();
+        uuids.sort();
+
+        PyParserState {
+            offset: self.inner.offset(),
+            chunks_start: self.inner.chunks_start,
+            ticks: self.ticks,
+            chunk_count: self.chunk_count,
+            uuids,
+            versions: self.versions.clone(),
+            inputs: self.inputs.clone(),
+            positions: self.positions.clone(),
+        }
+    }
+
+    /// Create a parser that continues where a saved state left off
+    ///
+    /// # Arguments
+    /// * `data` - The same teehistorian file data the state was saved from
+    /// * `state` - State returned by `save_state()`
+    /// * `skip_inputs`, `exclude_categories`, `emit_warnings`, `resolve_inputs`,
+    ///   `resolve_positions`, `unknown_uuid_policy` - Same as for the constructor
+    ///
+    /// # Example
+    /// ```python
+    /// state = ParserState.from_bytes(Path("job.state").read_bytes())
+    /// parser = Teehistorian.resume(data, state)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (data, state, *, skip_inputs=false, exclude_categories=None, emit_warnings=false, resolve_inputs=false, resolve_positions=false, unknown_uuid_policy="yield"))]
+    #[allow(clippy::too_many_arguments)]
+    fn resume(
+        data: &[u8],
+        state: &PyParserState,
+        skip_inputs: bool,
+        exclude_categories: Option<Vec<String>>,
+        emit_warnings: bool,
+        resolve_inputs: bool,
+        resolve_positions: bool,
+        unknown_uuid_policy: &str,
+    ) -> PyResult<Self> {
+        let mut parser = Self::new(
+            data,
+            skip_inputs,
+            exclude_categories,
+            emit_warnings,
+            resolve_inputs,
+            resolve_positions,
+            unknown_uuid_policy,
+        )?;
+        parser.restore(state)?;
+        parser.seek_checked(state.offset)?;
+        Ok(parser)
+    }
+
+    /// Create a parser that starts at a known chunk boundary
+    ///
+    /// Tools that built an index of chunk offsets can jump straight to a
+    /// chunk without parsing the prefix. Without a state, ticks and the chunk
+    /// count start from zero at `offset`.
+    ///
+    /// # Arguments
+    /// * `data` - Teehistorian file data the offset refers to
+    /// * `offset` - Byte offset of a chunk, e.g. from a saved `ParserState`
+    /// * `state` - Optional state whose ticks, chunk count, UUIDs, inputs and
+    ///   positions to restore
+    /// * `skip_inputs`, `exclude_categories`, `emit_warnings`, `resolve_inputs`,
+    ///   `resolve_positions`, `unknown_uuid_policy` - Same as for the constructor
+    ///
+    /// # Example
+    /// ```python
+    /// parser = Teehistorian.from_offset(data, index[tick])
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (data, offset, state=None, *, skip_inputs=false, exclude_categories=None, emit_warnings=false, resolve_inputs=false, resolve_positions=false, unknown_uuid_policy="yield"))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_offset(
+        data: &[u8],
+        offset: usize,
+        state: Option<&PyParserState>,
+        skip_inputs: bool,
+        exclude_categories: Option<Vec<String>>,
+        emit_warnings: bool,
+        resolve_inputs: bool,
+        resolve_positions: bool,
+        unknown_uuid_policy: &str,
+    ) -> PyResult<Self> {
+        let mut parser = Self::new(
+            data,
+            skip_inputs,
+            exclude_categories,
+            emit_warnings,
+            resolve_inputs,
+            resolve_positions,
+            unknown_uuid_policy,
+        )?;
+        if let Some(state) = state {
+            parser.restore(state)?;
+        }
+        parser.seek_checked(offset)?;
+        Ok(parser)
+    }
+
+    /// Follow a recording while the server is still writing it
+    ///
+    /// Like `tail -f`: when the iterator reaches the end of the file it
+    /// waits for more data instead of stopping, and ends after the `Eos`
+    /// chunk written when the recording is closed. The file may even be
+    /// empty or hold a partial header when iteration starts. Compressed
+    /// files cannot be followed.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the recording being written
+    /// * `poll_interval` - Seconds to wait before checking for new data
+    /// * `skip_inputs`, `exclude_categories`, `emit_warnings`, `resolve_inputs`,
+    ///   `resolve_positions`, `unknown_uuid_policy` - Same as for the constructor
+    ///
+    /// # Example
+    /// ```python
+    /// for chunk in Teehistorian.follow("live.teehistorian", poll_interval=0.2):
+    ///     dashboard.push(chunk)
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (path, poll_interval=0.5, *, skip_inputs=false, exclude_categories=None, emit_warnings=false, resolve_inputs=false, resolve_positions=false, unknown_uuid_policy="yield"))]
+    #[allow(clippy::too_many_arguments)]
+    fn follow(
+        path: PathBuf,
+        poll_interval: f64,
+        skip_inputs: bool,
+        exclude_categories: Option<Vec<String>>,
+        emit_warnings: bool,
+        resolve_inputs: bool,
+        resolve_positions: bool,
+        unknown_uuid_policy: &str,
+    ) -> PyResult<PyFollowIterator> {
+        PyFollowIterator::open(
+            &path,
+            poll_interval,
+            skip_inputs,
+            exclude_categories.unwrap_or_default(),
+            emit_warnings,
+            resolve_inputs,
+            resolve_positions,
+            unknown_uuid_policy,
+        )
+    }
+
+    /// Remove the handler of a custom UUID
+    ///
+    /// Chunks of the UUID are yielded as `Unknown` again. Returns whether a
+    /// handler was registered.
+    fn unregister_custom_uuid(&mut self, uuid_string: &str) -> bool {
+        if !self.handlers.contains_key(uuid_string) {
+            return false;
+        }
+        Arc::make_mut(&mut self.handlers).remove(uuid_string);
+        true
+    }
+
+    /// Get the registered custom UUIDs, sorted by UUID
+    fn get_registered_uuids(&self) -> Vec<PyRegisteredUuid> {
+        let mut registered: Vec<_> = self.handlers.values().map(PyRegisteredUuid::from).collect();
+        registered.sort_by(|a, b| a.uuid.cmp(&b.uuid));
+        registered
     }
 
     /// Context manager entry
@@ -265,18 +1325,178 @@ impl PyTeehistorian {
 }
 
 impl PyTeehistorian {
+    /// Read chunks until one passes `filter` and convert it to Python
+    ///
+    /// Returns None at the end of the stream, or when the filter can no
+    /// longer accept chunks. In the latter case the rejected chunk is left
+    /// unread so the next caller sees it.
+    fn next_matching(
+        &mut self,
+        py: Python<'_>,
+        filter: &ChunkFilter,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        self.next_sampled(py, filter, None)
+    }
+
+    /// Same as `next_matching`, additionally passing accepted chunks through
+    /// `sampler`, which may fold them into later ones
+    fn next_sampled(
+        &mut self,
+        py: Python<'_>,
+        filter: &ChunkFilter,
+        mut sampler: Option<&mut Sampler>,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        // Entering a concatenated stream needs `self` mutably
+        let handlers = Arc::clone(&self.handlers);
+        loop {
+            let offset = self.inner.offset();
+            if let Some(segment) = self.enter_segment()? {
+                if self.base_filter.accepts_segment() && filter.accepts_segment() {
+                    self.last_raw = Some(offset..self.inner.offset());
+                    return Ok(Some(Py::new(py, segment)?.into_any()));
+                }
+                continue;
+            }
+
+            let ticks = self.ticks;
+
+            let (chunk, end) = match self.inner.next_chunk() {
+                Ok(Some(decoded)) => decoded,
+                Ok(None) => return Ok(None),
+                Err(e) => {
+                    return Err(TeehistorianParseError::Parse(format!(
+                        "Failed to parse chunk {}: {}",
+                        self.chunk_count, e
+                    ))
+                    .into());
+                }
+            };
+
+            let tick = self.ticks.observe(&chunk);
+            if filter.is_exhausted(tick) {
+                self.inner.seek(offset);
+                self.ticks = ticks;
+                return Ok(None);
+            }
+
+            self.chunk_count += 1;
+            self.versions.observe(&chunk);
+            let converter = ChunkConverter::with_versions(&handlers, &self.versions);
+            self.warnings
+                .check(py, &chunk, converter.type_name(&chunk), offset..end, tick)?;
+            let chunk = match &mut self.inputs {
+                Some(inputs) => inputs.resolve(chunk),
+                None => chunk,
+            };
+            if !self.unknown_uuids.accepts(&chunk, &converter)? {
+                continue;
+            }
+            let position = self.positions.as_mut().and_then(|p| p.resolve(&chunk));
+            if !(self.base_filter.accepts(&chunk, tick, &converter)
+                && filter.accepts(&chunk, tick, &converter))
+            {
+                continue;
+            }
+            let chunk = match sampler.as_deref_mut() {
+                Some(sampler) => sampler.sample(chunk, tick),
+                None => Some(chunk),
+            };
+            if let Some(chunk) = chunk {
+                let py_chunk = match position {
+                    Some((cid, x, y)) => {
+                        Py::new(py, PyPlayerPosition::new(cid, tick, x, y))?.into_any()
+                    }
+                    None => converter.convert(py, chunk, tick)?,
+                };
+                self.last_raw = Some(offset..end);
+                return Ok(Some(py_chunk));
+            }
+        }
+    }
+
+    /// Restore the bookkeeping of a saved state, without moving the cursor
+    fn restore(&mut self, state: &PyParserState) -> PyResult<()> {
+        if state.chunks_start != self.inner.chunks_start {
+            return Err(TeehistorianParseError::Validation(
+                "Parser state does not belong to this data".to_string(),
+            )
+            .into());
+        }
+
+        if let Some(inputs) = &mut self.inputs {
+            *inputs = state.inputs.clone().ok_or_else(|| {
+                TeehistorianParseError::Validation(
+                    "Parser state was saved without resolve_inputs".to_string(),
+                )
+            })?;
+        }
+        if let Some(positions) = &mut self.positions {
+            *positions = state.positions.clone().ok_or_else(|| {
+                TeehistorianParseError::Validation(
+                    "Parser state was saved without resolve_positions".to_string(),
+                )
+            })?;
+        }
+
+        self.ticks = state.ticks;
+        self.chunk_count = state.chunk_count;
+        self.versions = state.versions.clone();
+        for uuid in &state.uuids {
+            if !self.handlers.contains_key(uuid) {
+                self.register_custom_uuid(uuid.clone(), None, None, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the cursor to `offset` after checking it is a chunk boundary
+    ///
+    /// Only offsets that obviously are not boundaries can be detected: those
+    /// outside the chunk stream or where no chunk decodes.
+    fn seek_checked(&mut self, offset: usize) -> PyResult<()> {
+        let invalid = |what: &str| -> PyResult<()> {
+            Err(TeehistorianParseError::Validation(format!("Offset {} {}", offset, what)).into())
+        };
+
+        if offset > self.inner.data.len() {
+            return invalid("is past the end of the data");
+        }
+        if offset < self.inner.chunks_start {
+            return invalid("is inside the header");
+        }
+        let data = &self.inner.data;
+        if !data[offset..].starts_with(&MAGIC)
+            && TeehistorianParserInner::decode_at(data, offset).is_err()
+        {
+            return invalid("is not a chunk boundary");
+        }
+
+        self.inner.seek(offset);
+        Ok(())
+    }
+
+    /// Move past a concatenated stream header at the cursor, if there is one
+    fn enter_segment(&mut self) -> PyResult<Option<PySegmentStart>> {
+        let offset = self.inner.offset();
+        let header = self.inner.next_segment().map_err(|e| {
+            TeehistorianParseError::Parse(format!("Invalid concatenated stream header: {}", e))
+        })?;
+
+        Ok(header.map(|header| {
+            self.ticks.restart();
+            self.versions = ClientVersions::default();
+            self.chunk_count += 1;
+            PySegmentStart::new(offset, header)
+        }))
+    }
+
     /// Parse header metadata and auto-register custom chunks
     ///
     /// This method looks for __teehistorian_py metadata in the file header
     /// and automatically registers any custom chunk definitions found.
     fn parse_and_register_metadata(&mut self) -> PyResult<()> {
         // Get header as string
-        let header_bytes = self
-            .inner
-            .get_header()
-            .map_err(|e| TeehistorianParseError::Header(format!("Failed to read header: {}", e)))?;
-
-        let header_str = String::from_utf8(header_bytes).map_err(|e| {
+        let header_str = String::from_utf8(self.inner.get_header().to_vec()).map_err(|e| {
             TeehistorianParseError::Header(format!("Invalid UTF-8 in header: {}", e))
         })?;
 
@@ -334,7 +1554,7 @@ impl PyTeehistorian {
                     // Create chunk definition
                     let chunk_def = registry::ChunkDef {
                         uuid: uuid.clone(),
-                        name: chunk_name,
+                        name: chunk_name.clone(),
                         fields,
                     };
 
@@ -342,7 +1562,7 @@ impl PyTeehistorian {
                     registry::register_global(chunk_def);
 
                     // Also register UUID handler for parsing
-                    self.register_custom_uuid(uuid.clone())?;
+                    self.register_custom_uuid(uuid.clone(), None, Some(chunk_name), None)?;
                 }
             }
         }
@@ -351,6 +1571,92 @@ impl PyTeehistorian {
     }
 }
 
+/// Iterator over a filtered view of a parser's chunks
+///
+/// Shares the position of the parser it was created from: reading from the
+/// iterator advances the parser and vice versa.
+#[pyclass(name = "ChunkIterator", module = "teehistorian_py")]
+pub struct PyChunkIterator {
+    parser: Py<PyTeehistorian>,
+    filter: ChunkFilter,
+    /// Yield `(tick, chunk)` tuples instead of bare chunks
+    with_ticks: bool,
+    sampler: Option<Sampler>,
+}
+
+impl PyChunkIterator {
+    fn new(parser: Py<PyTeehistorian>, filter: ChunkFilter) -> Self {
+        Self {
+            parser,
+            filter,
+            with_ticks: false,
+            sampler: None,
+        }
+    }
+}
+
+#[pymethods]
+impl PyChunkIterator {
+    /// Python iterator protocol support
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    /// Get the next chunk matching this iterator's filter
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        let mut parser = self.parser.borrow_mut(py);
+        let Some(chunk) = parser.next_sampled(py, &self.filter, self.sampler.as_mut())? else {
+            return Ok(None);
+        };
+        if !self.with_ticks {
+            return Ok(Some(chunk));
+        }
+        let tick = parser.ticks.tick();
+        Ok(Some((tick, chunk).into_pyobject(py)?.into_any().unbind()))
+    }
+}
+
+/// Decode a single encoded chunk, the inverse of `write_to_buffer()`
+///
+/// # Arguments
+/// * `data` - Bytes of exactly one chunk
+/// * `uuid_registry` - Extension UUIDs to decode as `CustomChunk` rather
+///   than `Unknown`, like `Teehistorian.register_custom_uuid()`
+///
+/// # Example
+/// ```python
+/// chunk = th.parse_chunk(th.PlayerNew(3, 100, 200).write_to_buffer())
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, uuid_registry=None))]
+fn parse_chunk(
+    py: Python<'_>,
+    data: &[u8],
+    uuid_registry: Option<Vec<String>>,
+) -> PyResult<Py<PyAny>> {
+    let mut handlers = HashMap::new();
+    for uuid_string in uuid_registry.unwrap_or_default() {
+        let uuid = uuid::Uuid::parse_str(&uuid_string).map_err(|_| {
+            TeehistorianParseError::Validation(format!("Invalid UUID format: {}", uuid_string))
+        })?;
+        handlers.insert(uuid.to_string(), UuidHandler::new(uuid.to_string())?);
+    }
+    let handlers = Arc::new(handlers);
+
+    let invalid = |msg: String| TeehistorianParseError::Parse(msg);
+    let (chunk, end) = TeehistorianParserInner::decode_at(data, 0)
+        .map_err(|e| invalid(format!("invalid chunk: {}", e)))?
+        .ok_or_else(|| invalid("chunk is truncated".to_string()))?;
+    if end != data.len() {
+        return Err(invalid(format!(
+            "{} bytes after the end of the chunk",
+            data.len() - end
+        ))
+        .into());
+    }
+    ChunkConverter::new(&handlers).convert(py, chunk, 0)
+}
+
 /// Validate UUID string format
 pub fn is_valid_uuid_format(uuid: &str) -> bool {
     uuid::Uuid::parse_str(uuid).is_ok()
@@ -371,14 +1677,40 @@ fn _rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
         m.py().get_type::<errors::TeehistorianError>(),
     )?;
 
+    m.add(
+        "TeehistorianWarning",
+        m.py().get_type::<errors::TeehistorianWarning>(),
+    )?;
+
     // Add main parser class
     m.add_class::<PyTeehistorian>()?;
+    m.add_class::<PyExtensionHandler>()?;
+    m.add_class::<PyRegisteredUuid>()?;
+    m.add_class::<PyChunkIterator>()?;
+    m.add_class::<PyFollowIterator>()?;
+    m.add_class::<PyHeader>()?;
+    m.add_class::<PyTuning>()?;
+    m.add_class::<PyParserState>()?;
+    m.add_class::<PyParserStats>()?;
+    m.add_class::<PyParserWarning>()?;
+    m.add_class::<PyValidationReport>()?;
+    m.add_class::<PyRecordingDiff>()?;
+    m.add_class::<PyRepairReport>()?;
+    m.add_class::<PyRecompressReport>()?;
+    m.add_class::<PyPlayerTracker>()?;
+    m.add_class::<PySnapshotIterator>()?;
+    m.add_class::<PyPlayerSession>()?;
+    m.add_class::<PyAnomaly>()?;
+    m.add_class::<PyFinish>()?;
+    m.add_class::<PyRunVerification>()?;
 
     // Add player lifecycle chunks
     m.add_class::<PyJoin>()?;
     m.add_class::<PyJoinVer6>()?;
+    m.add_class::<PyJoinVer7>()?;
     m.add_class::<PyDrop>()?;
     m.add_class::<PyPlayerReady>()?;
+    m.add_class::<PyPlayerRejoin>()?;
 
     // Add player state chunks
     m.add_class::<PyPlayerNew>()?;
@@ -386,6 +1718,8 @@ fn _rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyPlayerTeam>()?;
     m.add_class::<PyPlayerName>()?;
     m.add_class::<PyPlayerDiff>()?;
+    m.add_class::<PyPlayerPosition>()?;
+    m.add_class::<PyPlayerSwap>()?;
 
     // Add input chunks
     m.add_class::<PyInputNew>()?;
@@ -397,22 +1731,41 @@ fn _rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyConsoleCommand>()?;
 
     // Add authentication and version chunks
+    m.add_class::<PyAuthInit>()?;
     m.add_class::<PyAuthLogin>()?;
+    m.add_class::<PyAuthLogout>()?;
     m.add_class::<PyDdnetVersion>()?;
     m.add_class::<PyDdnetVersionOld>()?;
     m.add_class::<PyPlayerFinish>()?;
 
     // Add server event chunks
     m.add_class::<PyTickSkip>()?;
+    m.add_class::<PyTeamSaveSuccess>()?;
+    m.add_class::<PyTeamSaveFailure>()?;
     m.add_class::<PyTeamLoadSuccess>()?;
     m.add_class::<PyTeamLoadFailure>()?;
+    m.add_class::<PyTeamPractice>()?;
+    m.add_class::<PyTeamFinish>()?;
     m.add_class::<PyAntiBot>()?;
+    m.add_class::<antibot::PyAntibotEvent>()?;
+    m.add_class::<player_input::PyPlayerInput>()?;
+    m.add_class::<game_msg::PyChatMessage>()?;
+    m.add_class::<game_msg::PyKillMessage>()?;
+    m.add_class::<game_msg::PyEmoticon>()?;
+    m.add_class::<game_msg::PyVoteCall>()?;
+    m.add_class::<game_msg::PyVote>()?;
+    m.add_class::<game_msg::PySetTeam>()?;
+    m.add_class::<game_msg::PySetSpectatorMode>()?;
+    m.add_class::<game_msg::PyClientCommand>()?;
 
     // Add special chunks
     m.add_class::<PyEos>()?;
+    m.add_class::<PyTestChunk>()?;
     m.add_class::<PyUnknown>()?;
     m.add_class::<PyCustomChunk>()?;
-    m.add_class::<PyGeneric>()?;
+    m.add_class::<PyRawChunk>()?;
+    m.add_class::<PySegmentStart>()?;
+    m.add_class::<PyUuidRegistration>()?;
 
     // Add writer class (at end to debug export issue)
     m.add_class::<PyTeehistorianWriter>()?;
@@ -421,6 +1774,32 @@ fn _rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<FieldFormat>()?;
     m.add_class::<FieldSpec>()?;
     m.add_class::<ChunkDef>()?;
+    m.add_function(wrap_pyfunction!(format::detect_format, m)?)?;
+    m.add_function(wrap_pyfunction!(header::rewrite_header, m)?)?;
+    m.add_function(wrap_pyfunction!(transform::transform, m)?)?;
+    m.add_function(wrap_pyfunction!(transform::patch, m)?)?;
+    m.add_function(wrap_pyfunction!(anonymize::anonymize, m)?)?;
+    m.add_function(wrap_pyfunction!(slice::slice_ticks, m)?)?;
+    m.add_function(wrap_pyfunction!(slice::split, m)?)?;
+    m.add_function(wrap_pyfunction!(merge::merge, m)?)?;
+    m.add_function(wrap_pyfunction!(anonymize::redact_chat, m)?)?;
+    m.add_function(wrap_pyfunction!(extract::extract_player, m)?)?;
+    m.add_function(wrap_pyfunction!(diff::diff, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize::normalize, m)?)?;
+    m.add_function(wrap_pyfunction!(remap::shift_ticks, m)?)?;
+    m.add_function(wrap_pyfunction!(remap::remap_cids, m)?)?;
+    m.add_function(wrap_pyfunction!(transform::strip, m)?)?;
+    m.add_function(wrap_pyfunction!(repair::repair, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize::recompress, m)?)?;
+    m.add_function(wrap_pyfunction!(sessions::extract_sessions, m)?)?;
+    m.add_function(wrap_pyfunction!(input_stats::input_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(anomalies::detect_anomalies, m)?)?;
+    m.add_function(wrap_pyfunction!(finishes::extract_finishes, m)?)?;
+    m.add_function(wrap_pyfunction!(verify::verify_run, m)?)?;
+    m.add_function(wrap_pyfunction!(factory::make_chunk, m)?)?;
+    m.add_function(wrap_pyfunction!(factory::chunk_types, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_chunk, m)?)?;
+    m.add_function(wrap_pyfunction!(handlers::categories, m)?)?;
     m.add_function(wrap_pyfunction!(
         registry::py_api::register_global_chunk,
         m
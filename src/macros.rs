@@ -98,6 +98,12 @@ macro_rules! define_chunk {
                 $crate::chunks::PyChunkMethods::py_chunk_type(self)
             }
 
+            /// Category of the chunk type, e.g. "PlayerLifecycle"
+            #[getter]
+            fn category(&self) -> &'static str {
+                $crate::handlers::chunk_category(self.chunk_type())
+            }
+
             fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
                 let dict = pyo3::types::PyDict::new(py);
                 dict.set_item("type", self.chunk_type())?;
@@ -177,21 +183,40 @@ macro_rules! define_chunk_custom {
         $crate::macros::paste! {
             $(#[$meta])*
             #[pyclass(module = "teehistorian_py", frozen)]
-            #[derive(Debug, Clone)]
+            #[derive(Clone)]
             pub struct [<Py $name>] {
             $(
                 $(#[$field_meta])*
                 #[pyo3(get)]
                 pub $field: $field_ty,
             )*
+            // Encoding read from a file, written as-is if the text fields
+            // cannot reproduce it
+            recorded: Option<Vec<u8>>,
+        }
+
+        // `recorded` is an encoding detail and stays out of the repr
+        impl std::fmt::Debug for [<Py $name>] {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!([<Py $name>]))
+                    $(.field(stringify!($field), &self.$field))*
+                    .finish()
+            }
         }
 
         impl [<Py $name>] {
             pub fn new($($field: $field_ty),*) -> Self {
                 Self {
-                    $($field),*
+                    $($field,)*
+                    recorded: None,
                 }
             }
+
+            /// Write `recorded` instead of encoding the fields
+            pub fn with_recorded(mut self, recorded: Vec<u8>) -> Self {
+                self.recorded = Some(recorded);
+                self
+            }
         }
 
         impl $crate::chunks::TeehistorianChunk for [<Py $name>] {
@@ -204,6 +229,10 @@ macro_rules! define_chunk_custom {
                     }
                 )
             }
+
+            fn recorded(&self) -> Option<&[u8]> {
+                self.recorded.as_deref()
+            }
         }
 
         #[pymethods]
@@ -225,6 +254,12 @@ macro_rules! define_chunk_custom {
                 $crate::chunks::PyChunkMethods::py_chunk_type(self)
             }
 
+            /// Category of the chunk type, e.g. "PlayerLifecycle"
+            #[getter]
+            fn category(&self) -> &'static str {
+                $crate::handlers::chunk_category(self.chunk_type())
+            }
+
             fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
                 let dict = pyo3::types::PyDict::new(py);
                 dict.set_item("type", self.chunk_type())?;
@@ -254,21 +289,40 @@ macro_rules! define_chunk_custom {
         $crate::macros::paste! {
             $(#[$meta])*
             #[pyclass(module = "teehistorian_py", frozen)]
-            #[derive(Debug, Clone)]
+            #[derive(Clone)]
             pub struct [<Py $name>] {
             $(
                 $(#[$field_meta])*
                 #[pyo3(get)]
                 pub $field: $field_ty,
             )*
+            // Encoding read from a file, written as-is if the text fields
+            // cannot reproduce it
+            recorded: Option<Vec<u8>>,
+        }
+
+        // `recorded` is an encoding detail and stays out of the repr
+        impl std::fmt::Debug for [<Py $name>] {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!([<Py $name>]))
+                    $(.field(stringify!($field), &self.$field))*
+                    .finish()
+            }
         }
 
         impl [<Py $name>] {
             pub fn new($($field: $field_ty),*) -> Self {
                 Self {
-                    $($field),*
+                    $($field,)*
+                    recorded: None,
                 }
             }
+
+            /// Write `recorded` instead of encoding the fields
+            pub fn with_recorded(mut self, recorded: Vec<u8>) -> Self {
+                self.recorded = Some(recorded);
+                self
+            }
         }
 
         impl $crate::chunks::TeehistorianChunk for [<Py $name>] {
@@ -281,6 +335,10 @@ macro_rules! define_chunk_custom {
                     }
                 )
             }
+
+            fn recorded(&self) -> Option<&[u8]> {
+                self.recorded.as_deref()
+            }
         }
 
         #[pymethods]
@@ -302,6 +360,12 @@ macro_rules! define_chunk_custom {
                 $crate::chunks::PyChunkMethods::py_chunk_type(self)
             }
 
+            /// Category of the chunk type, e.g. "PlayerLifecycle"
+            #[getter]
+            fn category(&self) -> &'static str {
+                $crate::handlers::chunk_category(self.chunk_type())
+            }
+
             fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
                 let dict = pyo3::types::PyDict::new(py);
                 dict.set_item("type", self.chunk_type())?;
@@ -347,7 +411,8 @@ macro_rules! define_chunk_custom {
 /// Define an inline struct chunk (like Join { cid: i32 })
 ///
 /// For chunks where the teehistorian enum variant is an inline struct,
-/// not a tuple variant containing a struct.
+/// not a tuple variant containing a struct. The variant name defaults to the
+/// chunk name and can be given in parentheses when they differ.
 ///
 /// # Example
 /// ```ignore
@@ -357,9 +422,17 @@ macro_rules! define_chunk_custom {
 ///         client_id: i32 => cid,
 ///     }
 /// }
+///
+/// define_inline_chunk! {
+///     /// Player re-enters the game world
+///     PlayerRejoin(RejoinVer6) {
+///         client_id: i32 => cid,
+///     }
+/// }
 /// ```
 #[macro_export]
 macro_rules! define_inline_chunk {
+    // Version with the same name for chunk and variant
     (
         $(#[$meta:meta])*
         $name:ident {
@@ -368,6 +441,26 @@ macro_rules! define_inline_chunk {
                 $field:ident: $field_ty:ty => $teehistorian_field:ident
             ),* $(,)?
         }
+    ) => {
+        define_inline_chunk! {
+            $(#[$meta])*
+            $name($name) {
+                $(
+                    $(#[$field_meta])*
+                    $field: $field_ty => $teehistorian_field
+                ),*
+            }
+        }
+    };
+
+    (
+        $(#[$meta:meta])*
+        $name:ident($teehistorian_variant:ident) {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident: $field_ty:ty => $teehistorian_field:ident
+            ),* $(,)?
+        }
     ) => {
         $crate::macros::paste! {
             $(#[$meta])*
@@ -391,7 +484,7 @@ macro_rules! define_inline_chunk {
 
         impl $crate::chunks::TeehistorianChunk for [<Py $name>] {
             fn to_teehistorian_chunk(&self) -> teehistorian::Chunk<'_> {
-                teehistorian::Chunk::$name {
+                teehistorian::Chunk::$teehistorian_variant {
                     $(
                         $teehistorian_field: define_inline_chunk!(@convert_field self.$field, $field_ty),
                     )*
@@ -418,6 +511,12 @@ macro_rules! define_inline_chunk {
                 $crate::chunks::PyChunkMethods::py_chunk_type(self)
             }
 
+            /// Category of the chunk type, e.g. "PlayerLifecycle"
+            #[getter]
+            fn category(&self) -> &'static str {
+                $crate::handlers::chunk_category(self.chunk_type())
+            }
+
             fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
                 let dict = pyo3::types::PyDict::new(py);
                 dict.set_item("type", self.chunk_type())?;
@@ -504,6 +603,12 @@ macro_rules! define_zero_field_chunk {
                 stringify!($name)
             }
 
+            /// Category of the chunk type, e.g. "PlayerLifecycle"
+            #[getter]
+            fn category(&self) -> &'static str {
+                $crate::handlers::chunk_category(self.chunk_type())
+            }
+
             fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
                 let dict = pyo3::types::PyDict::new(py);
                 dict.set_item("type", self.chunk_type())?;
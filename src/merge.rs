@@ -0,0 +1,160 @@
+//! Joining the recordings of consecutive server runs
+//!
+//! A server writes a new recording every time it starts, with ticks counted
+//! from zero again. Merging renumbers the ticks so the runs read like one
+//! recording. A restart disconnects every client, so the clients a run ended
+//! with are dropped before the next one starts.
+use std::collections::BTreeSet;
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use teehistorian::Chunk;
+use teehistorian::chunks::Drop;
+
+use crate::errors::TeehistorianParseError;
+use crate::header::PyHeader;
+use crate::transform::{Item, Rewriter, rewrite_all};
+
+/// Reason of the `Drop` written for clients still connected at the end of a run
+const RESTART_REASON: &[u8] = b"server restart";
+
+/// Clients and characters of the current run
+#[derive(Debug, Default)]
+struct Connected {
+    clients: BTreeSet<i32>,
+    characters: BTreeSet<i32>,
+}
+
+impl Connected {
+    fn observe(&mut self, chunk: &Chunk) {
+        match *chunk {
+            Chunk::JoinVer6 { cid }
+            | Chunk::JoinVer7 { cid }
+            | Chunk::Join { cid }
+            | Chunk::RejoinVer6 { cid } => {
+                self.clients.insert(cid);
+            }
+            Chunk::Drop(ref drop) => {
+                self.clients.remove(&drop.cid);
+                self.characters.remove(&drop.cid);
+            }
+            Chunk::PlayerNew(ref player) => {
+                self.characters.insert(player.cid);
+            }
+            Chunk::PlayerOld { cid } => {
+                self.characters.remove(&cid);
+            }
+            _ => {}
+        }
+    }
+
+    /// Remove the characters and drop the clients left at `tick`
+    ///
+    /// Returns whether anything was written.
+    fn end_run(&mut self, out: &mut Rewriter, tick: i32) -> PyResult<bool> {
+        let ended = !self.clients.is_empty() || !self.characters.is_empty();
+        for cid in std::mem::take(&mut self.characters) {
+            out.write(&Chunk::PlayerOld { cid }, tick)?;
+        }
+        for cid in std::mem::take(&mut self.clients) {
+            let drop = Chunk::Drop(Drop {
+                cid,
+                reason: RESTART_REASON,
+            });
+            out.write(&drop, tick)?;
+        }
+        Ok(ended)
+    }
+}
+
+/// Merge the recordings of consecutive server runs into one
+#[pyfunction]
+pub fn merge<'py>(py: Python<'py>, files: Vec<Vec<u8>>) -> PyResult<Bound<'py, PyBytes>> {
+    if files.is_empty() {
+        return Err(
+            TeehistorianParseError::Validation("No recordings to merge".to_string()).into(),
+        );
+    }
+    let inputs: Vec<&[u8]> = files.iter().map(Vec::as_slice).collect();
+
+    let mut first: Option<PyHeader> = None;
+    let mut recording = 0;
+    let mut offset = 0;
+    let mut last_tick = 0;
+    let mut connected = Connected::default();
+    rewrite_all(py, &inputs, |out, item, encoded, _| {
+        let (chunk, tick) = match item {
+            Item::Header(fields) => {
+                let header = PyHeader::from_json(fields.clone())?;
+                let Some(first) = &first else {
+                    first = Some(header);
+                    return Ok(());
+                };
+                recording += 1;
+                let diff = first.diff(py, &header)?;
+                if !diff.is_empty() {
+                    let fields: Vec<String> = diff.keys().extract()?;
+                    return Err(TeehistorianParseError::Validation(format!(
+                        "Recording {} is not compatible with the first one: {}",
+                        recording + 1,
+                        fields.join(", ")
+                    ))
+                    .into());
+                }
+                offset = last_tick + 1;
+                // Give the drops a tick of their own, so the player chunks of
+                // the next run cannot end up in the same tick
+                if connected.end_run(out, offset)? {
+                    offset += 1;
+                }
+                return Ok(());
+            }
+            // Readers forget the clients at a new stream themselves
+            Item::Segment(_) => {
+                connected = Connected::default();
+                return out.copy(encoded, None);
+            }
+            Item::End => return Ok(()),
+            Item::Chunk(chunk, tick) => (chunk, tick + offset),
+        };
+
+        last_tick = tick;
+        connected.observe(&chunk);
+        match chunk {
+            // The runs continue with the next recording
+            Chunk::Eos if recording < files.len() - 1 => Ok(()),
+            Chunk::Eos => out.copy(encoded, None),
+            _ => out.copy(encoded, Some(tick)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use teehistorian::chunks::PlayerNew;
+
+    use super::*;
+
+    #[test]
+    fn test_connected() {
+        let mut connected = Connected::default();
+        let chunks = [
+            Chunk::JoinVer6 { cid: 0 },
+            Chunk::Join { cid: 0 },
+            Chunk::Join { cid: 1 },
+            Chunk::PlayerNew(PlayerNew { cid: 1, x: 0, y: 0 }),
+            Chunk::Join { cid: 2 },
+            Chunk::Drop(Drop {
+                cid: 0,
+                reason: b"",
+            }),
+        ];
+        for chunk in &chunks {
+            connected.observe(chunk);
+        }
+        assert_eq!(connected.clients, BTreeSet::from([1, 2]));
+        assert_eq!(connected.characters, BTreeSet::from([1]));
+        connected.observe(&Chunk::PlayerOld { cid: 1 });
+        assert!(connected.characters.is_empty());
+    }
+}
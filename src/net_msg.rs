@@ -1,8 +1,10 @@
 pub use libtw2_gamenet_ddnet::msg::game::Game as DdnetGameMsg;
 use libtw2_gamenet_teeworlds_0_7::msg::Game;
 pub use libtw2_gamenet_teeworlds_0_7::msg::game::Game as Tw07GameMsg;
-use libtw2_packer::Unpacker;
+use libtw2_packer::{Unpacker, Warning};
+use std::collections::HashMap;
 use std::fmt;
+use teehistorian::Chunk;
 use warn::Warn;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -12,6 +14,26 @@ pub enum NetVersion {
     Unknown,
 }
 
+impl NetVersion {
+    /// Protocol from its Python name, `"0.6"` or `"0.7"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "0.6" => Some(Self::V06),
+            "0.7" => Some(Self::V07),
+            _ => None,
+        }
+    }
+
+    /// Python name of the protocol, None if it is unknown
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Self::V06 => Some("0.6"),
+            Self::V07 => Some("0.7"),
+            Self::Unknown => None,
+        }
+    }
+}
+
 #[allow(clippy::enum_variant_names)]
 pub enum Error<'a> {
     NonClientGameMsg06(DdnetGameMsg<'a>),
@@ -459,9 +481,12 @@ impl<W: fmt::Debug> Warn<W> for Stdout {
 }
 
 #[allow(clippy::result_large_err)]
-fn parse_ddnet(buf: &[u8]) -> Result<ClNetMessage<'_>, Error<'_>> {
+fn parse_ddnet<'a>(
+    buf: &'a [u8],
+    warn: &mut impl Warn<Warning>,
+) -> Result<ClNetMessage<'a>, Error<'a>> {
     let mut b = Unpacker::new(buf);
-    match DdnetGameMsg::decode(&mut Stdout, &mut b) {
+    match DdnetGameMsg::decode(warn, &mut b) {
         Ok(msg) => match msg {
             DdnetGameMsg::SvMotd(_)
             | DdnetGameMsg::SvBroadcast(_)
@@ -511,9 +536,12 @@ fn parse_ddnet(buf: &[u8]) -> Result<ClNetMessage<'_>, Error<'_>> {
 }
 
 #[allow(clippy::result_large_err)]
-fn parse_teeworlds_07(buf: &[u8]) -> Result<ClNetMessage<'_>, Error<'_>> {
+fn parse_teeworlds_07<'a>(
+    buf: &'a [u8],
+    warn: &mut impl Warn<Warning>,
+) -> Result<ClNetMessage<'a>, Error<'a>> {
     let mut b = Unpacker::new(buf);
-    match Tw07GameMsg::decode(&mut Stdout, &mut b) {
+    match Tw07GameMsg::decode(warn, &mut b) {
         Ok(msg) => match msg {
             Game::SvMotd(_)
             | Game::SvBroadcast(_)
@@ -563,17 +591,27 @@ fn parse_teeworlds_07(buf: &[u8]) -> Result<ClNetMessage<'_>, Error<'_>> {
 pub fn parse_net_msg<'a>(
     buf: &'a [u8],
     net_version: &mut NetVersion,
+) -> Result<ClNetMessage<'a>, Error<'a>> {
+    parse_net_msg_with(buf, net_version, &mut Stdout)
+}
+
+/// Like `parse_net_msg()`, reporting malformed messages to `warn`
+#[allow(clippy::result_large_err)]
+pub fn parse_net_msg_with<'a>(
+    buf: &'a [u8],
+    net_version: &mut NetVersion,
+    warn: &mut impl Warn<Warning>,
 ) -> Result<ClNetMessage<'a>, Error<'a>> {
     match *net_version {
-        NetVersion::V06 => parse_ddnet(buf),
-        NetVersion::V07 => parse_teeworlds_07(buf),
-        NetVersion::Unknown => match parse_ddnet(buf) {
+        NetVersion::V06 => parse_ddnet(buf, warn),
+        NetVersion::V07 => parse_teeworlds_07(buf, warn),
+        NetVersion::Unknown => match parse_ddnet(buf, warn) {
             Ok(msg) => {
                 *net_version = NetVersion::V06;
                 Ok(msg)
             }
             Err(err) => {
-                if let Ok(msg) = parse_teeworlds_07(buf) {
+                if let Ok(msg) = parse_teeworlds_07(buf, warn) {
                     *net_version = NetVersion::V07;
                     Ok(msg)
                 } else {
@@ -638,6 +676,160 @@ pub fn encode_player_info_message(
     buf
 }
 
+/// Encode a chat message as the `Cl_Say` a DDNet (0.6) client sends
+pub fn encode_chat_message(message: &str, team: bool) -> Vec<u8> {
+    let game_msg =
+        libtw2_gamenet_ddnet::msg::game::Game::ClSay(libtw2_gamenet_ddnet::msg::game::ClSay {
+            team,
+            message: message.as_bytes(),
+        });
+    let mut buf: Vec<u8> = Vec::with_capacity(16 + message.len());
+    libtw2_packer::with_packer(&mut buf, |p| {
+        let _ = game_msg.encode(p);
+    });
+    buf
+}
+
+/// Protocol of every client, from the `JoinVer6`/`JoinVer7` before its `Join`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientVersions {
+    versions: HashMap<i32, NetVersion>,
+}
+
+impl ClientVersions {
+    /// Advance past `chunk`
+    pub fn observe(&mut self, chunk: &Chunk) {
+        match *chunk {
+            Chunk::JoinVer6 { cid } => {
+                self.versions.insert(cid, NetVersion::V06);
+            }
+            Chunk::JoinVer7 { cid } => {
+                self.versions.insert(cid, NetVersion::V07);
+            }
+            Chunk::Drop(ref drop) => {
+                self.versions.remove(&drop.cid);
+            }
+            _ => {}
+        }
+    }
+
+    /// Protocol of a client, Unknown if it was not announced
+    pub fn get(&self, cid: i32) -> NetVersion {
+        self.versions
+            .get(&cid)
+            .copied()
+            .unwrap_or(NetVersion::Unknown)
+    }
+
+    /// Announced protocols by name, ordered by client ID
+    pub fn names(&self) -> Vec<(i32, &'static str)> {
+        let mut names: Vec<_> = self
+            .versions
+            .iter()
+            .filter_map(|(&cid, version)| Some((cid, version.name()?)))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl FromIterator<(i32, NetVersion)> for ClientVersions {
+    fn from_iter<I: IntoIterator<Item = (i32, NetVersion)>>(versions: I) -> Self {
+        Self {
+            versions: versions.into_iter().collect(),
+        }
+    }
+}
+
+/// Text of a client message that can identify a player
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlayerText {
+    Name,
+    Clan,
+    Chat,
+}
+
+/// Re-encode a client message with some of its texts replaced
+///
+/// `replace` gets the player name and clan of `ClStartInfo`/`ClChangeInfo`
+/// and the message of `ClSay`, and returns the text to put in their place or
+/// None to keep it. Returns None if nothing changed or the message is not one
+/// of these.
+pub fn replace_player_texts(
+    buf: &[u8],
+    net_version: NetVersion,
+    mut replace: impl FnMut(PlayerText, &[u8]) -> Option<Vec<u8>>,
+) -> Option<Vec<u8>> {
+    let mut buf_out: Vec<u8> = Vec::with_capacity(buf.len() + 32);
+    let mut unpacker = Unpacker::new(buf);
+    if net_version != NetVersion::V07
+        && let Ok(msg) = DdnetGameMsg::decode(&mut warn::Ignore, &mut unpacker)
+    {
+        let (name, clan, chat);
+        let msg = match msg {
+            DdnetGameMsg::ClSay(mut say) => {
+                chat = replace(PlayerText::Chat, say.message)?;
+                say.message = &chat;
+                DdnetGameMsg::ClSay(say)
+            }
+            DdnetGameMsg::ClStartInfo(mut info) => {
+                (name, clan) = replace_name_clan(&mut replace, info.name, info.clan)?;
+                (info.name, info.clan) = (&name, &clan);
+                DdnetGameMsg::ClStartInfo(info)
+            }
+            DdnetGameMsg::ClChangeInfo(mut info) => {
+                (name, clan) = replace_name_clan(&mut replace, info.name, info.clan)?;
+                (info.name, info.clan) = (&name, &clan);
+                DdnetGameMsg::ClChangeInfo(info)
+            }
+            _ => return None,
+        };
+        libtw2_packer::with_packer(&mut buf_out, |p| msg.encode(p)).ok()?;
+        return Some(buf_out);
+    }
+
+    let mut unpacker = Unpacker::new(buf);
+    if net_version != NetVersion::V06
+        && let Ok(msg) = Tw07GameMsg::decode(&mut warn::Ignore, &mut unpacker)
+    {
+        let (name, clan, chat);
+        let msg = match msg {
+            Tw07GameMsg::ClSay(mut say) => {
+                chat = replace(PlayerText::Chat, say.message)?;
+                say.message = &chat;
+                Tw07GameMsg::ClSay(say)
+            }
+            Tw07GameMsg::ClStartInfo(mut info) => {
+                (name, clan) = replace_name_clan(&mut replace, info.name, info.clan)?;
+                (info.name, info.clan) = (&name, &clan);
+                Tw07GameMsg::ClStartInfo(info)
+            }
+            _ => return None,
+        };
+        libtw2_packer::with_packer(&mut buf_out, |p| msg.encode(p)).ok()?;
+        return Some(buf_out);
+    }
+    None
+}
+
+/// Replacements for a name and clan, None if both are kept
+fn replace_name_clan(
+    replace: &mut impl FnMut(PlayerText, &[u8]) -> Option<Vec<u8>>,
+    name: &[u8],
+    clan: &[u8],
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    match (
+        replace(PlayerText::Name, name),
+        replace(PlayerText::Clan, clan),
+    ) {
+        (None, None) => None,
+        (new_name, new_clan) => Some((
+            new_name.unwrap_or_else(|| name.to_vec()),
+            new_clan.unwrap_or_else(|| clan.to_vec()),
+        )),
+    }
+}
+
 // Test function to check if libtw2 has encode capability
 #[cfg(test)]
 mod tests {
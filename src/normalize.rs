@@ -0,0 +1,275 @@
+//! Canonical re-encoding of recordings
+//!
+//! The same recording can be written in many ways: varints with redundant
+//! continuation bytes, positions and inputs in full or as diffs, `TickSkip`
+//! chunks that could be implied and header fields in any order. Normalizing
+//! writes every recording that means the same with the same bytes.
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use teehistorian::Chunk;
+use teehistorian::chunks::{InputNew, PlayerNew};
+
+use crate::chunks::encode;
+use crate::errors::TeehistorianParseError;
+use crate::input;
+use crate::resolve::{Delta, DeltaEncoder};
+use crate::transform::{Item, rewrite};
+
+/// How positions and inputs are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Diffs {
+    /// As they are in the input
+    Keep,
+    /// As absolute values
+    Expand,
+    /// As diffs wherever a diff is possible, the way the server writes them
+    Compress,
+    /// As diffs or absolute values, whichever is shorter
+    Smallest,
+}
+
+/// Sizes before and after `recompress()`
+#[pyclass(name = "RecompressReport", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyRecompressReport {
+    /// Size of the uncompressed input
+    #[pyo3(get)]
+    original_bytes: usize,
+    /// Size of the recompressed recording
+    #[pyo3(get)]
+    recompressed_bytes: usize,
+}
+
+#[pymethods]
+impl PyRecompressReport {
+    /// Bytes saved by recompressing
+    #[getter]
+    fn saved_bytes(&self) -> i64 {
+        self.original_bytes as i64 - self.recompressed_bytes as i64
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RecompressReport(original_bytes={}, recompressed_bytes={})",
+            self.original_bytes, self.recompressed_bytes
+        )
+    }
+}
+
+/// Position and input chunks to write for `chunk`
+///
+/// `values` holds the positions and inputs before `chunk` and is advanced
+/// past it.
+fn rewrite_diffs<'a>(
+    values: &mut DeltaEncoder,
+    chunk: Chunk<'a>,
+    diffs: Diffs,
+) -> PyResult<Option<Chunk<'a>>> {
+    let compress = matches!(diffs, Diffs::Compress | Diffs::Smallest);
+    let delta = match chunk {
+        Chunk::PlayerNew(_) | Chunk::InputNew(_) if compress => values.encode(&chunk),
+        Chunk::PlayerDiff(ref diff) if compress && diff.dx == 0 && diff.dy == 0 => Delta::Unchanged,
+        Chunk::InputDiff(ref diff) if compress && diff.dinput == [0; 10] => Delta::Unchanged,
+        _ => Delta::Keep,
+    };
+    values.observe(&chunk);
+    let absolute = match chunk {
+        Chunk::PlayerDiff(ref diff) => {
+            let [x, y] = values.position(diff.cid).unwrap_or_default();
+            Some(Chunk::PlayerNew(PlayerNew {
+                cid: diff.cid,
+                x,
+                y,
+            }))
+        }
+        Chunk::InputDiff(ref diff) => Some(Chunk::InputNew(InputNew {
+            cid: diff.cid,
+            input: values.input(diff.cid).copied().unwrap_or_default(),
+        })),
+        _ => None,
+    };
+    let shorter = |a: &Chunk, b: &Chunk| PyResult::Ok(encode(a)?.len() < encode(b)?.len());
+
+    Ok(match (delta, absolute) {
+        (Delta::Unchanged, _) => None,
+        (Delta::Diff(diff), _) if diffs == Diffs::Smallest && shorter(&chunk, &diff)? => {
+            Some(chunk)
+        }
+        (Delta::Diff(diff), _) => Some(diff),
+        (Delta::Keep, Some(absolute)) if diffs == Diffs::Expand => Some(absolute),
+        (Delta::Keep, Some(absolute))
+            if diffs == Diffs::Smallest && shorter(&absolute, &chunk)? =>
+        {
+            Some(absolute)
+        }
+        (Delta::Keep, _) => Some(chunk),
+    })
+}
+
+/// Rewrite the positions and inputs of a recording
+///
+/// With `reencode`, every other chunk is decoded and encoded again as well,
+/// otherwise it is copied as it is.
+fn rewrite_values<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    diffs: Diffs,
+    reencode: bool,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut values = DeltaEncoder::new();
+    rewrite(py, data, |out, item, encoded, _| match item {
+        Item::Header(_) | Item::End => Ok(()),
+        Item::Segment(header) => {
+            values = DeltaEncoder::new();
+            let header = serde_json::from_slice(header).map_err(|e| {
+                TeehistorianParseError::Parse(format!("Failed to parse header: {}", e))
+            })?;
+            out.write_segment(&header)
+        }
+        Item::Chunk(Chunk::Eos, _) => out.copy(&encode(&Chunk::Eos)?, None),
+        Item::Chunk(chunk, tick) => {
+            let is_value = matches!(
+                chunk,
+                Chunk::PlayerNew(_)
+                    | Chunk::PlayerDiff(_)
+                    | Chunk::InputNew(_)
+                    | Chunk::InputDiff(_)
+            );
+            if !reencode && !is_value {
+                return out.copy(encoded, Some(tick));
+            }
+            match rewrite_diffs(&mut values, chunk, diffs)? {
+                Some(chunk) => out.write(&chunk, tick),
+                None => Ok(()),
+            }
+        }
+    })
+}
+
+/// Re-encode a recording canonically
+///
+/// Every chunk is decoded and encoded again, the needed `TickSkip` chunks
+/// are generated and the header is written with sorted keys, so byte-level
+/// deduplication and diffing of recordings become meaningful.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `expand_diffs` - Write `PlayerDiff`/`InputDiff` as the absolute
+///   `PlayerNew`/`InputNew` they result in; takes precedence over
+///   `recompress_diffs`
+/// * `recompress_diffs` - Write positions and inputs as diffs wherever
+///   possible and drop the ones that change nothing
+///
+/// # Example
+/// ```python
+/// assert th.normalize(a) == th.normalize(b)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, *, expand_diffs=false, recompress_diffs=true))]
+pub fn normalize<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    expand_diffs: bool,
+    recompress_diffs: bool,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let diffs = match (expand_diffs, recompress_diffs) {
+        (true, _) => Diffs::Expand,
+        (false, true) => Diffs::Compress,
+        (false, false) => Diffs::Keep,
+    };
+    rewrite_values(py, data, diffs, true)
+}
+
+/// Rewrite a recording to take less space
+///
+/// Positions and inputs that did not change are dropped and the others are
+/// written as diffs. The `"small"` profile writes an absolute value instead
+/// of a diff where that is shorter, e.g. after a teleport, and encodes every
+/// other chunk again with the shortest varints. The `"fast"` profile always
+/// writes diffs, like the server, and copies every other chunk. The result
+/// is the same for the same input.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `profile` - `"small"` or `"fast"`
+///
+/// # Returns
+/// The recompressed, uncompressed recording and a `RecompressReport`
+///
+/// # Example
+/// ```python
+/// smaller, report = th.recompress(data)
+/// print(f"Saved {report.saved_bytes} bytes")
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, profile="small"))]
+pub fn recompress<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    profile: &str,
+) -> PyResult<(Bound<'py, PyBytes>, PyRecompressReport)> {
+    let (diffs, reencode) = match profile {
+        "small" => (Diffs::Smallest, true),
+        "fast" => (Diffs::Compress, false),
+        _ => {
+            return Err(TeehistorianParseError::Validation(format!(
+                "Unknown profile: {}, expected \"small\" or \"fast\"",
+                profile
+            ))
+            .into());
+        }
+    };
+    let data = input::decode_input(data)?;
+    let recompressed = rewrite_values(py, &data, diffs, reencode)?;
+    let report = PyRecompressReport {
+        original_bytes: data.len(),
+        recompressed_bytes: recompressed.as_bytes().len(),
+    };
+    Ok((recompressed, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use teehistorian::chunks::PlayerDiff;
+
+    use super::*;
+
+    #[test]
+    fn test_rewrite_diffs() {
+        let new = |x| Chunk::PlayerNew(PlayerNew { cid: 0, x, y: 0 });
+        let diff = |dx| Chunk::PlayerDiff(PlayerDiff { cid: 0, dx, dy: 0 });
+        let rewrite =
+            |values: &mut DeltaEncoder, chunk, diffs| rewrite_diffs(values, chunk, diffs).unwrap();
+
+        let mut values = DeltaEncoder::new();
+        assert_eq!(rewrite(&mut values, new(1), Diffs::Compress), Some(new(1)));
+        assert_eq!(rewrite(&mut values, new(3), Diffs::Compress), Some(diff(2)));
+        assert_eq!(rewrite(&mut values, diff(0), Diffs::Compress), None);
+        assert_eq!(rewrite(&mut values, new(3), Diffs::Compress), None);
+
+        let mut values = DeltaEncoder::new();
+        assert_eq!(rewrite(&mut values, new(1), Diffs::Expand), Some(new(1)));
+        assert_eq!(rewrite(&mut values, diff(2), Diffs::Expand), Some(new(3)));
+        assert_eq!(rewrite(&mut values, diff(0), Diffs::Keep), Some(diff(0)));
+
+        // A teleport back to the origin is shorter as an absolute position
+        let mut values = DeltaEncoder::new();
+        let far = 1_000_000;
+        assert_eq!(
+            rewrite(&mut values, new(far), Diffs::Smallest),
+            Some(new(far))
+        );
+        assert_eq!(
+            rewrite(&mut values, new(far + 1), Diffs::Smallest),
+            Some(diff(1))
+        );
+        assert_eq!(
+            rewrite(&mut values, diff(-far - 1), Diffs::Smallest),
+            Some(new(0))
+        );
+        assert_eq!(
+            rewrite(&mut values, new(far), Diffs::Compress),
+            Some(diff(far))
+        );
+    }
+}
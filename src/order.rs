@@ -0,0 +1,134 @@
+//! Chunk order checks for the writer
+//!
+//! A server never moves a character that did not spawn or drops a client
+//! that did not join, and nothing follows the `Eos` of a stream. `ChunkOrder`
+//! tracks player slots the way the server fills them, so a strict writer can
+//! reject such chunks instead of producing a file other tools choke on. The
+//! `JoinVer6`/`JoinVer7` announcing a client's protocol is always directly
+//! followed by its `Join`.
+use std::collections::HashSet;
+
+use teehistorian::Chunk;
+
+use crate::errors::TeehistorianParseError;
+
+/// Joined clients and spawned characters of a chunk stream
+#[derive(Debug, Clone, Default)]
+pub struct ChunkOrder {
+    joined: HashSet<i32>,
+    spawned: HashSet<i32>,
+    /// Client whose `JoinVer6`/`JoinVer7` was the last chunk
+    announced: Option<i32>,
+    ended: bool,
+}
+
+impl ChunkOrder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check that `chunk` may follow the chunks observed so far
+    pub fn check(&self, chunk: &Chunk) -> Result<(), TeehistorianParseError> {
+        let message = if self.ended {
+            "Chunk written after Eos".to_string()
+        } else if let Some(announced) = self.announced
+            && !matches!(*chunk, Chunk::Join { cid } if cid == announced)
+        {
+            format!(
+                "Join of client {} missing after its protocol version",
+                announced
+            )
+        } else {
+            match *chunk {
+                Chunk::Join { cid } | Chunk::JoinVer6 { cid } | Chunk::JoinVer7 { cid }
+                    if self.joined.contains(&cid) =>
+                {
+                    format!("Client {} joined twice", cid)
+                }
+                Chunk::Drop(ref drop) if !self.joined.contains(&drop.cid) => {
+                    format!("Client {} dropped without joining", drop.cid)
+                }
+                Chunk::PlayerDiff(ref diff) if !self.spawned.contains(&diff.cid) => {
+                    format!("PlayerDiff for client {} before its PlayerNew", diff.cid)
+                }
+                _ => return Ok(()),
+            }
+        };
+        Err(TeehistorianParseError::Validation(message))
+    }
+
+    /// Advance past `chunk`, which passed `check()`
+    pub fn observe(&mut self, chunk: &Chunk) {
+        // Servers write the protocol version right before the `Join`
+        self.announced = match *chunk {
+            Chunk::JoinVer6 { cid } | Chunk::JoinVer7 { cid } => Some(cid),
+            _ => None,
+        };
+        match *chunk {
+            // Clients kept over a map change rejoin without a `Join`
+            Chunk::Join { cid } | Chunk::RejoinVer6 { cid } => {
+                self.joined.insert(cid);
+            }
+            Chunk::Drop(ref drop) => {
+                self.joined.remove(&drop.cid);
+                self.spawned.remove(&drop.cid);
+            }
+            Chunk::PlayerNew(ref player) => {
+                self.spawned.insert(player.cid);
+            }
+            Chunk::PlayerOld { cid } => {
+                self.spawned.remove(&cid);
+            }
+            Chunk::Eos => self.ended = true,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use teehistorian::chunks::{Drop, PlayerDiff, PlayerNew};
+
+    use super::*;
+
+    fn write(order: &mut ChunkOrder, chunk: Chunk) -> Result<(), TeehistorianParseError> {
+        order.check(&chunk)?;
+        order.observe(&chunk);
+        Ok(())
+    }
+
+    #[test]
+    fn test_player_lifecycle() {
+        let mut order = ChunkOrder::new();
+        let diff = |cid| Chunk::PlayerDiff(PlayerDiff { cid, dx: 1, dy: 0 });
+        let drop = |cid| {
+            Chunk::Drop(Drop {
+                cid,
+                reason: b"quit",
+            })
+        };
+
+        assert!(write(&mut order, drop(0)).is_err());
+        write(&mut order, Chunk::Join { cid: 0 }).unwrap();
+        assert!(write(&mut order, Chunk::Join { cid: 0 }).is_err());
+        assert!(write(&mut order, diff(0)).is_err());
+        write(
+            &mut order,
+            Chunk::PlayerNew(PlayerNew { cid: 0, x: 0, y: 0 }),
+        )
+        .unwrap();
+        write(&mut order, diff(0)).unwrap();
+        write(&mut order, Chunk::PlayerOld { cid: 0 }).unwrap();
+        assert!(write(&mut order, diff(0)).is_err());
+        write(&mut order, drop(0)).unwrap();
+        write(&mut order, Chunk::Join { cid: 0 }).unwrap();
+
+        write(&mut order, Chunk::JoinVer7 { cid: 1 }).unwrap();
+        assert!(write(&mut order, Chunk::Join { cid: 2 }).is_err());
+        write(&mut order, Chunk::Join { cid: 1 }).unwrap();
+        assert!(write(&mut order, Chunk::JoinVer6 { cid: 1 }).is_err());
+
+        write(&mut order, Chunk::Eos).unwrap();
+        assert!(write(&mut order, Chunk::TickSkip { dt: 0 }).is_err());
+    }
+}
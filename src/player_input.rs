@@ -0,0 +1,112 @@
+//! Named view of the ten input integers
+//!
+//! `InputNew` and `InputDiff` carry DDNet's `CNetObj_PlayerInput` as a flat
+//! list of ten integers. `PlayerInput` names them in the same order and maps
+//! back to the list for writing.
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+macro_rules! define_player_input {
+    ($($(#[$field_meta:meta])* $name:ident),* $(,)?) => {
+        /// Player input with named fields
+        ///
+        /// Obtained from `InputNew.player_input` or `InputDiff.player_input`;
+        /// for diffs the fields are the deltas.
+        #[pyclass(name = "PlayerInput", module = "teehistorian_py", frozen, get_all, eq)]
+        #[derive(Debug, Clone, Default, PartialEq, Eq)]
+        pub struct PyPlayerInput {
+            $($(#[$field_meta])* pub $name: i32,)*
+        }
+
+        impl PyPlayerInput {
+            /// Field names in wire order
+            pub const FIELDS: &'static [&'static str] = &[$(stringify!($name)),*];
+
+            /// Build from the input integers, missing values are 0
+            pub fn from_slice(input: &[i32]) -> Self {
+                let mut values = input.iter().copied();
+                Self {
+                    $($name: values.next().unwrap_or(0),)*
+                }
+            }
+
+            /// The input integers in wire order
+            pub fn to_array(&self) -> [i32; 10] {
+                [$(self.$name),*]
+            }
+        }
+
+        #[pymethods]
+        impl PyPlayerInput {
+            #[new]
+            #[allow(clippy::too_many_arguments)]
+            #[pyo3(signature = (*, $($name = 0),*))]
+            fn py_new($($name: i32),*) -> Self {
+                Self { $($name),* }
+            }
+
+            /// Field names in wire order
+            #[classattr]
+            #[pyo3(name = "FIELDS")]
+            fn fields() -> Vec<&'static str> {
+                Self::FIELDS.to_vec()
+            }
+
+            /// Build from the list of input integers
+            #[staticmethod]
+            fn from_list(input: Vec<i32>) -> Self {
+                Self::from_slice(&input)
+            }
+
+            /// The input integers in wire order, as taken by `InputNew`/`InputDiff`
+            fn to_list(&self) -> Vec<i32> {
+                self.to_array().to_vec()
+            }
+
+            fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+                let dict = PyDict::new(py);
+                for (name, value) in Self::FIELDS.iter().zip(self.to_array()) {
+                    dict.set_item(name, value)?;
+                }
+                Ok(dict)
+            }
+
+            fn __repr__(&self) -> String {
+                format!("{:?}", self).replacen("Py", "", 1)
+            }
+        }
+    };
+}
+
+define_player_input! {
+    /// -1 left, 0 none, 1 right
+    direction,
+    /// Aim position relative to the tee
+    target_x,
+    target_y,
+    jump,
+    /// Fire press counter, incremented on press and release
+    fire,
+    hook,
+    player_flags,
+    wanted_weapon,
+    next_weapon,
+    prev_weapon,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let input = [1, 100, -50, 1, 3, 0, 1, 2, 0, 0];
+        let named = PyPlayerInput::from_slice(&input);
+        assert_eq!(named.direction, 1);
+        assert_eq!(named.target_y, -50);
+        assert_eq!(named.fire, 3);
+        assert_eq!(named.wanted_weapon, 2);
+        assert_eq!(named.to_array(), input);
+        assert_eq!(PyPlayerInput::from_slice(&[5]).target_x, 0);
+    }
+}
@@ -21,40 +21,112 @@ Example:
 from __future__ import annotations
 
 from pathlib import Path
-from typing import TYPE_CHECKING, Any, Iterable, Union
+from typing import TYPE_CHECKING, Any, Dict, Iterable, List, Optional, Union
 
-from .utils import calculate_uuid, format_uuid_from_bytes
+from .session import RecordingSession
+from .utils import calculate_uuid, derive_uuid, format_uuid_from_bytes
 
 if TYPE_CHECKING:
     from os import PathLike
 
 from ._rust import (  # type: ignore[attr-defined]
+    Anomaly,
+    AntibotEvent,
+    ChunkIterator,
     CustomChunk,
-    Generic,
+    ExtensionHandler,
+    Finish,
+    FollowIterator,
+    Header,
+    ParserState,
+    ParserStats,
+    ParserWarning,
+    PlayerInput,
+    PlayerSession,
+    PlayerTracker,
+    RawChunk,
+    RecompressReport,
+    RecordingDiff,
+    RegisteredUuid,
+    RepairReport,
+    RunVerification,
+    SegmentStart,
+    SnapshotIterator,
     Teehistorian,
     TeehistorianError,
+    TeehistorianWarning,
+    Tuning,
     Unknown,
+    UuidRegistration,
+    ValidationReport,
+    anonymize,
+    categories,
+    detect_anomalies,
+    chunk_types,
+    detect_format,
+    diff,
+    extract_finishes,
+    extract_player,
+    extract_sessions,
+    input_stats,
+    make_chunk,
+    merge,
+    normalize,
+    parse_chunk,
+    patch,
+    recompress,
+    redact_chat,
+    remap_cids,
+    repair,
+    rewrite_header,
+    shift_ticks,
+    slice_ticks,
+    split,
+    strip,
+    transform,
+    verify_run,
     PyAntiBot as AntiBot,
+    PyAuthInit as AuthInit,
     PyAuthLogin as AuthLogin,
+    PyAuthLogout as AuthLogout,
+    PyChatMessage as ChatMessage,
+    PyClientCommand as ClientCommand,
     PyConsoleCommand as ConsoleCommand,
     PyDdnetVersion as DdnetVersion,
+    PyDdnetVersionOld as DdnetVersionOld,
     PyDrop as Drop,
+    PyEmoticon as Emoticon,
     PyEos as Eos,
     PyInputDiff as InputDiff,
     PyInputNew as InputNew,
     PyJoin as Join,
     PyJoinVer6 as JoinVer6,
+    PyJoinVer7 as JoinVer7,
+    PyKillMessage as KillMessage,
     PyNetMessage as NetMessage,
     PyNetMessagePlayerInfo as NetMessagePlayerInfo,
     PyPlayerDiff as PlayerDiff,
+    PyPlayerFinish as PlayerFinish,
     PyPlayerName as PlayerName,
     PyPlayerNew as PlayerNew,
     PyPlayerOld as PlayerOld,
+    PyPlayerPosition as PlayerPosition,
     PyPlayerReady as PlayerReady,
+    PyPlayerRejoin as PlayerRejoin,
+    PyPlayerSwap as PlayerSwap,
     PyPlayerTeam as PlayerTeam,
+    PySetSpectatorMode as SetSpectatorMode,
+    PySetTeam as SetTeam,
+    PyTeamFinish as TeamFinish,
     PyTeamLoadFailure as TeamLoadFailure,
     PyTeamLoadSuccess as TeamLoadSuccess,
+    PyTeamPractice as TeamPractice,
+    PyTeamSaveFailure as TeamSaveFailure,
+    PyTeamSaveSuccess as TeamSaveSuccess,
+    PyTestChunk as TestChunk,
     PyTickSkip as TickSkip,
+    PyVote as Vote,
+    PyVoteCall as VoteCall,
     TeehistorianWriter as RustTeehistorianWriter,
 )
 
@@ -83,14 +155,31 @@ __version__ = "2.0.0"
 
 
 # Modern Pythonic helpers
-def parse(path: Union[str, PathLike[str]]) -> Teehistorian:
+def parse(
+    path: Union[str, PathLike[str]],
+    *,
+    skip_inputs: bool = False,
+    exclude_categories: Optional[List[str]] = None,
+    emit_warnings: bool = False,
+    resolve_inputs: bool = False,
+    resolve_positions: bool = False,
+    unknown_uuid_policy: str = "yield",
+) -> Teehistorian:
     """
     Parse a teehistorian file from a path.
 
-    This is the recommended way to parse teehistorian files.
+    This is the recommended way to parse teehistorian files. Compressed
+    files (``.teehistorian.gz``, ``.teehistorian.zst``) are decompressed
+    transparently.
 
     Args:
         path: Path to the teehistorian file (str or Path object)
+        skip_inputs: Discard InputNew/InputDiff chunks while decoding
+        exclude_categories: Chunk categories to discard while decoding
+        emit_warnings: Also issue parser warnings through the warnings module
+        resolve_inputs: Return every InputDiff as InputNew with the absolute input
+        resolve_positions: Return PlayerNew/PlayerDiff as PlayerPosition chunks
+        unknown_uuid_policy: Yield, skip or raise on chunks of unregistered UUIDs
 
     Returns:
         Teehistorian parser instance
@@ -106,10 +195,27 @@ def parse(path: Union[str, PathLike[str]]) -> Teehistorian:
         >>> for chunk in parser:
         ...     print(chunk)
     """
-    return Teehistorian(Path(path).read_bytes())
-
-
-def open(path: Union[str, PathLike[str]]) -> Teehistorian:
+    return Teehistorian(
+        Path(path).read_bytes(),
+        skip_inputs=skip_inputs,
+        exclude_categories=exclude_categories,
+        emit_warnings=emit_warnings,
+        resolve_inputs=resolve_inputs,
+        resolve_positions=resolve_positions,
+        unknown_uuid_policy=unknown_uuid_policy,
+    )
+
+
+def open(
+    path: Union[str, PathLike[str]],
+    *,
+    skip_inputs: bool = False,
+    exclude_categories: Optional[List[str]] = None,
+    emit_warnings: bool = False,
+    resolve_inputs: bool = False,
+    resolve_positions: bool = False,
+    unknown_uuid_policy: str = "yield",
+) -> Teehistorian:
     """
     Open a teehistorian file for parsing.
 
@@ -118,6 +224,12 @@ def open(path: Union[str, PathLike[str]]) -> Teehistorian:
 
     Args:
         path: Path to the teehistorian file (str or Path object)
+        skip_inputs: Discard InputNew/InputDiff chunks while decoding
+        exclude_categories: Chunk categories to discard while decoding
+        emit_warnings: Also issue parser warnings through the warnings module
+        resolve_inputs: Return every InputDiff as InputNew with the absolute input
+        resolve_positions: Return PlayerNew/PlayerDiff as PlayerPosition chunks
+        unknown_uuid_policy: Yield, skip or raise on chunks of unregistered UUIDs
 
     Returns:
         Teehistorian parser instance
@@ -135,7 +247,15 @@ def open(path: Union[str, PathLike[str]]) -> Teehistorian:
         ...     for chunk in parser:
         ...         print(chunk)
     """
-    return parse(path)
+    return parse(
+        path,
+        skip_inputs=skip_inputs,
+        exclude_categories=exclude_categories,
+        emit_warnings=emit_warnings,
+        resolve_inputs=resolve_inputs,
+        resolve_positions=resolve_positions,
+        unknown_uuid_policy=unknown_uuid_policy,
+    )
 
 
 class TeehistorianWriter:
@@ -144,40 +264,187 @@ class TeehistorianWriter:
 
     This class provides a clean, high-level interface for creating teehistorian files
     following Python best practices.
+
+    Args:
+        file: Optional file-like object to stream to, e.g. a pipe or socket
+            file. Chunks are passed to its write() as they are flushed.
+        path: Optional file to stream to. Chunks are flushed to it in batches
+            instead of being kept in memory, so long sessions can be recorded.
+        buffer_size: Buffered bytes after which a streaming writer flushes,
+            0 flushes every chunk
+        strict: Reject chunks in an order no server writes, e.g. a PlayerDiff
+            before the client's PlayerNew or anything after Eos
+        delta_encode: Take absolute PlayerNew/PlayerPosition and InputNew
+            chunks and write PlayerDiff/InputDiff like the server does
+        spill_threshold: Bytes after which an in-memory writer moves its
+            data to a temporary file, so huge recordings fit in memory
+        extensions: Custom extension UUIDs to register in the header up front,
+            mapped to the handler_name of their chunks. A streaming writer
+            rejects custom UUIDs first written after its header was flushed.
+
+    Example:
+        >>> with th.TeehistorianWriter(path="live.teehistorian") as writer:
+        ...     _ = writer.write(th.Join(0))
     """
 
-    def __init__(self) -> None:
-        self._writer = RustTeehistorianWriter()
+    def __init__(
+        self,
+        file: Any = None,
+        *,
+        path: Optional[Union[str, PathLike[str]]] = None,
+        buffer_size: int = 64 * 1024,
+        strict: bool = False,
+        delta_encode: bool = False,
+        spill_threshold: Optional[int] = None,
+        extensions: Optional[Dict[str, str]] = None,
+    ) -> None:
+        self._writer = RustTeehistorianWriter(
+            file,
+            path=path,
+            buffer_size=buffer_size,
+            strict=strict,
+            delta_encode=delta_encode,
+            spill_threshold=spill_threshold,
+            extensions=extensions,
+        )
         self._closed = False
 
+    @classmethod
+    def append(
+        cls, source: Union[bytes, str, PathLike[str]], *, strict: bool = False
+    ) -> "TeehistorianWriter":
+        """
+        Continue an existing recording.
+
+        The header, registered extensions, last tick and (with ``strict``)
+        the joined clients are taken over. The final Eos, or the incomplete
+        last chunk of an interrupted recording, is dropped so new chunks
+        follow the existing ones. The result is kept in memory.
+
+        Args:
+            source: Path or bytes of the recording, possibly compressed
+            strict: Check chunk order like ``TeehistorianWriter(strict=True)``
+
+        Returns:
+            Writer holding the existing chunks
+
+        Example:
+            >>> with th.TeehistorianWriter.append("cut.teehistorian") as writer:
+            ...     _ = writer.write(th.Drop(0, "server restart"))
+            >>> writer.save("cut.teehistorian")
+        """
+        writer = cls()
+        writer._writer = RustTeehistorianWriter.append(source, strict=strict)
+        return writer
+
     def __enter__(self) -> "TeehistorianWriter":
         """Enter the context manager."""
         return self
 
     def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None:
-        if not self._closed:
+        if self._closed:
+            return
+        if exc_type is None:
+            self.finalize()
+        else:
+            # Leave the recording without Eos, so it is recognizably cut off
             self._closed = True
-            if exc_type is None:
-                self._writer.write(Eos())
+            self._writer.flush()
+
+    def finalize(self) -> None:
+        """
+        Finish the recording.
 
-    def write(self, chunk: Any) -> "TeehistorianWriter":
+        Writes the Eos chunk unless the last chunk already is one, flushes a
+        streaming writer and closes the writer, so further writes raise.
+        Leaving a ``with`` block without an exception calls this. Calling it
+        again does nothing.
+
+        Example:
+            >>> writer = th.create().write(th.Join(0))
+            >>> writer.finalize()
+            >>> writer.getvalue().endswith(th.Eos().write_to_buffer())
+            True
+        """
+        self._writer.finalize()
+        self._closed = True
+
+    def close(self) -> None:
+        """Finish the recording, same as finalize()."""
+        self.finalize()
+
+    def write(self, chunk: Any, tick: Optional[int] = None) -> "TeehistorianWriter":
         """
         Write a chunk to the teehistorian file.
 
         Args:
             chunk: A chunk object (Join, Drop, PlayerNew, etc.)
+            tick: Absolute tick to write the chunk at. The needed TickSkip
+                is inserted automatically.
 
         Returns:
             Self for method chaining
 
         Example:
             >>> _ = writer.write(th.Join(0)).write(th.PlayerName(0, "Player"))
+            >>> _ = writer.write(th.PlayerNew(0, 100, 200), tick=50)
         """
         if self._closed:
             raise ValueError("Cannot write to closed writer")
-        self._writer.write(chunk)
+        self._writer.write(chunk, tick)
         return self
 
+    def write_join(
+        self,
+        client_id: int,
+        protocol: Optional[int] = None,
+        *,
+        version: Any = None,
+        tick: Optional[int] = None,
+    ) -> "TeehistorianWriter":
+        """
+        Write the chunks a server records when a client connects.
+
+        Args:
+            client_id: ID of the connecting client
+            protocol: 6 or 7 writes the JoinVer6/JoinVer7 DDNet servers record
+                before the Join, None only the Join
+            version: DdnetVersion or DdnetVersionOld of the client, written
+                after the Join
+            tick: Absolute tick to write the chunks at
+
+        Returns:
+            Self for method chaining
+
+        Example:
+            >>> _ = writer.write_join(0, 7)
+            >>> _ = writer.write_join(1, 6, version=th.DdnetVersionOld(1, 16050))
+        """
+        if self._closed:
+            raise ValueError("Cannot write to closed writer")
+        self._writer.write_join(client_id, protocol, version=version, tick=tick)
+        return self
+
+    def advance_to_tick(self, tick: int) -> "TeehistorianWriter":
+        """
+        Advance to an absolute tick by writing the matching TickSkip.
+
+        Args:
+            tick: Tick to advance to, not before the current tick
+
+        Returns:
+            Self for method chaining
+        """
+        if self._closed:
+            raise ValueError("Cannot write to closed writer")
+        self._writer.advance_to_tick(tick)
+        return self
+
+    @property
+    def tick(self) -> int:
+        """Tick of the most recently written chunk."""
+        return self._writer.tick
+
     def write_all(self, chunks: Iterable[Any]) -> "TeehistorianWriter":
         """
         Write multiple chunks at once.
@@ -192,26 +459,67 @@ class TeehistorianWriter:
             self.write(chunk)  # type: ignore[reportUnusedCallResult]
         return self
 
-    def set_header(self, key: str, value: str) -> "TeehistorianWriter":
+    def write_from(self, chunks: Iterable[Any], batch_size: int = 1024) -> int:
+        """
+        Write chunks pulled from an iterable, e.g. a parser.
+
+        Chunks are serialized in batches with the GIL released, which makes
+        parse → transform → write pipelines much faster than write_all().
+
+        Args:
+            chunks: Iterable of chunk objects
+            batch_size: Number of chunks serialized per batch
+
+        Returns:
+            Number of chunks written
+
+        Example:
+            >>> parser = th.Teehistorian(data)
+            >>> writer.write_from(c for c in parser if not isinstance(c, th.ConsoleCommand))
+        """
+        if self._closed:
+            raise ValueError("Cannot write to closed writer")
+        return self._writer.write_from(chunks, batch_size)
+
+    def set_header(self, key: str, value: Any) -> "TeehistorianWriter":
         """
         Set a header field.
 
         Args:
             key: Header field name
-            value: Header field value
+            value: Header field value. Strings holding a JSON object or
+                array are parsed, other strings are stored as-is. Ints,
+                floats, bools, None, lists and dicts are stored as the
+                matching JSON type.
 
         Returns:
             Self for method chaining
 
         Example:
             >>> _ = writer.set_header("server_name", "My Server")
+            >>> _ = writer.set_header("config", {"sv_max_clients": 64})
         """
         if self._closed:
             raise ValueError("Cannot modify header of closed writer")
         self._writer.set_header(key, value)
         return self
 
-    def get_header(self, key: str) -> Union[str, None]:
+    def remove_header(self, key: str) -> "TeehistorianWriter":
+        """
+        Remove a header field, nothing happens if it is not set.
+
+        Args:
+            key: Header field name
+
+        Returns:
+            Self for method chaining
+        """
+        if self._closed:
+            raise ValueError("Cannot modify header of closed writer")
+        self._writer.remove_header(key)
+        return self
+
+    def get_header(self, key: str) -> Any:
         """
         Get a header field value.
 
@@ -219,19 +527,20 @@ class TeehistorianWriter:
             key: Header field name
 
         Returns:
-            Header field value or None if not set
+            Header field value as json.loads would return it, or None if
+            not set
 
         Example:
             >>> server_name = writer.get_header("server_name")
         """
         return self._writer.get_header(key)
 
-    def update_headers(self, headers: dict[str, str]) -> "TeehistorianWriter":
+    def update_headers(self, headers: dict[str, Any]) -> "TeehistorianWriter":
         """
         Update multiple header fields.
 
         Args:
-            headers: Dictionary of header fields
+            headers: Dictionary of header fields, values as in set_header()
 
         Returns:
             Self for method chaining
@@ -246,6 +555,26 @@ class TeehistorianWriter:
             self.set_header(key, value)
         return self
 
+    def copy_header_from(
+        self, source: Union[Teehistorian, Header, dict[str, Any], str, bytes]
+    ) -> "TeehistorianWriter":
+        """
+        Replace the header with the one of another recording.
+
+        Args:
+            source: A parser, a Header, a dict of header fields or the
+                header JSON
+
+        Returns:
+            Self for method chaining
+
+        Example:
+            >>> parser = th.Teehistorian(data)
+            >>> _ = writer.copy_header_from(parser).set_header("comment", "filtered")
+        """
+        self._writer.copy_header_from(source)
+        return self
+
     def save(self, path: Union[str, PathLike[str]]) -> None:
         """
         Save the teehistorian to a file.
@@ -286,6 +615,10 @@ class TeehistorianWriter:
         """
         self._writer.writeto(file)
 
+    def flush(self) -> None:
+        """Write buffered chunks to the file or file object of a streaming writer."""
+        self._writer.flush()
+
     @property
     def size(self) -> int:
         """Get the current size of the teehistorian data in bytes."""
@@ -296,6 +629,44 @@ class TeehistorianWriter:
         """Check if any data has been written."""
         return self._writer.is_empty()
 
+    @property
+    def spilled(self) -> bool:
+        """Whether the data was moved to a temporary file."""
+        return self._writer.spilled
+
+    def reserve(self, n_bytes: int) -> "TeehistorianWriter":
+        """
+        Preallocate memory for n_bytes more bytes.
+
+        A writer with a spill_threshold reserves at most that much.
+
+        Args:
+            n_bytes: Number of bytes expected to be written
+
+        Returns:
+            Self for method chaining
+        """
+        self._writer.reserve(n_bytes)
+        return self
+
+    def estimate_size(self, chunks: Iterable[Any]) -> int:
+        """
+        Count the bytes writing chunks would add, without writing them.
+
+        The header is included if it was not written yet. The writer is left
+        unchanged.
+
+        Args:
+            chunks: Iterable of chunk objects
+
+        Returns:
+            Number of bytes the chunks would add
+
+        Example:
+            >>> needed = th.TeehistorianWriter().estimate_size(chunks)
+        """
+        return self._writer.estimate_size(chunks)
+
     def reset(self) -> None:
         """Reset the writer to initial empty state."""
         self._writer.reset()
@@ -346,48 +717,161 @@ def create(**headers: str) -> TeehistorianWriter:
     return writer
 
 
+def roundtrip(data: bytes) -> bytes:
+    """
+    Parse a recording and write every chunk back.
+
+    Writing the chunks the parser yields reproduces the recording byte for
+    byte, including unknown extension payloads and text that is not valid
+    UTF-8. This checks that guarantee for one file: an AssertionError names
+    the first chunk that would change. Compressed data is compared after
+    decompression.
+
+    Args:
+        data: Raw or compressed teehistorian data
+
+    Returns:
+        The rewritten, uncompressed recording
+
+    Example:
+        >>> data = th.create().write(th.Join(0)).write(th.Eos()).getvalue()
+        >>> th.roundtrip(data) == data
+        True
+    """
+    parser = Teehistorian(data)
+    out = bytearray(SegmentStart(parser.get_header_str()).write_to_buffer())
+    if len(out) != parser.body_offset:
+        raise AssertionError("Header does not round-trip")
+    for chunk in parser:
+        written = chunk.write_to_buffer()
+        if written != parser.raw():
+            raise AssertionError(
+                f"{chunk.chunk_type()} at offset {len(out)} does not round-trip: "
+                f"read {parser.raw()!r}, written {written!r}"
+            )
+        out += written
+    if parser.truncated:
+        raise AssertionError(f"Data ends in the middle of a chunk at offset {len(out)}")
+    return bytes(out)
+
+
 __all__ = [
     # Core parsing interface
     "Teehistorian",
     "TeehistorianParser",  # Alias for Teehistorian
     "parse",  # Modern file parser
     "open",  # Alias for parse
+    "ChunkIterator",
+    "Header",
+    "Tuning",
+    "FollowIterator",
+    "ParserState",
+    "ParserStats",
+    "ParserWarning",
+    "ValidationReport",
+    "RecordingDiff",
+    "RepairReport",
+    "RecompressReport",
+    "PlayerTracker",
+    "SnapshotIterator",
+    "PlayerSession",
+    "Anomaly",
+    "Finish",
+    "RunVerification",
+    "ExtensionHandler",
+    "RegisteredUuid",
     # Core writing interface
     "TeehistorianWriter",
     "create",  # Modern writer creator
+    "roundtrip",
+    "RecordingSession",
     # All chunk types
     "Join",
     "JoinVer6",
+    "JoinVer7",
     "Drop",
     "PlayerReady",
+    "PlayerRejoin",
     "PlayerNew",
     "PlayerOld",
     "PlayerTeam",
     "PlayerName",
     "PlayerDiff",
+    "PlayerPosition",
+    "PlayerFinish",
+    "PlayerSwap",
     "InputNew",
     "InputDiff",
+    "PlayerInput",
     "NetMessage",
     "ConsoleCommand",
+    "AuthInit",
     "AuthLogin",
+    "AuthLogout",
     "DdnetVersion",
+    "DdnetVersionOld",
     "TickSkip",
+    "TeamSaveSuccess",
+    "TeamSaveFailure",
     "TeamLoadSuccess",
     "TeamLoadFailure",
+    "TeamPractice",
+    "TeamFinish",
     "AntiBot",
+    "AntibotEvent",
     "Eos",
+    "TestChunk",
     "Unknown",
     "CustomChunk",
-    "Generic",
+    "RawChunk",
+    "SegmentStart",
+    "UuidRegistration",
+    # Game messages
+    "ChatMessage",
+    "KillMessage",
+    "Emoticon",
+    "VoteCall",
+    "Vote",
+    "SetTeam",
+    "SetSpectatorMode",
+    "ClientCommand",
     # Exceptions
     "TeehistorianError",
+    "TeehistorianWarning",
     "ParseError",
     "ValidationError",
     "FileError",
     "WriteError",
     # Utilities
     "calculate_uuid",
+    "derive_uuid",
     "format_uuid_from_bytes",
+    "detect_format",
+    "rewrite_header",
+    "transform",
+    "patch",
+    "anonymize",
+    "slice_ticks",
+    "split",
+    "merge",
+    "redact_chat",
+    "extract_player",
+    "diff",
+    "normalize",
+    "shift_ticks",
+    "remap_cids",
+    "strip",
+    "repair",
+    "recompress",
+    "extract_sessions",
+    "input_stats",
+    "detect_anomalies",
+    "extract_finishes",
+    "verify_run",
+    "make_chunk",
+    "parse_chunk",
+    "categories",
+    "chunk_types",
     # Version info
     "__version__",
 ]
@@ -2,13 +2,20 @@
 # Auto-generated from Rust source code by build.rs
 # Do not edit manually
 
+from datetime import datetime
+from os import PathLike
 from typing import (
     Any,
+    Callable,
     Dict,
+    Iterable,
     Iterator,
     List,
+    Literal,
     Optional,
+    Pattern,
     Protocol,
+    Tuple,
     Union,
 )
 
@@ -28,6 +35,9 @@ class TeehistorianError(Exception):
 
     def __init__(self, message: str) -> None: ...
 
+class TeehistorianWarning(UserWarning):
+    """Warning category for non-fatal anomalies found while parsing."""
+
 # ============================================================================
 # Parser
 # ============================================================================
@@ -40,21 +50,48 @@ class Teehistorian:
     registration for extensibility.
     """
 
-    def __init__(self, data: bytes) -> None:
+    def __init__(
+        self,
+        data: bytes,
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> None:
         """Initialize parser with raw teehistorian data.
 
         Args:
             data: Raw bytes from a teehistorian file
+            skip_inputs: Discard InputNew/InputDiff chunks while decoding
+            exclude_categories: Chunk categories to discard while decoding
+            emit_warnings: Also issue parser warnings through the warnings module
+            resolve_inputs: Return every InputDiff as InputNew with the absolute input
+            resolve_positions: Return PlayerNew/PlayerDiff as PlayerPosition chunks
+            unknown_uuid_policy: Yield, skip or raise on chunks of unregistered UUIDs
 
         Raises:
             TeehistorianError: If data is empty or invalid
         """
 
-    def register_custom_uuid(self, uuid_string: str) -> None:
+    def register_custom_uuid(
+        self,
+        uuid_string: str,
+        decoder: Optional[Union[Callable[[str, bytes], Any], ExtensionHandler]] = None,
+        *,
+        name: Optional[str] = None,
+        fields: Optional[List[Tuple[str, str]]] = None,
+    ) -> None:
         """Register a custom UUID handler for chunk parsing.
 
         Args:
             uuid_string: UUID in format XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX
+            decoder: Called with the UUID and payload, its result is yielded
+                instead of a CustomChunk, or an ExtensionHandler
+            name: Name of the extension, used as CustomChunk.handler_name
+            fields: (name, format) pairs the payload is decoded into
 
         Raises:
             TeehistorianError: If UUID format is invalid
@@ -71,18 +108,270 @@ class Teehistorian:
         """Get the teehistorian header as a JSON string.
 
         Returns:
-            Header data as JSON string (must be called before iterating chunks)
+            Header data as JSON string
+        """
+
+    def header_json(self) -> Dict[str, Any]:
+        """Get the teehistorian header parsed as JSON.
+
+        Returns:
+            Dictionary of all header fields
+        """
+
+    def peek(self) -> Optional[Any]:
+        """Get the next chunk without consuming it.
+
+        Returns:
+            The chunk the next iteration step will return, or None at EOF
+        """
+
+    def reset(self) -> None:
+        """Rewind to the first chunk so the parser can be iterated again."""
+
+    def raw(self) -> Optional[bytes]:
+        """Get the encoded bytes of the most recently returned chunk.
+
+        Returns:
+            The bytes the decoder read, or None before the first chunk
+        """
+
+    def next_batch(self, size: int) -> List[Any]:
+        """Read up to size chunks at once, decoding without the GIL.
+
+        Args:
+            size: Maximum number of chunks to return
+
+        Returns:
+            List of chunks, empty at end of stream
         """
 
     @property
     def chunk_count(self) -> int:
         """Number of chunks processed so far."""
 
-    def get_registered_uuids(self) -> List[str]:
+    @property
+    def tick(self) -> int:
+        """Tick of the most recently read chunk."""
+
+    @property
+    def header_info(self) -> 'Header':
+        """Header with the well-known fields parsed from the JSON header."""
+
+    @property
+    def format_version(self) -> Optional[int]:
+        """Format version named in the header."""
+
+    @property
+    def header_span(self) -> Tuple[int, int]:
+        """Byte range of the JSON header, without its NUL terminator."""
+
+    @property
+    def body_offset(self) -> int:
+        """Byte offset of the first chunk."""
+
+    @property
+    def uuid_registrations(self) -> List['UuidRegistration']:
+        """Extension UUIDs registered in the header."""
+
+    @property
+    def warnings(self) -> List['ParserWarning']:
+        """Non-fatal anomalies found in the chunks read so far."""
+
+    @property
+    def truncated(self) -> bool:
+        """Whether iteration stopped at a chunk cut off by the end of the data."""
+
+    @property
+    def last_complete_offset(self) -> Optional[int]:
+        """Byte offset after the last complete chunk of a truncated file."""
+
+    def iter_ticks(self, start: int, end: Optional[int] = None) -> 'ChunkIterator':
+        """Iterate over the chunks within a tick range.
+
+        Args:
+            start: First tick to yield chunks for
+            end: Last tick to yield chunks for (inclusive), or None
+
+        Returns:
+            Iterator sharing this parser's position
+
+        Raises:
+            ValidationError: If end is before start
+        """
+
+    def iter_player(self, cid: int) -> 'ChunkIterator':
+        """Iterate over the chunks concerning a single player.
+
+        Args:
+            cid: Client ID to yield chunks for
+
+        Returns:
+            Iterator sharing this parser's position
+        """
+
+    def iter_players(self, cids: List[int]) -> 'ChunkIterator':
+        """Iterate over the chunks concerning any of the given players.
+
+        Args:
+            cids: Client IDs to yield chunks for
+
+        Returns:
+            Iterator sharing this parser's position
+        """
+
+    def filter(self, types: List[Union[type, str]]) -> 'ChunkIterator':
+        """Iterate over the chunks of the given types only.
+
+        Args:
+            types: Chunk classes or chunk type names
+
+        Returns:
+            Iterator sharing this parser's position
+
+        Raises:
+            ValidationError: If a chunk type is unknown
+        """
+
+    def take_until(
+        self,
+        *,
+        chunk_type: Optional[Union[type, str]] = None,
+        tick: Optional[int] = None,
+    ) -> Optional[Any]:
+        """Skip ahead to the first chunk matching all given conditions.
+
+        Args:
+            chunk_type: Chunk class or type name to stop at
+            tick: Stop at the first chunk at or after this tick
+
+        Returns:
+            The matching chunk, or None if the stream ends first
+        """
+
+    def iter_with_ticks(self) -> Iterator[Tuple[int, Any]]:
+        """Iterate over (tick, chunk) tuples.
+
+        Returns:
+            Iterator sharing this parser's position
+        """
+
+    def iter_sampled(self, every_n_ticks: int) -> 'ChunkIterator':
+        """Iterate with movement and input chunks thinned out to a tick stride.
+
+        Args:
+            every_n_ticks: Width of the sampling window in ticks
+
+        Raises:
+            ValidationError: If every_n_ticks is not positive
+        """
+
+    def stats(self) -> 'ParserStats':
+        """Summarize the whole file without creating chunk objects."""
+
+    @staticmethod
+    def validate(data: bytes) -> 'ValidationReport':
+        """Check a whole file and report every problem found.
+
+        Args:
+            data: Raw or compressed teehistorian file data
+        """
+
+    def count(self, chunk_type: Union[type, str]) -> int:
+        """Count the chunks of one type in the whole file.
+
+        Args:
+            chunk_type: Chunk class or type name
+        """
+
+    def save_state(self) -> 'ParserState':
+        """Snapshot the parser position for a later resume."""
+
+    @staticmethod
+    def resume(
+        data: bytes,
+        state: 'ParserState',
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> 'Teehistorian':
+        """Create a parser that continues where a saved state left off.
+
+        Args:
+            data: The same teehistorian data the state was saved from
+            state: State returned by save_state()
+            skip_inputs, exclude_categories, emit_warnings, resolve_inputs,
+                resolve_positions, unknown_uuid_policy: Same as for the constructor
+
+        Raises:
+            ValidationError: If the state does not belong to the data or was saved
+                without a resolve option set for this parser
+        """
+
+    @staticmethod
+    def from_offset(
+        data: bytes,
+        offset: int,
+        state: Optional['ParserState'] = None,
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> 'Teehistorian':
+        """Create a parser that starts at a known chunk boundary.
+
+        Args:
+            data: Teehistorian file data the offset refers to
+            offset: Byte offset of a chunk
+            state: Optional state whose ticks, counts, UUIDs and resolvers to restore
+            skip_inputs, exclude_categories, emit_warnings, resolve_inputs,
+                resolve_positions, unknown_uuid_policy: Same as for the constructor
+
+        Raises:
+            ValidationError: If the offset is not a chunk boundary
+        """
+
+    @staticmethod
+    def follow(
+        path: Union[str, PathLike[str]],
+        poll_interval: float = 0.5,
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> 'FollowIterator':
+        """Follow a recording while the server is still writing it.
+
+        Waits for new data at the end of the file and stops after `Eos`.
+
+        Args:
+            path: Path of the recording being written
+            poll_interval: Seconds to wait before checking for new data
+            skip_inputs, exclude_categories, emit_warnings, resolve_inputs,
+                resolve_positions, unknown_uuid_policy: Same as for the constructor
+        """
+
+    def unregister_custom_uuid(self, uuid_string: str) -> bool:
+        """Remove the handler of a custom UUID.
+
+        Returns:
+            Whether a handler was registered
+        """
+
+    def get_registered_uuids(self) -> List[RegisteredUuid]:
         """Get all registered custom UUID handlers.
 
         Returns:
-            List of registered UUID strings
+            Registered UUIDs with name and decoder kind, sorted by UUID
         """
 
     def __iter__(self) -> Iterator[Any]:
@@ -104,6 +393,486 @@ class Teehistorian:
     def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> bool:
         """Context manager exit."""
 
+class ChunkIterator:
+    """Filtered view of a parser's chunks.
+
+    Shares the position of the parser it was created from.
+    """
+
+    def __iter__(self) -> Iterator[Any]: ...
+    def __next__(self) -> Any: ...
+
+class FollowIterator:
+    """Chunks of a recording that is still being written."""
+
+    def __iter__(self) -> Iterator[Any]: ...
+    def __next__(self) -> Any: ...
+    @property
+    def tick(self) -> int: ...
+
+class ParserState:
+    """Opaque snapshot of a parser's position."""
+
+    @property
+    def offset(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+    @property
+    def chunk_count(self) -> int: ...
+    @property
+    def registered_uuids(self) -> List[str]: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(data: bytes) -> 'ParserState': ...
+
+class ParserStats:
+    """Summary of a whole teehistorian file."""
+
+    @property
+    def chunk_counts(self) -> Dict[str, int]: ...
+    @property
+    def total_chunks(self) -> int: ...
+    @property
+    def first_tick(self) -> Optional[int]: ...
+    @property
+    def last_tick(self) -> Optional[int]: ...
+    @property
+    def player_ids(self) -> List[int]: ...
+    @property
+    def duration(self) -> float: ...
+
+class Header:
+    """Typed view of the JSON header."""
+
+    @property
+    def map_name(self) -> Optional[str]: ...
+    @property
+    def map_sha256(self) -> Optional[str]: ...
+    @property
+    def map_crc(self) -> Optional[int]: ...
+    @property
+    def server_version(self) -> Optional[str]: ...
+    @property
+    def start_time(self) -> Optional[str]: ...
+    @property
+    def start_datetime(self) -> Optional[datetime]: ...
+    @property
+    def game_uuid(self) -> Optional[str]: ...
+    @property
+    def prng_description(self) -> Optional[str]: ...
+    @property
+    def prng_seed(self) -> Optional[Tuple[int, int]]: ...
+    @property
+    def config(self) -> Dict[str, str]: ...
+    @property
+    def tuning(self) -> 'Tuning': ...
+    def tick_to_datetime(self, tick: int) -> Optional[datetime]: ...
+    def validate(self) -> List[str]: ...
+    def compatible_with(self, other: 'Header') -> bool: ...
+    def diff(self, other: 'Header') -> Dict[str, Tuple[Any, Any]]: ...
+
+class UuidRegistration:
+    """Extension UUID registered in the header."""
+
+    def __init__(self, uuid: str, name: str) -> None: ...
+    @property
+    def uuid(self) -> str: ...
+    @property
+    def name(self) -> str: ...
+
+class Tuning:
+    """Physics tuning recorded in the header."""
+
+    @property
+    def changed(self) -> Dict[str, float]: ...
+    @property
+    def is_default(self) -> bool: ...
+    @property
+    def ground_control_speed(self) -> float: ...
+    @property
+    def ground_control_accel(self) -> float: ...
+    @property
+    def ground_friction(self) -> float: ...
+    @property
+    def ground_jump_impulse(self) -> float: ...
+    @property
+    def air_jump_impulse(self) -> float: ...
+    @property
+    def air_control_speed(self) -> float: ...
+    @property
+    def air_control_accel(self) -> float: ...
+    @property
+    def air_friction(self) -> float: ...
+    @property
+    def hook_length(self) -> float: ...
+    @property
+    def hook_fire_speed(self) -> float: ...
+    @property
+    def hook_drag_accel(self) -> float: ...
+    @property
+    def hook_drag_speed(self) -> float: ...
+    @property
+    def gravity(self) -> float: ...
+    @property
+    def velramp_start(self) -> float: ...
+    @property
+    def velramp_range(self) -> float: ...
+    @property
+    def velramp_curvature(self) -> float: ...
+    @property
+    def gun_curvature(self) -> float: ...
+    @property
+    def gun_speed(self) -> float: ...
+    @property
+    def gun_lifetime(self) -> float: ...
+    @property
+    def shotgun_curvature(self) -> float: ...
+    @property
+    def shotgun_speed(self) -> float: ...
+    @property
+    def shotgun_speeddiff(self) -> float: ...
+    @property
+    def shotgun_lifetime(self) -> float: ...
+    @property
+    def grenade_curvature(self) -> float: ...
+    @property
+    def grenade_speed(self) -> float: ...
+    @property
+    def grenade_lifetime(self) -> float: ...
+    @property
+    def laser_reach(self) -> float: ...
+    @property
+    def laser_bounce_delay(self) -> float: ...
+    @property
+    def laser_bounce_num(self) -> float: ...
+    @property
+    def laser_bounce_cost(self) -> float: ...
+    @property
+    def laser_damage(self) -> float: ...
+    @property
+    def player_collision(self) -> float: ...
+    @property
+    def player_hooking(self) -> float: ...
+    @property
+    def jetpack_strength(self) -> float: ...
+    @property
+    def shotgun_strength(self) -> float: ...
+    @property
+    def explosion_strength(self) -> float: ...
+    @property
+    def hammer_strength(self) -> float: ...
+    @property
+    def hook_duration(self) -> float: ...
+    @property
+    def hammer_fire_delay(self) -> float: ...
+    @property
+    def gun_fire_delay(self) -> float: ...
+    @property
+    def shotgun_fire_delay(self) -> float: ...
+    @property
+    def grenade_fire_delay(self) -> float: ...
+    @property
+    def laser_fire_delay(self) -> float: ...
+    @property
+    def ninja_fire_delay(self) -> float: ...
+    @property
+    def hammer_hit_fire_delay(self) -> float: ...
+    @property
+    def ground_elasticity_x(self) -> float: ...
+    @property
+    def ground_elasticity_y(self) -> float: ...
+    def to_dict(self) -> Dict[str, float]: ...
+
+class AntibotEvent:
+    """Antibot payload split into its fields."""
+
+    @property
+    def event(self) -> Optional[str]: ...
+    @property
+    def args(self) -> List[str]: ...
+    @property
+    def params(self) -> Dict[str, str]: ...
+    @property
+    def raw(self) -> bytes: ...
+
+class PlayerInput:
+    """Player input with named fields, in wire order."""
+
+    FIELDS: List[str]
+    direction: int
+    target_x: int
+    target_y: int
+    jump: int
+    fire: int
+    hook: int
+    player_flags: int
+    wanted_weapon: int
+    next_weapon: int
+    prev_weapon: int
+    def __init__(self, *, direction: int = 0, target_x: int = 0, target_y: int = 0, jump: int = 0, fire: int = 0, hook: int = 0, player_flags: int = 0, wanted_weapon: int = 0, next_weapon: int = 0, prev_weapon: int = 0) -> None: ...
+    @staticmethod
+    def from_list(input: List[int]) -> 'PlayerInput': ...
+    def to_list(self) -> List[int]: ...
+    def to_dict(self) -> Dict[str, int]: ...
+
+class ChatMessage:
+    mode: str
+    target: int
+    message: str
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class KillMessage:
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class Emoticon:
+    emoticon: int
+    name: str
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class VoteCall:
+    vote_type: str
+    value: str
+    reason: str
+    force: bool
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class Vote:
+    vote: int
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class SetTeam:
+    team: int
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class SetSpectatorMode:
+    spectator_id: int
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class ClientCommand:
+    name: str
+    arguments: str
+    def message_type(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+GameMessage = Union[
+    ChatMessage,
+    KillMessage,
+    Emoticon,
+    VoteCall,
+    Vote,
+    SetTeam,
+    SetSpectatorMode,
+    ClientCommand,
+]
+
+class ExtensionHandler:
+    """Base class of stateful handlers for a custom UUID."""
+
+    def on_chunk(self, chunk: Any, tick: int) -> Any: ...
+    def reset(self) -> None: ...
+
+class RegisteredUuid:
+    """Custom UUID registered with a parser."""
+
+    @property
+    def uuid(self) -> str: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def kind(self) -> str: ...
+
+class ParserWarning:
+    """Non-fatal anomaly found while parsing."""
+
+    @property
+    def kind(self) -> str: ...
+    @property
+    def message(self) -> str: ...
+    @property
+    def offset(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+
+class ValidationReport:
+    """Result of Teehistorian.validate()."""
+
+    @property
+    def is_valid(self) -> bool: ...
+    @property
+    def header_ok(self) -> bool: ...
+    @property
+    def header_error(self) -> Optional[str]: ...
+    @property
+    def eos_present(self) -> bool: ...
+    @property
+    def unknown_uuids(self) -> List[str]: ...
+    @property
+    def error_offset(self) -> Optional[int]: ...
+    @property
+    def error(self) -> Optional[str]: ...
+    @property
+    def truncated(self) -> bool: ...
+    @property
+    def ticks_monotonic(self) -> bool: ...
+    @property
+    def chunk_counts(self) -> Dict[str, int]: ...
+
+class RecordingDiff:
+    """Result of diff()."""
+
+    @property
+    def identical(self) -> bool: ...
+    @property
+    def header(self) -> Dict[str, Tuple[Any, Any]]: ...
+    @property
+    def chunk_counts(self) -> Dict[str, int]: ...
+    @property
+    def first_index(self) -> Optional[int]: ...
+    @property
+    def offsets(self) -> Optional[Tuple[Optional[int], Optional[int]]]: ...
+    @property
+    def ticks(self) -> Optional[Tuple[Optional[int], Optional[int]]]: ...
+    @property
+    def chunks(self) -> Optional[Tuple[Optional[Chunk], Optional[Chunk]]]: ...
+
+class RepairReport:
+    """Result of repair()."""
+
+    @property
+    def repaired(self) -> bool: ...
+    @property
+    def kept_chunks(self) -> int: ...
+    @property
+    def dropped_bytes(self) -> int: ...
+    @property
+    def error_offset(self) -> Optional[int]: ...
+    @property
+    def error(self) -> Optional[str]: ...
+    @property
+    def truncated(self) -> bool: ...
+    @property
+    def eos_added(self) -> bool: ...
+    @property
+    def last_tick(self) -> int: ...
+    @property
+    def decompress_error(self) -> Optional[str]: ...
+
+class RecompressReport:
+    """Result of recompress()."""
+
+    @property
+    def original_bytes(self) -> int: ...
+    @property
+    def recompressed_bytes(self) -> int: ...
+    @property
+    def saved_bytes(self) -> int: ...
+
+class PlayerTracker:
+    """Positions of every character over a recording."""
+
+    def __init__(self, data: bytes) -> None:
+        """Track the characters of a recording.
+
+        Raises:
+            TeehistorianError: If the data cannot be parsed
+        """
+
+    def positions_at(self, tick: int) -> Dict[int, Tuple[int, int]]: ...
+    def trajectory(self, cid: int) -> List[Tuple[int, int, int]]: ...
+    def kinematics(self, cid: int) -> List[Tuple[int, int, int, int, int]]: ...
+    def snapshots(self) -> 'SnapshotIterator': ...
+    @property
+    def client_ids(self) -> List[int]: ...
+    @property
+    def ticks(self) -> List[int]: ...
+
+class SnapshotIterator:
+    """World snapshots of a PlayerTracker."""
+
+    def __iter__(self) -> Iterator[Tuple[int, Dict[int, Tuple[int, int]]]]: ...
+    def __next__(self) -> Tuple[int, Dict[int, Tuple[int, int]]]: ...
+
+class PlayerSession:
+    """One connection of a player."""
+
+    @property
+    def cid(self) -> int: ...
+    @property
+    def name(self) -> Optional[str]: ...
+    @property
+    def join_tick(self) -> int: ...
+    @property
+    def drop_tick(self) -> Optional[int]: ...
+    @property
+    def drop_reason(self) -> Optional[str]: ...
+    @property
+    def duration(self) -> float: ...
+    @property
+    def ddnet_version(self) -> Optional[int]: ...
+
+class Anomaly:
+    """Something a player did that looks inhuman."""
+
+    @property
+    def kind(self) -> Literal["teleport", "input_rate", "periodic_input"]: ...
+    @property
+    def cid(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+    @property
+    def end_tick(self) -> int: ...
+    @property
+    def score(self) -> float: ...
+    @property
+    def details(self) -> str: ...
+
+class Finish:
+    """A finished race."""
+
+    @property
+    def kind(self) -> Literal["player", "team"]: ...
+    @property
+    def cids(self) -> List[int]: ...
+    @property
+    def names(self) -> List[Optional[str]]: ...
+    @property
+    def team(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+    @property
+    def start_tick(self) -> int: ...
+    @property
+    def time(self) -> int: ...
+    @property
+    def seconds(self) -> float: ...
+    @property
+    def practice(self) -> bool: ...
+
+class RunVerification:
+    """Result of verify_run()."""
+
+    @property
+    def verified(self) -> bool: ...
+    @property
+    def cid(self) -> int: ...
+    @property
+    def claimed_time(self) -> float: ...
+    @property
+    def recorded_time(self) -> Optional[float]: ...
+    @property
+    def start_tick(self) -> Optional[int]: ...
+    @property
+    def finish_tick(self) -> Optional[int]: ...
+    @property
+    def discrepancies(self) -> List[str]: ...
+
 # ============================================================================
 # Writer
 # ============================================================================
@@ -116,23 +885,79 @@ class TeehistorianWriter:
     and context manager protocol for clean resource management.
     """
 
-    def __init__(self, file: Optional[Any] = None) -> None:
+    def __init__(
+        self,
+        file: Optional[Any] = None,
+        *,
+        path: Optional[Union[str, PathLike[str]]] = None,
+        buffer_size: int = 65536,
+        strict: bool = False,
+        delta_encode: bool = False,
+        spill_threshold: Optional[int] = None,
+        extensions: Optional[Dict[str, str]] = None,
+    ) -> None:
         """Initialize a new teehistorian writer.
 
         Args:
-            file: Optional file-like object (for future use)
+            file: Optional file-like object to stream chunks to
+            path: Optional file to stream chunks to instead of memory
+            buffer_size: Buffered bytes after which a streaming writer flushes
+            strict: Reject chunks in an order no server writes
+            delta_encode: Write absolute positions and inputs as diffs
+            spill_threshold: Bytes after which data moves to a temporary file
+            extensions: Custom extension UUIDs to register, mapped to names
+        """
+
+    @staticmethod
+    def append(
+        source: Union[bytes, str, PathLike[str]], *, strict: bool = False
+    ) -> 'TeehistorianWriter':
+        """Continue an existing recording, dropping its final Eos.
+
+        Args:
+            source: Path or bytes of the recording
+            strict: Check chunk order against the recorded clients
         """
 
-    def write(self, chunk: Any) -> 'TeehistorianWriter':
+    def write(self, chunk: Any, tick: Optional[int] = None) -> 'TeehistorianWriter':
         """Write a chunk to the teehistorian.
 
         Args:
             chunk: A chunk object to write
+            tick: Optional absolute tick to write the chunk at
 
         Returns:
             Self for method chaining
         """
 
+    def write_join(
+        self,
+        client_id: int,
+        protocol: Optional[int] = None,
+        *,
+        version: Any = None,
+        tick: Optional[int] = None,
+    ) -> None:
+        """Write the chunks a server records when a client connects.
+
+        Args:
+            client_id: ID of the connecting client
+            protocol: 6 or 7 to write JoinVer6/JoinVer7 before the Join
+            version: DdnetVersion or DdnetVersionOld written after the Join
+            tick: Optional absolute tick to write the chunks at
+        """
+
+    def advance_to_tick(self, tick: int) -> None:
+        """Emit the TickSkip that makes `tick` the current tick.
+
+        Args:
+            tick: Absolute tick, not before the current one
+        """
+
+    @property
+    def tick(self) -> int:
+        """Tick of the most recently written chunk"""
+
     def write_all(self, chunks: List[Any]) -> 'TeehistorianWriter':
         """Write multiple chunks at once.
 
@@ -143,18 +968,37 @@ class TeehistorianWriter:
             Self for method chaining
         """
 
-    def set_header(self, key: str, value: str) -> 'TeehistorianWriter':
+    def write_from(self, chunks: Iterable[Any], batch_size: int = 1024) -> int:
+        """Write chunks pulled from an iterable, e.g. a parser.
+
+        Chunks are serialized in batches with the GIL released.
+
+        Returns:
+            Number of chunks written
+        """
+
+    def set_header(self, key: str, value: Any) -> 'TeehistorianWriter':
         """Set a header field value.
 
         Args:
             key: Header field name
-            value: Header field value
+            value: Header field value, str or any JSON-compatible value
+
+        Returns:
+            Self for method chaining
+        """
+
+    def remove_header(self, key: str) -> 'TeehistorianWriter':
+        """Remove a header field.
+
+        Args:
+            key: Header field name
 
         Returns:
             Self for method chaining
         """
 
-    def get_header(self, key: str) -> Optional[str]:
+    def get_header(self, key: str) -> Any:
         """Get a header field value.
 
         Args:
@@ -164,7 +1008,7 @@ class TeehistorianWriter:
             Header value or None if not set
         """
 
-    def update_headers(self, headers: Dict[str, str]) -> 'TeehistorianWriter':
+    def update_headers(self, headers: Dict[str, Any]) -> 'TeehistorianWriter':
         """Update multiple header fields from a dictionary.
 
         Args:
@@ -174,6 +1018,18 @@ class TeehistorianWriter:
             Self for method chaining
         """
 
+    def copy_header_from(
+        self, source: Union['Teehistorian', 'Header', Dict[str, Any], str, bytes]
+    ) -> 'TeehistorianWriter':
+        """Replace the header with the one of another recording.
+
+        Args:
+            source: A parser, a Header, a dict of header fields or the header JSON
+
+        Returns:
+            Self for method chaining
+        """
+
     def getvalue(self) -> bytes:
         """Get all written data as bytes.
 
@@ -195,6 +1051,16 @@ class TeehistorianWriter:
             file: File-like object with write() method
         """
 
+    def flush(self) -> None:
+        """Write buffered chunks to the file or file object of a streaming writer."""
+
+    def finalize(self) -> None:
+        """Write Eos if missing, flush and refuse further chunks."""
+
+    @property
+    def finished(self) -> bool:
+        """Whether finalize() was called"""
+
     def size(self) -> int:
         """Get current buffer size in bytes.
 
@@ -208,6 +1074,23 @@ class TeehistorianWriter:
         Clears all written data and resets headers to defaults.
         """
 
+    @property
+    def spilled(self) -> bool:
+        """Whether the data was moved to a temporary file"""
+
+    def reserve(self, n_bytes: int) -> None:
+        """Preallocate memory for n_bytes more bytes."""
+
+    def estimate_size(self, chunks: Iterable[Any]) -> int:
+        """Count the bytes writing chunks would add, without writing them.
+
+        Args:
+            chunks: Iterable of chunk objects
+
+        Returns:
+            Number of bytes the chunks would add, header included if unwritten
+        """
+
     def is_empty(self) -> bool:
         """Check if any data has been written.
 
@@ -229,6 +1112,218 @@ class TeehistorianWriter:
     def __repr__(self) -> str:
         """Get string representation."""
 
+def detect_format(data: bytes) -> Optional[int]:
+    """Get the format version of teehistorian data without parsing its chunks.
+
+    Raises:
+        ValidationError: If the data is not a teehistorian file
+    """
+
+def rewrite_header(data: bytes, new_fields: Dict[str, Optional[str]]) -> bytes:
+    """Replace fields of the JSON header, keeping the chunks byte for byte.
+
+    Raises:
+        ValidationError: If the data is not a teehistorian file
+    """
+
+def transform(
+    data: bytes,
+    *,
+    keep: Optional[Iterable[Union[type, str]]] = None,
+    drop: Optional[Iterable[Union[type, str]]] = None,
+    map_fn: Optional[Callable[[Chunk], Any]] = None,
+    map_types: Optional[Iterable[Union[type, str]]] = None,
+) -> bytes:
+    """Rewrite a recording in Rust, keeping, dropping or mapping chunks by type.
+
+    map_fn returns the chunk to write instead, a list of chunks or None.
+
+    Raises:
+        TeehistorianError: If a chunk type is unknown or the data cannot be parsed
+    """
+
+def patch(
+    data: bytes,
+    predicate: Callable[[Chunk], bool],
+    replacement_fn: Callable[[Chunk], Any],
+) -> bytes:
+    """Replace the chunks a predicate selects, keeping every other byte.
+
+    replacement_fn returns the chunk to write instead, a list of chunks or None.
+
+    Raises:
+        TeehistorianError: If the data cannot be parsed
+    """
+
+def anonymize(
+    data: bytes,
+    *,
+    names: bool = True,
+    ips: bool = True,
+    auth: bool = True,
+    chat: bool = False,
+) -> bytes:
+    """Replace player names, account names and IP addresses with stable pseudonyms.
+
+    Raises:
+        TeehistorianError: If the data cannot be parsed
+    """
+
+def slice_ticks(data: bytes, start: int, end: Optional[int] = None) -> bytes:
+    """Cut a tick range out of a recording as a file of its own.
+
+    Raises:
+        TeehistorianError: If end is before start or the data cannot be parsed
+    """
+
+def split(
+    data: bytes, every_ticks: Optional[int] = None, *, every_minutes: Optional[float] = None
+) -> List[bytes]:
+    """Split a recording into recordings covering a fixed number of ticks each.
+
+    Raises:
+        TeehistorianError: If the part length is missing or not positive, or the data cannot be parsed
+    """
+
+def merge(files: List[bytes]) -> bytes:
+    """Merge the recordings of consecutive server runs into one.
+
+    Raises:
+        TeehistorianError: If the headers are not compatible or the data cannot be parsed
+    """
+
+def redact_chat(
+    data: bytes, matcher: Union[str, Pattern[str], Iterable[str]], *, remove: bool = False
+) -> bytes:
+    """Blank or remove the chat messages that match.
+
+    Raises:
+        TeehistorianError: If the word list is empty or the data cannot be parsed
+    """
+
+def extract_player(data: bytes, cid_or_name: Union[int, str]) -> bytes:
+    """Extract the chunks of one player into a recording of its own.
+
+    Raises:
+        TeehistorianError: If the player is not found, the name is ambiguous or the data cannot be parsed
+    """
+
+def diff(a: bytes, b: bytes) -> RecordingDiff:
+    """Compare what two recordings mean, chunk by chunk.
+
+    Raises:
+        TeehistorianError: If either recording cannot be parsed
+    """
+
+def normalize(
+    data: bytes, *, expand_diffs: bool = False, recompress_diffs: bool = True
+) -> bytes:
+    """Re-encode a recording canonically.
+
+    Raises:
+        TeehistorianError: If the data cannot be parsed
+    """
+
+def shift_ticks(data: bytes, delta: int) -> bytes:
+    """Move every chunk of a recording by a number of ticks.
+
+    Raises:
+        TeehistorianError: If a tick would become negative or the data cannot be parsed
+    """
+
+def remap_cids(data: bytes, mapping: Dict[int, int]) -> bytes:
+    """Give the clients of a recording new client IDs.
+
+    Raises:
+        TeehistorianError: If two clients would get the same ID or the data cannot be parsed
+    """
+
+def strip(data: bytes, categories: List[str] = ["Input"]) -> bytes:
+    """Remove whole categories of chunks from a recording.
+
+    Raises:
+        TeehistorianError: If a category is unknown or the data cannot be parsed
+    """
+
+def repair(data: bytes) -> Tuple[bytes, RepairReport]:
+    """Salvage the readable part of a damaged recording.
+
+    Raises:
+        TeehistorianError: If the header cannot be read, even after decompressing what is left
+    """
+
+def recompress(
+    data: bytes, profile: Literal["small", "fast"] = "small"
+) -> Tuple[bytes, RecompressReport]:
+    """Rewrite a recording to take less space.
+
+    Raises:
+        TeehistorianError: If the profile is unknown or the data cannot be parsed
+    """
+
+def extract_sessions(data: bytes) -> List[PlayerSession]:
+    """Every session of every player in a recording.
+
+    Raises:
+        TeehistorianError: If the data cannot be parsed
+    """
+
+def input_stats(data: bytes) -> Dict[int, Dict[str, Union[int, float]]]:
+    """Input statistics of every player in a recording.
+
+    Raises:
+        TeehistorianError: If the data cannot be parsed
+    """
+
+def detect_anomalies(
+    data: bytes,
+    *,
+    max_speed: float = 128.0,
+    max_presses_per_second: float = 20.0,
+    min_periodic_presses: int = 10,
+) -> List[Anomaly]:
+    """Look for movement and input a human player cannot produce.
+
+    Raises:
+        TeehistorianError: If a threshold is not positive or the data cannot be parsed
+    """
+
+def extract_finishes(data: bytes) -> List[Finish]:
+    """Every race finish in a recording.
+
+    Raises:
+        TeehistorianError: If the data cannot be parsed
+    """
+
+def verify_run(
+    data: bytes, cid: int, claimed_time: float, *, tolerance: float = 0.02
+) -> RunVerification:
+    """Check a claimed race time against the recording of the run.
+
+    Raises:
+        TeehistorianError: If the data cannot be parsed
+    """
+
+def make_chunk(chunk_type: str, **fields: Any) -> Chunk:
+    """Create a chunk from its type name and constructor arguments.
+
+    Raises:
+        ValidationError: If the chunk type is unknown
+    """
+
+def chunk_types() -> Dict[str, type]:
+    """Get the class of every chunk type, keyed by type name."""
+
+def categories() -> Dict[str, List[str]]:
+    """Get the chunk type names of every category."""
+
+def parse_chunk(data: bytes, uuid_registry: Optional[List[str]] = None) -> Chunk:
+    """Decode a single encoded chunk, the inverse of write_to_buffer().
+
+    Raises:
+        ParseError: If the data is not exactly one chunk
+    """
+
 # ============================================================================
 # Chunk Types
 # ============================================================================
@@ -243,6 +1338,10 @@ class Chunk:
     def chunk_type(self) -> str:
         """Get the chunk type identifier."""
 
+    @property
+    def category(self) -> str:
+        """Category of the chunk type, e.g. \"PlayerLifecycle\"."""
+
     def __repr__(self) -> str:
         """Get string representation for debugging."""
 
@@ -284,6 +1383,17 @@ Category: Input"""
     def to_dict(self) -> Dict[str, Any]: ...
 
 # Other Chunks
+class AntiBot(Chunk):
+    """Anti-bot system event"""
+
+    data: str
+
+    def __init__(self, data: str) -> None: ...
+
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
 class CustomChunk(Chunk):
     """Custom chunk with registered handler"""
 
@@ -309,12 +1419,13 @@ class DdnetVersionOld(Chunk):
     def __str__(self) -> str: ...
     def to_dict(self) -> Dict[str, Any]: ...
 
-class Generic(Chunk):
-    """Generic/fallback chunk type"""
+class NetMessage(Chunk):
+    """Network message from/to player"""
 
-    data: str
+    client_id: int
+    msg: bytes
 
-    def __init__(self, data: str) -> None: ...
+    def __init__(self, client_id: int, msg: bytes) -> None: ...
 
     def __repr__(self) -> str: ...
     def __str__(self) -> str: ...
@@ -340,6 +1451,21 @@ This is used when a NetMessage contains ClStartInfo or ClChangeInfo"""
     def __str__(self) -> str: ...
     def to_dict(self) -> Dict[str, Any]: ...
 
+class SegmentStart(Chunk):
+    """Start of another teehistorian stream concatenated after the previous one
+
+Yielded when a new header follows the previous stream's end. Writing it
+emits that header again, so concatenated files round-trip."""
+
+    offset: int
+    header: str
+
+    def __init__(self, offset: int, header: str) -> None: ...
+
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
 class Unknown(Chunk):
     """Unknown chunk with UUID (not registered)"""
 
@@ -353,6 +1479,19 @@ class Unknown(Chunk):
     def to_dict(self) -> Dict[str, Any]: ...
 
 # PlayerLifecycle Chunks
+class Drop(Chunk):
+    """Player disconnects from server
+Category: PlayerLifecycle"""
+
+    client_id: int
+    reason: str
+
+    def __init__(self, client_id: int, reason: str) -> None: ...
+
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
 class PlayerReady(Chunk):
     """Player becomes ready to play
 Category: PlayerLifecycle"""
@@ -366,6 +1505,23 @@ Category: PlayerLifecycle"""
     def to_dict(self) -> Dict[str, Any]: ...
 
 # PlayerState Chunks
+class PlayerPosition(Chunk):
+    """Absolute player position, produced by `resolve_positions=True`
+
+Written as a `PlayerNew` chunk.
+Category: PlayerState"""
+
+    client_id: int
+    tick: int
+    x: int
+    y: int
+
+    def __init__(self, client_id: int, tick: int, x: int, y: int) -> None: ...
+
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
 class PlayerTeam(Chunk):
     """Player changes team
 Category: PlayerState"""
@@ -379,6 +1535,24 @@ Category: PlayerState"""
     def __str__(self) -> str: ...
     def to_dict(self) -> Dict[str, Any]: ...
 
+# Special Chunks
+class RawChunk(Chunk):
+    """Chunk kept as its encoded bytes
+
+`type_id` is the chunk tag: the client id for position diffs, a negative
+built-in tag, or -11 for extension chunks whose payload starts with the
+UUID. Writing it emits the tag followed by the payload unchanged.
+Category: Special"""
+
+    type_id: int
+    payload: bytes
+
+    def __init__(self, type_id: int, payload: bytes) -> None: ...
+
+    def __repr__(self) -> str: ...
+    def __str__(self) -> str: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
 # ============================================================================
 # Type Aliases and Categories
 # ============================================================================
@@ -389,31 +1563,44 @@ InputChunk = Union[
 ]
 
 OtherChunk = Union[
+    AntiBot,
     CustomChunk,
     DdnetVersionOld,
-    Generic,
+    NetMessage,
     NetMessagePlayerInfo,
+    SegmentStart,
     Unknown
 ]
 
 PlayerLifecycleChunk = Union[
+    Drop,
     PlayerReady
 ]
 
 PlayerStateChunk = Union[
+    PlayerPosition,
     PlayerTeam
 ]
 
+SpecialChunk = Union[
+    RawChunk
+]
+
 # All chunk types
 AllChunks = Union[
+    AntiBot,
     CustomChunk,
     DdnetVersionOld,
-    Generic,
+    Drop,
     InputDiff,
     InputNew,
+    NetMessage,
     NetMessagePlayerInfo,
+    PlayerPosition,
     PlayerReady,
     PlayerTeam,
+    RawChunk,
+    SegmentStart,
     Unknown
 ]
 
@@ -421,12 +1608,17 @@ AllChunks = Union[
 # Aliases for Rust class names (PyXxx -> Xxx)
 # ============================================================================
 
+PyAntiBot = AntiBot
 PyCustomChunk = CustomChunk
 PyDdnetVersionOld = DdnetVersionOld
-PyGeneric = Generic
+PyDrop = Drop
 PyInputDiff = InputDiff
 PyInputNew = InputNew
+PyNetMessage = NetMessage
 PyNetMessagePlayerInfo = NetMessagePlayerInfo
+PyPlayerPosition = PlayerPosition
 PyPlayerReady = PlayerReady
 PyPlayerTeam = PlayerTeam
+PyRawChunk = RawChunk
+PySegmentStart = SegmentStart
 PyUnknown = Unknown
@@ -26,27 +26,40 @@ __all__ = [
     "ValidatedChunk",
     "Join",
     "JoinVer6",
+    "JoinVer7",
     "Drop",
     "PlayerReady",
+    "PlayerRejoin",
     "PlayerNew",
     "PlayerOld",
     "PlayerTeam",
     "PlayerName",
     "PlayerDiff",
+    "PlayerPosition",
+    "PlayerFinish",
+    "PlayerSwap",
     "InputNew",
     "InputDiff",
     "NetMessage",
     "ConsoleCommand",
+    "AuthInit",
     "AuthLogin",
+    "AuthLogout",
     "DdnetVersion",
+    "DdnetVersionOld",
     "TickSkip",
+    "TeamSaveSuccess",
+    "TeamSaveFailure",
     "TeamLoadSuccess",
     "TeamLoadFailure",
+    "TeamPractice",
+    "TeamFinish",
     "AntiBot",
     "Eos",
+    "TestChunk",
     "Unknown",
     "CustomChunk",
-    "Generic",
+    "RawChunk",
 ]
 
 
@@ -65,6 +78,10 @@ class ValidatedChunk:
     def chunk_type(self) -> str:
         return self._chunk_name
 
+    @property
+    def category(self) -> str:
+        return self._rust.category
+
     def to_dict(self) -> dict[str, Any]:
         d: dict[str, Any] = {"type": self._chunk_name}
         for f in self._fields:
@@ -112,14 +129,27 @@ class JoinVer6(ValidatedChunk):
         self._rust = _rust.JoinVer6(client_id=self.client_id)
 
 
+class JoinVer7(ValidatedChunk):
+    _chunk_name = "JoinVer7"
+    _fields = ("client_id",)
+    _rust_cls = _rust.JoinVer7
+
+    def __init__(self, client_id: int) -> None:
+        self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self._rust = _rust.JoinVer7(client_id=self.client_id)
+
+
 class Drop(ValidatedChunk):
     _chunk_name = "Drop"
     _fields = ("client_id", "reason")
     _rust_cls = _rust.Drop
 
-    def __init__(self, client_id: int, reason: str) -> None:
+    def __init__(self, client_id: int, reason: str | bytes) -> None:
         self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
-        self.reason = validate_str(reason, "reason", max_len=128)
+        if isinstance(reason, bytes):
+            self.reason = reason
+        else:
+            self.reason = validate_str(reason, "reason", max_len=128)
         self._rust = _rust.Drop(client_id=self.client_id, reason=self.reason)
 
 
@@ -147,6 +177,16 @@ class PlayerNew(ValidatedChunk):
         self._rust = _rust.PlayerNew(client_id=self.client_id, x=self.x, y=self.y)
 
 
+class PlayerRejoin(ValidatedChunk):
+    _chunk_name = "PlayerRejoin"
+    _fields = ("client_id",)
+    _rust_cls = _rust.PlayerRejoin
+
+    def __init__(self, client_id: int) -> None:
+        self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self._rust = _rust.PlayerRejoin(client_id=self.client_id)
+
+
 class PlayerOld(ValidatedChunk):
     _chunk_name = "PlayerOld"
     _fields = ("client_id",)
@@ -191,6 +231,43 @@ class PlayerDiff(ValidatedChunk):
         self._rust = _rust.PlayerDiff(client_id=self.client_id, dx=self.dx, dy=self.dy)
 
 
+class PlayerPosition(ValidatedChunk):
+    _chunk_name = "PlayerPosition"
+    _fields = ("client_id", "tick", "x", "y")
+    _rust_cls = _rust.PlayerPosition
+
+    def __init__(self, client_id: int, tick: int, x: int, y: int) -> None:
+        self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self.tick = validate_int(tick, "tick", 0)
+        self.x = validate_int(x, "x")
+        self.y = validate_int(y, "y")
+        self._rust = _rust.PlayerPosition(
+            client_id=self.client_id, tick=self.tick, x=self.x, y=self.y
+        )
+
+
+class PlayerFinish(ValidatedChunk):
+    _chunk_name = "PlayerFinish"
+    _fields = ("client_id", "time")
+    _rust_cls = _rust.PlayerFinish
+
+    def __init__(self, client_id: int, time: int) -> None:
+        self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self.time = validate_int(time, "time", 0)
+        self._rust = _rust.PlayerFinish(client_id=self.client_id, time=self.time)
+
+
+class PlayerSwap(ValidatedChunk):
+    _chunk_name = "PlayerSwap"
+    _fields = ("client_id1", "client_id2")
+    _rust_cls = _rust.PlayerSwap
+
+    def __init__(self, client_id1: int, client_id2: int) -> None:
+        self.client_id1 = validate_int(client_id1, "client_id1", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self.client_id2 = validate_int(client_id2, "client_id2", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self._rust = _rust.PlayerSwap(client_id1=self.client_id1, client_id2=self.client_id2)
+
+
 # Input Chunks
 
 class InputNew(ValidatedChunk):
@@ -245,6 +322,18 @@ class ConsoleCommand(ValidatedChunk):
 
 # Authentication & Version Chunks
 
+class AuthInit(ValidatedChunk):
+    _chunk_name = "AuthInit"
+    _fields = ("client_id", "level", "auth_name")
+    _rust_cls = _rust.AuthInit
+
+    def __init__(self, client_id: int, level: int, auth_name: str) -> None:
+        self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self.level = validate_int(level, "level", min_val=0)
+        self.auth_name = validate_str(auth_name, "auth_name", allow_empty=False)
+        self._rust = _rust.AuthInit(client_id=self.client_id, level=self.level, auth_name=self.auth_name)
+
+
 class AuthLogin(ValidatedChunk):
     _chunk_name = "AuthLogin"
     _fields = ("client_id", "level", "auth_name")
@@ -257,6 +346,16 @@ class AuthLogin(ValidatedChunk):
         self._rust = _rust.AuthLogin(client_id=self.client_id, level=self.level, auth_name=self.auth_name)
 
 
+class AuthLogout(ValidatedChunk):
+    _chunk_name = "AuthLogout"
+    _fields = ("client_id",)
+    _rust_cls = _rust.AuthLogout
+
+    def __init__(self, client_id: int) -> None:
+        self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self._rust = _rust.AuthLogout(client_id=self.client_id)
+
+
 class DdnetVersion(ValidatedChunk):
     _chunk_name = "DdnetVersion"
     _fields = ("client_id", "connection_id", "version", "version_str")
@@ -273,6 +372,17 @@ class DdnetVersion(ValidatedChunk):
         )
 
 
+class DdnetVersionOld(ValidatedChunk):
+    _chunk_name = "DdnetVersionOld"
+    _fields = ("client_id", "version")
+    _rust_cls = _rust.DdnetVersionOld
+
+    def __init__(self, client_id: int, version: int) -> None:
+        self.client_id = validate_int(client_id, "client_id", CLIENT_ID_MIN, CLIENT_ID_MAX)
+        self.version = validate_int(version, "version", min_val=0)
+        self._rust = _rust.DdnetVersionOld(client_id=self.client_id, version=self.version)
+
+
 # Server Event Chunks
 
 class TickSkip(ValidatedChunk):
@@ -285,6 +395,28 @@ class TickSkip(ValidatedChunk):
         self._rust = _rust.TickSkip(dt=self.dt)
 
 
+class TeamSaveSuccess(ValidatedChunk):
+    _chunk_name = "TeamSaveSuccess"
+    _fields = ("team", "save_id", "save")
+    _rust_cls = _rust.TeamSaveSuccess
+
+    def __init__(self, team: int, save_id: str, save: str) -> None:
+        self.team = validate_int(team, "team", TEAM_MIN, TEAM_MAX)
+        self.save_id = validate_uuid(save_id, "save_id")
+        self.save = validate_str(save, "save")
+        self._rust = _rust.TeamSaveSuccess(team=self.team, save_id=self.save_id, save=self.save)
+
+
+class TeamSaveFailure(ValidatedChunk):
+    _chunk_name = "TeamSaveFailure"
+    _fields = ("team",)
+    _rust_cls = _rust.TeamSaveFailure
+
+    def __init__(self, team: int) -> None:
+        self.team = validate_int(team, "team", TEAM_MIN, TEAM_MAX)
+        self._rust = _rust.TeamSaveFailure(team=self.team)
+
+
 class TeamLoadSuccess(ValidatedChunk):
     _chunk_name = "TeamLoadSuccess"
     _fields = ("team", "save_id", "save")
@@ -307,6 +439,28 @@ class TeamLoadFailure(ValidatedChunk):
         self._rust = _rust.TeamLoadFailure(team=self.team)
 
 
+class TeamPractice(ValidatedChunk):
+    _chunk_name = "TeamPractice"
+    _fields = ("team", "practice")
+    _rust_cls = _rust.TeamPractice
+
+    def __init__(self, team: int, practice: int) -> None:
+        self.team = validate_int(team, "team", TEAM_MIN, TEAM_MAX)
+        self.practice = validate_int(practice, "practice", 0, 1)
+        self._rust = _rust.TeamPractice(team=self.team, practice=self.practice)
+
+
+class TeamFinish(ValidatedChunk):
+    _chunk_name = "TeamFinish"
+    _fields = ("team", "time")
+    _rust_cls = _rust.TeamFinish
+
+    def __init__(self, team: int, time: int) -> None:
+        self.team = validate_int(team, "team", TEAM_MIN, TEAM_MAX)
+        self.time = validate_int(time, "time", 0)
+        self._rust = _rust.TeamFinish(team=self.team, time=self.time)
+
+
 class AntiBot(ValidatedChunk):
     _chunk_name = "AntiBot"
     _fields = ("data",)
@@ -316,6 +470,9 @@ class AntiBot(ValidatedChunk):
         self.data = validate_str(data, "data")
         self._rust = _rust.AntiBot(data=self.data)
 
+    def decode(self) -> Any:
+        return self._rust.decode()
+
 
 # Special Chunks
 
@@ -328,6 +485,15 @@ class Eos(ValidatedChunk):
         self._rust = _rust.Eos()
 
 
+class TestChunk(ValidatedChunk):
+    _chunk_name = "TestChunk"
+    _fields = ()
+    _rust_cls = _rust.TestChunk
+
+    def __init__(self) -> None:
+        self._rust = _rust.TestChunk()
+
+
 class Unknown(ValidatedChunk):
     _chunk_name = "Unknown"
     _fields = ("uuid", "data")
@@ -351,11 +517,12 @@ class CustomChunk(ValidatedChunk):
         self._rust = _rust.CustomChunk(uuid=self.uuid, data=self.data, handler_name=self.handler_name)
 
 
-class Generic(ValidatedChunk):
-    _chunk_name = "Generic"
-    _fields = ("data",)
-    _rust_cls = _rust.Generic
+class RawChunk(ValidatedChunk):
+    _chunk_name = "RawChunk"
+    _fields = ("type_id", "payload")
+    _rust_cls = _rust.RawChunk
 
-    def __init__(self, data: str) -> None:
-        self.data = validate_str(data, "data")
-        self._rust = _rust.Generic(data=self.data)
+    def __init__(self, type_id: int, payload: bytes) -> None:
+        self.type_id = validate_int(type_id, "type_id")
+        self.payload = validate_bytes(payload, "payload")
+        self._rust = _rust.RawChunk(type_id=self.type_id, payload=self.payload)
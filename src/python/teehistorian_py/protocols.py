@@ -17,6 +17,11 @@ class ChunkProtocol(Protocol):
         """Get the type identifier for this chunk."""
         ...
 
+    @property
+    def category(self) -> str:
+        """Category of the chunk type, e.g. "PlayerLifecycle"."""
+        ...
+
     def to_dict(self) -> dict[str, Any]:
         """Convert chunk to dictionary representation."""
         ...
@@ -48,11 +53,21 @@ class JoinVer6Protocol(ChunkProtocol, Protocol):
         ...
 
 
+class JoinVer7Protocol(ChunkProtocol, Protocol):
+    """Player joins the server (version 7 protocol)."""
+
+    client_id: int
+
+    def chunk_type(self) -> Literal["JoinVer7"]:
+        ...
+
+
 class DropProtocol(ChunkProtocol, Protocol):
     """Player disconnects from server."""
 
     client_id: int
     reason: str
+    reason_bytes: bytes
 
     def chunk_type(self) -> Literal["Drop"]:
         ...
@@ -71,6 +86,15 @@ class PlayerReadyProtocol(ChunkProtocol, Protocol):
 # ============================================================================
 
 
+class PlayerRejoinProtocol(ChunkProtocol, Protocol):
+    """Player re-enters the game world after a map change."""
+
+    client_id: int
+
+    def chunk_type(self) -> Literal["PlayerRejoin"]:
+        ...
+
+
 class PlayerNewProtocol(ChunkProtocol, Protocol):
     """New player position data."""
 
@@ -122,6 +146,38 @@ class PlayerDiffProtocol(ChunkProtocol, Protocol):
         ...
 
 
+class PlayerPositionProtocol(ChunkProtocol, Protocol):
+    """Absolute player position (resolve_positions mode)."""
+
+    client_id: int
+    tick: int
+    x: int
+    y: int
+
+    def chunk_type(self) -> Literal["PlayerPosition"]:
+        ...
+
+
+class PlayerFinishProtocol(ChunkProtocol, Protocol):
+    """Player finished a race."""
+
+    client_id: int
+    time: int
+
+    def chunk_type(self) -> Literal["PlayerFinish"]:
+        ...
+
+
+class PlayerSwapProtocol(ChunkProtocol, Protocol):
+    """Two players swapped their positions."""
+
+    client_id1: int
+    client_id2: int
+
+    def chunk_type(self) -> Literal["PlayerSwap"]:
+        ...
+
+
 # Input Chunks
 # ============================================================================
 
@@ -176,6 +232,17 @@ class ConsoleCommandProtocol(ChunkProtocol, Protocol):
 # ============================================================================
 
 
+class AuthInitProtocol(ChunkProtocol, Protocol):
+    """Player already authenticated when the recording started."""
+
+    client_id: int
+    level: int
+    auth_name: str
+
+    def chunk_type(self) -> Literal["AuthInit"]:
+        ...
+
+
 class AuthLoginProtocol(ChunkProtocol, Protocol):
     """Player authentication information."""
 
@@ -187,6 +254,15 @@ class AuthLoginProtocol(ChunkProtocol, Protocol):
         ...
 
 
+class AuthLogoutProtocol(ChunkProtocol, Protocol):
+    """Player logged out of their account."""
+
+    client_id: int
+
+    def chunk_type(self) -> Literal["AuthLogout"]:
+        ...
+
+
 class DdnetVersionProtocol(ChunkProtocol, Protocol):
     """DDNet client version information."""
 
@@ -199,6 +275,16 @@ class DdnetVersionProtocol(ChunkProtocol, Protocol):
         ...
 
 
+class DdnetVersionOldProtocol(ChunkProtocol, Protocol):
+    """DDNet client version information (old format)."""
+
+    client_id: int
+    version: int
+
+    def chunk_type(self) -> Literal["DdnetVersionOld"]:
+        ...
+
+
 # Server Event Chunks
 # ============================================================================
 
@@ -212,6 +298,26 @@ class TickSkipProtocol(ChunkProtocol, Protocol):
         ...
 
 
+class TeamSaveSuccessProtocol(ChunkProtocol, Protocol):
+    """Team saved successfully."""
+
+    team: int
+    save_id: str
+    save: str
+
+    def chunk_type(self) -> Literal["TeamSaveSuccess"]:
+        ...
+
+
+class TeamSaveFailureProtocol(ChunkProtocol, Protocol):
+    """Team save failed."""
+
+    team: int
+
+    def chunk_type(self) -> Literal["TeamSaveFailure"]:
+        ...
+
+
 class TeamLoadSuccessProtocol(ChunkProtocol, Protocol):
     """Team save loaded successfully."""
 
@@ -232,6 +338,26 @@ class TeamLoadFailureProtocol(ChunkProtocol, Protocol):
         ...
 
 
+class TeamPracticeProtocol(ChunkProtocol, Protocol):
+    """Team practice mode changed."""
+
+    team: int
+    practice: int
+
+    def chunk_type(self) -> Literal["TeamPractice"]:
+        ...
+
+
+class TeamFinishProtocol(ChunkProtocol, Protocol):
+    """Team finished a race."""
+
+    team: int
+    time: int
+
+    def chunk_type(self) -> Literal["TeamFinish"]:
+        ...
+
+
 class AntiBotProtocol(ChunkProtocol, Protocol):
     """Antibot data."""
 
@@ -252,6 +378,13 @@ class EosProtocol(ChunkProtocol, Protocol):
         ...
 
 
+class TestChunkProtocol(ChunkProtocol, Protocol):
+    """Test chunk from DDNet's teehistorian self-test."""
+
+    def chunk_type(self) -> Literal["TestChunk"]:
+        ...
+
+
 class UnknownProtocol(ChunkProtocol, Protocol):
     """Unknown chunk type with UUID."""
 
@@ -273,12 +406,13 @@ class CustomChunkProtocol(ChunkProtocol, Protocol):
         ...
 
 
-class GenericProtocol(ChunkProtocol, Protocol):
-    """Generic fallback for unhandled chunks."""
+class RawChunkProtocol(ChunkProtocol, Protocol):
+    """Chunk kept as its encoded bytes."""
 
-    data: str
+    type_id: int
+    payload: bytes
 
-    def chunk_type(self) -> Literal["Generic"]:
+    def chunk_type(self) -> Literal["RawChunk"]:
         ...
 
 
@@ -288,25 +422,38 @@ class GenericProtocol(ChunkProtocol, Protocol):
 Chunk = (
     JoinProtocol
     | JoinVer6Protocol
+    | JoinVer7Protocol
     | DropProtocol
     | PlayerReadyProtocol
+    | PlayerRejoinProtocol
     | PlayerNewProtocol
     | PlayerOldProtocol
     | PlayerTeamProtocol
     | PlayerNameProtocol
     | PlayerDiffProtocol
+    | PlayerPositionProtocol
+    | PlayerFinishProtocol
+    | PlayerSwapProtocol
     | InputNewProtocol
     | InputDiffProtocol
     | NetMessageProtocol
     | ConsoleCommandProtocol
+    | AuthInitProtocol
     | AuthLoginProtocol
+    | AuthLogoutProtocol
     | DdnetVersionProtocol
+    | DdnetVersionOldProtocol
     | TickSkipProtocol
+    | TeamSaveSuccessProtocol
+    | TeamSaveFailureProtocol
     | TeamLoadSuccessProtocol
     | TeamLoadFailureProtocol
+    | TeamPracticeProtocol
+    | TeamFinishProtocol
     | AntiBotProtocol
     | EosProtocol
+    | TestChunkProtocol
     | UnknownProtocol
     | CustomChunkProtocol
-    | GenericProtocol
+    | RawChunkProtocol
 )
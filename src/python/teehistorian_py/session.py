@@ -0,0 +1,258 @@
+"""Recording game sessions without knowing the chunk format.
+
+A teehistorian file stores positions and inputs as differences to the
+previous tick, and ticks only implicitly. ``RecordingSession`` takes game
+events with absolute values and writes the chunks a DDNet server would:
+``PlayerNew``/``PlayerDiff``/``PlayerOld`` in client ID order at the start of
+each tick, ``InputNew`` followed by ``InputDiff``, and the ``TickSkip`` chunks
+between ticks.
+"""
+
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any, Dict, List, Optional, Tuple
+
+from ._rust import (  # type: ignore[attr-defined]
+    PlayerInput,
+    PyConsoleCommand as ConsoleCommand,
+    PyDrop as Drop,
+    PyInputDiff as InputDiff,
+    PyInputNew as InputNew,
+    PyJoin as Join,
+    PyJoinVer6 as JoinVer6,
+    PyJoinVer7 as JoinVer7,
+    PyNetMessage as NetMessage,
+    PyPlayerDiff as PlayerDiff,
+    PyPlayerName as PlayerName,
+    PyPlayerNew as PlayerNew,
+    PyPlayerOld as PlayerOld,
+)
+
+if TYPE_CHECKING:
+    from . import TeehistorianWriter
+
+__all__ = ["RecordingSession"]
+
+
+class RecordingSession:
+    """
+    Write a recording from game events.
+
+    Events are collected per tick and written when the session moves on with
+    tick(): character changes first, sorted by client ID, then everything
+    else in call order. The session starts at tick 1, the first tick a
+    parser reports for player chunks.
+
+    Args:
+        writer: Writer to record to, a new in-memory writer by default
+        **headers: Header fields to set on the writer
+
+    Example:
+        >>> session = th.RecordingSession(map_name="Kobra")
+        >>> session.join(0, "nameless tee").spawn(0, 100, 200).tick()
+        >>> session.move(0, 110, 200).input(0, direction=1).tick(50)
+        >>> session.chat(0, "gg").leave(0, "quit")
+        >>> data = session.finish().getvalue()
+    """
+
+    def __init__(self, writer: Optional[TeehistorianWriter] = None, **headers: Any) -> None:
+        if writer is None:
+            from . import TeehistorianWriter
+
+            writer = TeehistorianWriter()
+        self.writer = writer
+        for key, value in headers.items():
+            self.writer.set_header(key, value)
+        self._tick = 1
+        # Character positions as written, and as they are at the end of the tick
+        self._written: Dict[int, Tuple[int, int]] = {}
+        self._positions: Dict[int, Tuple[int, int]] = {}
+        self._inputs: Dict[int, List[int]] = {}
+        self._joined: set[int] = set()
+        self._events: List[Any] = []
+
+    @property
+    def current_tick(self) -> int:
+        """Tick the next events are recorded at."""
+        return self._tick
+
+    def join(
+        self, cid: int, name: Optional[str] = None, protocol: Optional[int] = None
+    ) -> RecordingSession:
+        """
+        Connect a client, optionally recording its name.
+
+        With protocol 6 or 7, the JoinVer6/JoinVer7 DDNet servers write
+        before the Join is recorded too.
+
+        Raises:
+            ValueError: If the client already joined or the protocol is unknown
+        """
+        if cid in self._joined:
+            raise ValueError(f"Client {cid} already joined")
+        if protocol == 6:
+            self._events.append(JoinVer6(cid))
+        elif protocol == 7:
+            self._events.append(JoinVer7(cid))
+        elif protocol is not None:
+            raise ValueError(f"Unknown protocol {protocol}, expected 6 or 7")
+        self._joined.add(cid)
+        self._events.append(Join(cid))
+        if name is not None:
+            self._events.append(PlayerName(cid, name))
+        return self
+
+    def leave(self, cid: int, reason: str = "") -> RecordingSession:
+        """
+        Disconnect a client, removing its character.
+
+        Raises:
+            ValueError: If the client did not join
+        """
+        self._require_joined(cid)
+        self._joined.discard(cid)
+        self._positions.pop(cid, None)
+        self._inputs.pop(cid, None)
+        self._events.append(Drop(cid, reason))
+        return self
+
+    def spawn(self, cid: int, x: int, y: int) -> RecordingSession:
+        """
+        Spawn the character of a client at a position.
+
+        Raises:
+            ValueError: If the client did not join or its character is alive
+        """
+        self._require_joined(cid)
+        if cid in self._positions:
+            raise ValueError(f"Character of client {cid} already spawned")
+        self._positions[cid] = (x, y)
+        return self
+
+    def move(self, cid: int, x: int, y: int) -> RecordingSession:
+        """
+        Move the character of a client to an absolute position.
+
+        Raises:
+            ValueError: If the character is not spawned
+        """
+        if cid not in self._positions:
+            raise ValueError(f"Character of client {cid} is not spawned")
+        self._positions[cid] = (x, y)
+        return self
+
+    def kill(self, cid: int) -> RecordingSession:
+        """
+        Remove the character of a client.
+
+        Raises:
+            ValueError: If the character is not spawned
+        """
+        if self._positions.pop(cid, None) is None:
+            raise ValueError(f"Character of client {cid} is not spawned")
+        return self
+
+    def input(self, cid: int, **fields: int) -> RecordingSession:
+        """
+        Record the input of a client, fields named as in PlayerInput.
+
+        Fields not given keep their previous value, 0 for the first input.
+
+        Example:
+            >>> session.input(0, direction=-1, jump=1, target_x=100)
+
+        Raises:
+            ValueError: If the client did not join
+            TypeError: For a field name PlayerInput does not have
+        """
+        self._require_joined(cid)
+        unknown = set(fields) - set(PlayerInput.FIELDS)
+        if unknown:
+            raise TypeError(f"Unknown input fields: {', '.join(sorted(unknown))}")
+        previous = self._inputs.get(cid)
+        current = list(previous or [0] * len(PlayerInput.FIELDS))
+        for i, name in enumerate(PlayerInput.FIELDS):
+            current[i] = fields.get(name, current[i])
+        self._inputs[cid] = current
+        if previous is None:
+            self._events.append(InputNew(cid, current))
+        else:
+            self._events.append(InputDiff(cid, [c - p for c, p in zip(current, previous)]))
+        return self
+
+    def chat(self, cid: int, message: str, team: bool = False) -> RecordingSession:
+        """
+        Record a chat message sent by a client.
+
+        Raises:
+            ValueError: If the client did not join
+        """
+        self._require_joined(cid)
+        self._events.append(NetMessage.chat(cid, message, team))
+        return self
+
+    def command(self, cid: int, command: str, *args: str, flags: int = 0) -> RecordingSession:
+        """Record a console command, cid -1 for the server console."""
+        self._events.append(ConsoleCommand(cid, flags, command, list(args)))
+        return self
+
+    def write(self, chunk: Any) -> RecordingSession:
+        """
+        Record any other chunk at the current tick.
+
+        Custom extension chunks get their UUID registered in the header.
+        """
+        self._events.append(chunk)
+        return self
+
+    def tick(self, n: int = 1) -> RecordingSession:
+        """
+        Write the events of the current tick and advance by n ticks.
+
+        Raises:
+            ValueError: If n is less than 1
+        """
+        if n < 1:
+            raise ValueError("Ticks can only advance")
+        self._flush()
+        self._tick += n
+        return self
+
+    def finish(self) -> TeehistorianWriter:
+        """
+        Write the pending events and finalize the writer.
+
+        Returns:
+            The writer, to get the data from
+        """
+        self._flush()
+        self.writer.finalize()
+        return self.writer
+
+    def __enter__(self) -> RecordingSession:
+        return self
+
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None:
+        if exc_type is None:
+            self.finish()
+
+    def _require_joined(self, cid: int) -> None:
+        if cid not in self._joined:
+            raise ValueError(f"Client {cid} did not join")
+
+    def _flush(self) -> None:
+        chunks: List[Any] = []
+        for cid in sorted(set(self._written) | set(self._positions)):
+            old = self._written.get(cid)
+            new = self._positions.get(cid)
+            if old is None and new is not None:
+                chunks.append(PlayerNew(cid, *new))
+            elif old is not None and new is None:
+                chunks.append(PlayerOld(cid))
+            elif old is not None and new is not None and old != new:
+                chunks.append(PlayerDiff(cid, new[0] - old[0], new[1] - old[1]))
+        chunks.extend(self._events)
+        for chunk in chunks:
+            self.writer.write(chunk, tick=self._tick)
+        self._written = dict(self._positions)
+        self._events = []
@@ -6,8 +6,21 @@ Provides complete type hints for the Python wrapper around the Rust extension.
 This helps IDEs and type checkers understand the API.
 """
 
+from datetime import datetime
 from os import PathLike
-from typing import Any, Dict, Iterator, List, Optional, Union
+from typing import (
+    Any,
+    Callable,
+    Dict,
+    Iterable,
+    Iterator,
+    List,
+    Literal,
+    Optional,
+    Pattern,
+    Tuple,
+    Union,
+)
 
 # ============================================================================
 # Exceptions
@@ -38,6 +51,11 @@ class WriteError(TeehistorianError):
 
     ...
 
+class TeehistorianWarning(UserWarning):
+    """Warning category for non-fatal anomalies found while parsing"""
+
+    ...
+
 # ============================================================================
 # Core Parser Class
 # ============================================================================
@@ -45,12 +63,37 @@ class WriteError(TeehistorianError):
 class Teehistorian:
     """High-performance teehistorian file parser"""
 
-    def __init__(self, data: bytes) -> None:
+    def __init__(
+        self,
+        data: bytes,
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> None:
         """Create parser from raw file data"""
         ...
 
-    def register_custom_uuid(self, uuid_string: str) -> None:
-        """Register a custom UUID handler"""
+    def register_custom_uuid(
+        self,
+        uuid_string: str,
+        decoder: Optional[Union[Callable[[str, bytes], Any], ExtensionHandler]] = None,
+        *,
+        name: Optional[str] = None,
+        fields: Optional[List[Tuple[str, str]]] = None,
+    ) -> None:
+        """Register a custom UUID handler, optionally decoding its chunks"""
+        ...
+
+    def unregister_custom_uuid(self, uuid_string: str) -> bool:
+        """Remove the handler of a custom UUID, returns whether one was registered"""
+        ...
+
+    def get_registered_uuids(self) -> List[RegisteredUuid]:
+        """Get the registered custom UUIDs, sorted by UUID"""
         ...
 
     def get_header_str(self) -> str:
@@ -61,6 +104,167 @@ class Teehistorian:
         """Get the header as bytes"""
         ...
 
+    def header_json(self) -> Dict[str, Any]:
+        """Get the header parsed as JSON (raises ParseError if malformed)"""
+        ...
+
+    def peek(self) -> Optional[Any]:
+        """Get the next chunk without consuming it"""
+        ...
+
+    def reset(self) -> None:
+        """Rewind to the first chunk so the parser can be iterated again"""
+        ...
+
+    def raw(self) -> Optional[bytes]:
+        """Get the encoded bytes of the most recently returned chunk"""
+        ...
+
+    def next_batch(self, size: int) -> List[Any]:
+        """Read up to size chunks at once, decoding without the GIL"""
+        ...
+
+    @property
+    def tick(self) -> int:
+        """Tick of the most recently read chunk"""
+        ...
+
+    @property
+    def header_info(self) -> "Header":
+        """Header with the well-known fields parsed from the JSON header"""
+        ...
+
+    @property
+    def format_version(self) -> Optional[int]:
+        """Format version named in the header"""
+        ...
+
+    @property
+    def header_span(self) -> Tuple[int, int]:
+        """Byte range of the JSON header, without its NUL terminator"""
+        ...
+
+    @property
+    def body_offset(self) -> int:
+        """Byte offset of the first chunk"""
+        ...
+
+    @property
+    def uuid_registrations(self) -> List[UuidRegistration]:
+        """Extension UUIDs registered in the header"""
+        ...
+
+    @property
+    def warnings(self) -> List["ParserWarning"]:
+        """Non-fatal anomalies found in the chunks read so far"""
+        ...
+
+    @property
+    def truncated(self) -> bool:
+        """Whether iteration stopped at a chunk cut off by the end of the data"""
+        ...
+
+    @property
+    def last_complete_offset(self) -> Optional[int]:
+        """Byte offset after the last complete chunk of a truncated file"""
+        ...
+
+    def iter_ticks(self, start: int, end: Optional[int] = None) -> "ChunkIterator":
+        """Iterate over the chunks within a tick range"""
+        ...
+
+    def iter_player(self, cid: int) -> "ChunkIterator":
+        """Iterate over the chunks concerning a single player"""
+        ...
+
+    def iter_players(self, cids: List[int]) -> "ChunkIterator":
+        """Iterate over the chunks concerning any of the given players"""
+        ...
+
+    def filter(self, types: List[Union[type, str]]) -> "ChunkIterator":
+        """Iterate over the chunks of the given types only"""
+        ...
+
+    def take_until(
+        self,
+        *,
+        chunk_type: Optional[Union[type, str]] = None,
+        tick: Optional[int] = None,
+    ) -> Optional[Any]:
+        """Skip ahead to the first chunk matching all given conditions"""
+        ...
+
+    def iter_with_ticks(self) -> Iterator[Tuple[int, Any]]:
+        """Iterate over (tick, chunk) tuples"""
+        ...
+
+    def iter_sampled(self, every_n_ticks: int) -> "ChunkIterator":
+        """Iterate with movement and input chunks thinned out to a tick stride"""
+        ...
+
+    def stats(self) -> "ParserStats":
+        """Summarize the whole file without creating chunk objects"""
+        ...
+
+    @staticmethod
+    def validate(data: bytes) -> "ValidationReport":
+        """Check a whole file and report every problem found"""
+        ...
+
+    def count(self, chunk_type: Union[type, str]) -> int:
+        """Count the chunks of one type in the whole file"""
+        ...
+
+    def save_state(self) -> "ParserState":
+        """Snapshot the parser position for a later resume"""
+        ...
+
+    @staticmethod
+    def resume(
+        data: bytes,
+        state: "ParserState",
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> "Teehistorian":
+        """Create a parser that continues where a saved state left off"""
+        ...
+
+    @staticmethod
+    def from_offset(
+        data: bytes,
+        offset: int,
+        state: Optional["ParserState"] = None,
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> "Teehistorian":
+        """Create a parser that starts at a known chunk boundary"""
+        ...
+
+    @staticmethod
+    def follow(
+        path: Union[str, PathLike[str]],
+        poll_interval: float = 0.5,
+        *,
+        skip_inputs: bool = False,
+        exclude_categories: Optional[List[str]] = None,
+        emit_warnings: bool = False,
+        resolve_inputs: bool = False,
+        resolve_positions: bool = False,
+        unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+    ) -> "FollowIterator":
+        """Follow a recording while the server is still writing it"""
+        ...
+
     def __iter__(self) -> Iterator[Any]:
         """Iterator support for processing chunks"""
         ...
@@ -79,6 +283,397 @@ class Teehistorian:
 
     def __repr__(self) -> str: ...
 
+class ChunkIterator:
+    """Filtered view of a parser's chunks, sharing the parser's position"""
+
+    def __iter__(self) -> Iterator[Any]: ...
+    def __next__(self) -> Any: ...
+
+class FollowIterator:
+    """Chunks of a recording that is still being written, see Teehistorian.follow()"""
+
+    def __iter__(self) -> Iterator[Any]: ...
+    def __next__(self) -> Any: ...
+    @property
+    def tick(self) -> int: ...
+
+class ParserState:
+    """Opaque snapshot of a parser's position, see Teehistorian.save_state()"""
+
+    @property
+    def offset(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+    @property
+    def chunk_count(self) -> int: ...
+    @property
+    def registered_uuids(self) -> List[str]: ...
+    def to_bytes(self) -> bytes: ...
+    @staticmethod
+    def from_bytes(data: bytes) -> "ParserState": ...
+
+class ParserStats:
+    """Summary of a whole teehistorian file, see Teehistorian.stats()"""
+
+    @property
+    def chunk_counts(self) -> Dict[str, int]: ...
+    @property
+    def total_chunks(self) -> int: ...
+    @property
+    def first_tick(self) -> Optional[int]: ...
+    @property
+    def last_tick(self) -> Optional[int]: ...
+    @property
+    def player_ids(self) -> List[int]: ...
+    @property
+    def duration(self) -> float: ...
+
+class Header:
+    """Typed view of the JSON header, see Teehistorian.header_info"""
+
+    @property
+    def map_name(self) -> Optional[str]: ...
+    @property
+    def map_sha256(self) -> Optional[str]: ...
+    @property
+    def map_crc(self) -> Optional[int]: ...
+    @property
+    def server_version(self) -> Optional[str]: ...
+    @property
+    def start_time(self) -> Optional[str]: ...
+    @property
+    def start_datetime(self) -> Optional[datetime]: ...
+    @property
+    def game_uuid(self) -> Optional[str]: ...
+    @property
+    def prng_description(self) -> Optional[str]: ...
+    @property
+    def prng_seed(self) -> Optional[Tuple[int, int]]: ...
+    @property
+    def config(self) -> Dict[str, str]: ...
+    @property
+    def tuning(self) -> "Tuning": ...
+    def tick_to_datetime(self, tick: int) -> Optional[datetime]: ...
+    def validate(self) -> List[str]: ...
+    def compatible_with(self, other: "Header") -> bool: ...
+    def diff(self, other: "Header") -> Dict[str, Tuple[Any, Any]]: ...
+
+class Tuning:
+    """Physics tuning recorded in the header, see Header.tuning"""
+
+    @property
+    def changed(self) -> Dict[str, float]: ...
+    @property
+    def is_default(self) -> bool: ...
+    @property
+    def ground_control_speed(self) -> float: ...
+    @property
+    def ground_control_accel(self) -> float: ...
+    @property
+    def ground_friction(self) -> float: ...
+    @property
+    def ground_jump_impulse(self) -> float: ...
+    @property
+    def air_jump_impulse(self) -> float: ...
+    @property
+    def air_control_speed(self) -> float: ...
+    @property
+    def air_control_accel(self) -> float: ...
+    @property
+    def air_friction(self) -> float: ...
+    @property
+    def hook_length(self) -> float: ...
+    @property
+    def hook_fire_speed(self) -> float: ...
+    @property
+    def hook_drag_accel(self) -> float: ...
+    @property
+    def hook_drag_speed(self) -> float: ...
+    @property
+    def gravity(self) -> float: ...
+    @property
+    def velramp_start(self) -> float: ...
+    @property
+    def velramp_range(self) -> float: ...
+    @property
+    def velramp_curvature(self) -> float: ...
+    @property
+    def gun_curvature(self) -> float: ...
+    @property
+    def gun_speed(self) -> float: ...
+    @property
+    def gun_lifetime(self) -> float: ...
+    @property
+    def shotgun_curvature(self) -> float: ...
+    @property
+    def shotgun_speed(self) -> float: ...
+    @property
+    def shotgun_speeddiff(self) -> float: ...
+    @property
+    def shotgun_lifetime(self) -> float: ...
+    @property
+    def grenade_curvature(self) -> float: ...
+    @property
+    def grenade_speed(self) -> float: ...
+    @property
+    def grenade_lifetime(self) -> float: ...
+    @property
+    def laser_reach(self) -> float: ...
+    @property
+    def laser_bounce_delay(self) -> float: ...
+    @property
+    def laser_bounce_num(self) -> float: ...
+    @property
+    def laser_bounce_cost(self) -> float: ...
+    @property
+    def laser_damage(self) -> float: ...
+    @property
+    def player_collision(self) -> float: ...
+    @property
+    def player_hooking(self) -> float: ...
+    @property
+    def jetpack_strength(self) -> float: ...
+    @property
+    def shotgun_strength(self) -> float: ...
+    @property
+    def explosion_strength(self) -> float: ...
+    @property
+    def hammer_strength(self) -> float: ...
+    @property
+    def hook_duration(self) -> float: ...
+    @property
+    def hammer_fire_delay(self) -> float: ...
+    @property
+    def gun_fire_delay(self) -> float: ...
+    @property
+    def shotgun_fire_delay(self) -> float: ...
+    @property
+    def grenade_fire_delay(self) -> float: ...
+    @property
+    def laser_fire_delay(self) -> float: ...
+    @property
+    def ninja_fire_delay(self) -> float: ...
+    @property
+    def hammer_hit_fire_delay(self) -> float: ...
+    @property
+    def ground_elasticity_x(self) -> float: ...
+    @property
+    def ground_elasticity_y(self) -> float: ...
+    def to_dict(self) -> Dict[str, float]: ...
+
+class ExtensionHandler:
+    """Base class of stateful handlers for a custom UUID"""
+
+    def on_chunk(self, chunk: CustomChunk, tick: int) -> Any:
+        """Return the object to yield instead of the chunk, or None to yield it"""
+        ...
+    def reset(self) -> None:
+        """Drop accumulated state, called by Teehistorian.reset()"""
+        ...
+
+class RegisteredUuid:
+    """Custom UUID registered with a parser"""
+
+    @property
+    def uuid(self) -> str: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def kind(self) -> str:
+        """"none", "callback", "fields" or "handler""""
+        ...
+
+class ParserWarning:
+    """Non-fatal anomaly found while parsing, see Teehistorian.warnings"""
+
+    @property
+    def kind(self) -> str: ...
+    @property
+    def message(self) -> str: ...
+    @property
+    def offset(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+
+class ValidationReport:
+    """Result of Teehistorian.validate()"""
+
+    @property
+    def is_valid(self) -> bool: ...
+    @property
+    def header_ok(self) -> bool: ...
+    @property
+    def header_error(self) -> Optional[str]: ...
+    @property
+    def eos_present(self) -> bool: ...
+    @property
+    def unknown_uuids(self) -> List[str]: ...
+    @property
+    def error_offset(self) -> Optional[int]: ...
+    @property
+    def error(self) -> Optional[str]: ...
+    @property
+    def truncated(self) -> bool: ...
+    @property
+    def ticks_monotonic(self) -> bool: ...
+    @property
+    def chunk_counts(self) -> Dict[str, int]: ...
+
+class RecordingDiff:
+    """Result of diff()"""
+
+    @property
+    def identical(self) -> bool: ...
+    @property
+    def header(self) -> Dict[str, Tuple[Any, Any]]: ...
+    @property
+    def chunk_counts(self) -> Dict[str, int]: ...
+    @property
+    def first_index(self) -> Optional[int]: ...
+    @property
+    def offsets(self) -> Optional[Tuple[Optional[int], Optional[int]]]: ...
+    @property
+    def ticks(self) -> Optional[Tuple[Optional[int], Optional[int]]]: ...
+    @property
+    def chunks(self) -> Optional[Tuple[Optional[AnyChunk], Optional[AnyChunk]]]: ...
+
+class RepairReport:
+    """Result of repair()"""
+
+    @property
+    def repaired(self) -> bool: ...
+    @property
+    def kept_chunks(self) -> int: ...
+    @property
+    def dropped_bytes(self) -> int: ...
+    @property
+    def error_offset(self) -> Optional[int]: ...
+    @property
+    def error(self) -> Optional[str]: ...
+    @property
+    def truncated(self) -> bool: ...
+    @property
+    def eos_added(self) -> bool: ...
+    @property
+    def last_tick(self) -> int: ...
+    @property
+    def decompress_error(self) -> Optional[str]: ...
+
+class RecompressReport:
+    """Result of recompress()"""
+
+    @property
+    def original_bytes(self) -> int: ...
+    @property
+    def recompressed_bytes(self) -> int: ...
+    @property
+    def saved_bytes(self) -> int: ...
+
+class PlayerTracker:
+    """Positions of every character over a recording"""
+
+    def __init__(self, data: bytes) -> None: ...
+    def positions_at(self, tick: int) -> Dict[int, Tuple[int, int]]:
+        """Positions of the characters spawned at a tick, by client ID"""
+        ...
+
+    def trajectory(self, cid: int) -> List[Tuple[int, int, int]]:
+        """Every position of one character as (tick, x, y) tuples"""
+        ...
+
+    def kinematics(self, cid: int) -> List[Tuple[int, int, int, int, int]]:
+        """Velocity and acceleration of one character as (tick, vx, vy, ax, ay) tuples"""
+        ...
+
+    def snapshots(self) -> "SnapshotIterator":
+        """Iterate over the whole world at every tick at which it changed"""
+        ...
+
+    @property
+    def client_ids(self) -> List[int]: ...
+    @property
+    def ticks(self) -> List[int]: ...
+
+class SnapshotIterator:
+    """World snapshots of a PlayerTracker, see PlayerTracker.snapshots()"""
+
+    def __iter__(self) -> Iterator[Tuple[int, Dict[int, Tuple[int, int]]]]: ...
+    def __next__(self) -> Tuple[int, Dict[int, Tuple[int, int]]]: ...
+
+class PlayerSession:
+    """One connection of a player, see extract_sessions()"""
+
+    @property
+    def cid(self) -> int: ...
+    @property
+    def name(self) -> Optional[str]: ...
+    @property
+    def join_tick(self) -> int: ...
+    @property
+    def drop_tick(self) -> Optional[int]: ...
+    @property
+    def drop_reason(self) -> Optional[str]: ...
+    @property
+    def duration(self) -> float: ...
+    @property
+    def ddnet_version(self) -> Optional[int]: ...
+
+class Anomaly:
+    """Something a player did that looks inhuman, see detect_anomalies()"""
+
+    @property
+    def kind(self) -> Literal["teleport", "input_rate", "periodic_input"]: ...
+    @property
+    def cid(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+    @property
+    def end_tick(self) -> int: ...
+    @property
+    def score(self) -> float: ...
+    @property
+    def details(self) -> str: ...
+
+class Finish:
+    """A finished race, see extract_finishes()"""
+
+    @property
+    def kind(self) -> Literal["player", "team"]: ...
+    @property
+    def cids(self) -> List[int]: ...
+    @property
+    def names(self) -> List[Optional[str]]: ...
+    @property
+    def team(self) -> int: ...
+    @property
+    def tick(self) -> int: ...
+    @property
+    def start_tick(self) -> int: ...
+    @property
+    def time(self) -> int: ...
+    @property
+    def seconds(self) -> float: ...
+    @property
+    def practice(self) -> bool: ...
+
+class RunVerification:
+    """Result of verify_run()"""
+
+    @property
+    def verified(self) -> bool: ...
+    @property
+    def cid(self) -> int: ...
+    @property
+    def claimed_time(self) -> float: ...
+    @property
+    def recorded_time(self) -> Optional[float]: ...
+    @property
+    def start_tick(self) -> Optional[int]: ...
+    @property
+    def finish_tick(self) -> Optional[int]: ...
+    @property
+    def discrepancies(self) -> List[str]: ...
+
 # ============================================================================
 # Core Writer Class
 # ============================================================================
@@ -86,8 +681,25 @@ class Teehistorian:
 class TeehistorianWriter:
     """Pythonic teehistorian file writer with context manager support"""
 
-    def __init__(self, file: Optional[Any] = None) -> None:
-        """Initialize a new teehistorian writer"""
+    def __init__(
+        self,
+        file: Optional[Any] = None,
+        *,
+        path: Optional[Union[str, PathLike[str]]] = None,
+        buffer_size: int = 65536,
+        strict: bool = False,
+        delta_encode: bool = False,
+        spill_threshold: Optional[int] = None,
+        extensions: Optional[Dict[str, str]] = None,
+    ) -> None:
+        """Initialize a new teehistorian writer, streaming to file or path if given"""
+        ...
+
+    @classmethod
+    def append(
+        cls, source: Union[bytes, str, PathLike[str]], *, strict: bool = False
+    ) -> "TeehistorianWriter":
+        """Continue an existing recording, dropping its final Eos"""
         ...
 
     def __enter__(self) -> "TeehistorianWriter":
@@ -103,26 +715,60 @@ class TeehistorianWriter:
         """Context manager exit"""
         ...
 
-    def write(self, chunk: Any) -> "TeehistorianWriter":
-        """Write a chunk to the teehistorian file"""
+    def write(self, chunk: Any, tick: Optional[int] = None) -> "TeehistorianWriter":
+        """Write a chunk, optionally at an absolute tick"""
+        ...
+
+    def write_join(
+        self,
+        client_id: int,
+        protocol: Optional[int] = None,
+        *,
+        version: Any = None,
+        tick: Optional[int] = None,
+    ) -> "TeehistorianWriter":
+        """Write JoinVer6/JoinVer7, Join and the client's version like a server"""
+        ...
+
+    def advance_to_tick(self, tick: int) -> "TeehistorianWriter":
+        """Emit the TickSkip that makes `tick` the current tick"""
+        ...
+
+    @property
+    def tick(self) -> int:
+        """Tick of the most recently written chunk"""
         ...
 
     def write_all(self, chunks: List[Any]) -> "TeehistorianWriter":
         """Write multiple chunks at once"""
         ...
 
-    def set_header(self, key: str, value: str) -> "TeehistorianWriter":
+    def write_from(self, chunks: Iterable[Any], batch_size: int = 1024) -> int:
+        """Write chunks from an iterable, serialized with the GIL released"""
+        ...
+
+    def set_header(self, key: str, value: Any) -> "TeehistorianWriter":
         """Set a header field"""
         ...
 
-    def get_header(self, key: str) -> Optional[str]:
+    def remove_header(self, key: str) -> "TeehistorianWriter":
+        """Remove a header field"""
+        ...
+
+    def get_header(self, key: str) -> Any:
         """Get a header field value"""
         ...
 
-    def update_headers(self, headers: Dict[str, str]) -> "TeehistorianWriter":
+    def update_headers(self, headers: Dict[str, Any]) -> "TeehistorianWriter":
         """Update multiple header fields from a dictionary"""
         ...
 
+    def copy_header_from(
+        self, source: Union["Teehistorian", "Header", Dict[str, Any], str, bytes]
+    ) -> "TeehistorianWriter":
+        """Replace the header with the one of a parser, Header, dict or JSON"""
+        ...
+
     def save(self, path: Union[str, PathLike[str]]) -> None:
         """Save the teehistorian to a file"""
         ...
@@ -135,6 +781,18 @@ class TeehistorianWriter:
         """Write all data to a file-like object"""
         ...
 
+    def flush(self) -> None:
+        """Write buffered chunks to the file or file object of a streaming writer"""
+        ...
+
+    def finalize(self) -> None:
+        """Write Eos if missing, flush and close the writer"""
+        ...
+
+    def close(self) -> None:
+        """Finish the recording, same as finalize()"""
+        ...
+
     @property
     def size(self) -> int:
         """Get the current size of the teehistorian data in bytes"""
@@ -145,6 +803,19 @@ class TeehistorianWriter:
         """Check if any data has been written"""
         ...
 
+    @property
+    def spilled(self) -> bool:
+        """Whether the data was moved to a temporary file"""
+        ...
+
+    def reserve(self, n_bytes: int) -> "TeehistorianWriter":
+        """Preallocate memory for n_bytes more bytes"""
+        ...
+
+    def estimate_size(self, chunks: Iterable[Any]) -> int:
+        """Count the bytes writing chunks would add, without writing them"""
+        ...
+
     def reset(self) -> None:
         """Reset the writer to initial empty state"""
         ...
@@ -155,11 +826,29 @@ class TeehistorianWriter:
 # Helper Functions
 # ============================================================================
 
-def parse(path: Union[str, PathLike[str]]) -> Teehistorian:
+def parse(
+    path: Union[str, PathLike[str]],
+    *,
+    skip_inputs: bool = False,
+    exclude_categories: Optional[List[str]] = None,
+    emit_warnings: bool = False,
+    resolve_inputs: bool = False,
+    resolve_positions: bool = False,
+    unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+) -> Teehistorian:
     """Parse a teehistorian file from a path"""
     ...
 
-def open(path: Union[str, PathLike[str]]) -> Teehistorian:
+def open(
+    path: Union[str, PathLike[str]],
+    *,
+    skip_inputs: bool = False,
+    exclude_categories: Optional[List[str]] = None,
+    emit_warnings: bool = False,
+    resolve_inputs: bool = False,
+    resolve_positions: bool = False,
+    unknown_uuid_policy: Literal["yield", "skip", "error"] = "yield",
+) -> Teehistorian:
     """Open and parse a teehistorian file (alias for parse)"""
     ...
 
@@ -167,14 +856,186 @@ def create(**headers: str) -> TeehistorianWriter:
     """Create a new teehistorian writer with optional headers"""
     ...
 
+def roundtrip(data: bytes) -> bytes:
+    """Rewrite a recording from its parsed chunks, asserting it is unchanged"""
+    ...
+
+class RecordingSession:
+    """Write a recording from game events with absolute values"""
+
+    writer: TeehistorianWriter
+
+    def __init__(self, writer: Optional[TeehistorianWriter] = None, **headers: Any) -> None: ...
+    @property
+    def current_tick(self) -> int: ...
+    def join(
+        self, cid: int, name: Optional[str] = None, protocol: Optional[int] = None
+    ) -> "RecordingSession": ...
+    def leave(self, cid: int, reason: str = "") -> "RecordingSession": ...
+    def spawn(self, cid: int, x: int, y: int) -> "RecordingSession": ...
+    def move(self, cid: int, x: int, y: int) -> "RecordingSession": ...
+    def kill(self, cid: int) -> "RecordingSession": ...
+    def input(self, cid: int, **fields: int) -> "RecordingSession": ...
+    def chat(self, cid: int, message: str, team: bool = False) -> "RecordingSession": ...
+    def command(
+        self, cid: int, command: str, *args: str, flags: int = 0
+    ) -> "RecordingSession": ...
+    def write(self, chunk: Any) -> "RecordingSession": ...
+    def tick(self, n: int = 1) -> "RecordingSession": ...
+    def finish(self) -> TeehistorianWriter: ...
+    def __enter__(self) -> "RecordingSession": ...
+    def __exit__(self, exc_type: Any, exc_val: Any, exc_tb: Any) -> None: ...
+
 def calculate_uuid(name: str) -> str:
     """Calculate a UUID from a chunk name"""
     ...
 
+def derive_uuid(name: str) -> str:
+    """Derive an extension UUID from its name like the server's CalculateUuid()"""
+    ...
+
 def format_uuid_from_bytes(data: bytes) -> str:
     """Format a UUID from 16 bytes"""
     ...
 
+def detect_format(data: bytes) -> Optional[int]:
+    """Get the format version of teehistorian data without parsing its chunks"""
+    ...
+
+def rewrite_header(data: bytes, new_fields: Dict[str, Optional[str]]) -> bytes:
+    """Replace fields of the JSON header, keeping the chunks byte for byte"""
+    ...
+
+def transform(
+    data: bytes,
+    *,
+    keep: Optional[Iterable[Union[type, str]]] = None,
+    drop: Optional[Iterable[Union[type, str]]] = None,
+    map_fn: Optional[Callable[[AnyChunk], Any]] = None,
+    map_types: Optional[Iterable[Union[type, str]]] = None,
+) -> bytes:
+    """Rewrite a recording in Rust, keeping, dropping or mapping chunks by type"""
+    ...
+
+def patch(
+    data: bytes,
+    predicate: Callable[[AnyChunk], bool],
+    replacement_fn: Callable[[AnyChunk], Any],
+) -> bytes:
+    """Replace the chunks a predicate selects, keeping every other byte"""
+    ...
+
+def anonymize(
+    data: bytes,
+    *,
+    names: bool = True,
+    ips: bool = True,
+    auth: bool = True,
+    chat: bool = False,
+) -> bytes:
+    """Replace player names, account names and IP addresses with stable pseudonyms"""
+    ...
+
+def slice_ticks(data: bytes, start: int, end: Optional[int] = None) -> bytes:
+    """Cut a tick range out of a recording as a file of its own"""
+    ...
+
+def split(
+    data: bytes, every_ticks: Optional[int] = None, *, every_minutes: Optional[float] = None
+) -> List[bytes]:
+    """Split a recording into recordings covering a fixed number of ticks each"""
+    ...
+
+def merge(files: List[bytes]) -> bytes:
+    """Merge the recordings of consecutive server runs into one"""
+    ...
+
+def redact_chat(
+    data: bytes, matcher: Union[str, Pattern[str], Iterable[str]], *, remove: bool = False
+) -> bytes:
+    """Blank or remove the chat messages that match"""
+    ...
+
+def extract_player(data: bytes, cid_or_name: Union[int, str]) -> bytes:
+    """Extract the chunks of one player into a recording of its own"""
+    ...
+
+def diff(a: bytes, b: bytes) -> RecordingDiff:
+    """Compare what two recordings mean, chunk by chunk"""
+    ...
+
+def normalize(
+    data: bytes, *, expand_diffs: bool = False, recompress_diffs: bool = True
+) -> bytes:
+    """Re-encode a recording canonically"""
+    ...
+
+def shift_ticks(data: bytes, delta: int) -> bytes:
+    """Move every chunk of a recording by a number of ticks"""
+    ...
+
+def remap_cids(data: bytes, mapping: Dict[int, int]) -> bytes:
+    """Give the clients of a recording new client IDs"""
+    ...
+
+def strip(data: bytes, categories: List[str] = ["Input"]) -> bytes:
+    """Remove whole categories of chunks from a recording"""
+    ...
+
+def repair(data: bytes) -> Tuple[bytes, RepairReport]:
+    """Salvage the readable part of a damaged recording"""
+    ...
+
+def recompress(
+    data: bytes, profile: Literal["small", "fast"] = "small"
+) -> Tuple[bytes, RecompressReport]:
+    """Rewrite a recording to take less space"""
+    ...
+
+def extract_sessions(data: bytes) -> List[PlayerSession]:
+    """Every session of every player in a recording"""
+    ...
+
+def input_stats(data: bytes) -> Dict[int, Dict[str, Union[int, float]]]:
+    """Input statistics of every player in a recording"""
+    ...
+
+def detect_anomalies(
+    data: bytes,
+    *,
+    max_speed: float = 128.0,
+    max_presses_per_second: float = 20.0,
+    min_periodic_presses: int = 10,
+) -> List[Anomaly]:
+    """Look for movement and input a human player cannot produce"""
+    ...
+
+def extract_finishes(data: bytes) -> List[Finish]:
+    """Every race finish in a recording"""
+    ...
+
+def verify_run(
+    data: bytes, cid: int, claimed_time: float, *, tolerance: float = 0.02
+) -> RunVerification:
+    """Check a claimed race time against the recording of the run"""
+    ...
+
+def make_chunk(chunk_type: str, **fields: Any) -> AnyChunk:
+    """Create a chunk from its type name and constructor arguments"""
+    ...
+
+def chunk_types() -> Dict[str, type]:
+    """Get the class of every chunk type, keyed by type name"""
+    ...
+
+def categories() -> Dict[str, List[str]]:
+    """Get the chunk type names of every category"""
+    ...
+
+def parse_chunk(data: bytes, uuid_registry: Optional[List[str]] = None) -> AnyChunk:
+    """Decode a single encoded chunk, the inverse of write_to_buffer()"""
+    ...
+
 # ============================================================================
 # Chunk Types - Player Lifecycle
 # ============================================================================
@@ -193,13 +1054,21 @@ class JoinVer6:
 
     def __init__(self, client_id: int) -> None: ...
 
+class JoinVer7:
+    """Player joins with Teeworlds 0.7 protocol"""
+
+    client_id: int
+
+    def __init__(self, client_id: int) -> None: ...
+
 class Drop:
     """Player leaves the game"""
 
     client_id: int
     reason: str
+    reason_bytes: bytes
 
-    def __init__(self, client_id: int, reason: str) -> None: ...
+    def __init__(self, client_id: int, reason: Union[str, bytes]) -> None: ...
 
 class PlayerReady:
     """Player becomes ready to play"""
@@ -212,6 +1081,13 @@ class PlayerReady:
 # Chunk Types - Player State
 # ============================================================================
 
+class PlayerRejoin:
+    """Player re-enters the game world after a map change"""
+
+    client_id: int
+
+    def __init__(self, client_id: int) -> None: ...
+
 class PlayerNew:
     """New player information"""
 
@@ -253,6 +1129,32 @@ class PlayerDiff:
 
     def __init__(self, client_id: int, dx: int, dy: int) -> None: ...
 
+class PlayerPosition:
+    """Absolute player position, produced by resolve_positions=True"""
+
+    client_id: int
+    tick: int
+    x: int
+    y: int
+
+    def __init__(self, client_id: int, tick: int, x: int, y: int) -> None: ...
+
+class PlayerFinish:
+    """Player finished a race, time in ticks"""
+
+    client_id: int
+    time: int
+
+    def __init__(self, client_id: int, time: int) -> None: ...
+
+class PlayerSwap:
+    """Two players swapped their positions in the race"""
+
+    client_id1: int
+    client_id2: int
+
+    def __init__(self, client_id1: int, client_id2: int) -> None: ...
+
 # ============================================================================
 # Chunk Types - Input
 # ============================================================================
@@ -261,17 +1163,55 @@ class InputNew:
     """New player input state"""
 
     client_id: int
-    input: bytes
+    input: List[int]
 
-    def __init__(self, client_id: int, input: bytes) -> None: ...
+    def __init__(self, client_id: int, input: List[int]) -> None: ...
+    @property
+    def player_input(self) -> "PlayerInput": ...
 
 class InputDiff:
     """Player input difference from previous state"""
 
     client_id: int
-    input: bytes
+    input: List[int]
 
-    def __init__(self, client_id: int, input: bytes) -> None: ...
+    def __init__(self, client_id: int, input: List[int]) -> None: ...
+    @property
+    def player_input(self) -> "PlayerInput": ...
+
+class PlayerInput:
+    """Player input with named fields, in wire order"""
+
+    FIELDS: List[str]
+    direction: int
+    target_x: int
+    target_y: int
+    jump: int
+    fire: int
+    hook: int
+    player_flags: int
+    wanted_weapon: int
+    next_weapon: int
+    prev_weapon: int
+
+    def __init__(
+        self,
+        *,
+        direction: int = 0,
+        target_x: int = 0,
+        target_y: int = 0,
+        jump: int = 0,
+        fire: int = 0,
+        hook: int = 0,
+        player_flags: int = 0,
+        wanted_weapon: int = 0,
+        next_weapon: int = 0,
+        prev_weapon: int = 0,
+    ) -> None: ...
+    @staticmethod
+    def from_list(input: List[int]) -> "PlayerInput": ...
+    def to_list(self) -> List[int]: ...
+    def to_dict(self) -> Dict[str, int]: ...
 
 # ============================================================================
 # Chunk Types - Communication
@@ -282,8 +1222,12 @@ class NetMessage:
 
     client_id: int
     msg: bytes
+    protocol: Optional[str]
 
     def __init__(self, client_id: int, msg: bytes) -> None: ...
+    @staticmethod
+    def chat(client_id: int, message: str, team: bool = False) -> "NetMessage": ...
+    def decode(self, protocol: Optional[str] = None) -> Optional["GameMessage"]: ...
 
 class NetMessagePlayerInfo:
     """Parsed network message containing player information"""
@@ -327,6 +1271,15 @@ class ConsoleCommand:
 # Chunk Types - Authentication & Version
 # ============================================================================
 
+class AuthInit:
+    """Player already logged in when the recording started"""
+
+    client_id: int
+    level: int
+    auth_name: str
+
+    def __init__(self, client_id: int, level: int, auth_name: str) -> None: ...
+
 class AuthLogin:
     """Authentication login information"""
 
@@ -336,6 +1289,13 @@ class AuthLogin:
 
     def __init__(self, client_id: int, level: int, auth_name: str) -> None: ...
 
+class AuthLogout:
+    """Player logged out of their account"""
+
+    client_id: int
+
+    def __init__(self, client_id: int) -> None: ...
+
 class DdnetVersion:
     """DDNet client version information"""
 
@@ -348,6 +1308,14 @@ class DdnetVersion:
         self, client_id: int, connection_id: str, version: int, version_str: bytes
     ) -> None: ...
 
+class DdnetVersionOld:
+    """DDNet client version information (old format)"""
+
+    client_id: int
+    version: int
+
+    def __init__(self, client_id: int, version: int) -> None: ...
+
 # ============================================================================
 # Chunk Types - Game Events
 # ============================================================================
@@ -359,6 +1327,22 @@ class TickSkip:
 
     def __init__(self, ticks: int) -> None: ...
 
+class TeamSaveSuccess:
+    """Team saved successfully"""
+
+    team: int
+    save_id: str
+    save: str
+
+    def __init__(self, team: int, save_id: str, save: str) -> None: ...
+
+class TeamSaveFailure:
+    """Team save failed"""
+
+    team: int
+
+    def __init__(self, team: int) -> None: ...
+
 class TeamLoadSuccess:
     """Team file loaded successfully"""
 
@@ -374,12 +1358,102 @@ class TeamLoadFailure:
 
     def __init__(self, team_id: int) -> None: ...
 
+class TeamPractice:
+    """Team practice mode changed, practice is 1 when enabled"""
+
+    team: int
+    practice: int
+
+    def __init__(self, team: int, practice: int) -> None: ...
+
+class TeamFinish:
+    """Team finished a race, time in ticks"""
+
+    team: int
+    time: int
+
+    def __init__(self, team: int, time: int) -> None: ...
+
 class AntiBot:
     """Anti-bot detection event"""
 
-    detection_event: str
+    data: str
+    data_bytes: bytes
+
+    def __init__(self, data: Union[str, bytes]) -> None: ...
+    def decode(self) -> "AntibotEvent": ...
 
-    def __init__(self, detection_event: str) -> None: ...
+class AntibotEvent:
+    """Antibot payload split into its fields, see AntiBot.decode()"""
+
+    @property
+    def event(self) -> Optional[str]: ...
+    @property
+    def args(self) -> List[str]: ...
+    @property
+    def params(self) -> Dict[str, str]: ...
+    @property
+    def raw(self) -> bytes: ...
+
+# ============================================================================
+# Game Messages (NetMessage.decode())
+# ============================================================================
+
+class ChatMessage:
+    """Chat message (Cl_Say)"""
+
+    mode: str
+    target: int
+    message: str
+
+class KillMessage:
+    """Self-kill request (Cl_Kill)"""
+
+class Emoticon:
+    """Emoticon shown above the tee (Cl_Emoticon)"""
+
+    emoticon: int
+    name: str
+
+class VoteCall:
+    """Vote started by the client (Cl_CallVote)"""
+
+    vote_type: str
+    value: str
+    reason: str
+    force: bool
+
+class Vote:
+    """Vote cast (Cl_Vote)"""
+
+    vote: int
+
+class SetTeam:
+    """Team change request (Cl_SetTeam)"""
+
+    team: int
+
+class SetSpectatorMode:
+    """Spectator target change (Cl_SetSpectatorMode)"""
+
+    spectator_id: int
+
+class ClientCommand:
+    """Console command sent by a 0.7 client (Cl_Command)"""
+
+    name: str
+    arguments: str
+
+GameMessage = Union[
+    ChatMessage,
+    KillMessage,
+    Emoticon,
+    VoteCall,
+    Vote,
+    SetTeam,
+    SetSpectatorMode,
+    ClientCommand,
+]
 
 # ============================================================================
 # Chunk Types - Special
@@ -390,13 +1464,20 @@ class Eos:
 
     def __init__(self) -> None: ...
 
+class TestChunk:
+    """Test chunk written by DDNet's teehistorian self-test"""
+
+    def __init__(self) -> None: ...
+
 class Unknown:
     """Unknown chunk with UUID (not registered)"""
 
     uuid: str
     data: bytes
+    uuid_name: Optional[str]
 
     def __init__(self, uuid: str, data: bytes) -> None: ...
+    def decode(self) -> Optional[AnyChunk]: ...
 
 class CustomChunk:
     """Custom chunk with registered handler"""
@@ -404,59 +1485,123 @@ class CustomChunk:
     uuid: str
     data: bytes
     handler_name: str
+    fields: Optional[Dict[str, Any]]
 
     def __init__(self, uuid: str, data: bytes, handler_name: str) -> None: ...
+    def __getattr__(self, name: str) -> Any: ...
+    def read_int(self, offset: int = 0) -> Tuple[int, int]:
+        """Read a varint, returning it with the offset after it"""
+        ...
+    def read_string(self, offset: int = 0) -> Tuple[str, int]:
+        """Read a NUL-terminated string, returning it with the offset after it"""
+        ...
+    def unpack(self, spec: List[str]) -> List[Any]:
+        """Decode the whole payload as one value per format name"""
+        ...
 
-class Generic:
-    """Generic/fallback chunk type"""
+class RawChunk:
+    """Chunk kept as its encoded bytes, written back unchanged"""
 
-    data: str
+    type_id: int
+    payload: bytes
+
+    def __init__(self, type_id: int, payload: bytes) -> None: ...
+    @staticmethod
+    def from_bytes(data: bytes) -> RawChunk: ...
+
+class SegmentStart:
+    """Start of another teehistorian stream concatenated after the previous one"""
+
+    offset: int
+    header: str
 
-    def __init__(self, data: str) -> None: ...
+    def __init__(self, header: str, offset: int = 0) -> None: ...
+
+class UuidRegistration:
+    """Extension UUID registered in the header, see Teehistorian.uuid_registrations"""
+
+    uuid: str
+    name: str
+
+    def __init__(self, uuid: str, name: str) -> None: ...
 
 # ============================================================================
 # Type Aliases
 # ============================================================================
 
-PlayerLifecycleChunk = Union[Join, JoinVer6, Drop, PlayerReady]
+PlayerLifecycleChunk = Union[Join, JoinVer6, JoinVer7, Drop, PlayerReady, PlayerRejoin]
 
-PlayerStateChunk = Union[PlayerNew, PlayerOld, PlayerName, PlayerTeam, PlayerDiff]
+PlayerStateChunk = Union[
+    PlayerNew,
+    PlayerOld,
+    PlayerName,
+    PlayerTeam,
+    PlayerDiff,
+    PlayerPosition,
+    PlayerFinish,
+    PlayerSwap,
+]
 
 InputChunk = Union[InputNew, InputDiff]
 
 CommunicationChunk = Union[NetMessage, NetMessagePlayerInfo, ConsoleCommand]
 
-AuthVersionChunk = Union[AuthLogin, DdnetVersion]
+AuthVersionChunk = Union[AuthInit, AuthLogin, AuthLogout, DdnetVersion, DdnetVersionOld]
 
-GameEventChunk = Union[TickSkip, TeamLoadSuccess, TeamLoadFailure, AntiBot]
+GameEventChunk = Union[
+    TickSkip,
+    TeamSaveSuccess,
+    TeamSaveFailure,
+    TeamLoadSuccess,
+    TeamLoadFailure,
+    TeamPractice,
+    TeamFinish,
+    AntiBot,
+]
 
-SpecialChunk = Union[Eos, Unknown, CustomChunk, Generic]
+SpecialChunk = Union[
+    Eos, TestChunk, Unknown, CustomChunk, RawChunk, SegmentStart
+]
 
 AnyChunk = Union[
     Join,
     JoinVer6,
+    JoinVer7,
     Drop,
     PlayerReady,
+    PlayerRejoin,
     PlayerNew,
     PlayerOld,
     PlayerName,
     PlayerTeam,
     PlayerDiff,
+    PlayerPosition,
+    PlayerFinish,
+    PlayerSwap,
     InputNew,
     InputDiff,
     NetMessage,
     NetMessagePlayerInfo,
     ConsoleCommand,
+    AuthInit,
     AuthLogin,
+    AuthLogout,
     DdnetVersion,
+    DdnetVersionOld,
     TickSkip,
+    TeamSaveSuccess,
+    TeamSaveFailure,
     TeamLoadSuccess,
     TeamLoadFailure,
+    TeamPractice,
+    TeamFinish,
     AntiBot,
     Eos,
+    TestChunk,
     Unknown,
     CustomChunk,
-    Generic,
+    RawChunk,
+    SegmentStart,
 ]
 
 __version__: str
@@ -6,9 +6,32 @@ import uuid
 TEEWORLDS_NAMESPACE = uuid.UUID("e05ddaaa-c4e6-4cfb-b642-5d48e80c0029")
 
 
+def derive_uuid(name: str) -> str:
+    """Derive the UUID of an extension from its name.
+
+    Implements the server's ``CalculateUuid()``: the MD5 hash of the Teeworlds
+    namespace UUID followed by the name, with version 3 and the RFC 4122
+    variant set. Mods name extensions like ``kog-checkpoint@kog.tw``, so
+    writers and servers agree on the UUID without a shared table.
+
+    Args:
+        name: The extension name (e.g., 'teehistorian-player-name@ddnet.org')
+
+    Returns:
+        Formatted UUID string in standard format
+
+    Example:
+        >>> derive_uuid('teehistorian-player-name@ddnet.org')
+        'd016f9b9-4151-3b87-87e5-3a6087eb5f26'
+    """
+    return str(uuid.uuid3(TEEWORLDS_NAMESPACE, name))
+
+
 def calculate_uuid(name: str) -> str:
     """Calculate UUID v3 from name using Teeworlds namespace.
 
+    Same as :func:`derive_uuid`.
+
     Args:
         name: The UUID name string (e.g., 'kog-one-login@kog.tw')
 
@@ -19,7 +42,7 @@ def calculate_uuid(name: str) -> str:
         >>> calculate_uuid('teehistorian@ddnet.tw')
         '699db17b-8efb-34ff-b1d8-da6f60c15dd1'
     """
-    return str(uuid.uuid3(TEEWORLDS_NAMESPACE, name))
+    return derive_uuid(name)
 
 
 def format_uuid_from_bytes(uuid_bytes: bytes) -> str:
@@ -2,6 +2,7 @@
 //!
 //! This module provides the infrastructure for registering and managing custom
 //! chunk types that don't have direct teehistorian::Chunk enum variants.
+use libtw2_packer::Unpacker;
 use parking_lot::RwLock;
 use pyo3::prelude::*;
 use std::collections::HashMap;
@@ -21,7 +22,7 @@ pub enum FieldFormat {
     I32,
     /// Fixed-width 64-bit integer
     I64,
-    /// Length-prefixed UTF-8 string
+    /// NUL-terminated UTF-8 string
     String,
     /// Length-prefixed raw bytes
     Bytes,
@@ -29,6 +30,65 @@ pub enum FieldFormat {
     Uuid,
 }
 
+impl FieldFormat {
+    /// Parse a format name as given to `register_custom_uuid(fields=...)`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let format = match name.to_ascii_lowercase().as_str() {
+            "int" | "varint" => Self::Varint,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "str" | "string" => Self::String,
+            "bytes" => Self::Bytes,
+            "uuid" => Self::Uuid,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Read one value of this format, fixed-width integers are little-endian
+    fn read(&self, unpacker: &mut Unpacker<'_>) -> Option<FieldValue> {
+        fn raw<const N: usize>(unpacker: &mut Unpacker<'_>) -> Option<[u8; N]> {
+            unpacker.read_raw(N).ok()?.try_into().ok()
+        }
+
+        let value = match self {
+            Self::Varint => FieldValue::Int(unpacker.read_int(&mut warn::Ignore).ok()?.into()),
+            Self::I8 => FieldValue::Int(i8::from_le_bytes(raw(unpacker)?).into()),
+            Self::I16 => FieldValue::Int(i16::from_le_bytes(raw(unpacker)?).into()),
+            Self::I32 => FieldValue::Int(i32::from_le_bytes(raw(unpacker)?).into()),
+            Self::I64 => FieldValue::Int(i64::from_le_bytes(raw(unpacker)?)),
+            Self::String => {
+                let string = unpacker.read_string().ok()?;
+                FieldValue::String(String::from_utf8_lossy(string).into_owned())
+            }
+            Self::Bytes => FieldValue::Bytes(unpacker.read_data(&mut warn::Ignore).ok()?.to_vec()),
+            Self::Uuid => FieldValue::Uuid(uuid::Uuid::from_bytes(raw(unpacker)?).to_string()),
+        };
+        Some(value)
+    }
+
+    /// Read one value at byte `offset` of `data`, with the offset after it
+    pub fn read_at(&self, data: &[u8], offset: usize) -> Option<(FieldValue, usize)> {
+        let mut unpacker = Unpacker::new(data.get(offset..)?);
+        let value = self.read(&mut unpacker)?;
+        let rest = unpacker.read_rest().ok()?.len();
+        Some((value, data.len() - rest))
+    }
+
+    /// Read one value of each format, returning None unless they cover
+    /// `data` exactly
+    pub fn read_all(formats: &[FieldFormat], data: &[u8]) -> Option<Vec<FieldValue>> {
+        let mut unpacker = Unpacker::new(data);
+        let values = formats
+            .iter()
+            .map(|format| format.read(&mut unpacker))
+            .collect::<Option<Vec<_>>>()?;
+        unpacker.is_empty().then_some(values)
+    }
+}
+
 #[pymethods]
 impl FieldFormat {
     fn __repr__(&self) -> String {
@@ -36,6 +96,15 @@ impl FieldFormat {
     }
 }
 
+/// Decoded value of a custom chunk field
+#[derive(Debug, Clone, PartialEq, IntoPyObject)]
+pub enum FieldValue {
+    Int(i64),
+    String(String),
+    Bytes(Vec<u8>),
+    Uuid(String),
+}
+
 /// Field specification for a custom chunk
 #[pyclass(module = "teehistorian_py")]
 #[derive(Debug, Clone)]
@@ -99,6 +168,22 @@ impl ChunkDef {
     }
 }
 
+impl ChunkDef {
+    /// Decode a payload into its fields
+    ///
+    /// Returns None if the payload is too short or has bytes left over.
+    pub fn decode(&self, data: &[u8]) -> Option<Vec<(String, FieldValue)>> {
+        let formats: Vec<_> = self
+            .fields
+            .iter()
+            .map(|field| field.format.clone())
+            .collect();
+        let values = FieldFormat::read_all(&formats, data)?;
+        let names = self.fields.iter().map(|field| field.name.clone());
+        Some(names.zip(values).collect())
+    }
+}
+
 /// Global chunk registry
 static GLOBAL_REGISTRY: once_cell::sync::Lazy<Arc<RwLock<HashMap<String, ChunkDef>>>> =
     once_cell::sync::Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
@@ -261,6 +346,48 @@ mod tests {
         assert_eq!(chunk_def.fields.len(), 2);
     }
 
+    #[test]
+    fn test_chunk_def_decode() {
+        let field = |name: &str, format| FieldSpec {
+            name: name.to_string(),
+            format,
+            description: None,
+        };
+        let chunk_def = ChunkDef::new(
+            "decode-uuid".to_string(),
+            "DecodeChunk".to_string(),
+            vec![
+                field("cid", FieldFormat::Varint),
+                field("time", FieldFormat::I32),
+                field("name", FieldFormat::String),
+            ],
+        );
+
+        let decoded = chunk_def
+            .decode(b"\x03\xdc\x05\x00\x00nameless\x00")
+            .unwrap();
+        assert_eq!(
+            decoded,
+            vec![
+                ("cid".to_string(), FieldValue::Int(3)),
+                ("time".to_string(), FieldValue::Int(1500)),
+                (
+                    "name".to_string(),
+                    FieldValue::String("nameless".to_string())
+                ),
+            ]
+        );
+        assert_eq!(chunk_def.decode(b"\x03\xdc\x05"), None);
+        assert_eq!(chunk_def.decode(b"\x03\xdc\x05\x00\x00a\x00\x01"), None);
+        assert_eq!(
+            FieldFormat::Varint.read_at(b"\x03\x01", 1),
+            Some((FieldValue::Int(1), 2))
+        );
+        assert_eq!(FieldFormat::Varint.read_at(b"\x03\x01", 2), None);
+        assert_eq!(FieldFormat::from_name("Int"), Some(FieldFormat::Varint));
+        assert_eq!(FieldFormat::from_name("float"), None);
+    }
+
     #[test]
     fn test_instance_registry() {
         let mut registry = InstanceRegistry::new();
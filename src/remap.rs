@@ -0,0 +1,163 @@
+//! Moving recordings into a common timeline and client ID space
+//!
+//! Recordings of several servers start at their own tick zero and hand out
+//! client IDs independently. Shifting ticks and remapping client IDs lines
+//! them up before they are analyzed together.
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use teehistorian::Chunk;
+
+use crate::chunks::{encode, map_cids};
+use crate::errors::TeehistorianParseError;
+use crate::transform::{Item, PlayerChunks, rewrite};
+
+/// Move every chunk of a recording by `delta` ticks
+///
+/// Chunks are copied as they are, only the `TickSkip` chunks change.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `delta` - Ticks to add, negative to move the recording back
+///
+/// # Example
+/// ```python
+/// # Start where the other server's recording starts
+/// data = th.shift_ticks(data, 1500)
+/// ```
+#[pyfunction]
+pub fn shift_ticks<'py>(py: Python<'py>, data: &[u8], delta: i32) -> PyResult<Bound<'py, PyBytes>> {
+    rewrite(py, data, |out, item, encoded, _| match item {
+        Item::Header(_) | Item::End => Ok(()),
+        Item::Segment(_) | Item::Chunk(Chunk::Eos, _) => out.copy(encoded, None),
+        Item::Chunk(_, tick) => {
+            let shifted = tick.checked_add(delta).filter(|&shifted| shifted >= 0);
+            let Some(shifted) = shifted else {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Tick {} would be moved to {}",
+                    tick,
+                    i64::from(tick) + i64::from(delta)
+                ))
+                .into());
+            };
+            out.copy(encoded, Some(shifted))
+        }
+    })
+}
+
+/// Client ID mapping of `remap_cids()`
+struct ClientMapping {
+    ids: HashMap<i32, i32>,
+}
+
+impl ClientMapping {
+    /// Check that no two clients end up with the same ID
+    fn new(ids: HashMap<i32, i32>) -> PyResult<Self> {
+        let mut sources = HashMap::new();
+        for (&from, &to) in &ids {
+            if from < 0 || to < 0 {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Cannot map client ID {} to {}, client IDs are not negative",
+                    from, to
+                ))
+                .into());
+            }
+            if let Some(other) = sources.insert(to, from) {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Client IDs {} and {} are both mapped to {}",
+                    other.min(from),
+                    other.max(from),
+                    to
+                ))
+                .into());
+            }
+        }
+        Ok(Self { ids })
+    }
+
+    /// New ID of a client
+    fn get(&self, cid: i32) -> PyResult<i32> {
+        if let Some(&id) = self.ids.get(&cid) {
+            return Ok(id);
+        }
+        match self.ids.iter().find(|&(_, &to)| to == cid) {
+            Some((from, _)) => Err(TeehistorianParseError::Validation(format!(
+                "Client {} keeps its ID, but client {} is mapped to it",
+                cid, from
+            ))
+            .into()),
+            None => Ok(cid),
+        }
+    }
+}
+
+/// Give the clients of a recording new client IDs
+///
+/// Clients missing from `mapping` keep their ID. Player chunks are
+/// reordered by their new ID where needed.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `mapping` - New client ID of each client ID
+///
+/// # Example
+/// ```python
+/// # Move the clients of the second server behind those of the first
+/// data = th.remap_cids(data, {cid: cid + 32 for cid in range(32)})
+/// ```
+#[pyfunction]
+pub fn remap_cids<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    mapping: HashMap<i32, i32>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mapping = ClientMapping::new(mapping)?;
+    let mut players = PlayerChunks::default();
+    rewrite(py, data, |out, item, encoded, _| {
+        let (chunk, tick) = match item {
+            Item::Header(_) => return Ok(()),
+            Item::End => return players.flush(out),
+            Item::Segment(_) | Item::Chunk(Chunk::Eos, _) => {
+                players.flush(out)?;
+                return out.copy(encoded, None);
+            }
+            Item::Chunk(chunk, tick) => (chunk, tick),
+        };
+
+        let mut error = None;
+        let mapped = map_cids(&chunk, |cid| {
+            mapping.get(cid).unwrap_or_else(|e| {
+                error.get_or_insert(e);
+                cid
+            })
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        let encoded = if mapped == chunk {
+            encoded.to_vec()
+        } else {
+            encode(&mapped)?
+        };
+        players.write(out, &mapped, encoded, tick)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_mapping() {
+        let mapping = ClientMapping::new(HashMap::from([(0, 5), (5, 0), (1, 7)])).unwrap();
+        assert_eq!(mapping.get(0).unwrap(), 5);
+        assert_eq!(mapping.get(5).unwrap(), 0);
+        assert_eq!(mapping.get(2).unwrap(), 2);
+        assert_eq!(mapping.get(-1).unwrap(), -1);
+        assert!(mapping.get(7).is_err());
+
+        assert!(ClientMapping::new(HashMap::from([(0, 3), (1, 3)])).is_err());
+        assert!(ClientMapping::new(HashMap::from([(0, -1)])).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Salvaging damaged recordings
+//!
+//! A server that crashes or runs out of disk leaves a recording that ends in
+//! the middle of a chunk and without `Eos`, and a damaged disk can garble a
+//! chunk. Everything before the first chunk that cannot be decoded is still
+//! good, so repairing keeps it and closes the recording properly.
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use teehistorian::Chunk;
+
+use crate::chunks::encode;
+use crate::ticks::TickTracker;
+use crate::{PyTeehistorian, TeehistorianParserInner, input};
+
+/// What `repair()` kept and dropped
+#[pyclass(name = "RepairReport", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyRepairReport {
+    /// Chunks kept, not counting the added `Eos`
+    #[pyo3(get)]
+    kept_chunks: usize,
+    /// Bytes dropped from the end of the decompressed data
+    #[pyo3(get)]
+    dropped_bytes: usize,
+    /// Byte offset of the first chunk that could not be decoded
+    #[pyo3(get)]
+    error_offset: Option<usize>,
+    /// Why the chunk at `error_offset` could not be decoded
+    #[pyo3(get)]
+    error: Option<String>,
+    /// Whether the data ended in the middle of a chunk
+    #[pyo3(get)]
+    truncated: bool,
+    /// Whether an `Eos` chunk was added at the end
+    #[pyo3(get)]
+    eos_added: bool,
+    /// Tick of the last chunk kept
+    #[pyo3(get)]
+    last_tick: i32,
+    /// Why compressed input could not be decompressed completely
+    #[pyo3(get)]
+    decompress_error: Option<String>,
+}
+
+#[pymethods]
+impl PyRepairReport {
+    /// Whether the recording had to be changed
+    #[getter]
+    fn repaired(&self) -> bool {
+        self.dropped_bytes > 0 || self.eos_added || self.decompress_error.is_some()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RepairReport(kept_chunks={}, dropped_bytes={}, eos_added={})",
+            self.kept_chunks, self.dropped_bytes, self.eos_added
+        )
+    }
+}
+
+/// Salvage the readable part of a damaged recording
+///
+/// Keeps everything before the first chunk that cannot be decoded, or before
+/// the chunk the data ends in, and adds an `Eos` if the kept part does not
+/// end with one. The kept bytes are copied as they are. A truncated or
+/// damaged archive is repaired from the data decompressed before the damage.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+///
+/// # Returns
+/// The repaired, uncompressed recording and a `RepairReport`
+///
+/// # Example
+/// ```python
+/// fixed, report = th.repair(data)
+/// if report.repaired:
+///     print(f"Dropped {report.dropped_bytes} bytes: {report.error}")
+/// ```
+#[pyfunction]
+pub fn repair<'py>(
+    py: Python<'py>,
+    data: &[u8],
+) -> PyResult<(Bound<'py, PyBytes>, PyRepairReport)> {
+    let (data, decompress_error) = input::decode_input_partial(data);
+    let parser = PyTeehistorian::new(&data, false, None, false, false, false, "yield")?;
+    let data = &parser.inner.data[..];
+
+    let mut report = PyRepairReport {
+        decompress_error,
+        ..Default::default()
+    };
+    let mut ticks = TickTracker::new();
+    let mut last_was_eos = false;
+    let end = TeehistorianParserInner::walk(data, parser.inner.chunks_start, |chunk| match chunk {
+        Some(chunk) => {
+            report.kept_chunks += 1;
+            report.last_tick = ticks.observe(&chunk);
+            last_was_eos = matches!(chunk, Chunk::Eos);
+        }
+        None => {
+            ticks.restart();
+            last_was_eos = false;
+        }
+    });
+    let end = match end {
+        Ok(end) => {
+            report.truncated = end < data.len();
+            if report.truncated {
+                report.error_offset = Some(end);
+                report.error = Some("Data ends in the middle of a chunk".to_string());
+            }
+            end
+        }
+        Err((offset, e)) => {
+            report.error_offset = Some(offset);
+            report.error = Some(e.to_string());
+            offset
+        }
+    };
+    report.dropped_bytes = data.len() - end;
+
+    let mut repaired = data[..end].to_vec();
+    if !last_was_eos {
+        repaired.extend_from_slice(&encode(&Chunk::Eos)?);
+        report.eos_added = true;
+    }
+    Ok((PyBytes::new(py, &repaired), report))
+}
@@ -0,0 +1,325 @@
+//! Resolution of delta-encoded chunks into absolute values
+//!
+//! Teehistorian only stores the full input of a player once per join
+//! (`InputNew`) and differences to the previous input afterwards
+//! (`InputDiff`). Positions work the same way with `PlayerNew` and
+//! `PlayerDiff`. The resolvers keep the last value per client so every diff
+//! can be handed out as the absolute value it results in. `DeltaEncoder`
+//! goes the other way for writers that are given absolute values.
+use std::collections::HashMap;
+
+use teehistorian::Chunk;
+use teehistorian::chunks::{Drop, InputDiff, InputNew, PlayerDiff};
+
+/// Last known input of every client
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputResolver {
+    inputs: HashMap<i32, [i32; 10]>,
+}
+
+impl InputResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `chunk`, turning an `InputDiff` into the `InputNew` it results in
+    ///
+    /// A diff without a preceding `InputNew` is applied to an all-zero input.
+    pub fn resolve<'a>(&mut self, chunk: Chunk<'a>) -> Chunk<'a> {
+        match chunk {
+            Chunk::InputNew(ref new) => {
+                self.inputs.insert(new.cid, new.input);
+            }
+            Chunk::InputDiff(diff) => {
+                let input = self.inputs.entry(diff.cid).or_default();
+                for (value, d) in input.iter_mut().zip(diff.dinput) {
+                    *value = value.wrapping_add(d);
+                }
+                return Chunk::InputNew(InputNew {
+                    cid: diff.cid,
+                    input: *input,
+                });
+            }
+            Chunk::Drop(ref drop) => {
+                self.inputs.remove(&drop.cid);
+            }
+            _ => {}
+        }
+        chunk
+    }
+
+    /// Last known input of a client
+    pub fn input(&self, cid: i32) -> Option<&[i32; 10]> {
+        self.inputs.get(&cid)
+    }
+
+    /// Known inputs, ordered by client ID
+    pub fn inputs(&self) -> Vec<(i32, [i32; 10])> {
+        let mut inputs: Vec<_> = self.inputs.iter().map(|(&cid, &i)| (cid, i)).collect();
+        inputs.sort_unstable();
+        inputs
+    }
+}
+
+impl FromIterator<(i32, [i32; 10])> for InputResolver {
+    fn from_iter<I: IntoIterator<Item = (i32, [i32; 10])>>(inputs: I) -> Self {
+        Self {
+            inputs: inputs.into_iter().collect(),
+        }
+    }
+}
+
+/// Last known position of every character
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PositionResolver {
+    positions: HashMap<i32, [i32; 2]>,
+}
+
+impl PositionResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `chunk`, returning `(cid, x, y)` if it moved a character
+    ///
+    /// A diff without a preceding `PlayerNew` is applied to the origin.
+    pub fn resolve(&mut self, chunk: &Chunk) -> Option<(i32, i32, i32)> {
+        match *chunk {
+            Chunk::PlayerNew(ref new) => {
+                self.positions.insert(new.cid, [new.x, new.y]);
+                Some((new.cid, new.x, new.y))
+            }
+            Chunk::PlayerDiff(ref diff) => {
+                let [x, y] = self.positions.entry(diff.cid).or_default();
+                *x = x.wrapping_add(diff.dx);
+                *y = y.wrapping_add(diff.dy);
+                Some((diff.cid, *x, *y))
+            }
+            Chunk::PlayerOld { cid } => {
+                self.positions.remove(&cid);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Last known position of a character, None if it is not spawned
+    pub fn position(&self, cid: i32) -> Option<[i32; 2]> {
+        self.positions.get(&cid).copied()
+    }
+
+    /// Spawned characters with their positions, ordered by client ID
+    pub fn spawned(&self) -> Vec<(i32, [i32; 2])> {
+        let mut spawned: Vec<_> = self.positions.iter().map(|(&cid, &p)| (cid, p)).collect();
+        spawned.sort_unstable();
+        spawned
+    }
+}
+
+impl FromIterator<(i32, [i32; 2])> for PositionResolver {
+    fn from_iter<I: IntoIterator<Item = (i32, [i32; 2])>>(spawned: I) -> Self {
+        Self {
+            positions: spawned.into_iter().collect(),
+        }
+    }
+}
+
+/// Encoding of absolute values as the DDNet server writes them
+///
+/// A `PlayerNew` for a character that is already spawned becomes a
+/// `PlayerDiff`, or nothing if it did not move. An `InputNew` for a client
+/// with a known input becomes an `InputDiff`, or nothing if it is unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct DeltaEncoder {
+    inputs: InputResolver,
+    positions: PositionResolver,
+}
+
+impl DeltaEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What the server would write for `chunk`
+    pub fn encode(&self, chunk: &Chunk) -> Delta {
+        match *chunk {
+            Chunk::PlayerNew(ref new) => match self.positions.position(new.cid) {
+                Some([x, y]) if [x, y] == [new.x, new.y] => Delta::Unchanged,
+                Some([x, y]) => Delta::Diff(Chunk::PlayerDiff(PlayerDiff {
+                    cid: new.cid,
+                    dx: new.x.wrapping_sub(x),
+                    dy: new.y.wrapping_sub(y),
+                })),
+                None => Delta::Keep,
+            },
+            Chunk::InputNew(ref new) => match self.inputs.input(new.cid) {
+                Some(input) if *input == new.input => Delta::Unchanged,
+                Some(input) => {
+                    let mut dinput = [0; 10];
+                    for ((d, value), previous) in dinput.iter_mut().zip(new.input).zip(input) {
+                        *d = value.wrapping_sub(*previous);
+                    }
+                    Delta::Diff(Chunk::InputDiff(InputDiff {
+                        cid: new.cid,
+                        dinput,
+                    }))
+                }
+                None => Delta::Keep,
+            },
+            _ => Delta::Keep,
+        }
+    }
+
+    /// Last known input of a client
+    pub fn input(&self, cid: i32) -> Option<&[i32; 10]> {
+        self.inputs.input(cid)
+    }
+
+    /// Last known position of a character, None if it is not spawned
+    pub fn position(&self, cid: i32) -> Option<[i32; 2]> {
+        self.positions.position(cid)
+    }
+
+    /// Spawned characters with their positions, ordered by client ID
+    pub fn spawned(&self) -> Vec<(i32, [i32; 2])> {
+        self.positions.spawned()
+    }
+
+    /// Track a written chunk
+    pub fn observe(&mut self, chunk: &Chunk) {
+        match *chunk {
+            Chunk::InputNew(ref new) => {
+                self.inputs.resolve(Chunk::InputNew(InputNew {
+                    cid: new.cid,
+                    input: new.input,
+                }));
+            }
+            Chunk::InputDiff(ref diff) => {
+                self.inputs.resolve(Chunk::InputDiff(InputDiff {
+                    cid: diff.cid,
+                    dinput: diff.dinput,
+                }));
+            }
+            Chunk::Drop(ref drop) => {
+                self.inputs.resolve(Chunk::Drop(Drop {
+                    cid: drop.cid,
+                    reason: drop.reason,
+                }));
+                // A client's character is gone once it dropped
+                self.positions.resolve(&Chunk::PlayerOld { cid: drop.cid });
+            }
+            _ => {}
+        }
+        self.positions.resolve(chunk);
+    }
+}
+
+/// Result of [`DeltaEncoder::encode`]
+#[derive(Debug, PartialEq)]
+pub enum Delta {
+    /// Write the chunk as it is
+    Keep,
+    /// Write this diff instead
+    Diff(Chunk<'static>),
+    /// Write nothing, the value did not change
+    Unchanged,
+}
+
+#[cfg(test)]
+mod tests {
+    use teehistorian::chunks::PlayerNew;
+
+    use super::*;
+
+    #[test]
+    fn test_diffs_are_applied() {
+        let mut resolver = InputResolver::new();
+        let mut input = [0; 10];
+        input[1] = 100;
+        resolver.resolve(Chunk::InputNew(InputNew { cid: 2, input }));
+
+        let mut dinput = [0; 10];
+        dinput[1] = -30;
+        dinput[3] = 1;
+        let resolved = resolver.resolve(Chunk::InputDiff(InputDiff { cid: 2, dinput }));
+        let mut expected = [0; 10];
+        expected[1] = 70;
+        expected[3] = 1;
+        assert_eq!(
+            resolved,
+            Chunk::InputNew(InputNew {
+                cid: 2,
+                input: expected
+            })
+        );
+    }
+
+    #[test]
+    fn test_delta_encoding() {
+        let mut encoder = DeltaEncoder::new();
+        let mut write = |chunk: Chunk<'static>| {
+            let encoded = match encoder.encode(&chunk) {
+                Delta::Keep => Some(chunk),
+                Delta::Diff(diff) => Some(diff),
+                Delta::Unchanged => None,
+            };
+            if let Some(ref chunk) = encoded {
+                encoder.observe(chunk);
+            }
+            encoded
+        };
+        let new = |x| Chunk::PlayerNew(PlayerNew { cid: 1, x, y: 10 });
+        let input = |fire| {
+            let mut input = [0; 10];
+            input[4] = fire;
+            Chunk::InputNew(InputNew { cid: 1, input })
+        };
+
+        assert_eq!(write(new(5)), Some(new(5)));
+        assert_eq!(
+            write(new(8)),
+            Some(Chunk::PlayerDiff(PlayerDiff {
+                cid: 1,
+                dx: 3,
+                dy: 0
+            }))
+        );
+        assert_eq!(write(new(8)), None);
+        write(Chunk::PlayerOld { cid: 1 });
+        assert_eq!(write(new(8)), Some(new(8)));
+
+        assert_eq!(write(input(1)), Some(input(1)));
+        assert_eq!(write(input(1)), None);
+        let mut dinput = [0; 10];
+        dinput[4] = 2;
+        assert_eq!(
+            write(input(3)),
+            Some(Chunk::InputDiff(InputDiff { cid: 1, dinput }))
+        );
+        write(Chunk::Drop(Drop {
+            cid: 1,
+            reason: b"",
+        }));
+        assert_eq!(write(input(3)), Some(input(3)));
+        assert_eq!(write(new(8)), Some(new(8)));
+    }
+
+    #[test]
+    fn test_positions_are_tracked() {
+        let mut resolver = PositionResolver::new();
+        let new = Chunk::PlayerNew(PlayerNew {
+            cid: 0,
+            x: 100,
+            y: 200,
+        });
+        assert_eq!(resolver.resolve(&new), Some((0, 100, 200)));
+        let diff = Chunk::PlayerDiff(PlayerDiff {
+            cid: 0,
+            dx: 5,
+            dy: -10,
+        });
+        assert_eq!(resolver.resolve(&diff), Some((0, 105, 190)));
+        assert_eq!(resolver.resolve(&Chunk::PlayerOld { cid: 0 }), None);
+        assert_eq!(resolver.resolve(&diff), Some((0, 5, -10)));
+    }
+}
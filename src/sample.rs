@@ -0,0 +1,137 @@
+//! Tick-stride decimation of movement and input chunks
+//!
+//! `PlayerDiff` and `InputDiff` chunks make up nearly all of a recording.
+//! For plotting a long run, one of them per player every few ticks is
+//! plenty. Skipped diffs are added up and folded into the next diff that is
+//! yielded, so summing the sampled diffs still gives exact positions and
+//! inputs.
+use std::collections::HashMap;
+
+use teehistorian::Chunk;
+use teehistorian::chunks::{InputDiff, PlayerDiff};
+
+use crate::errors::TeehistorianParseError;
+
+/// Diffs of one player not yet handed out
+#[derive(Debug, Clone, Default)]
+struct Pending<T> {
+    /// Stride window of the last diff handed out
+    window: Option<i32>,
+    sum: T,
+}
+
+/// Decimation state of one sampled iterator
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    every_n_ticks: i32,
+    positions: HashMap<i32, Pending<[i32; 2]>>,
+    inputs: HashMap<i32, Pending<[i32; 10]>>,
+}
+
+impl Sampler {
+    pub fn new(every_n_ticks: i32) -> Result<Self, TeehistorianParseError> {
+        if every_n_ticks < 1 {
+            return Err(TeehistorianParseError::Validation(format!(
+                "every_n_ticks must be positive, got {}",
+                every_n_ticks
+            )));
+        }
+        Ok(Self {
+            every_n_ticks,
+            positions: HashMap::new(),
+            inputs: HashMap::new(),
+        })
+    }
+
+    /// Decide whether `chunk` at `tick` is yielded, and in which form
+    ///
+    /// Returns the chunk to yield (diffs carry the sum of the skipped ones),
+    /// or None if it is folded into a later one.
+    pub fn sample<'a>(&mut self, chunk: Chunk<'a>, tick: i32) -> Option<Chunk<'a>> {
+        let window = tick.div_euclid(self.every_n_ticks);
+        match chunk {
+            Chunk::PlayerDiff(diff) => {
+                let pending = self.positions.entry(diff.cid).or_default();
+                pending.sum[0] = pending.sum[0].wrapping_add(diff.dx);
+                pending.sum[1] = pending.sum[1].wrapping_add(diff.dy);
+                take_due(pending, window).map(|[dx, dy]| {
+                    Chunk::PlayerDiff(PlayerDiff {
+                        cid: diff.cid,
+                        dx,
+                        dy,
+                    })
+                })
+            }
+            Chunk::InputDiff(diff) => {
+                let pending = self.inputs.entry(diff.cid).or_default();
+                for (sum, d) in pending.sum.iter_mut().zip(diff.dinput) {
+                    *sum = sum.wrapping_add(d);
+                }
+                take_due(pending, window).map(|dinput| {
+                    Chunk::InputDiff(InputDiff {
+                        cid: diff.cid,
+                        dinput,
+                    })
+                })
+            }
+            // Absolute values make the pending diffs obsolete
+            Chunk::PlayerNew(ref new) => {
+                self.positions.remove(&new.cid);
+                Some(chunk)
+            }
+            Chunk::PlayerOld { cid } => {
+                self.positions.remove(&cid);
+                Some(chunk)
+            }
+            Chunk::InputNew(ref new) => {
+                self.inputs.remove(&new.cid);
+                Some(chunk)
+            }
+            _ => Some(chunk),
+        }
+    }
+}
+
+/// Hand out the pending sum if no diff was handed out in `window` yet
+fn take_due<T: Default>(pending: &mut Pending<T>, window: i32) -> Option<T> {
+    if pending.window == Some(window) {
+        return None;
+    }
+    pending.window = Some(window);
+    Some(std::mem::take(&mut pending.sum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(dx: i32) -> Chunk<'static> {
+        Chunk::PlayerDiff(PlayerDiff { cid: 0, dx, dy: 0 })
+    }
+
+    #[test]
+    fn test_diffs_are_accumulated() {
+        let mut sampler = Sampler::new(10).unwrap();
+        let yielded: Vec<_> = (0..25)
+            .filter_map(|tick| sampler.sample(diff(1), tick))
+            .collect();
+        // First diff of each window, carrying the skipped ones
+        assert_eq!(yielded, vec![diff(1), diff(10), diff(10)]);
+    }
+
+    #[test]
+    fn test_lifecycle_passes_through() {
+        let mut sampler = Sampler::new(10).unwrap();
+        assert!(sampler.sample(diff(1), 0).is_some());
+        assert!(sampler.sample(diff(1), 1).is_none());
+
+        let join = Chunk::Join { cid: 1 };
+        assert_eq!(sampler.sample(join, 2), Some(Chunk::Join { cid: 1 }));
+
+        // A new position drops the pending diff
+        sampler.sample(Chunk::PlayerOld { cid: 0 }, 3).unwrap();
+        assert_eq!(sampler.sample(diff(5), 4), Some(diff(5)));
+
+        assert!(Sampler::new(0).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! Header-only chunk scanning
+//!
+//! Reads just enough of each chunk to know its tag and where it ends, without
+//! decoding the payload into a `Chunk`. This makes questions like "how many
+//! console commands are in this file" much cheaper than a full parse.
+use teehistorian::{Error, ErrorKind};
+use uuid::Uuid;
+
+use crate::extensions;
+
+/// Tag of an extension chunk
+pub const TAG_EX: i32 = -11;
+
+/// Number of ints in an input chunk
+const INPUT_SIZE: usize = 10;
+
+/// Identity of a chunk as read from its header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawTag<'a> {
+    /// Built-in chunk; non-negative tags are `PlayerDiff` client IDs
+    Builtin(i32),
+    /// Extension chunk identified by its UUID
+    Ex(&'a [u8; 16]),
+}
+
+impl RawTag<'_> {
+    /// Get the `chunk_type()` name if the header alone determines it
+    ///
+    /// Returns `None` for chunks whose Python type depends on the payload
+    /// (`NetMessage` may become `NetMessagePlayerInfo`) or on registered
+    /// handlers (unknown extension UUIDs).
+    pub fn type_name(&self) -> Option<&'static str> {
+        let name = match *self {
+            RawTag::Builtin(0..) => "PlayerDiff",
+            RawTag::Builtin(-1) => "Eos",
+            RawTag::Builtin(-2) => "TickSkip",
+            RawTag::Builtin(-3) => "PlayerNew",
+            RawTag::Builtin(-4) => "PlayerOld",
+            RawTag::Builtin(-5) => "InputDiff",
+            RawTag::Builtin(-6) => "InputNew",
+            RawTag::Builtin(-8) => "Join",
+            RawTag::Builtin(-9) => "Drop",
+            RawTag::Builtin(-10) => "ConsoleCommand",
+            RawTag::Builtin(_) => return None,
+            RawTag::Ex(uuid) => {
+                return extensions::lookup(&Uuid::from_bytes(*uuid)).map(|ext| ext.chunk_type);
+            }
+        };
+        Some(name)
+    }
+}
+
+/// Read the header of the chunk at `offset` and find where the chunk ends
+///
+/// Returns `None` if the data ends before the chunk does.
+pub fn scan_chunk(data: &[u8], offset: usize) -> Result<Option<(RawTag<'_>, usize)>, Error> {
+    let mut reader = Reader { data, pos: offset };
+    let Some(tag) = reader.int() else {
+        return Ok(None);
+    };
+
+    let scanned = match tag {
+        0.. => reader.ints(2),
+        -1 => Some(()),
+        -2 | -4 | -8 => reader.ints(1),
+        -3 => reader.ints(3),
+        -5 | -6 => reader.ints(1 + INPUT_SIZE),
+        -7 => reader.int().and_then(|_| reader.sized()).transpose()?,
+        -9 => reader.int().and_then(|_| reader.cstring()),
+        -10 => reader.console_command().transpose()?,
+        TAG_EX => {
+            let uuid = reader.take(16).map(|uuid| uuid.try_into().unwrap());
+            match (uuid, reader.sized().transpose()?) {
+                (Some(uuid), Some(())) => return Ok(Some((RawTag::Ex(uuid), reader.pos))),
+                _ => None,
+            }
+        }
+        other => return Err(ErrorKind::UnknownTag(other).into()),
+    };
+
+    Ok(scanned.map(|()| (RawTag::Builtin(tag), reader.pos)))
+}
+
+/// Read the tag at the start of `data`, returning it and its encoded length
+pub fn read_tag(data: &[u8]) -> Option<(i32, usize)> {
+    let mut reader = Reader { data, pos: 0 };
+    let tag = reader.int()?;
+    Some((tag, reader.pos))
+}
+
+/// Cursor reading teehistorian primitives; `None` means the data ran out
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    /// Read a variable-width int (sign bit in the first byte)
+    fn int(&mut self) -> Option<i32> {
+        let first = self.byte()?;
+        let sign = ((first >> 6) & 1) as i32;
+        let mut value = (first & 0b0011_1111) as i32;
+        let mut byte = first;
+        for i in 0..4 {
+            if byte & 0b1000_0000 == 0 {
+                break;
+            }
+            byte = self.byte()?;
+            value |= ((byte & 0b0111_1111) as i32) << (6 + 7 * i);
+        }
+        Some(value ^ -sign)
+    }
+
+    fn ints(&mut self, n: usize) -> Option<()> {
+        (0..n).try_for_each(|_| self.int().map(drop))
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(bytes)
+    }
+
+    fn cstring(&mut self) -> Option<()> {
+        let len = self.data.get(self.pos..)?.iter().position(|&b| b == 0)?;
+        self.pos += len + 1;
+        Some(())
+    }
+
+    /// Skip a size-prefixed byte buffer
+    fn sized(&mut self) -> Option<Result<(), Error>> {
+        let size = self.int()?;
+        if size < 0 {
+            return Some(Err(ErrorKind::NegativeBufLen(size).into()));
+        }
+        self.take(size as usize).map(|_| Ok(()))
+    }
+
+    fn console_command(&mut self) -> Option<Result<(), Error>> {
+        self.ints(2)?;
+        self.cstring()?;
+        let num_args = self.int()?;
+        if num_args < 0 {
+            return Some(Err(ErrorKind::NegativeBufLen(num_args).into()));
+        }
+        (0..num_args).try_for_each(|_| self.cstring())?;
+        Some(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teehistorian::Chunk;
+
+    fn serialize(chunk: &Chunk) -> Vec<u8> {
+        let mut buf = Vec::new();
+        chunk.serialize_into(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_scan_matches_decoder() {
+        use teehistorian::chunks::{ConsoleCommand, InputNew, PlayerDiff};
+
+        let chunks = [
+            Chunk::PlayerDiff(PlayerDiff {
+                cid: 3,
+                dx: -70000,
+                dy: 5,
+            }),
+            Chunk::TickSkip { dt: 4 },
+            Chunk::InputNew(InputNew {
+                cid: 1,
+                input: [1, -1, 200, 0, 0, 0, 0, 0, 0, 0],
+            }),
+            Chunk::ConsoleCommand(ConsoleCommand {
+                cid: 0,
+                flags: 1,
+                cmd: b"tune",
+                args: vec![b"gravity", b"0.5"],
+            }),
+            Chunk::PlayerReady { cid: 2 },
+            Chunk::Eos,
+        ];
+
+        for chunk in &chunks {
+            let data = serialize(chunk);
+            let (tag, end) = scan_chunk(&data, 0).unwrap().unwrap();
+            assert_eq!(end, data.len(), "{:?}", chunk);
+            assert!(tag.type_name().is_some());
+            assert_eq!(scan_chunk(&data[..end - 1], 0).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_payload_dependent_types() {
+        assert_eq!(RawTag::Builtin(-7).type_name(), None);
+        assert_eq!(RawTag::Ex(&[0; 16]).type_name(), None);
+        assert_eq!(RawTag::Builtin(-10).type_name(), Some("ConsoleCommand"));
+        assert!(scan_chunk(&[0x40 | 20], 0).is_err());
+    }
+
+    #[test]
+    fn test_read_tag() {
+        assert_eq!(
+            read_tag(&serialize(&Chunk::TickSkip { dt: 4 })),
+            Some((-2, 1))
+        );
+        assert_eq!(read_tag(&serialize(&Chunk::Test)), Some((TAG_EX, 1)));
+        assert_eq!(read_tag(&[0x80]), None);
+    }
+}
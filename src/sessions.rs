@@ -0,0 +1,205 @@
+//! Join and leave timeline of a recording
+//!
+//! A session lasts from the `Join` of a client to its `Drop`. Everything
+//! known about the player in between, like the name and client version, is
+//! collected in the same pass.
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+use crate::ticks::TICK_SPEED;
+use crate::walk_recording;
+
+/// One connection of a player, from `extract_sessions()`
+#[pyclass(name = "PlayerSession", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyPlayerSession {
+    /// Client ID
+    #[pyo3(get)]
+    cid: i32,
+    /// Last name the player used, None if it never set one
+    #[pyo3(get)]
+    name: Option<String>,
+    /// Tick of the `Join`
+    #[pyo3(get)]
+    join_tick: i32,
+    /// Tick of the `Drop`, None if the stream ended with the player connected
+    #[pyo3(get)]
+    drop_tick: Option<i32>,
+    /// Reason of the `Drop`
+    #[pyo3(get)]
+    drop_reason: Option<String>,
+    /// DDNet client version, None for vanilla clients
+    #[pyo3(get)]
+    ddnet_version: Option<i32>,
+    /// Tick the session lasted to, the end of the stream without `Drop`
+    end_tick: i32,
+    /// Whether the `Join` was seen, not only chunks sent before it
+    joined: bool,
+}
+
+impl PyPlayerSession {
+    fn new(cid: i32, tick: i32) -> Self {
+        Self {
+            cid,
+            name: None,
+            join_tick: tick,
+            drop_tick: None,
+            drop_reason: None,
+            ddnet_version: None,
+            end_tick: tick,
+            joined: false,
+        }
+    }
+}
+
+#[pymethods]
+impl PyPlayerSession {
+    /// Length of the session in seconds
+    #[getter]
+    fn duration(&self) -> f64 {
+        f64::from(self.end_tick - self.join_tick) / f64::from(TICK_SPEED)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "PlayerSession(cid={}, name={}, join_tick={}, drop_tick={})",
+            self.cid,
+            self.name.as_deref().into_pyobject(py)?.repr()?,
+            self.join_tick,
+            self.drop_tick
+                .map_or("None".to_string(), |tick| tick.to_string())
+        ))
+    }
+}
+
+/// Sessions found so far
+#[derive(Debug, Default)]
+struct Sessions {
+    open: HashMap<i32, PyPlayerSession>,
+    closed: Vec<PyPlayerSession>,
+}
+
+impl Sessions {
+    /// Session of a client, opened at `tick` if it has none
+    fn get(&mut self, cid: i32, tick: i32) -> &mut PyPlayerSession {
+        self.open
+            .entry(cid)
+            .or_insert_with(|| PyPlayerSession::new(cid, tick))
+    }
+
+    fn observe(&mut self, chunk: &Chunk, tick: i32) {
+        match *chunk {
+            Chunk::Join { cid } | Chunk::RejoinVer6 { cid } => {
+                // A second join without a drop in between starts over
+                if let Some(mut session) = self.open.remove(&cid) {
+                    if session.joined {
+                        session.end_tick = tick;
+                        self.closed.push(session);
+                    } else {
+                        self.open.insert(cid, session);
+                    }
+                }
+                let session = self.get(cid, tick);
+                session.join_tick = tick;
+                session.joined = true;
+            }
+            Chunk::PlayerName(ref player) => {
+                self.get(player.cid, tick).name =
+                    Some(String::from_utf8_lossy(player.name).into_owned());
+            }
+            Chunk::DdnetVersion(ref version) => {
+                self.get(version.cid, tick).ddnet_version = Some(version.version);
+            }
+            Chunk::DdnetVersionOld(ref version) => {
+                self.get(version.cid, tick).ddnet_version = Some(version.version);
+            }
+            Chunk::Drop(ref drop) => {
+                let mut session = self
+                    .open
+                    .remove(&drop.cid)
+                    .unwrap_or_else(|| PyPlayerSession::new(drop.cid, tick));
+                session.drop_tick = Some(tick);
+                session.drop_reason = Some(String::from_utf8_lossy(drop.reason).into_owned());
+                session.end_tick = tick;
+                self.closed.push(session);
+            }
+            _ => {}
+        }
+    }
+
+    /// Close the sessions still open at the end of a stream
+    fn end_stream(&mut self, tick: i32) {
+        for (_, mut session) in self.open.drain() {
+            session.end_tick = tick;
+            self.closed.push(session);
+        }
+    }
+
+    /// All sessions, ordered by join tick and client ID
+    fn finish(mut self, tick: i32) -> Vec<PyPlayerSession> {
+        self.end_stream(tick);
+        self.closed
+            .sort_by_key(|session| (session.join_tick, session.cid));
+        self.closed
+    }
+}
+
+/// Every session of every player in a recording
+///
+/// A session lasts from the `Join` of a client to its `Drop`. Sessions still
+/// open when a stream ends have no drop tick and last to the end of their
+/// stream. The recording is scanned once with the GIL released.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+///
+/// # Returns
+/// A list of `PlayerSession`, ordered by join tick and client ID
+///
+/// # Example
+/// ```python
+/// for session in th.extract_sessions(data):
+///     print(session.name, session.duration, session.drop_reason)
+/// ```
+#[pyfunction]
+pub fn extract_sessions(py: Python<'_>, data: &[u8]) -> PyResult<Vec<PyPlayerSession>> {
+    let mut sessions = Sessions::default();
+    let tick = walk_recording(py, data, |chunk, tick| match chunk {
+        Some(chunk) => sessions.observe(&chunk, tick),
+        None => sessions.end_stream(tick),
+    })?;
+    Ok(sessions.finish(tick))
+}
+
+#[cfg(test)]
+mod tests {
+    use teehistorian::chunks::Drop;
+
+    use super::*;
+
+    #[test]
+    fn test_sessions() {
+        let mut sessions = Sessions::default();
+        sessions.observe(&Chunk::Join { cid: 0 }, 0);
+        sessions.observe(&Chunk::Join { cid: 1 }, 0);
+        sessions.observe(
+            &Chunk::Drop(Drop {
+                cid: 0,
+                reason: b"quit",
+            }),
+            100,
+        );
+        sessions.observe(&Chunk::Join { cid: 0 }, 150);
+        let sessions = sessions.finish(200);
+
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sessions[0].drop_tick, Some(100));
+        assert_eq!(sessions[0].drop_reason.as_deref(), Some("quit"));
+        assert_eq!(sessions[0].duration(), 2.0);
+        assert_eq!((sessions[1].cid, sessions[1].drop_tick), (1, None));
+        assert_eq!(sessions[1].duration(), 4.0);
+        assert_eq!((sessions[2].cid, sessions[2].join_tick), (0, 150));
+    }
+}
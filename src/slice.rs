@@ -0,0 +1,483 @@
+//! Cutting tick ranges out of recordings
+//!
+//! A chunk only makes sense with what came before it: a `PlayerDiff` moves a
+//! character spawned by an earlier `PlayerNew`, an `InputDiff` changes the
+//! input of an earlier `InputNew` and a `Drop` ends the session of an
+//! earlier `Join`. A `Roster` follows the clients of a recording, so the
+//! chunks that set them up can be written again at the start of a slice.
+use std::collections::{BTreeMap, HashSet};
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde_json::Value;
+use teehistorian::Chunk;
+use teehistorian::chunks::{Auth, InputNew, PlayerNew};
+
+use crate::chunks::encode;
+use crate::errors::TeehistorianParseError;
+use crate::resolve::DeltaEncoder;
+use crate::ticks::TICK_SPEED;
+use crate::transform::{Item, Rewriter, rewrite};
+
+/// Encoded chunks describing a connected client
+#[derive(Debug, Clone, Default)]
+struct Client {
+    /// `JoinVer6`/`JoinVer7` and `Join`, or `RejoinVer6`
+    join: Vec<Vec<u8>>,
+    version: Option<Vec<u8>>,
+    name: Option<Vec<u8>>,
+    team: Option<Vec<u8>>,
+    /// Login as an `AuthInit`
+    auth: Option<Vec<u8>>,
+}
+
+/// Clients and characters at the current position of a recording
+#[derive(Debug, Clone, Default)]
+pub struct Roster {
+    clients: BTreeMap<i32, Client>,
+    values: DeltaEncoder,
+}
+
+impl Roster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance past `chunk`, given with its encoded bytes
+    pub fn observe(&mut self, chunk: &Chunk, encoded: &[u8]) -> PyResult<()> {
+        match *chunk {
+            Chunk::JoinVer6 { cid } | Chunk::JoinVer7 { cid } => {
+                *self.client(cid) = Client {
+                    join: vec![encoded.to_vec()],
+                    ..Client::default()
+                };
+            }
+            Chunk::Join { cid } | Chunk::RejoinVer6 { cid } => {
+                self.client(cid).join.push(encoded.to_vec());
+            }
+            Chunk::Drop(ref drop) => {
+                self.clients.remove(&drop.cid);
+            }
+            Chunk::DdnetVersion(ref version) => {
+                self.client(version.cid).version = Some(encoded.to_vec());
+            }
+            Chunk::DdnetVersionOld(ref version) => {
+                self.client(version.cid).version = Some(encoded.to_vec());
+            }
+            Chunk::PlayerName(ref name) => self.client(name.cid).name = Some(encoded.to_vec()),
+            Chunk::PlayerTeam { cid, .. } => self.client(cid).team = Some(encoded.to_vec()),
+            Chunk::AuthInit(ref auth) => self.client(auth.cid).auth = Some(encoded.to_vec()),
+            Chunk::AuthLogin(ref auth) => {
+                let init = Chunk::AuthInit(Auth {
+                    cid: auth.cid,
+                    level: auth.level,
+                    auth_name: auth.auth_name,
+                });
+                self.client(auth.cid).auth = Some(encode(&init)?);
+            }
+            Chunk::AuthLogout { cid } => self.client(cid).auth = None,
+            _ => {}
+        }
+        self.values.observe(chunk);
+        Ok(())
+    }
+
+    fn client(&mut self, cid: i32) -> &mut Client {
+        self.clients.entry(cid).or_default()
+    }
+
+    /// Write the chunks that connect every client again at `tick`
+    ///
+    /// Characters are left out, they have to be written in client ID order
+    /// with the other player chunks of the tick.
+    pub fn write_clients(&self, out: &mut Rewriter, tick: i32) -> PyResult<()> {
+        for client in self.clients.values() {
+            let chunks = client.join.iter().chain(
+                [&client.version, &client.name, &client.team, &client.auth]
+                    .into_iter()
+                    .flatten(),
+            );
+            for encoded in chunks {
+                out.copy(encoded, Some(tick))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `PlayerNew` spawning a character where it currently is
+    pub fn spawn(&self, cid: i32) -> Option<Chunk<'static>> {
+        let [x, y] = self.values.position(cid)?;
+        Some(Chunk::PlayerNew(PlayerNew { cid, x, y }))
+    }
+
+    /// `InputNew` with the current input of a client
+    pub fn input(&self, cid: i32) -> Option<Chunk<'static>> {
+        let input = *self.values.input(cid)?;
+        Some(Chunk::InputNew(InputNew { cid, input }))
+    }
+
+    /// Client IDs of the connected clients with a known input, in order
+    pub fn with_input(&self) -> Vec<i32> {
+        self.clients
+            .keys()
+            .copied()
+            .filter(|&cid| self.values.input(cid).is_some())
+            .collect()
+    }
+
+    /// Client IDs of the spawned characters, in order
+    pub fn spawned(&self) -> Vec<i32> {
+        self.values
+            .spawned()
+            .into_iter()
+            .map(|(cid, _)| cid)
+            .collect()
+    }
+}
+
+/// Writes the chunks of a tick range as a recording of its own
+pub struct Slicer {
+    start: i32,
+    end: Option<i32>,
+    roster: Roster,
+    /// Header of the current stream, if it is not the first one
+    segment: Option<Vec<u8>>,
+    /// Characters spawned before `start` that still need a `PlayerNew`
+    pending: Option<Vec<i32>>,
+    /// Clients with an input from before `start` that still need an `InputNew`
+    pending_inputs: Vec<i32>,
+    /// Clients whose input was written in full inside the range
+    inputs: HashSet<i32>,
+    /// Whether the last chunk written was an `Eos`
+    eos: bool,
+    ended: bool,
+}
+
+impl Slicer {
+    pub fn new(start: i32, end: Option<i32>) -> Self {
+        Self {
+            start,
+            end,
+            roster: Roster::new(),
+            segment: None,
+            pending: None,
+            pending_inputs: Vec::new(),
+            inputs: HashSet::new(),
+            eos: false,
+            ended: false,
+        }
+    }
+
+    /// Handle an item of the recording
+    pub fn item(&mut self, out: &mut Rewriter, item: Item, encoded: &[u8]) -> PyResult<()> {
+        match item {
+            Item::Header(_) => Ok(()),
+            // A recording cut off without `Eos`, or one ending before the range
+            Item::End if !self.eos => self.finish(out),
+            Item::End => Ok(()),
+            Item::Segment(_) => {
+                // Clients of a previous stream are gone
+                self.roster = Roster::new();
+                self.inputs.clear();
+                self.segment = Some(encoded.to_vec());
+                if self.started() && !self.ended {
+                    self.eos = false;
+                    out.copy(encoded, None)?;
+                }
+                Ok(())
+            }
+            Item::Chunk(chunk, tick) => self.chunk(out, chunk, tick, encoded),
+        }
+    }
+
+    fn chunk(
+        &mut self,
+        out: &mut Rewriter,
+        chunk: Chunk,
+        tick: i32,
+        encoded: &[u8],
+    ) -> PyResult<()> {
+        if self.ended {
+            return Ok(());
+        }
+        if self.end.is_some_and(|end| tick >= end) {
+            return self.finish(out);
+        }
+        if tick < self.start {
+            if !matches!(chunk, Chunk::Eos) {
+                self.roster.observe(&chunk, encoded)?;
+            }
+            return Ok(());
+        }
+        if !self.started() {
+            self.begin(out)?;
+        }
+
+        let player = match chunk {
+            Chunk::PlayerNew(ref p) => Some(p.cid),
+            Chunk::PlayerDiff(ref p) => Some(p.cid),
+            Chunk::PlayerOld { cid } => Some(cid),
+            _ => None,
+        };
+        let input = match chunk {
+            Chunk::InputNew(ref new) => Some(new.cid),
+            Chunk::InputDiff(ref diff) => Some(diff.cid),
+            _ => None,
+        };
+        match (player, input) {
+            (Some(cid), _) if tick == self.start => self.flush(out, cid)?,
+            // Written in full below
+            (_, Some(cid)) if tick == self.start => {
+                self.flush(out, i32::MAX)?;
+                self.pending_inputs.retain(|&pending| pending != cid);
+            }
+            _ => {
+                self.flush(out, i32::MAX)?;
+                self.flush_inputs(out)?;
+            }
+        }
+        self.roster.observe(&chunk, encoded)?;
+
+        let spawned_before = self.take_pending(player);
+        match chunk {
+            // The character was spawned before the range and left right at its start
+            Chunk::PlayerOld { .. } if spawned_before => Ok(()),
+            Chunk::PlayerDiff(diff) if spawned_before => match self.roster.spawn(diff.cid) {
+                Some(spawn) => out.write(&spawn, tick),
+                None => out.copy(encoded, Some(tick)),
+            },
+            Chunk::InputDiff(diff) if !self.inputs.contains(&diff.cid) => {
+                self.inputs.insert(diff.cid);
+                match self.roster.input(diff.cid) {
+                    Some(input) => out.write(&input, tick),
+                    None => out.copy(encoded, Some(tick)),
+                }
+            }
+            Chunk::InputNew(new) => {
+                self.inputs.insert(new.cid);
+                out.copy(encoded, Some(tick))
+            }
+            Chunk::Drop(drop) => {
+                self.inputs.remove(&drop.cid);
+                out.copy(encoded, Some(tick))
+            }
+            Chunk::Eos => {
+                self.eos = true;
+                out.copy(encoded, None)
+            }
+            _ => out.copy(encoded, Some(tick)),
+        }
+    }
+
+    /// Connect the clients of the recording at `start` again
+    ///
+    /// Their characters follow with the first chunk that is not a player
+    /// chunk of `start`, their inputs with the first one that is neither a
+    /// player nor an input chunk of `start`.
+    fn begin(&mut self, out: &mut Rewriter) -> PyResult<()> {
+        if let Some(segment) = &self.segment {
+            out.copy(segment, None)?;
+        }
+        self.roster.write_clients(out, self.start)?;
+        self.pending = Some(self.roster.spawned());
+        self.pending_inputs = self.roster.with_input();
+        Ok(())
+    }
+
+    /// End the range with an `Eos` if it did not end yet
+    ///
+    /// A range without chunks still gets the clients connected at its start.
+    pub fn finish(&mut self, out: &mut Rewriter) -> PyResult<()> {
+        if self.ended {
+            return Ok(());
+        }
+        if !self.started() {
+            self.begin(out)?;
+        }
+        self.ended = true;
+        self.flush(out, i32::MAX)?;
+        self.flush_inputs(out)?;
+        out.copy(&encode(&Chunk::Eos)?, None)
+    }
+
+    /// Continue with the range from `start` up to `end`, keeping the clients
+    pub fn restart(&mut self, start: i32, end: i32) {
+        self.start = start;
+        self.end = Some(end);
+        self.pending = None;
+        self.pending_inputs.clear();
+        self.inputs.clear();
+        self.eos = false;
+        self.ended = false;
+    }
+
+    /// Whether a chunk of the range was reached
+    pub fn started(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// End of the range, if it has one
+    pub fn end(&self) -> Option<i32> {
+        self.end
+    }
+
+    /// Spawn the pending characters with a client ID below `below` at `start`
+    fn flush(&mut self, out: &mut Rewriter, below: i32) -> PyResult<()> {
+        let Some(pending) = &mut self.pending else {
+            return Ok(());
+        };
+        let count = pending.iter().take_while(|&&cid| cid < below).count();
+        for cid in pending.drain(..count) {
+            if let Some(spawn) = self.roster.spawn(cid) {
+                out.write(&spawn, self.start)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the inputs set before `start` that did not change at `start`
+    fn flush_inputs(&mut self, out: &mut Rewriter) -> PyResult<()> {
+        for cid in std::mem::take(&mut self.pending_inputs) {
+            if let Some(input) = self.roster.input(cid) {
+                self.inputs.insert(cid);
+                out.write(&input, self.start)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `cid` was a character spawned before the range, which is now handled
+    fn take_pending(&mut self, cid: Option<i32>) -> bool {
+        match (&mut self.pending, cid) {
+            (Some(pending), Some(cid)) if pending.first() == Some(&cid) => {
+                pending.remove(0);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Cut the ticks from `start` up to `end` out of a recording
+#[pyfunction]
+#[pyo3(signature = (data, start, end=None))]
+pub fn slice_ticks<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    start: i32,
+    end: Option<i32>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    if let Some(end) = end
+        && end < start
+    {
+        return Err(TeehistorianParseError::Validation(format!(
+            "End tick {} is before start tick {}",
+            end, start
+        ))
+        .into());
+    }
+    let mut slicer = Slicer::new(start, end);
+    rewrite(py, data, |out, item, encoded, _| {
+        slicer.item(out, item, encoded)
+    })
+}
+
+/// Split a recording into recordings of `every_ticks` or `every_minutes` each
+#[pyfunction]
+#[pyo3(signature = (data, every_ticks=None, *, every_minutes=None))]
+pub fn split<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    every_ticks: Option<i32>,
+    every_minutes: Option<f64>,
+) -> PyResult<Vec<Bound<'py, PyBytes>>> {
+    let every = match (every_ticks, every_minutes) {
+        (Some(ticks), None) => ticks,
+        (None, Some(minutes)) => (minutes * 60.0 * TICK_SPEED as f64).round() as i32,
+        _ => {
+            return Err(TeehistorianParseError::Validation(
+                "Expected either every_ticks or every_minutes".to_string(),
+            )
+            .into());
+        }
+    };
+    if every <= 0 {
+        return Err(TeehistorianParseError::Validation(format!(
+            "Parts must be at least one tick long, got {}",
+            every
+        ))
+        .into());
+    }
+
+    let mut header = Value::Null;
+    let mut parts = Vec::new();
+    let mut slicer = Slicer::new(0, Some(every));
+    let last = rewrite(py, data, |out, item, encoded, _| {
+        match item {
+            Item::Header(ref fields) => header = (**fields).clone(),
+            // Ranges without chunks are left out
+            Item::Chunk(ref chunk, tick)
+                if !matches!(chunk, Chunk::Eos) && slicer.end().is_some_and(|end| tick >= end) =>
+            {
+                if slicer.started() {
+                    slicer.finish(out)?;
+                    let mut part = std::mem::replace(out, Rewriter::new(py, header.clone()));
+                    parts.push(PyBytes::new(py, &part.contents()?));
+                }
+                let start = tick - tick.rem_euclid(every);
+                slicer.restart(start, start.saturating_add(every));
+            }
+            _ => {}
+        }
+        slicer.item(out, item, encoded)
+    })?;
+    parts.push(last);
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use teehistorian::chunks::{Drop, PlayerDiff};
+
+    use super::*;
+
+    #[test]
+    fn test_roster() {
+        let mut roster = Roster::new();
+        let chunks = [
+            Chunk::JoinVer7 { cid: 3 },
+            Chunk::Join { cid: 3 },
+            Chunk::PlayerNew(PlayerNew {
+                cid: 3,
+                x: 10,
+                y: 20,
+            }),
+            Chunk::Join { cid: 1 },
+            Chunk::PlayerNew(PlayerNew { cid: 1, x: 0, y: 0 }),
+            Chunk::PlayerDiff(PlayerDiff {
+                cid: 3,
+                dx: 5,
+                dy: 0,
+            }),
+            Chunk::Drop(Drop {
+                cid: 1,
+                reason: b"",
+            }),
+        ];
+        for chunk in &chunks {
+            roster.observe(chunk, &encode(chunk).unwrap()).unwrap();
+        }
+        assert_eq!(roster.clients[&3].join.len(), 2);
+        assert!(!roster.clients.contains_key(&1));
+        assert_eq!(roster.spawned(), vec![3]);
+        assert_eq!(
+            roster.spawn(3),
+            Some(Chunk::PlayerNew(PlayerNew {
+                cid: 3,
+                x: 15,
+                y: 20
+            }))
+        );
+        assert_eq!(roster.input(3), None);
+    }
+}
@@ -0,0 +1,229 @@
+//! Parser checkpoints
+//!
+//! A `ParserState` captures everything needed to continue parsing a file from
+//! where a previous parser stopped, so batch jobs can resume after restarts.
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde_json::{Value, json};
+
+use crate::errors::TeehistorianParseError;
+use crate::net_msg::{ClientVersions, NetVersion};
+use crate::resolve::{InputResolver, PositionResolver};
+use crate::ticks::TickTracker;
+
+/// Version of the serialized state format
+const STATE_VERSION: u64 = 1;
+
+/// Opaque snapshot of a parser's position
+///
+/// Obtained from `Teehistorian.save_state()` and passed to
+/// `Teehistorian.resume()`. Can be stored with `to_bytes()` or pickled.
+#[pyclass(name = "ParserState", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyParserState {
+    pub offset: usize,
+    pub chunks_start: usize,
+    pub ticks: TickTracker,
+    pub chunk_count: usize,
+    pub uuids: Vec<String>,
+    /// Protocol of every client, for decoding its messages
+    pub versions: ClientVersions,
+    /// Client inputs, if the parser resolved inputs
+    pub inputs: Option<InputResolver>,
+    /// Character positions, if the parser resolved positions
+    pub positions: Option<PositionResolver>,
+}
+
+#[pymethods]
+impl PyParserState {
+    /// Byte offset of the next chunk to read
+    #[getter]
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Tick of the last chunk read before the snapshot
+    #[getter]
+    fn tick(&self) -> i32 {
+        self.ticks.tick()
+    }
+
+    /// Number of chunks read before the snapshot
+    #[getter]
+    fn chunk_count(&self) -> usize {
+        self.chunk_count
+    }
+
+    /// Custom UUIDs registered on the parser
+    #[getter]
+    fn registered_uuids(&self) -> Vec<String> {
+        self.uuids.clone()
+    }
+
+    /// Serialize the state for storage
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        let value = json!({
+            "version": STATE_VERSION,
+            "offset": self.offset,
+            "chunks_start": self.chunks_start,
+            "tick": self.ticks.tick(),
+            "last_player_cid": self.ticks.last_player_cid(),
+            "chunk_count": self.chunk_count,
+            "uuids": self.uuids,
+            "versions": self.versions.names(),
+            "inputs": self.inputs.as_ref().map(InputResolver::inputs),
+            "positions": self.positions.as_ref().map(PositionResolver::spawned),
+        });
+        PyBytes::new(py, value.to_string().as_bytes())
+    }
+
+    /// Restore a state serialized with `to_bytes()`
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        Ok(Self::decode(data)?)
+    }
+
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyAny>, (Bound<'py, PyBytes>,))> {
+        let from_bytes = slf.get_type().getattr("from_bytes")?;
+        Ok((from_bytes, (slf.get().to_bytes(slf.py()),)))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParserState(offset={}, tick={}, chunk_count={})",
+            self.offset,
+            self.ticks.tick(),
+            self.chunk_count
+        )
+    }
+}
+
+impl PyParserState {
+    fn decode(data: &[u8]) -> Result<Self, TeehistorianParseError> {
+        let invalid = |what: &str| {
+            TeehistorianParseError::Validation(format!("Invalid parser state: {}", what))
+        };
+
+        let value: Value = serde_json::from_slice(data).map_err(|e| invalid(&e.to_string()))?;
+        if value.get("version").and_then(Value::as_u64) != Some(STATE_VERSION) {
+            return Err(invalid("unsupported version"));
+        }
+
+        let usize_field = |name: &str| {
+            value
+                .get(name)
+                .and_then(Value::as_u64)
+                .map(|v| v as usize)
+                .ok_or_else(|| invalid(&format!("missing {}", name)))
+        };
+        let i32_field = |name: &str| {
+            value
+                .get(name)
+                .and_then(Value::as_i64)
+                .and_then(|v| i32::try_from(v).ok())
+                .ok_or_else(|| invalid(&format!("missing {}", name)))
+        };
+
+        let uuids = value
+            .get("uuids")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("missing uuids"))?
+            .iter()
+            .map(|uuid| {
+                uuid.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| invalid("bad uuid"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Missing in states saved before protocols and resolvers were part of the state
+        let versions = value
+            .get("versions")
+            .cloned()
+            .unwrap_or(Value::Array(Vec::new()));
+        let versions = serde_json::from_value::<Vec<(i32, String)>>(versions)
+            .map_err(|_| invalid("bad versions"))?
+            .into_iter()
+            .map(|(cid, name)| Some((cid, NetVersion::from_name(&name)?)))
+            .collect::<Option<ClientVersions>>()
+            .ok_or_else(|| invalid("bad versions"))?;
+        let inputs = value.get("inputs").cloned().unwrap_or(Value::Null);
+        let inputs = serde_json::from_value::<Option<Vec<(i32, [i32; 10])>>>(inputs)
+            .map_err(|_| invalid("bad inputs"))?
+            .map(InputResolver::from_iter);
+        let positions = value.get("positions").cloned().unwrap_or(Value::Null);
+        let positions = serde_json::from_value::<Option<Vec<(i32, [i32; 2])>>>(positions)
+            .map_err(|_| invalid("bad positions"))?
+            .map(PositionResolver::from_iter);
+
+        Ok(Self {
+            offset: usize_field("offset")?,
+            chunks_start: usize_field("chunks_start")?,
+            ticks: TickTracker::from_parts(i32_field("tick")?, i32_field("last_player_cid")?),
+            chunk_count: usize_field("chunk_count")?,
+            uuids,
+            versions,
+            inputs,
+            positions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(PyParserState::decode(b"not json").is_err());
+        assert!(PyParserState::decode(br#"{"version": 99}"#).is_err());
+        assert!(PyParserState::decode(br#"{"version": 1, "offset": 3}"#).is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        let state = PyParserState::decode(
+            br#"{"version": 1, "offset": 40, "chunks_start": 30, "tick": 7,
+                 "last_player_cid": 2, "chunk_count": 5, "uuids": ["a"]}"#,
+        )
+        .unwrap();
+        assert_eq!(state.offset, 40);
+        assert_eq!(state.ticks, TickTracker::from_parts(7, 2));
+        assert_eq!(state.uuids, vec!["a".to_string()]);
+        assert_eq!(state.versions, ClientVersions::default());
+        assert_eq!(state.inputs, None);
+        assert_eq!(state.positions, None);
+    }
+
+    #[test]
+    fn test_decode_resolvers() {
+        let state = PyParserState::decode(
+            br#"{"version": 1, "offset": 40, "chunks_start": 30, "tick": 7,
+                 "last_player_cid": 2, "chunk_count": 5, "uuids": [],
+                 "versions": [[2, "0.7"]],
+                 "inputs": [[2, [1, 0, 0, 0, 0, 0, 0, 0, 0, 5]]],
+                 "positions": [[2, [160, -32]]]}"#,
+        )
+        .unwrap();
+        assert_eq!(state.versions.get(2), NetVersion::V07);
+        let inputs = state.inputs.unwrap();
+        assert_eq!(inputs.input(2), Some(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 5]));
+        let positions = state.positions.unwrap();
+        assert_eq!(positions.spawned(), vec![(2, [160, -32])]);
+
+        assert!(
+            PyParserState::decode(
+                br#"{"version": 1, "offset": 40, "chunks_start": 30, "tick": 7,
+                     "last_player_cid": 2, "chunk_count": 5, "uuids": [],
+                     "positions": [[2, "far away"]]}"#,
+            )
+            .is_err()
+        );
+    }
+}
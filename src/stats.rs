@@ -0,0 +1,123 @@
+//! Whole-file summary statistics
+//!
+//! `ParserStats` is filled by scanning every chunk in Rust, so summarizing a
+//! recording never creates per-chunk Python objects.
+use std::collections::{BTreeMap, BTreeSet};
+
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+use crate::filter::chunk_cids;
+use crate::ticks::TICK_SPEED;
+
+/// Summary of a whole teehistorian file
+///
+/// Obtained from `Teehistorian.stats()`.
+#[pyclass(name = "ParserStats", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyParserStats {
+    counts: BTreeMap<&'static str, usize>,
+    total_chunks: usize,
+    first_tick: Option<i32>,
+    last_tick: Option<i32>,
+    player_ids: BTreeSet<i32>,
+}
+
+impl PyParserStats {
+    /// Account for the header of a concatenated stream
+    pub fn observe_segment(&mut self) {
+        *self.counts.entry("SegmentStart").or_default() += 1;
+        self.total_chunks += 1;
+    }
+
+    /// Account for one chunk of type `type_name` at `tick`
+    pub fn observe(&mut self, chunk: &Chunk, tick: i32, type_name: &'static str) {
+        *self.counts.entry(type_name).or_default() += 1;
+        self.total_chunks += 1;
+        self.first_tick.get_or_insert(tick);
+        self.last_tick = Some(tick);
+        self.player_ids.extend(
+            chunk_cids(chunk)
+                .into_iter()
+                .flatten()
+                .filter(|cid| *cid >= 0),
+        );
+    }
+}
+
+#[pymethods]
+impl PyParserStats {
+    /// Number of chunks per `chunk_type()` name
+    #[getter]
+    fn chunk_counts(&self) -> BTreeMap<&'static str, usize> {
+        self.counts.clone()
+    }
+
+    /// Total number of chunks in the file
+    #[getter]
+    fn total_chunks(&self) -> usize {
+        self.total_chunks
+    }
+
+    /// Tick of the first chunk, `None` for a file without chunks
+    #[getter]
+    fn first_tick(&self) -> Option<i32> {
+        self.first_tick
+    }
+
+    /// Tick of the last chunk, `None` for a file without chunks
+    #[getter]
+    fn last_tick(&self) -> Option<i32> {
+        self.last_tick
+    }
+
+    /// Sorted client IDs that appear in any chunk
+    #[getter]
+    fn player_ids(&self) -> Vec<i32> {
+        self.player_ids.iter().copied().collect()
+    }
+
+    /// Time covered by the recording in seconds
+    #[getter]
+    fn duration(&self) -> f64 {
+        match (self.first_tick, self.last_tick) {
+            (Some(first), Some(last)) => f64::from(last - first) / f64::from(TICK_SPEED),
+            _ => 0.0,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParserStats(total_chunks={}, ticks={}..{}, players={})",
+            self.total_chunks,
+            self.first_tick.unwrap_or(0),
+            self.last_tick.unwrap_or(0),
+            self.player_ids.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe() {
+        let mut stats = PyParserStats::default();
+        assert_eq!(stats.duration(), 0.0);
+
+        stats.observe(&Chunk::Join { cid: 3 }, 0, "Join");
+        stats.observe(&Chunk::TickSkip { dt: 99 }, 100, "TickSkip");
+        stats.observe(&Chunk::PlayerSwap { cid1: 1, cid2: 3 }, 100, "PlayerSwap");
+        stats.observe(&Chunk::Join { cid: 5 }, 100, "Join");
+
+        assert_eq!(stats.total_chunks(), 4);
+        assert_eq!(stats.chunk_counts()["Join"], 2);
+        assert_eq!(stats.player_ids(), vec![1, 3, 5]);
+        assert_eq!(
+            (stats.first_tick(), stats.last_tick()),
+            (Some(0), Some(100))
+        );
+        assert_eq!(stats.duration(), 2.0);
+    }
+}
@@ -0,0 +1,115 @@
+use teehistorian::Chunk;
+
+/// Server ticks per second
+pub const TICK_SPEED: i32 = 50;
+
+/// Tracks the game tick while walking a chunk stream
+///
+/// Teehistorian files do not store the tick of every chunk. A `TickSkip { dt }`
+/// chunk advances the tick by `dt + 1`, and a player chunk (`PlayerDiff`,
+/// `PlayerNew`, `PlayerOld`) whose client ID is not greater than the previous
+/// player chunk's implicitly starts the next tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickTracker {
+    tick: i32,
+    last_player_cid: i32,
+}
+
+impl Default for TickTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TickTracker {
+    /// Create a tracker positioned before the first chunk of a file
+    pub fn new() -> Self {
+        Self {
+            tick: 0,
+            // The first player chunk of a file always opens a new tick
+            last_player_cid: i32::MAX,
+        }
+    }
+
+    /// Restore a tracker from the values of `tick()` and `last_player_cid()`
+    pub fn from_parts(tick: i32, last_player_cid: i32) -> Self {
+        Self {
+            tick,
+            last_player_cid,
+        }
+    }
+
+    /// Continue with a concatenated stream, keeping the tick count monotonic
+    ///
+    /// The first player chunk of the new stream opens a new tick, as it does
+    /// at the start of a file.
+    pub fn restart(&mut self) {
+        self.last_player_cid = i32::MAX;
+    }
+
+    /// Get the tick of the most recently observed chunk
+    pub fn tick(&self) -> i32 {
+        self.tick
+    }
+
+    /// Get the client ID of the most recent player chunk in the current tick
+    pub fn last_player_cid(&self) -> i32 {
+        self.last_player_cid
+    }
+
+    /// Advance past `chunk` and return the tick it belongs to
+    pub fn observe(&mut self, chunk: &Chunk) -> i32 {
+        match *chunk {
+            Chunk::TickSkip { dt } => {
+                self.tick = self.tick.saturating_add(dt).saturating_add(1);
+                self.last_player_cid = -1;
+            }
+            Chunk::PlayerDiff(ref diff) => self.observe_player(diff.cid),
+            Chunk::PlayerNew(ref player) => self.observe_player(player.cid),
+            Chunk::PlayerOld { cid } => self.observe_player(cid),
+            _ => {}
+        }
+        self.tick
+    }
+
+    fn observe_player(&mut self, cid: i32) {
+        if cid <= self.last_player_cid {
+            self.tick = self.tick.saturating_add(1);
+        }
+        self.last_player_cid = cid;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teehistorian::chunks::{PlayerDiff, PlayerNew};
+
+    #[test]
+    fn test_tick_skip_advances() {
+        let mut ticks = TickTracker::new();
+        assert_eq!(ticks.observe(&Chunk::TickSkip { dt: 0 }), 1);
+        assert_eq!(ticks.observe(&Chunk::TickSkip { dt: 9 }), 11);
+        assert_eq!(ticks.observe(&Chunk::Join { cid: 3 }), 11);
+    }
+
+    #[test]
+    fn test_implicit_tick_on_player_order() {
+        let mut ticks = TickTracker::new();
+        let new = |cid| Chunk::PlayerNew(PlayerNew { cid, x: 0, y: 0 });
+        let diff = |cid| Chunk::PlayerDiff(PlayerDiff { cid, dx: 1, dy: 1 });
+
+        assert_eq!(ticks.observe(&new(0)), 1);
+        assert_eq!(ticks.observe(&new(4)), 1);
+        // Client IDs restart, so a new tick began
+        assert_eq!(ticks.observe(&diff(0)), 2);
+        assert_eq!(ticks.observe(&diff(4)), 2);
+        assert_eq!(ticks.observe(&diff(4)), 3);
+        // An explicit tick skip resets the ordering
+        assert_eq!(ticks.observe(&Chunk::TickSkip { dt: 2 }), 6);
+        assert_eq!(ticks.observe(&diff(0)), 6);
+        // A concatenated stream starts a new tick on its first player chunk
+        ticks.restart();
+        assert_eq!(ticks.observe(&diff(5)), 7);
+    }
+}
@@ -0,0 +1,294 @@
+//! Character positions over the whole recording
+//!
+//! Positions are only written when a character moves, mostly as diffs to
+//! its previous position. The tracker resolves them once in Rust and keeps
+//! the absolute position of every character from each change on, so the
+//! world can be looked at any tick without replaying the recording.
+use std::collections::BTreeMap;
+
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+use crate::resolve::PositionResolver;
+use crate::walk_recording;
+
+/// Position of a character from a tick on, None once it is no longer spawned
+type Change = (i32, Option<[i32; 2]>);
+
+/// Positions of the spawned characters by client ID
+type World = BTreeMap<i32, (i32, i32)>;
+
+/// `(tick, vx, vy, ax, ay)` of a character
+type Motion = (i32, i32, i32, i32, i32);
+
+/// Position and velocity of a character at a tick
+#[derive(Debug, Clone, Copy)]
+struct Kinematics {
+    tick: i32,
+    position: [i32; 2],
+    velocity: [i32; 2],
+}
+
+impl Kinematics {
+    /// Move on to the next tick, at which the character is at `position`
+    fn step(&mut self, position: [i32; 2]) -> Motion {
+        let velocity = [
+            position[0].wrapping_sub(self.position[0]),
+            position[1].wrapping_sub(self.position[1]),
+        ];
+        let acceleration = [
+            velocity[0].wrapping_sub(self.velocity[0]),
+            velocity[1].wrapping_sub(self.velocity[1]),
+        ];
+        *self = Self {
+            tick: self.tick + 1,
+            position,
+            velocity,
+        };
+        (
+            self.tick,
+            velocity[0],
+            velocity[1],
+            acceleration[0],
+            acceleration[1],
+        )
+    }
+}
+
+/// Positions of every character over a recording
+#[pyclass(name = "PlayerTracker", module = "teehistorian_py", frozen)]
+#[derive(Debug, Default)]
+pub struct PyPlayerTracker {
+    /// Position changes of every client ID, ordered by tick; a character
+    /// that moves and despawns in the same tick has two changes in it
+    changes: BTreeMap<i32, Vec<Change>>,
+    /// Every tick at which a position changed, in order
+    ticks: Vec<i32>,
+}
+
+impl PyPlayerTracker {
+    fn record(&mut self, cid: i32, tick: i32, position: Option<[i32; 2]>) {
+        let changes = self.changes.entry(cid).or_default();
+        if changes.last().is_some_and(|&(_, last)| last == position) {
+            return;
+        }
+        changes.push((tick, position));
+        if self.ticks.last() != Some(&tick) {
+            self.ticks.push(tick);
+        }
+    }
+
+    /// Track one chunk
+    fn observe(&mut self, positions: &mut PositionResolver, chunk: &Chunk, tick: i32) {
+        let despawned = match *chunk {
+            Chunk::PlayerOld { cid } => cid,
+            Chunk::Drop(ref drop) => drop.cid,
+            _ => {
+                if let Some((cid, x, y)) = positions.resolve(chunk) {
+                    self.record(cid, tick, Some([x, y]));
+                }
+                return;
+            }
+        };
+        if positions.position(despawned).is_some() {
+            positions.resolve(&Chunk::PlayerOld { cid: despawned });
+            self.record(despawned, tick, None);
+        }
+    }
+
+    /// Forget the characters of the previous stream
+    fn restart(&mut self, positions: &mut PositionResolver, tick: i32) {
+        for (cid, _) in positions.spawned() {
+            self.record(cid, tick, None);
+        }
+        *positions = PositionResolver::new();
+    }
+
+    /// Spawned characters with their positions at `tick`
+    fn world_at(&self, tick: i32) -> World {
+        self.changes
+            .iter()
+            .filter_map(|(&cid, changes)| {
+                let index = changes.partition_point(|&(t, _)| t <= tick);
+                let [x, y] = changes[..index].last()?.1?;
+                Some((cid, (x, y)))
+            })
+            .collect()
+    }
+}
+
+#[pymethods]
+impl PyPlayerTracker {
+    /// Track the characters of a recording
+    ///
+    /// Positions are resolved in a single pass with the GIL released.
+    ///
+    /// # Arguments
+    /// * `data` - Raw or compressed teehistorian data
+    ///
+    /// # Example
+    /// ```python
+    /// tracker = th.PlayerTracker(data)
+    /// for cid, (x, y) in tracker.positions_at(9000).items():
+    ///     print(cid, x / 32, y / 32)
+    /// ```
+    #[new]
+    fn new(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        let mut tracker = Self::default();
+        let mut positions = PositionResolver::new();
+        walk_recording(py, data, |chunk, tick| match chunk {
+            Some(chunk) => tracker.observe(&mut positions, &chunk, tick),
+            None => tracker.restart(&mut positions, tick),
+        })?;
+        Ok(tracker)
+    }
+
+    /// Positions of the characters spawned at `tick`, by client ID
+    ///
+    /// A character keeps its last position until it moves again.
+    fn positions_at(&self, tick: i32) -> World {
+        self.world_at(tick)
+    }
+
+    /// Every position of one character as `(tick, x, y)` tuples
+    ///
+    /// Only ticks at which the character moved or spawned are included.
+    fn trajectory(&self, cid: i32) -> Vec<(i32, i32, i32)> {
+        self.changes.get(&cid).map_or_else(Vec::new, |changes| {
+            changes
+                .iter()
+                .filter_map(|&(tick, position)| position.map(|[x, y]| (tick, x, y)))
+                .collect()
+        })
+    }
+
+    /// Velocity and acceleration of one character at every tick
+    ///
+    /// Returns `(tick, vx, vy, ax, ay)` tuples in world units per tick and
+    /// per tick squared, from the tick after each spawn to the last tick
+    /// the character was spawned. A character is at rest when it spawns and
+    /// between the ticks at which it moved.
+    fn kinematics(&self, cid: i32) -> Vec<Motion> {
+        let mut series = Vec::new();
+        let mut life: Option<Kinematics> = None;
+        for &(tick, position) in self.changes.get(&cid).into_iter().flatten() {
+            let Some(state) = &mut life else {
+                life = position.map(|position| Kinematics {
+                    tick,
+                    position,
+                    velocity: [0, 0],
+                });
+                continue;
+            };
+            while state.tick + 1 < tick {
+                series.push(state.step(state.position));
+            }
+            match position {
+                Some(position) if state.tick < tick => series.push(state.step(position)),
+                Some(_) => {}
+                None => life = None,
+            }
+        }
+        series
+    }
+
+    /// Iterate over `(tick, positions)` for every tick at which a character
+    /// moved, spawned or despawned
+    ///
+    /// `positions` is the whole world at that tick, as from `positions_at()`.
+    fn snapshots(slf: Py<Self>) -> PySnapshotIterator {
+        PySnapshotIterator {
+            tracker: slf,
+            index: 0,
+        }
+    }
+
+    /// Client IDs of every character that was spawned
+    #[getter]
+    fn client_ids(&self) -> Vec<i32> {
+        self.changes.keys().copied().collect()
+    }
+
+    /// Every tick at which a character moved, spawned or despawned
+    #[getter]
+    fn ticks(&self) -> Vec<i32> {
+        self.ticks.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PlayerTracker(client_ids={:?}, ticks={})",
+            self.client_ids(),
+            self.ticks.len()
+        )
+    }
+}
+
+/// Iterator over the world snapshots of a `PlayerTracker`
+#[pyclass(name = "SnapshotIterator", module = "teehistorian_py")]
+pub struct PySnapshotIterator {
+    tracker: Py<PyPlayerTracker>,
+    index: usize,
+}
+
+#[pymethods]
+impl PySnapshotIterator {
+    /// Python iterator protocol support
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    /// Get the tick and positions of the next snapshot
+    fn __next__(&mut self) -> Option<(i32, World)> {
+        let tracker = self.tracker.get();
+        let &tick = tracker.ticks.get(self.index)?;
+        self.index += 1;
+        Some((tick, tracker.world_at(tick)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use teehistorian::chunks::{PlayerDiff, PlayerNew};
+
+    use super::*;
+
+    #[test]
+    fn test_tracker() {
+        let mut tracker = PyPlayerTracker::default();
+        let mut positions = PositionResolver::new();
+        let new = |cid, x, y| Chunk::PlayerNew(PlayerNew { cid, x, y });
+        tracker.observe(&mut positions, &new(0, 10, 20), 1);
+        tracker.observe(&mut positions, &new(1, 5, 5), 1);
+        let diff = Chunk::PlayerDiff(PlayerDiff {
+            cid: 0,
+            dx: 1,
+            dy: -1,
+        });
+        tracker.observe(&mut positions, &diff, 3);
+        tracker.observe(&mut positions, &Chunk::PlayerOld { cid: 1 }, 4);
+        // Already despawned, not a change
+        tracker.observe(&mut positions, &Chunk::PlayerOld { cid: 1 }, 5);
+        tracker.restart(&mut positions, 6);
+
+        assert_eq!(tracker.ticks, [1, 3, 4, 6]);
+        assert_eq!(tracker.world_at(0), BTreeMap::new());
+        assert_eq!(
+            tracker.world_at(2),
+            BTreeMap::from([(0, (10, 20)), (1, (5, 5))])
+        );
+        assert_eq!(tracker.world_at(5), BTreeMap::from([(0, (11, 19))]));
+        assert_eq!(tracker.world_at(6), BTreeMap::new());
+        assert_eq!(tracker.trajectory(0), [(1, 10, 20), (3, 11, 19)]);
+        assert_eq!(
+            tracker.kinematics(0),
+            [
+                (2, 0, 0, 0, 0),
+                (3, 1, -1, 1, -1),
+                (4, 0, 0, -1, 1),
+                (5, 0, 0, 0, 0)
+            ]
+        );
+        assert_eq!(tracker.kinematics(1), [(2, 0, 0, 0, 0), (3, 0, 0, 0, 0)]);
+    }
+}
@@ -0,0 +1,466 @@
+//! Rewriting recordings chunk by chunk inside Rust
+//!
+//! `transform()` walks the chunks of a recording and copies the ones it keeps
+//! straight from their encoded bytes into a writer, so rewriting a large file
+//! creates no Python object per chunk. Only chunks handed to a map function
+//! are converted. `TickSkip` chunks are not copied: the writer emits the ones
+//! needed to put every written chunk at its original tick, so dropping chunks
+//! never shifts the ticks of the ones after them.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde_json::Value;
+use teehistorian::Chunk;
+
+use crate::chunks::encode;
+use crate::errors::TeehistorianParseError;
+use crate::filter::resolve_chunk_type;
+use crate::handlers::{ChunkConverter, UuidHandler, category_chunk_types};
+use crate::header::PyHeader;
+use crate::writer::PyTeehistorianWriter;
+use crate::{MAGIC, PyTeehistorian, TeehistorianParserInner};
+
+/// Item of a recording handed to a rewrite
+pub enum Item<'a> {
+    /// Header of a recording, handed out before its chunks
+    Header(&'a mut Value),
+    /// Header of a concatenated stream, without the magic UUID
+    Segment(&'a [u8]),
+    /// Chunk with its tick
+    Chunk(Chunk<'a>, i32),
+    /// End of the last recording, handed out last
+    End,
+}
+
+/// Writer the output of a rewrite goes to
+pub struct Rewriter<'py> {
+    py: Python<'py>,
+    writer: PyTeehistorianWriter,
+    none: Bound<'py, PyAny>,
+}
+
+impl<'py> Rewriter<'py> {
+    /// Writer for a recording with the given header
+    pub fn new(py: Python<'py>, header: Value) -> Self {
+        let mut writer = PyTeehistorianWriter::new();
+        writer.load_header(header);
+        Self {
+            py,
+            writer,
+            none: py.None().into_bound(py),
+        }
+    }
+
+    /// Everything written so far
+    pub fn contents(&mut self) -> PyResult<Vec<u8>> {
+        self.writer.contents()
+    }
+
+    /// Write encoded bytes as they are, at `tick` if given
+    pub fn copy(&mut self, encoded: &[u8], tick: Option<i32>) -> PyResult<()> {
+        self.writer
+            .write_encoded(self.py, &self.none, encoded.to_vec(), tick)
+    }
+
+    /// Encode and write a chunk at `tick`
+    pub fn write(&mut self, chunk: &Chunk, tick: i32) -> PyResult<()> {
+        self.writer
+            .write_encoded(self.py, &self.none, encode(chunk)?, Some(tick))
+    }
+
+    /// Write a chunk object through its `write_to_buffer()` at `tick`
+    pub fn write_object(&mut self, chunk: &Bound<'py, PyAny>, tick: i32) -> PyResult<()> {
+        self.writer.write(self.py, chunk, Some(tick))
+    }
+
+    /// Start a concatenated stream with the given header
+    pub fn write_segment(&mut self, header: &Value) -> PyResult<()> {
+        let header = serde_json::to_vec(header).map_err(|e| {
+            TeehistorianParseError::Write(format!("Failed to serialize header: {}", e))
+        })?;
+        let mut encoded = Vec::with_capacity(MAGIC.len() + header.len() + 1);
+        encoded.extend_from_slice(&MAGIC);
+        encoded.extend_from_slice(&header);
+        encoded.push(0);
+        self.copy(&encoded, None)
+    }
+}
+
+/// Player chunks of the current tick, written ordered by client ID
+///
+/// Rewrites that change client IDs can change the order of the player
+/// chunks of a tick, which must be ascending or a chunk would open the next
+/// tick.
+#[derive(Debug, Default)]
+pub struct PlayerChunks {
+    tick: i32,
+    chunks: Vec<(i32, Vec<u8>)>,
+}
+
+impl PlayerChunks {
+    /// Write a chunk at `tick`, holding back player chunks until their tick
+    /// is complete
+    pub fn write(
+        &mut self,
+        out: &mut Rewriter,
+        chunk: &Chunk,
+        encoded: Vec<u8>,
+        tick: i32,
+    ) -> PyResult<()> {
+        let cid = match *chunk {
+            Chunk::PlayerDiff(_) | Chunk::PlayerNew(_) | Chunk::PlayerOld { .. } => chunk.cid(),
+            _ => None,
+        };
+        if cid.is_none() || tick != self.tick {
+            self.flush(out)?;
+        }
+        match cid {
+            Some(cid) => {
+                self.tick = tick;
+                self.chunks.push((cid, encoded));
+                Ok(())
+            }
+            None => out.copy(&encoded, Some(tick)),
+        }
+    }
+
+    /// Write the player chunks held back, ordered by client ID
+    pub fn flush(&mut self, out: &mut Rewriter) -> PyResult<()> {
+        self.chunks.sort_by_key(|&(cid, _)| cid);
+        for (_, encoded) in self.chunks.drain(..) {
+            out.copy(&encoded, Some(self.tick))?;
+        }
+        Ok(())
+    }
+}
+
+/// Rewrite a recording item by item
+///
+/// `rewrite` may change the header when it is handed out first. After that
+/// it gets every item with its encoded bytes and writes whatever should take
+/// its place to the `Rewriter`, nothing to drop it. `TickSkip` chunks are
+/// never handed out; writing chunks at their tick emits the ones that are
+/// needed.
+pub fn rewrite<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    rewrite: impl FnMut(&mut Rewriter<'py>, Item<'_>, &[u8], &ChunkConverter) -> PyResult<()>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    rewrite_all(py, &[data], rewrite)
+}
+
+/// Rewrite several recordings into one, item by item
+///
+/// Works like `rewrite()` with the items of every recording in turn. Each
+/// recording starts with its header, the first one becomes the header of
+/// the output. Ticks are counted from zero in every recording.
+pub fn rewrite_all<'py>(
+    py: Python<'py>,
+    inputs: &[&[u8]],
+    mut rewrite: impl FnMut(&mut Rewriter<'py>, Item<'_>, &[u8], &ChunkConverter) -> PyResult<()>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut rewriter = Rewriter::new(py, Value::Null);
+    for (i, data) in inputs.iter().enumerate() {
+        let mut parser = PyTeehistorian::new(data, false, None, false, false, false, "yield")?;
+        let handlers = Arc::clone(&parser.handlers);
+
+        let mut header = PyHeader::parse(parser.inner.get_header())?.to_json();
+        let converter = ChunkConverter::new(&handlers);
+        rewrite(&mut rewriter, Item::Header(&mut header), &[], &converter)?;
+        if i == 0 {
+            rewriter.writer.load_header(header);
+        }
+        rewrite_chunks(&mut parser, &mut rewriter, &handlers, &mut rewrite)?;
+        if i == inputs.len() - 1 {
+            rewrite(&mut rewriter, Item::End, &[], &converter)?;
+        }
+    }
+    Ok(PyBytes::new(py, &rewriter.contents()?))
+}
+
+/// Hand the items after the header of a parser to `rewrite`
+///
+/// The converter handed out with a chunk parses messages with the protocol
+/// of their client, like iterating the parser does.
+fn rewrite_chunks<'py>(
+    parser: &mut PyTeehistorian,
+    rewriter: &mut Rewriter<'py>,
+    handlers: &Arc<HashMap<String, UuidHandler>>,
+    rewrite: &mut impl FnMut(&mut Rewriter<'py>, Item<'_>, &[u8], &ChunkConverter) -> PyResult<()>,
+) -> PyResult<()> {
+    loop {
+        let start = parser.inner.offset();
+        if parser.enter_segment()?.is_some() {
+            let encoded = &parser.inner.data[start..parser.inner.offset()];
+            let header = &encoded[MAGIC.len()..encoded.len() - 1];
+            let converter = ChunkConverter::with_versions(handlers, &parser.versions);
+            rewrite(rewriter, Item::Segment(header), encoded, &converter)?;
+            continue;
+        }
+
+        let Some((chunk, end)) = decode_at(&parser.inner.data, start)? else {
+            break;
+        };
+        parser.inner.offset = end;
+        let tick = parser.ticks.observe(&chunk);
+        parser.versions.observe(&chunk);
+        if matches!(chunk, Chunk::TickSkip { .. }) {
+            continue;
+        }
+        let encoded = &parser.inner.data[start..end];
+        let converter = ChunkConverter::with_versions(handlers, &parser.versions);
+        rewrite(rewriter, Item::Chunk(chunk, tick), encoded, &converter)?;
+    }
+    Ok(())
+}
+
+/// Decode the chunk at `start`, returning it with the offset after it
+fn decode_at(data: &[u8], start: usize) -> PyResult<Option<(Chunk<'_>, usize)>> {
+    TeehistorianParserInner::decode_at(data, start).map_err(|e| {
+        TeehistorianParseError::Parse(format!("Failed to parse chunk at offset {}: {}", start, e))
+            .into()
+    })
+}
+
+/// Chunks returned by a map function: a chunk, an iterable of chunks or None
+fn returned_chunks<'py>(returned: Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    if returned.is_none() {
+        Ok(Vec::new())
+    } else if returned.hasattr("write_to_buffer")? {
+        Ok(vec![returned])
+    } else {
+        returned.try_iter()?.collect()
+    }
+}
+
+/// Chunk types a transform keeps, and the ones it hands to the map function
+#[derive(Debug, Clone, Default)]
+struct Selection {
+    keep: Option<Vec<&'static str>>,
+    drop: Vec<&'static str>,
+    map_types: Option<Vec<&'static str>>,
+}
+
+impl Selection {
+    fn keeps(&self, name: &str) -> bool {
+        self.keep.as_ref().is_none_or(|keep| keep.contains(&name)) && !self.drop.contains(&name)
+    }
+
+    fn maps(&self, name: &str) -> bool {
+        self.map_types
+            .as_ref()
+            .is_none_or(|types| types.contains(&name))
+    }
+}
+
+/// Resolve chunk types given as classes or names
+fn resolve_types(types: Option<Vec<Bound<'_, PyAny>>>) -> PyResult<Option<Vec<&'static str>>> {
+    types
+        .map(|types| types.iter().map(resolve_chunk_type).collect())
+        .transpose()
+}
+
+/// Rewrite a recording, keeping, dropping or mapping chunks by type
+///
+/// Chunks are streamed from the parser into a writer without leaving Rust;
+/// only the chunks `map_fn` is called for are converted to Python objects.
+/// `map_fn` returns the chunk to write instead, a list of chunks or None to
+/// drop it. Every written chunk keeps its tick, the needed `TickSkip` chunks
+/// are generated. The header is copied and the result is uncompressed.
+///
+/// `Eos` is always kept, so the result stays a complete recording. Without
+/// `SegmentStart` the streams of a concatenated file become one and only
+/// the last `Eos` is kept.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `keep` - Only keep chunks of these types, classes or names
+/// * `drop` - Drop chunks of these types
+/// * `map_fn` - Called with every kept chunk of `map_types`
+/// * `map_types` - Types `map_fn` is called for, all kept chunks if None
+///
+/// # Example
+/// ```python
+/// data = th.transform(data, drop=[th.InputNew, th.InputDiff])
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, *, keep=None, drop=None, map_fn=None, map_types=None))]
+pub fn transform<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    keep: Option<Vec<Bound<'py, PyAny>>>,
+    drop: Option<Vec<Bound<'py, PyAny>>>,
+    map_fn: Option<Bound<'py, PyAny>>,
+    map_types: Option<Vec<Bound<'py, PyAny>>>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let selection = Selection {
+        keep: resolve_types(keep)?,
+        drop: resolve_types(drop)?.unwrap_or_default(),
+        map_types: resolve_types(map_types)?,
+    };
+    // `Eos` of the current stream, written once it is known what follows
+    let mut eos: Option<Vec<u8>> = None;
+    rewrite(py, data, |out, item, encoded, converter| {
+        let (chunk, tick) = match item {
+            Item::Segment(_) if selection.keeps("SegmentStart") => {
+                if let Some(eos) = eos.take() {
+                    out.copy(&eos, None)?;
+                }
+                return out.copy(encoded, None);
+            }
+            Item::Segment(_) => {
+                eos = None;
+                return Ok(());
+            }
+            Item::End => match eos.take() {
+                Some(eos) => return out.copy(&eos, None),
+                None => return Ok(()),
+            },
+            Item::Header(_) => return Ok(()),
+            Item::Chunk(Chunk::Eos, _) => {
+                eos = Some(encoded.to_vec());
+                return Ok(());
+            }
+            Item::Chunk(chunk, tick) => (chunk, tick),
+        };
+        let name = converter.type_name(&chunk);
+        if !selection.keeps(name) {
+            return Ok(());
+        }
+        match &map_fn {
+            Some(map_fn) if selection.maps(name) => {
+                let mapped = map_fn.call1((converter.convert(py, chunk, tick)?,))?;
+                for chunk in returned_chunks(mapped)? {
+                    out.write_object(&chunk, tick)?;
+                }
+                Ok(())
+            }
+            _ => out.copy(encoded, Some(tick)),
+        }
+    })
+}
+
+/// Replace the chunks a predicate selects, keeping every other byte
+///
+/// `predicate` is called with every chunk, including `TickSkip`. For the
+/// chunks it returns true for, `replacement_fn` returns the chunk to put in
+/// its place, a list of chunks or None to remove it. The header and all
+/// other chunks are copied byte for byte and no `TickSkip` is added, so a
+/// replacement moving a player chunk to another client can change ticks.
+/// The result is uncompressed.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `predicate` - Called with every chunk, selects the ones to replace
+/// * `replacement_fn` - Called with every selected chunk
+///
+/// # Example
+/// ```python
+/// data = th.patch(
+///     data,
+///     lambda c: isinstance(c, th.PlayerName) and c.name == "nameless tee",
+///     lambda c: th.PlayerName(c.client_id, "brainless tee"),
+/// )
+/// ```
+#[pyfunction]
+pub fn patch<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    predicate: Bound<'py, PyAny>,
+    replacement_fn: Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut parser = PyTeehistorian::new(data, false, None, false, false, false, "yield")?;
+    let handlers = Arc::clone(&parser.handlers);
+    let mut patched = Vec::with_capacity(parser.inner.data.len());
+    let mut copied = 0;
+    loop {
+        if parser.enter_segment()?.is_some() {
+            continue;
+        }
+        let start = parser.inner.offset();
+        let Some((chunk, end)) = decode_at(&parser.inner.data, start)? else {
+            break;
+        };
+        parser.inner.offset = end;
+        let tick = parser.ticks.observe(&chunk);
+        parser.versions.observe(&chunk);
+        let converter = ChunkConverter::with_versions(&handlers, &parser.versions);
+        let chunk = converter.convert(py, chunk, tick)?;
+        if !predicate.call1((&chunk,))?.is_truthy()? {
+            continue;
+        }
+        patched.extend_from_slice(&parser.inner.data[copied..start]);
+        copied = end;
+        for chunk in returned_chunks(replacement_fn.call1((chunk,))?)? {
+            let encoded: Vec<u8> = chunk.call_method0("write_to_buffer")?.extract()?;
+            patched.extend_from_slice(&encoded);
+        }
+    }
+    patched.extend_from_slice(&parser.inner.data[copied..]);
+    Ok(PyBytes::new(py, &patched))
+}
+
+/// Remove whole categories of chunks from a recording
+///
+/// `Eos` and the headers of concatenated streams are kept in any case, so
+/// the result is a complete recording of the same length. The remaining
+/// chunks keep their ticks, the needed `TickSkip` chunks are generated.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `categories` - Names of the categories to remove, see `categories()`
+///
+/// # Example
+/// ```python
+/// events = th.strip(data, ["Input", "PlayerState"])
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, categories=vec!["Input".to_string()]))]
+pub fn strip<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    categories: Vec<String>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut stripped = Vec::new();
+    for category in &categories {
+        let types = category_chunk_types(category).ok_or_else(|| {
+            TeehistorianParseError::Validation(format!("Unknown chunk category: {}", category))
+        })?;
+        stripped.extend_from_slice(types);
+    }
+    rewrite(py, data, |out, item, encoded, converter| match item {
+        Item::Header(_) | Item::End => Ok(()),
+        Item::Segment(_) => out.copy(encoded, None),
+        Item::Chunk(chunk, _)
+            if !matches!(chunk, Chunk::Eos) && stripped.contains(&converter.type_name(&chunk)) =>
+        {
+            Ok(())
+        }
+        Item::Chunk(_, tick) => out.copy(encoded, Some(tick)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection() {
+        let selection = Selection {
+            keep: Some(vec!["Join", "Drop", "Eos"]),
+            drop: vec!["Drop"],
+            map_types: Some(vec!["Join"]),
+        };
+        assert!(selection.keeps("Join"));
+        assert!(!selection.keeps("Drop"));
+        assert!(!selection.keeps("PlayerNew"));
+        assert!(selection.maps("Join"));
+        assert!(!selection.maps("Eos"));
+
+        let everything = Selection::default();
+        assert!(everything.keeps("PlayerNew"));
+        assert!(everything.maps("PlayerNew"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Physics tuning recorded in the header
+//!
+//! DDNet stores tuning parameters as fixed-point integers (value × 100) and
+//! only writes the ones that differ from the defaults into the `tuning`
+//! header object. `Tuning` fills in the defaults and converts the values
+//! back to floats.
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+macro_rules! define_tuning {
+    ($($name:ident = $default:expr),* $(,)?) => {
+        /// Tuning parameters the recording was made with
+        ///
+        /// Obtained from `Header.tuning`.
+        #[pyclass(name = "Tuning", module = "teehistorian_py", frozen, get_all)]
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct PyTuning {
+            $(pub $name: f64,)*
+            /// Parameters written to the header, i.e. the non-default ones
+            pub changed: HashMap<String, f64>,
+        }
+
+        impl Default for PyTuning {
+            fn default() -> Self {
+                Self {
+                    $($name: fixed_to_float($default),)*
+                    changed: HashMap::new(),
+                }
+            }
+        }
+
+        impl PyTuning {
+            /// Names of all tuning parameters, in DDNet's order
+            pub const NAMES: &'static [&'static str] = &[$(stringify!($name)),*];
+
+            /// Set a parameter by name, returning false for unknown names
+            fn set(&mut self, name: &str, value: f64) -> bool {
+                match name {
+                    $(stringify!($name) => self.$name = value,)*
+                    _ => return false,
+                }
+                true
+            }
+
+            /// Get a parameter by name
+            pub fn get(&self, name: &str) -> Option<f64> {
+                match name {
+                    $(stringify!($name) => Some(self.$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+// Defaults of DDNet's tuning.h, in fixed point
+define_tuning! {
+    ground_control_speed = 1000,
+    ground_control_accel = 200,
+    ground_friction = 50,
+    ground_jump_impulse = 1320,
+    air_jump_impulse = 1200,
+    air_control_speed = 500,
+    air_control_accel = 150,
+    air_friction = 95,
+    hook_length = 38000,
+    hook_fire_speed = 8000,
+    hook_drag_accel = 300,
+    hook_drag_speed = 1500,
+    gravity = 50,
+    velramp_start = 55000,
+    velramp_range = 200000,
+    velramp_curvature = 140,
+    gun_curvature = 125,
+    gun_speed = 220000,
+    gun_lifetime = 200,
+    shotgun_curvature = 125,
+    shotgun_speed = 275000,
+    shotgun_speeddiff = 80,
+    shotgun_lifetime = 20,
+    grenade_curvature = 700,
+    grenade_speed = 100000,
+    grenade_lifetime = 200,
+    laser_reach = 80000,
+    laser_bounce_delay = 15000,
+    laser_bounce_num = 100000,
+    laser_bounce_cost = 0,
+    laser_damage = 500,
+    player_collision = 100,
+    player_hooking = 100,
+    jetpack_strength = 40000,
+    shotgun_strength = 1000,
+    explosion_strength = 600,
+    hammer_strength = 100,
+    hook_duration = 125,
+    hammer_fire_delay = 12500,
+    gun_fire_delay = 12500,
+    shotgun_fire_delay = 50000,
+    grenade_fire_delay = 50000,
+    laser_fire_delay = 80000,
+    ninja_fire_delay = 80000,
+    hammer_hit_fire_delay = 32000,
+    ground_elasticity_x = 0,
+    ground_elasticity_y = 0,
+}
+
+fn fixed_to_float(value: i32) -> f64 {
+    f64::from(value) / 100.0
+}
+
+impl PyTuning {
+    /// Build the tuning from the `tuning` header object
+    ///
+    /// Values that are not fixed-point integers are skipped. Unknown
+    /// parameter names only show up in `changed`.
+    pub fn from_header(values: &HashMap<String, String>) -> Self {
+        let mut tuning = Self::default();
+        for (name, value) in values {
+            let Ok(value) = value.trim().parse::<i32>() else {
+                continue;
+            };
+            let value = fixed_to_float(value);
+            tuning.set(name, value);
+            tuning.changed.insert(name.clone(), value);
+        }
+        tuning
+    }
+}
+
+#[pymethods]
+impl PyTuning {
+    /// Whether the recording used the default tuning
+    #[getter]
+    fn is_default(&self) -> bool {
+        self.changed.is_empty()
+    }
+
+    /// All parameters as a dictionary
+    fn to_dict(&self) -> HashMap<&'static str, f64> {
+        Self::NAMES
+            .iter()
+            .filter_map(|&name| Some((name, self.get(name)?)))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        let mut changed: Vec<_> = self.changed.iter().collect();
+        changed.sort_by(|a, b| a.0.cmp(b.0));
+        let changed: Vec<_> = changed
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        format!("Tuning({})", changed.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_overrides() {
+        let header = HashMap::from([
+            ("gravity".to_string(), "25".to_string()),
+            ("player_hooking".to_string(), "0".to_string()),
+            ("not_a_tune".to_string(), "100".to_string()),
+        ]);
+        let tuning = PyTuning::from_header(&header);
+        assert_eq!(tuning.gravity, 0.25);
+        assert_eq!(tuning.player_hooking, 0.0);
+        assert_eq!(tuning.ground_jump_impulse, 13.2);
+        assert_eq!(tuning.changed.len(), 3);
+        assert!(!tuning.is_default());
+        assert!(PyTuning::default().is_default());
+        assert_eq!(PyTuning::default().to_dict().len(), PyTuning::NAMES.len());
+    }
+}
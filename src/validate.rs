@@ -0,0 +1,199 @@
+//! Whole-file validation
+//!
+//! `ValidationReport` collects everything an archive ingestion check wants to
+//! know about a file in one pass, without raising on the first problem.
+use std::collections::{BTreeMap, BTreeSet};
+
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+/// Result of `Teehistorian.validate()`
+#[pyclass(name = "ValidationReport", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyValidationReport {
+    header_error: Option<String>,
+    counts: BTreeMap<&'static str, usize>,
+    unknown_uuids: BTreeSet<String>,
+    error_offset: Option<usize>,
+    error: Option<String>,
+    truncated: bool,
+    ticks_monotonic: bool,
+    last_tick: i32,
+    /// Whether the last chunk read was an `Eos`
+    last_was_eos: bool,
+    /// Whether every stream before the current one ended with an `Eos`
+    earlier_streams_closed: bool,
+}
+
+impl Default for PyValidationReport {
+    fn default() -> Self {
+        Self {
+            header_error: None,
+            counts: BTreeMap::new(),
+            unknown_uuids: BTreeSet::new(),
+            error_offset: None,
+            error: None,
+            truncated: false,
+            ticks_monotonic: true,
+            last_tick: 0,
+            last_was_eos: false,
+            earlier_streams_closed: true,
+        }
+    }
+}
+
+impl PyValidationReport {
+    /// Report for data whose header could not be read
+    pub fn header_failure(message: String) -> Self {
+        Self {
+            header_error: Some(message),
+            ..Self::default()
+        }
+    }
+
+    /// Account for one chunk of type `type_name` at `tick`
+    pub fn observe(&mut self, chunk: &Chunk, tick: i32, type_name: &'static str) {
+        *self.counts.entry(type_name).or_default() += 1;
+        if tick < self.last_tick {
+            self.ticks_monotonic = false;
+        }
+        self.last_tick = tick;
+        self.last_was_eos = matches!(chunk, Chunk::Eos);
+
+        if type_name == "Unknown"
+            && let Chunk::UnknownEx(unknown) = chunk
+        {
+            self.unknown_uuids.insert(unknown.uuid.to_string());
+        }
+    }
+
+    /// Account for the header of a concatenated stream
+    pub fn observe_segment(&mut self) {
+        *self.counts.entry("SegmentStart").or_default() += 1;
+        self.earlier_streams_closed &= self.last_was_eos;
+        self.last_was_eos = false;
+    }
+
+    /// Record the first chunk that failed to decode
+    pub fn fail(&mut self, offset: usize, message: String) {
+        self.error_offset = Some(offset);
+        self.error = Some(message);
+    }
+
+    /// Record that the data ends in the middle of the chunk at `offset`
+    pub fn truncate(&mut self, offset: usize) {
+        self.truncated = true;
+        self.fail(offset, "Data ends in the middle of a chunk".to_string());
+    }
+}
+
+#[pymethods]
+impl PyValidationReport {
+    /// Whether the file passed every check
+    #[getter]
+    fn is_valid(&self) -> bool {
+        self.header_ok() && self.error.is_none() && self.eos_present() && self.ticks_monotonic
+    }
+
+    /// Whether the magic UUID and JSON header could be read
+    #[getter]
+    fn header_ok(&self) -> bool {
+        self.header_error.is_none()
+    }
+
+    /// Why the header could not be read
+    #[getter]
+    fn header_error(&self) -> Option<String> {
+        self.header_error.clone()
+    }
+
+    /// Whether every stream in the data ends with an `Eos` chunk
+    #[getter]
+    fn eos_present(&self) -> bool {
+        self.earlier_streams_closed && self.last_was_eos
+    }
+
+    /// Sorted UUIDs of extension chunks without a registered handler
+    #[getter]
+    fn unknown_uuids(&self) -> Vec<String> {
+        self.unknown_uuids.iter().cloned().collect()
+    }
+
+    /// Byte offset of the first chunk that could not be decoded
+    #[getter]
+    fn error_offset(&self) -> Option<usize> {
+        self.error_offset
+    }
+
+    /// Why the chunk at `error_offset` could not be decoded
+    #[getter]
+    fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+
+    /// Whether the data ends in the middle of a chunk
+    #[getter]
+    fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Whether ticks never go backwards
+    #[getter]
+    fn ticks_monotonic(&self) -> bool {
+        self.ticks_monotonic
+    }
+
+    /// Number of chunks per `chunk_type()` name, up to the first error
+    #[getter]
+    fn chunk_counts(&self) -> BTreeMap<&'static str, usize> {
+        self.counts.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ValidationReport(is_valid={}, header_ok={}, eos_present={}, error_offset={:?})",
+            self.is_valid(),
+            self.header_ok(),
+            self.eos_present(),
+            self.error_offset
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_stream() {
+        let mut report = PyValidationReport::default();
+        assert!(!report.is_valid());
+
+        report.observe(&Chunk::TickSkip { dt: 0 }, 1, "TickSkip");
+        report.observe(&Chunk::Eos, 1, "Eos");
+        assert!(report.is_valid());
+
+        // A concatenated stream must be closed as well
+        report.observe_segment();
+        assert!(!report.eos_present());
+        report.observe(&Chunk::Eos, 1, "Eos");
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn test_problems() {
+        let mut report = PyValidationReport::default();
+        report.observe(&Chunk::Eos, 5, "Eos");
+        report.observe(&Chunk::Eos, 3, "Eos");
+        assert!(!report.ticks_monotonic());
+        assert!(!report.is_valid());
+
+        let mut report = PyValidationReport::default();
+        report.observe(&Chunk::Eos, 0, "Eos");
+        report.truncate(42);
+        assert_eq!(report.error_offset(), Some(42));
+        assert!(!report.is_valid());
+
+        assert!(!PyValidationReport::header_failure("bad".to_string()).header_ok());
+    }
+}
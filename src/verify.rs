@@ -0,0 +1,191 @@
+//! Checking claimed race times against a recording
+//!
+//! A rank submission names a player and a time. The recording of the run
+//! has to contain a finish with that time, and the player has to have been
+//! connected and alive for the whole race, since leaving or dying restarts
+//! the race in DDNet.
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+use crate::finishes::{PyFinish, Races};
+use crate::ticks::TICK_SPEED;
+use crate::walk_recording;
+
+/// Result of `verify_run()`
+#[pyclass(name = "RunVerification", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyRunVerification {
+    /// Client ID of the player
+    #[pyo3(get)]
+    cid: i32,
+    /// Claimed race time in seconds
+    #[pyo3(get)]
+    claimed_time: f64,
+    /// Race time of the finish in the recording in seconds
+    #[pyo3(get)]
+    recorded_time: Option<f64>,
+    /// Tick the race started at
+    #[pyo3(get)]
+    start_tick: Option<i32>,
+    /// Tick of the finish
+    #[pyo3(get)]
+    finish_tick: Option<i32>,
+    /// Everything that does not fit the claim
+    #[pyo3(get)]
+    discrepancies: Vec<String>,
+}
+
+#[pymethods]
+impl PyRunVerification {
+    /// Whether the recording supports the claim
+    #[getter]
+    fn verified(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RunVerification(cid={}, claimed_time={}, verified={})",
+            self.cid,
+            self.claimed_time,
+            self.verified()
+        )
+    }
+}
+
+/// What the verified client did
+#[derive(Debug, Default)]
+struct ClientEvents {
+    /// Ticks of its joins
+    joins: Vec<i32>,
+    /// Ticks at which it dropped or a new stream started
+    drops: Vec<i32>,
+    /// Ticks at which its character was killed
+    kills: Vec<i32>,
+}
+
+impl ClientEvents {
+    fn observe(&mut self, cid: i32, chunk: &Chunk, tick: i32) {
+        match *chunk {
+            Chunk::Join { cid: c } | Chunk::RejoinVer6 { cid: c } if c == cid => {
+                self.joins.push(tick);
+            }
+            Chunk::Drop(ref drop) if drop.cid == cid => self.drops.push(tick),
+            Chunk::PlayerOld { cid: c } if c == cid => self.kills.push(tick),
+            _ => {}
+        }
+    }
+
+    /// What contradicts a race from `start` to `finish`
+    fn check(&self, finish: &PyFinish) -> Vec<String> {
+        let (start, end) = (finish.start_tick(), finish.tick);
+        let mut discrepancies = Vec::new();
+        if start < 0 {
+            discrepancies.push(format!(
+                "Race started at tick {}, before the recording",
+                start
+            ));
+        }
+        match self.joins.iter().rev().find(|&&join| join <= end) {
+            Some(&join) if join > start => discrepancies.push(format!(
+                "Client joined at tick {}, after the race started at tick {}",
+                join, start
+            )),
+            _ => {}
+        }
+        let during = |tick: &&i32| start < **tick && **tick < end;
+        if let Some(drop) = self.drops.iter().find(during) {
+            discrepancies.push(format!("Client left at tick {} during the race", drop));
+        }
+        if let Some(kill) = self.kills.iter().find(during) {
+            discrepancies.push(format!(
+                "Character was killed at tick {} during the race",
+                kill
+            ));
+        }
+        if finish.practice {
+            discrepancies.push(format!("Team {} was in practice mode", finish.team));
+        }
+        discrepancies
+    }
+}
+
+/// Check a claimed race time against the recording of the run
+///
+/// The finish of the player closest to `claimed_time` has to be within
+/// `tolerance` seconds of it, the player has to have joined before the race
+/// started and must not have left or died until the finish, and the team
+/// must not have been in practice mode.
+///
+/// # Arguments
+/// * `data` - Raw or compressed teehistorian data
+/// * `cid` - Client ID of the player
+/// * `claimed_time` - Race time in seconds
+/// * `tolerance` - Largest difference to the recorded time in seconds
+///
+/// # Example
+/// ```python
+/// result = th.verify_run(data, 3, 62.46)
+/// if not result.verified:
+///     print("\n".join(result.discrepancies))
+/// ```
+#[pyfunction]
+#[pyo3(signature = (data, cid, claimed_time, *, tolerance=0.02))]
+pub fn verify_run(
+    py: Python<'_>,
+    data: &[u8],
+    cid: i32,
+    claimed_time: f64,
+    tolerance: f64,
+) -> PyResult<PyRunVerification> {
+    let mut races = Races::default();
+    let mut events = ClientEvents::default();
+    walk_recording(py, data, |chunk, tick| match chunk {
+        Some(chunk) => {
+            races.observe(&chunk, tick);
+            events.observe(cid, &chunk, tick);
+        }
+        None => {
+            races.restart();
+            events.drops.push(tick);
+        }
+    })?;
+
+    let closest = races
+        .finishes
+        .iter()
+        .filter(|finish| finish.kind == "player" && finish.cids == [cid])
+        .min_by(|a, b| {
+            let error = |finish: &PyFinish| (finish.seconds() - claimed_time).abs();
+            error(a).total_cmp(&error(b))
+        });
+    let Some(finish) = closest else {
+        return Ok(PyRunVerification {
+            cid,
+            claimed_time,
+            recorded_time: None,
+            start_tick: None,
+            finish_tick: None,
+            discrepancies: vec![format!("No finish of client {}", cid)],
+        });
+    };
+
+    let mut discrepancies = Vec::new();
+    let recorded = finish.seconds();
+    // Allow for the rounding of times given in decimal
+    if (recorded - claimed_time).abs() > tolerance + 1e-9 {
+        discrepancies.push(format!(
+            "Claimed {:.2} s, but the recorded time is {:.2} s ({} ticks at {} ticks per second)",
+            claimed_time, recorded, finish.time, TICK_SPEED
+        ));
+    }
+    discrepancies.extend(events.check(finish));
+    Ok(PyRunVerification {
+        cid,
+        claimed_time,
+        recorded_time: Some(recorded),
+        start_tick: Some(finish.start_tick()),
+        finish_tick: Some(finish.tick),
+        discrepancies,
+    })
+}
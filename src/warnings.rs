@@ -0,0 +1,198 @@
+//! Non-fatal anomalies noticed while parsing
+//!
+//! Files written by misbehaving servers or tools often still parse, but hold
+//! things worth a second look. They are recorded as `ParserWarning`s instead
+//! of being ignored or aborting the parse.
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::ops::Range;
+
+use pyo3::prelude::*;
+use teehistorian::Chunk;
+
+use crate::errors::TeehistorianWarning;
+use crate::ticks::TICK_SPEED;
+
+/// `TickSkip` distance above which the jump is reported (one hour)
+const SUSPICIOUS_TICK_JUMP: i32 = TICK_SPEED * 60 * 60;
+
+/// Encoded size above which a chunk is reported
+const OVERSIZED_CHUNK: usize = 64 * 1024;
+
+/// Anomaly found in a chunk that was otherwise decoded fine
+#[pyclass(name = "ParserWarning", module = "teehistorian_py", frozen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyParserWarning {
+    kind: &'static str,
+    message: String,
+    offset: usize,
+    tick: i32,
+}
+
+#[pymethods]
+impl PyParserWarning {
+    /// `"unknown_uuid"`, `"tick_jump"` or `"oversized_chunk"`
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    /// Human-readable description
+    #[getter]
+    fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// Byte offset of the chunk
+    #[getter]
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Tick of the chunk
+    #[getter]
+    fn tick(&self) -> i32 {
+        self.tick
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ParserWarning(kind='{}', offset={}, tick={}, message='{}')",
+            self.kind, self.offset, self.tick, self.message
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.message.clone()
+    }
+}
+
+/// Warnings collected by one parser
+#[derive(Debug, Clone, Default)]
+pub struct WarningLog {
+    records: Vec<PyParserWarning>,
+    /// Unknown UUIDs are only reported the first time they are seen
+    unknown_uuids: HashSet<String>,
+    /// Whether to also issue each warning through Python's `warnings` module
+    pub emit: bool,
+}
+
+impl WarningLog {
+    pub fn new(emit: bool) -> Self {
+        Self {
+            emit,
+            ..Self::default()
+        }
+    }
+
+    /// Check a chunk spanning `range` at `tick`, issuing any new warnings
+    pub fn check(
+        &mut self,
+        py: Python<'_>,
+        chunk: &Chunk,
+        type_name: &'static str,
+        range: Range<usize>,
+        tick: i32,
+    ) -> PyResult<()> {
+        let new = self.inspect(chunk, type_name, range, tick);
+        if self.emit {
+            let category = py.get_type::<TeehistorianWarning>();
+            for record in &self.records[new..] {
+                let message = CString::new(record.message.as_str())?;
+                PyErr::warn(py, &category, &message, 1)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Record the anomalies of one chunk, returning the index of the first
+    /// new record
+    fn inspect(
+        &mut self,
+        chunk: &Chunk,
+        type_name: &'static str,
+        range: Range<usize>,
+        tick: i32,
+    ) -> usize {
+        let new = self.records.len();
+        let mut record = |kind, message| {
+            self.records.push(PyParserWarning {
+                kind,
+                message,
+                offset: range.start,
+                tick,
+            })
+        };
+
+        let size = range.len();
+        if size > OVERSIZED_CHUNK {
+            record(
+                "oversized_chunk",
+                format!(
+                    "{} chunk of {} bytes at offset {}",
+                    type_name, size, range.start
+                ),
+            );
+        }
+
+        match chunk {
+            Chunk::TickSkip { dt } if !(0..=SUSPICIOUS_TICK_JUMP).contains(dt) => {
+                record(
+                    "tick_jump",
+                    format!("TickSkip of {} ticks before tick {}", dt, tick),
+                );
+            }
+            Chunk::UnknownEx(unknown)
+                if type_name == "Unknown"
+                    && self.unknown_uuids.insert(unknown.uuid.to_string()) =>
+            {
+                record(
+                    "unknown_uuid",
+                    format!("Extension chunk with unknown UUID {}", unknown.uuid),
+                );
+            }
+            _ => {}
+        }
+        new
+    }
+
+    /// Warnings collected so far
+    pub fn records(&self) -> &[PyParserWarning] {
+        &self.records
+    }
+
+    /// Forget all warnings, e.g. when the parser starts over
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.unknown_uuids.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_jump() {
+        let mut log = WarningLog::default();
+        log.inspect(&Chunk::TickSkip { dt: 10 }, "TickSkip", 0..2, 11);
+        assert!(log.records().is_empty());
+
+        let dt = SUSPICIOUS_TICK_JUMP + 1;
+        log.inspect(&Chunk::TickSkip { dt }, "TickSkip", 2..6, dt + 12);
+        log.inspect(&Chunk::TickSkip { dt: -5 }, "TickSkip", 6..8, 0);
+        let kinds: Vec<_> = log.records().iter().map(|r| (r.kind, r.offset)).collect();
+        assert_eq!(kinds, vec![("tick_jump", 2), ("tick_jump", 6)]);
+    }
+
+    #[test]
+    fn test_oversized_chunk() {
+        let mut log = WarningLog::default();
+        let range = 100..100 + OVERSIZED_CHUNK + 1;
+        log.inspect(&Chunk::Eos, "Eos", range, 0);
+        assert_eq!(log.records()[0].kind, "oversized_chunk");
+
+        log.clear();
+        assert!(log.records().is_empty());
+    }
+}
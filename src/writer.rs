@@ -1,10 +1,196 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict, PyString};
 use serde_json::{Value, json};
+use teehistorian::Chunk;
 use uuid::Uuid;
 
+use crate::chunks::*;
 use crate::errors::TeehistorianParseError;
-use crate::registry;
+use crate::header::{PyHeader, json_to_py, py_to_json};
+use crate::order::ChunkOrder;
+use crate::resolve::{Delta, DeltaEncoder};
+use crate::scan::{TAG_EX, read_tag};
+use crate::ticks::TickTracker;
+use crate::{MAGIC, PyTeehistorian, TeehistorianParserInner, extensions, input, registry};
+
+/// Default number of buffered bytes after which a streaming writer flushes
+const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Default number of chunks `write_from()` serializes per GIL release
+const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// Destination of a streaming writer
+enum Sink {
+    /// File opened by `TeehistorianWriter(path=...)`
+    File { file: File, path: PathBuf },
+    /// Python file-like object passed as `TeehistorianWriter(file)`
+    Stream { file: Py<PyAny> },
+    /// Temporary file an in-memory writer moved to after reaching its
+    /// `spill_threshold`, removed when the writer is dropped
+    Spill { file: File, path: PathBuf },
+    /// Discards the data, the writer only counts it in `flushed`
+    Count,
+}
+
+/// Decode an encoded chunk, None for a `SegmentStart`
+fn decode(data: &[u8]) -> Option<Chunk<'_>> {
+    if data.starts_with(&MAGIC) {
+        return None;
+    }
+    teehistorian::chunks::chunk(data)
+        .ok()
+        .map(|(_, chunk)| chunk)
+}
+
+/// Chunk taken by `write_from()`, waiting to be serialized
+enum Pending {
+    /// Copy of a chunk class of this library, serialized without the GIL
+    Chunk(Box<dyn TeehistorianChunk + Send>),
+    /// Encoding returned by the `write_to_buffer()` of another object
+    Encoded(Vec<u8>),
+}
+
+macro_rules! owned_chunks {
+    ($($name:ident),* $(,)?) => {
+        $crate::macros::paste! {
+            /// Copy a chunk class of this library, so it can be serialized
+            /// without the GIL
+            ///
+            /// Only exact classes are copied, subclasses may override
+            /// `write_to_buffer()`.
+            fn owned_chunk(chunk: &Bound<'_, PyAny>) -> Option<Box<dyn TeehistorianChunk + Send>> {
+                $(
+                    if let Ok(chunk) = chunk.cast_exact::<[<Py $name>]>() {
+                        return Some(Box::new(chunk.borrow().clone()));
+                    }
+                )*
+                None
+            }
+        }
+    };
+}
+
+owned_chunks! {
+    Join,
+    JoinVer6,
+    JoinVer7,
+    Drop,
+    PlayerReady,
+    PlayerRejoin,
+    PlayerNew,
+    PlayerOld,
+    PlayerTeam,
+    PlayerName,
+    PlayerDiff,
+    PlayerPosition,
+    PlayerFinish,
+    PlayerSwap,
+    InputNew,
+    InputDiff,
+    NetMessage,
+    NetMessagePlayerInfo,
+    ConsoleCommand,
+    AuthInit,
+    AuthLogin,
+    AuthLogout,
+    DdnetVersion,
+    DdnetVersionOld,
+    TickSkip,
+    TeamSaveSuccess,
+    TeamSaveFailure,
+    TeamLoadSuccess,
+    TeamLoadFailure,
+    TeamPractice,
+    TeamFinish,
+    AntiBot,
+    Eos,
+    TestChunk,
+    Unknown,
+    CustomChunk,
+}
+
+fn io_error(path: &Path, action: &str, e: std::io::Error) -> PyErr {
+    TeehistorianParseError::File(format!("Failed to {} {}: {}", action, path.display(), e)).into()
+}
+
+impl Sink {
+    /// Create the temporary file of a spilling writer
+    fn spill() -> PyResult<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir();
+        loop {
+            let path = dir.join(format!(
+                "teehistorian-py-{}-{}.tmp",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            match File::options()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(file) => return Ok(Sink::Spill { file, path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(io_error(&path, "create", e)),
+            }
+        }
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> PyResult<()> {
+        match self {
+            Sink::File { file, path } | Sink::Spill { file, path } => file
+                .write_all(data)
+                .map_err(|e| io_error(path, "write to", e)),
+            Sink::Stream { file } => Python::attach(|py| {
+                file.call_method1(py, "write", (PyBytes::new(py, data),))?;
+                if file.bind(py).hasattr("flush")? {
+                    file.call_method0(py, "flush")?;
+                }
+                Ok(())
+            }),
+            Sink::Count => Ok(()),
+        }
+    }
+
+    /// Read back everything written to the sink
+    fn read_all(&mut self) -> PyResult<Vec<u8>> {
+        match self {
+            Sink::File { file, path } | Sink::Spill { file, path } => (|| {
+                let mut data = Vec::new();
+                file.seek(SeekFrom::Start(0))?;
+                file.read_to_end(&mut data)?;
+                Ok(data)
+            })()
+            .map_err(|e| io_error(path, "read", e)),
+            Sink::Stream { .. } => Err(TeehistorianParseError::Validation(
+                "Data streamed to a file object cannot be read back".to_string(),
+            )
+            .into()),
+            Sink::Count => Err(TeehistorianParseError::Validation(
+                "Counted data cannot be read back".to_string(),
+            )
+            .into()),
+        }
+    }
+
+    /// Drop everything written to the sink, streams keep their data
+    fn clear(&mut self) -> PyResult<()> {
+        match self {
+            Sink::File { file, path } | Sink::Spill { file, path } => file
+                .set_len(0)
+                .and_then(|_| file.rewind())
+                .map_err(|e| io_error(path, "truncate", e)),
+            Sink::Stream { .. } | Sink::Count => Ok(()),
+        }
+    }
+}
 
 /// Teehistorian file writer
 ///
@@ -66,10 +252,34 @@ use crate::registry;
 /// ```
 #[pyclass(name = "TeehistorianWriter", module = "teehistorian_py")]
 pub struct PyTeehistorianWriter {
+    /// Written bytes, or the ones not flushed to `sink` yet
     buffer: Vec<u8>,
+    sink: Option<Sink>,
+    /// Bytes already moved from `buffer` to `sink`
+    flushed: usize,
+    /// Number of buffered bytes after which `buffer` is moved to `sink`
+    buffer_size: usize,
+    /// Number of bytes after which an in-memory writer moves to a temporary file
+    spill_threshold: Option<usize>,
+    /// Tick of the written chunks, tracked like the parser does
+    ticks: TickTracker,
+    /// Player slots of the written chunks, only tracked in strict mode
+    order: Option<ChunkOrder>,
+    /// Last positions and inputs, only tracked when encoding deltas
+    deltas: Option<DeltaEncoder>,
+    /// Whether the last written chunk is an `Eos`
+    ended: bool,
+    /// Whether `finalize()` was called, so no more chunks are taken
+    finished: bool,
     header_written: bool,
+    /// Whether the header left `buffer`, so it can no longer change
+    header_flushed: bool,
     header_data: Value,
     include_custom_chunk_metadata: bool,
+    /// Length of the header at the start of `buffer`
+    header_len: usize,
+    /// Extension UUIDs written so far that need a registration, with names
+    extensions: Vec<(Uuid, String)>,
 }
 
 #[pymethods]
@@ -77,35 +287,153 @@ impl PyTeehistorianWriter {
     /// Create a new teehistorian writer
     ///
     /// # Arguments
-    /// * `file` - Optional file-like object to write to
+    /// * `file` - Optional file-like object to stream to, e.g. a pipe or a
+    ///   socket file. Buffered chunks are passed to its `write()` and
+    ///   `flush()` methods.
+    /// * `path` - Optional file to stream to. The file is truncated, the
+    ///   header is written with the first chunk and chunks are flushed to
+    ///   disk in batches instead of being kept in memory.
+    /// * `buffer_size` - Number of buffered bytes after which a streaming
+    ///   writer flushes, 0 flushes every chunk
+    /// * `strict` - Reject chunks no server would write in this order: a
+    ///   `PlayerDiff` before the client's `PlayerNew`, a `Drop` of a client
+    ///   that did not join, a second `Join` and any chunk after `Eos`
+    /// * `delta_encode` - Take absolute values and write them like the
+    ///   server: a `PlayerNew` or `PlayerPosition` of a spawned character
+    ///   becomes a `PlayerDiff` and an `InputNew` of a client with a known
+    ///   input an `InputDiff`; unchanged positions and inputs are left out
+    /// * `spill_threshold` - Number of bytes after which an in-memory writer
+    ///   moves its data to a temporary file, so generating huge recordings
+    ///   does not run out of memory. `getvalue()` and `save()` work as before.
+    /// * `extensions` - Custom extension UUIDs to register in the header up
+    ///   front, mapped to the `handler_name` of their chunks. Once the header
+    ///   was flushed it cannot change, so a streaming writer rejects chunks
+    ///   of custom UUIDs not declared here.
     ///
     /// # Example
     /// ```python
     /// # Write to memory
     /// writer = th.TeehistorianWriter()
     ///
-    /// # Write to file
-    /// with open("output.teehistorian", "wb") as f:
-    ///     writer = th.TeehistorianWriter(f)
+    /// # Stream to a file
+    /// writer = th.TeehistorianWriter(path="output.teehistorian")
+    ///
+    /// # Stream every chunk to stdout as soon as it is written
+    /// writer = th.TeehistorianWriter(sys.stdout.buffer, buffer_size=0)
+    ///
+    /// # Catch mistakes in generated files early
+    /// writer = th.TeehistorianWriter(strict=True)
+    ///
+    /// # Keep at most 256 MiB in memory
+    /// writer = th.TeehistorianWriter(spill_threshold=256 * 1024 * 1024)
+    ///
+    /// # Register a custom extension before anything is streamed
+    /// writer = th.TeehistorianWriter(
+    ///     path="output.teehistorian",
+    ///     extensions={th.derive_uuid("kog-checkpoint@kog.tw"): "kog-checkpoint"},
+    /// )
     /// ```
     #[new]
-    fn new() -> Self {
-        let default_header = json!({
-            "version": "2",
-            "version_minor": "9",
-            "comment": "teehistorian-py",
-            "game_uuid": Uuid::nil().to_string(),
-            "prev_game_uuid": Uuid::nil().to_string(),
-            "server_version": "teehistorian-py 2.0.0",
-            "server_name": "teehistorian-py Server"
-        });
+    #[pyo3(signature = (
+        file=None,
+        *,
+        path=None,
+        buffer_size=DEFAULT_BUFFER_SIZE,
+        strict=false,
+        delta_encode=false,
+        spill_threshold=None,
+        extensions=None,
+    ))]
+    fn py_new(
+        file: Option<Py<PyAny>>,
+        path: Option<PathBuf>,
+        buffer_size: usize,
+        strict: bool,
+        delta_encode: bool,
+        spill_threshold: Option<usize>,
+        extensions: Option<BTreeMap<String, String>>,
+    ) -> PyResult<Self> {
+        let mut writer = Self::new();
+        for (uuid, name) in extensions.unwrap_or_default() {
+            writer.declare_extension(&uuid, name)?;
+        }
+        writer.buffer_size = buffer_size;
+        writer.spill_threshold = spill_threshold;
+        if strict {
+            writer.order = Some(ChunkOrder::new());
+        }
+        if delta_encode {
+            writer.deltas = Some(DeltaEncoder::new());
+        }
+        if file.is_some() && path.is_some() {
+            return Err(TeehistorianParseError::Validation(
+                "file and path cannot be combined".to_string(),
+            )
+            .into());
+        }
+        if spill_threshold.is_some() && (file.is_some() || path.is_some()) {
+            return Err(TeehistorianParseError::Validation(
+                "spill_threshold only applies to in-memory writers".to_string(),
+            )
+            .into());
+        }
+        if let Some(file) = file {
+            writer.sink = Some(Sink::Stream { file });
+        }
+        if let Some(path) = path {
+            let file = File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)
+                .map_err(|e| {
+                    TeehistorianParseError::File(format!(
+                        "Failed to open {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+            writer.sink = Some(Sink::File { file, path });
+        }
+        Ok(writer)
+    }
 
-        Self {
-            buffer: Vec::new(),
-            header_written: false,
-            header_data: default_header,
-            include_custom_chunk_metadata: false,
+    /// Continue an existing recording
+    ///
+    /// The header and its extension registrations are taken over, and the
+    /// chunks are kept up to the final `Eos`, or up to the last complete
+    /// chunk of an interrupted recording. The writer picks up the tick and,
+    /// with `strict`, the joined clients, so new chunks stay consistent with
+    /// the recording. The result is kept in memory; `save()` writes it back.
+    ///
+    /// # Arguments
+    /// * `source` - Path or bytes of the recording, possibly compressed
+    /// * `strict` - Check chunk order as `TeehistorianWriter(strict=True)`
+    ///
+    /// # Example
+    /// ```python
+    /// writer = th.TeehistorianWriter.append("interrupted.teehistorian")
+    /// writer.write(th.Drop(0, "server restart"))
+    /// writer.finalize()
+    /// writer.save("interrupted.teehistorian")
+    /// ```
+    #[staticmethod]
+    #[pyo3(signature = (source, *, strict=false))]
+    fn append(source: &Bound<'_, PyAny>, strict: bool) -> PyResult<Self> {
+        let data = match source.cast::<PyBytes>() {
+            Ok(bytes) => bytes.as_bytes().to_vec(),
+            Err(_) => {
+                let path: PathBuf = source.extract()?;
+                std::fs::read(&path).map_err(|e| io_error(&path, "read", e))?
+            }
+        };
+        let mut writer = Self::new();
+        if strict {
+            writer.order = Some(ChunkOrder::new());
         }
+        writer.load(input::decode_input(&data)?)?;
+        Ok(writer)
     }
 
     /// Write a chunk to the teehistorian
@@ -121,22 +449,179 @@ impl PyTeehistorianWriter {
     /// writer.write(th.Drop(0, "quit"))
     /// writer.write(th.Eos())
     /// ```
-    fn write(&mut self, _py: Python<'_>, chunk: &Bound<'_, PyAny>) -> PyResult<()> {
-        // Ensure header is written first
-        if !self.header_written {
-            self.write_header()?;
-        }
-
+    ///
+    /// With `tick`, the chunk is written at that absolute tick: the writer
+    /// emits the `TickSkip` needed to get there, see `advance_to_tick()`.
+    #[pyo3(signature = (chunk, tick=None))]
+    pub(crate) fn write(
+        &mut self,
+        py: Python<'_>,
+        chunk: &Bound<'_, PyAny>,
+        tick: Option<i32>,
+    ) -> PyResult<()> {
         // Get the serialized data from the chunk
         let py_bytes = chunk.call_method0("write_to_buffer")?;
         let chunk_data: Vec<u8> = py_bytes.extract()?;
+        self.write_encoded(py, chunk, chunk_data, tick)
+    }
 
-        // Append to our buffer
-        self.buffer.extend_from_slice(&chunk_data);
+    /// Write the chunks a server records when a client connects
+    ///
+    /// # Arguments
+    /// * `client_id` - ID of the connecting client
+    /// * `protocol` - 6 or 7 writes the `JoinVer6`/`JoinVer7` DDNet servers
+    ///   record before the `Join`, None only the `Join` of older servers
+    /// * `version` - `DdnetVersion` or `DdnetVersionOld` of the client,
+    ///   written after the `Join`
+    /// * `tick` - Absolute tick to write the chunks at
+    ///
+    /// # Example
+    /// ```python
+    /// writer.write_join(0, 7)
+    /// writer.write_join(1, 6, version=th.DdnetVersionOld(1, 16050))
+    /// ```
+    #[pyo3(signature = (client_id, protocol=None, *, version=None, tick=None))]
+    fn write_join(
+        &mut self,
+        py: Python<'_>,
+        client_id: i32,
+        protocol: Option<i32>,
+        version: Option<&Bound<'_, PyAny>>,
+        tick: Option<i32>,
+    ) -> PyResult<()> {
+        if let Some(version) = version {
+            if !version.is_instance_of::<PyDdnetVersion>()
+                && !version.is_instance_of::<PyDdnetVersionOld>()
+            {
+                return Err(TeehistorianParseError::Validation(
+                    "version must be a DdnetVersion or DdnetVersionOld chunk".to_string(),
+                )
+                .into());
+            }
+            let version_cid: i32 = version.getattr("client_id")?.extract()?;
+            if version_cid != client_id {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Version of client {} written for client {}",
+                    version_cid, client_id
+                ))
+                .into());
+            }
+        }
+        let joinver = match protocol {
+            None => None,
+            Some(6) => Some(Py::new(py, PyJoinVer6::new(client_id))?.into_any()),
+            Some(7) => Some(Py::new(py, PyJoinVer7::new(client_id))?.into_any()),
+            Some(other) => {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Unknown protocol {}, expected 6 or 7",
+                    other
+                ))
+                .into());
+            }
+        };
+
+        if let Some(joinver) = joinver {
+            self.write(py, joinver.bind(py), tick)?;
+        }
+        let join = Py::new(py, PyJoin::new(client_id))?;
+        self.write(py, join.bind(py).as_any(), tick)?;
+        if let Some(version) = version {
+            self.write(py, version, tick)?;
+        }
+        Ok(())
+    }
+
+    /// Write chunks pulled from an iterable, e.g. a parser
+    ///
+    /// Chunks are taken in batches of `batch_size`. The chunk classes of this
+    /// library are serialized with the GIL released, so other threads keep
+    /// running while a parse → transform → write pipeline rewrites a large
+    /// file. Other objects are serialized through their `write_to_buffer()`.
+    /// Returns the number of chunks written.
+    ///
+    /// # Example
+    /// ```python
+    /// parser = th.Teehistorian(data)
+    /// writer = th.TeehistorianWriter(path="filtered.teehistorian")
+    /// writer.write_from(c for c in parser if not isinstance(c, th.ConsoleCommand))
+    /// ```
+    #[pyo3(signature = (iterable, batch_size=DEFAULT_BATCH_SIZE))]
+    fn write_from(
+        &mut self,
+        py: Python<'_>,
+        iterable: &Bound<'_, PyAny>,
+        batch_size: usize,
+    ) -> PyResult<usize> {
+        let mut iter = iterable.try_iter()?;
+        let mut written = 0;
+        loop {
+            let mut objects = Vec::with_capacity(batch_size);
+            for chunk in iter.by_ref().take(batch_size.max(1)) {
+                objects.push(chunk?);
+            }
+            if objects.is_empty() {
+                return Ok(written);
+            }
+            let pending = objects
+                .iter()
+                .map(|chunk| match owned_chunk(chunk) {
+                    Some(owned) => Ok(Pending::Chunk(owned)),
+                    None => Ok(Pending::Encoded(
+                        chunk.call_method0("write_to_buffer")?.extract()?,
+                    )),
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            let encoded = py.detach(|| {
+                pending
+                    .into_iter()
+                    .map(|pending| match pending {
+                        Pending::Chunk(chunk) => chunk.write_to_buffer(),
+                        Pending::Encoded(data) => Ok(data),
+                    })
+                    .collect::<PyResult<Vec<_>>>()
+            })?;
+            for (chunk, data) in objects.iter().zip(encoded) {
+                self.write_encoded(py, chunk, data, None)?;
+                written += 1;
+            }
+        }
+    }
 
+    /// Emit the `TickSkip` that makes `tick` the current tick
+    ///
+    /// Ticks are implicit in teehistorian: a `TickSkip { dt }` advances
+    /// `dt + 1` ticks and player chunks with a client ID not above the
+    /// previous one open the next tick. The writer tracks them like the
+    /// parser does, so generators can work with absolute ticks. Does nothing
+    /// if `tick` is the current tick.
+    ///
+    /// # Example
+    /// ```python
+    /// writer.advance_to_tick(50)
+    /// writer.write(th.PlayerNew(0, 100, 200))  # at tick 50
+    /// ```
+    fn advance_to_tick(&mut self, py: Python<'_>, tick: i32) -> PyResult<()> {
+        let current = self.ticks.tick();
+        if tick < current {
+            return Err(TeehistorianParseError::Validation(format!(
+                "Cannot go back from tick {} to tick {}",
+                current, tick
+            ))
+            .into());
+        }
+        if tick > current {
+            let skip = Py::new(py, PyTickSkip::new(tick - current - 1))?;
+            self.write(py, skip.bind(py).as_any(), None)?;
+        }
         Ok(())
     }
 
+    /// Tick of the most recently written chunk
+    #[getter]
+    fn tick(&self) -> i32 {
+        self.ticks.tick()
+    }
+
     /// Write multiple chunks at once
     ///
     /// # Arguments
@@ -153,7 +638,7 @@ impl PyTeehistorianWriter {
     /// ```
     fn write_all(&mut self, py: Python<'_>, chunks: Vec<Py<PyAny>>) -> PyResult<()> {
         for chunk in chunks {
-            self.write(py, chunk.bind(py))?;
+            self.write(py, chunk.bind(py), None)?;
         }
         Ok(())
     }
@@ -162,13 +647,15 @@ impl PyTeehistorianWriter {
     ///
     /// # Arguments
     /// * `key` - Header field name
-    /// * `value` - Header field value (string)
+    /// * `value` - Header field value: a string, or an int, float, bool,
+    ///   None, list or dict stored as the matching JSON type
     ///
     /// # Note
-    /// Only JSON objects and arrays (strings starting with { or [) are parsed as JSON.
-    /// All other values are stored as strings. This preserves string types for fields
-    /// like "map_size": "299932" and prevents numeric strings from being converted to
-    /// JSON numbers.
+    /// Of string values, only JSON objects and arrays (strings starting with { or [)
+    /// are parsed as JSON. All other strings are stored as strings. This preserves
+    /// string types for fields like "map_size": "299932" and prevents numeric strings
+    /// from being converted to JSON numbers. Strings nested in lists and dicts are
+    /// always stored as strings.
     ///
     /// # Example
     /// ```python
@@ -176,12 +663,33 @@ impl PyTeehistorianWriter {
     /// writer.set_header("server_name", "My Custom Server")
     /// writer.set_header("comment", "Generated by my script")
     /// writer.set_header("map_size", "299932")  # Stored as string, not number
-    /// # JSON objects/arrays are automatically parsed
+    /// writer.set_header("map_size", 299932)  # Stored as number
+    /// writer.set_header("config", {"sv_motd": "Welcome", "sv_max_clients": 64})
+    /// # JSON objects/arrays in strings are automatically parsed
     /// writer.set_header("config", json.dumps({"sv_motd": "Welcome"}))
     /// ```
-    fn set_header(&mut self, key: String, value: String) -> PyResult<()> {
+    #[pyo3(name = "set_header")]
+    fn py_set_header(&mut self, key: String, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        if let Ok(value) = value.cast::<PyString>() {
+            return self.set_header(key, value.to_str()?.to_string());
+        }
         self.ensure_header_not_written()?;
-        self.header_data[key] = Self::parse_header_value(&value);
+        self.header_data[key] = py_to_json(value)?;
+        Ok(())
+    }
+
+    /// Remove a header field, does nothing if it is not set
+    ///
+    /// # Example
+    /// ```python
+    /// writer.copy_header_from(parser)
+    /// writer.remove_header("config")
+    /// ```
+    fn remove_header(&mut self, key: &str) -> PyResult<()> {
+        self.ensure_header_not_written()?;
+        if let Some(fields) = self.header_data.as_object_mut() {
+            fields.remove(key);
+        }
         Ok(())
     }
 
@@ -191,12 +699,52 @@ impl PyTeehistorianWriter {
     /// * `key` - Header field name
     ///
     /// # Returns
-    /// Header field value or None if not set
-    fn get_header(&self, key: String) -> Option<String> {
+    /// Header field value as `json.loads` would return it, or None if not set
+    fn get_header<'py>(&self, py: Python<'py>, key: String) -> PyResult<Option<Bound<'py, PyAny>>> {
         self.header_data
             .get(&key)
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
+            .map(|value| json_to_py(py, value))
+            .transpose()
+    }
+
+    /// Replace the header with the one of another recording
+    ///
+    /// A filter or rewrite tool can keep the original metadata, e.g. map
+    /// hash, config and tuning, when emitting a modified file. Fields can
+    /// still be changed with `set_header()` afterwards.
+    ///
+    /// # Arguments
+    /// * `source` - A parser, a `Header`, a dict of header fields or the
+    ///   header JSON as str or bytes
+    ///
+    /// # Example
+    /// ```python
+    /// parser = th.Teehistorian(data)
+    /// writer = th.TeehistorianWriter()
+    /// writer.copy_header_from(parser)
+    /// writer.write_from(c for c in parser if not isinstance(c, th.ConsoleCommand))
+    /// ```
+    fn copy_header_from(&mut self, py: Python<'_>, source: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.ensure_header_not_written()?;
+
+        let header = if let Ok(parser) = source.cast::<PyTeehistorian>() {
+            PyHeader::parse(parser.borrow().inner.get_header())?
+        } else if let Ok(header) = source.cast::<PyHeader>() {
+            header.borrow().clone()
+        } else if let Ok(bytes) = source.cast::<PyBytes>() {
+            PyHeader::parse(bytes.as_bytes())?
+        } else if source.is_instance_of::<PyDict>() {
+            let json: String = py
+                .import("json")?
+                .call_method1("dumps", (source,))?
+                .extract()?;
+            PyHeader::parse(json.as_bytes())?
+        } else {
+            let json: String = source.extract()?;
+            PyHeader::parse(json.as_bytes())?
+        };
+        self.load_header(header.to_json());
+        Ok(())
     }
 
     /// Update multiple header fields from a dictionary
@@ -205,7 +753,7 @@ impl PyTeehistorianWriter {
     /// * `headers` - Dictionary of field names and values
     ///
     /// # Note
-    /// Like set_header, only JSON objects/arrays are parsed.
+    /// Values are handled like in set_header, only JSON objects/arrays in strings are parsed.
     /// This allows you to set complex header structures in a single call.
     ///
     /// # Example
@@ -225,9 +773,7 @@ impl PyTeehistorianWriter {
         self.ensure_header_not_written()?;
 
         for (key, value) in headers.iter() {
-            let key_str = key.extract::<String>()?;
-            let value_str = value.extract::<String>()?;
-            self.header_data[key_str] = Self::parse_header_value(&value_str);
+            self.py_set_header(key.extract()?, &value)?;
         }
 
         Ok(())
@@ -246,12 +792,8 @@ impl PyTeehistorianWriter {
     /// data = writer.getvalue()  # Like BytesIO.getvalue()
     /// ```
     fn getvalue(&mut self, py: Python<'_>) -> PyResult<Py<PyAny>> {
-        // Ensure header is written
-        if !self.header_written {
-            self.write_header()?;
-        }
-
-        Ok(PyBytes::new(py, &self.buffer).into())
+        let data = self.contents()?;
+        Ok(PyBytes::new(py, &data).into())
     }
 
     /// Save to file path
@@ -267,12 +809,8 @@ impl PyTeehistorianWriter {
     /// writer.save("output.teehistorian")
     /// ```
     fn save(&mut self, path: String) -> PyResult<()> {
-        // Ensure header is written
-        if !self.header_written {
-            self.write_header()?;
-        }
-
-        std::fs::write(&path, &self.buffer).map_err(|e| {
+        let data = self.contents()?;
+        std::fs::write(&path, data).map_err(|e| {
             TeehistorianParseError::File(format!("Failed to save to {}: {}", path, e))
         })?;
         Ok(())
@@ -293,27 +831,124 @@ impl PyTeehistorianWriter {
     ///     writer.writeto(f)
     /// ```
     fn writeto(&mut self, py: Python<'_>, file: &Bound<'_, PyAny>) -> PyResult<()> {
-        // Ensure header is written
-        if !self.header_written {
-            self.write_header()?;
-        }
-
         // Write to file-like object
-        let data = PyBytes::new(py, &self.buffer);
+        let data = PyBytes::new(py, &self.contents()?);
         file.call_method1("write", (data,))?;
 
         Ok(())
     }
 
-    /// Get current buffer size
+    /// Move buffered chunks to the file or file object of a streaming writer
+    ///
+    /// Does nothing for writers that keep everything in memory.
+    fn flush(&mut self) -> PyResult<()> {
+        let Some(sink) = &mut self.sink else {
+            return Ok(());
+        };
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        sink.write_all(&self.buffer)?;
+        self.flushed += self.buffer.len();
+        self.header_flushed = self.header_written;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Finish the recording
+    ///
+    /// Writes the `Eos` chunk unless the last chunk already is one, flushes a
+    /// streaming writer and refuses any further chunks. Calling it again does
+    /// nothing; `reset()` starts a new recording.
+    fn finalize(&mut self, py: Python<'_>) -> PyResult<()> {
+        if self.finished {
+            return Ok(());
+        }
+        if !self.ended {
+            let eos = Py::new(py, PyEos::new())?;
+            self.write(py, eos.bind(py).as_any(), None)?;
+        }
+        self.flush()?;
+        self.finished = true;
+        Ok(())
+    }
+
+    /// Whether `finalize()` was called
+    #[getter]
+    fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Get the number of bytes written, including flushed ones
     fn size(&self) -> usize {
-        self.buffer.len()
+        self.flushed + self.buffer.len()
+    }
+
+    /// Number of bytes writing `chunks` would add, without writing them
+    ///
+    /// The chunks are serialized into a sink that only counts bytes,
+    /// starting from the current state of the writer: the header is counted
+    /// if it was not written yet, as is its growth by newly registered
+    /// extensions, and delta encoding and strict checks apply as in
+    /// `write()`. The writer itself is left unchanged.
+    ///
+    /// # Example
+    /// ```python
+    /// writer = th.TeehistorianWriter()
+    /// needed = writer.estimate_size(chunks)  # size of the file without Eos
+    /// ```
+    fn estimate_size(&self, py: Python<'_>, chunks: &Bound<'_, PyAny>) -> PyResult<usize> {
+        let mut counter = Self {
+            buffer: Vec::new(),
+            sink: Some(Sink::Count),
+            flushed: self.size(),
+            buffer_size: 0,
+            spill_threshold: None,
+            ticks: self.ticks,
+            order: self.order.clone(),
+            deltas: self.deltas.clone(),
+            ended: self.ended,
+            finished: self.finished,
+            header_written: self.header_written,
+            header_flushed: self.header_flushed,
+            header_data: self.header_data.clone(),
+            include_custom_chunk_metadata: self.include_custom_chunk_metadata,
+            header_len: self.header_len,
+            extensions: self.extensions.clone(),
+        };
+        for chunk in chunks.try_iter()? {
+            counter.write(py, &chunk?, None)?;
+        }
+        Ok(counter.size() - self.size())
     }
 
     /// Reset the writer to initial state
-    fn reset(&mut self) {
+    ///
+    /// A streaming writer truncates its file. Data sent to a file object
+    /// cannot be taken back, so the buffer is sent too and the next
+    /// recording follows it, which parsers read as a concatenated stream.
+    fn reset(&mut self) -> PyResult<()> {
+        if let Some(Sink::Stream { .. }) = self.sink {
+            self.flush()?;
+        }
+        if let Some(sink) = &mut self.sink {
+            sink.clear()?;
+        }
+        self.flushed = 0;
         self.buffer.clear();
         self.header_written = false;
+        self.header_flushed = false;
+        self.header_len = 0;
+        self.extensions.clear();
+        self.ticks = TickTracker::new();
+        self.ended = false;
+        self.finished = false;
+        if self.order.is_some() {
+            self.order = Some(ChunkOrder::new());
+        }
+        if self.deltas.is_some() {
+            self.deltas = Some(DeltaEncoder::new());
+        }
 
         // Reset to default header
         self.header_data = json!({
@@ -325,6 +960,26 @@ impl PyTeehistorianWriter {
             "server_version": "teehistorian-py 2.0.0",
             "server_name": "teehistorian-py Server"
         });
+        Ok(())
+    }
+
+    /// Whether the writer moved its data to a temporary file
+    #[getter]
+    fn spilled(&self) -> bool {
+        matches!(self.sink, Some(Sink::Spill { .. }))
+    }
+
+    /// Preallocate memory for `n_bytes` more bytes
+    ///
+    /// Avoids repeated reallocation when the size of a generated recording
+    /// is known up front. A writer with a `spill_threshold` reserves at most
+    /// that much.
+    fn reserve(&mut self, n_bytes: usize) {
+        let limit = match (&self.sink, self.spill_threshold) {
+            (None, Some(threshold)) => threshold.saturating_sub(self.buffer.len()),
+            _ => n_bytes,
+        };
+        self.buffer.reserve(n_bytes.min(limit));
     }
 
     /// Check if any data has been written
@@ -337,28 +992,35 @@ impl PyTeehistorianWriter {
         slf
     }
 
-    /// Context manager exit - ensures EOS is written if not already present
+    /// Context manager exit - finalizes the recording unless an exception
+    /// is propagating, in which case buffered chunks are only flushed
     fn __exit__(
         &mut self,
-        _py: Python<'_>,
-        _exc_type: Option<&Bound<'_, pyo3::types::PyAny>>,
+        py: Python<'_>,
+        exc_type: Option<&Bound<'_, pyo3::types::PyAny>>,
         _exc_value: Option<&Bound<'_, pyo3::types::PyAny>>,
         _traceback: Option<&Bound<'_, pyo3::types::PyAny>>,
     ) -> PyResult<bool> {
-        // For safety, we don't auto-write chunks in __exit__
+        if exc_type.is_none() {
+            self.finalize(py)?;
+        } else {
+            self.flush()?;
+        }
         Ok(false)
     }
 
     /// String representation
     fn __repr__(&self) -> String {
-        let status = if self.header_written {
+        let status = if self.finished {
+            "finished"
+        } else if self.header_written {
             "started"
         } else {
             "empty"
         };
         format!(
             "TeehistorianWriter(size={}, status={})",
-            self.buffer.len(),
+            self.size(),
             status
         )
     }
@@ -389,6 +1051,218 @@ impl PyTeehistorianWriter {
 }
 
 impl PyTeehistorianWriter {
+    /// Create a writer that keeps everything in memory
+    pub(crate) fn new() -> Self {
+        let default_header = json!({
+            "version": "2",
+            "version_minor": "9",
+            "comment": "teehistorian-py",
+            "game_uuid": Uuid::nil().to_string(),
+            "prev_game_uuid": Uuid::nil().to_string(),
+            "server_version": "teehistorian-py 2.0.0",
+            "server_name": "teehistorian-py Server"
+        });
+
+        Self {
+            buffer: Vec::new(),
+            sink: None,
+            flushed: 0,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            spill_threshold: None,
+            ticks: TickTracker::new(),
+            order: None,
+            deltas: None,
+            ended: false,
+            finished: false,
+            header_written: false,
+            header_flushed: false,
+            header_data: default_header,
+            include_custom_chunk_metadata: false,
+            header_len: 0,
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Take over the recording in `data` as if its chunks had been written
+    fn load(&mut self, data: Vec<u8>) -> PyResult<()> {
+        let inner = TeehistorianParserInner::from_data(data).map_err(|e| {
+            TeehistorianParseError::Parse(format!("Failed to read recording: {}", e))
+        })?;
+        self.load_header(PyHeader::parse(inner.get_header())?.to_json());
+
+        let data = &inner.data[..];
+        let mut offset = inner.chunks_start;
+        // End of the data to keep, which leaves out the final `Eos`
+        let mut keep = offset;
+        let decode_error = |offset: usize, e: teehistorian::Error| {
+            TeehistorianParseError::Parse(format!(
+                "Failed to decode chunk at offset {}: {}",
+                offset, e
+            ))
+        };
+        loop {
+            if let Some(start) = TeehistorianParserInner::segment_at(data, offset)
+                .map_err(|e| decode_error(offset, e))?
+            {
+                self.ticks.restart();
+                if let Some(order) = &mut self.order {
+                    *order = ChunkOrder::new();
+                }
+                offset = start;
+                keep = offset;
+                continue;
+            }
+            let Some((chunk, end)) = TeehistorianParserInner::decode_at(data, offset)
+                .map_err(|e| decode_error(offset, e))?
+            else {
+                break;
+            };
+            if chunk != Chunk::Eos {
+                self.ticks.observe(&chunk);
+                if let Some(order) = &mut self.order {
+                    order.observe(&chunk);
+                }
+                keep = end;
+            }
+            offset = end;
+        }
+
+        self.buffer = data[..keep].to_vec();
+        self.header_len = inner.chunks_start;
+        self.header_written = true;
+        Ok(())
+    }
+
+    /// Write an already serialized chunk, see `write()`
+    pub(crate) fn write_encoded(
+        &mut self,
+        py: Python<'_>,
+        chunk: &Bound<'_, PyAny>,
+        chunk_data: Vec<u8>,
+        tick: Option<i32>,
+    ) -> PyResult<()> {
+        if self.finished {
+            return Err(TeehistorianParseError::Validation(
+                "Cannot write to a finalized writer".to_string(),
+            )
+            .into());
+        }
+
+        let replaced = match (&self.deltas, decode(&chunk_data)) {
+            (Some(deltas), Some(decoded)) => match deltas.encode(&decoded) {
+                Delta::Keep => None,
+                Delta::Diff(diff) => Some(encode(&diff)?),
+                Delta::Unchanged => return Ok(()),
+            },
+            _ => None,
+        };
+        let chunk_data = replaced.unwrap_or(chunk_data);
+
+        if let Some(order) = &self.order
+            && let Some(decoded) = decode(&chunk_data)
+        {
+            order.check(&decoded)?;
+        }
+
+        if let Some(tick) = tick {
+            if self.tick_of(&chunk_data) != tick {
+                self.advance_to_tick(py, tick)?;
+            }
+            // A player chunk can open the next tick by itself
+            let actual = self.tick_of(&chunk_data);
+            if actual != tick {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Chunk would be written at tick {} instead of {}",
+                    actual, tick
+                ))
+                .into());
+            }
+        }
+
+        if let Some((uuid, name)) = self.new_extension(chunk, &chunk_data) {
+            if self.header_flushed {
+                return Err(TeehistorianParseError::Validation(format!(
+                    "Extension {} ({}) was not declared before the header was flushed, \
+                     pass it in TeehistorianWriter(extensions=...)",
+                    uuid, name
+                ))
+                .into());
+            }
+            self.extensions.push((uuid, name));
+            if self.header_written {
+                self.rewrite_header()?;
+            }
+        }
+
+        // Ensure header is written first
+        if !self.header_written {
+            self.write_header()?;
+        }
+
+        // Append to our buffer
+        self.buffer.extend_from_slice(&chunk_data);
+        Self::observe(&mut self.ticks, &chunk_data);
+        self.ended = matches!(decode(&chunk_data), Some(Chunk::Eos));
+        if let Some(order) = &mut self.order {
+            match decode(&chunk_data) {
+                Some(decoded) => order.observe(&decoded),
+                None if chunk_data.starts_with(&MAGIC) => *order = ChunkOrder::new(),
+                None => {}
+            }
+        }
+        if let Some(deltas) = &mut self.deltas {
+            match decode(&chunk_data) {
+                Some(decoded) => deltas.observe(&decoded),
+                None if chunk_data.starts_with(&MAGIC) => *deltas = DeltaEncoder::new(),
+                None => {}
+            }
+        }
+        if self.sink.is_none()
+            && let Some(threshold) = self.spill_threshold
+            && self.buffer.len() >= threshold
+        {
+            self.sink = Some(Sink::spill()?);
+            self.flush()?;
+            self.buffer.shrink_to(self.buffer_size);
+        }
+        if self.buffer.len() >= self.buffer_size {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Take over a parsed header, including its extension registrations
+    pub(crate) fn load_header(&mut self, header: Value) {
+        self.header_data = header;
+        self.extensions.clear();
+        if let Some(chunks) = self.header_data["__teehistorian_py"]["chunks"].as_object() {
+            for (uuid, chunk) in chunks {
+                if let Ok(uuid) = Uuid::parse_str(uuid) {
+                    let name = chunk["name"].as_str().unwrap_or_default();
+                    self.extensions.push((uuid, name.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Advance `ticks` past an encoded chunk
+    fn observe(ticks: &mut TickTracker, data: &[u8]) {
+        if data.starts_with(&MAGIC) {
+            // `SegmentStart` begins a concatenated stream
+            ticks.restart();
+        } else if let Some(chunk) = decode(data) {
+            ticks.observe(&chunk);
+        }
+    }
+
+    /// Tick an encoded chunk would be written at
+    fn tick_of(&self, data: &[u8]) -> i32 {
+        let mut ticks = self.ticks;
+        Self::observe(&mut ticks, data);
+        ticks.tick()
+    }
+
     /// Validates that the header hasn't been written yet
     fn ensure_header_not_written(&self) -> PyResult<()> {
         if self.header_written {
@@ -400,11 +1274,18 @@ impl PyTeehistorianWriter {
         Ok(())
     }
 
+    /// Set a header field from a string value, see `parse_header_value()`
+    fn set_header(&mut self, key: String, value: String) -> PyResult<()> {
+        self.ensure_header_not_written()?;
+        self.header_data[key] = Self::parse_header_value(&value);
+        Ok(())
+    }
+
     /// Parses a header value string into a JSON value
     ///
     /// Only parses JSON objects and arrays. All other values are stored as strings.
     /// This preserves string types for fields like "map_size": "299932"
-    fn parse_header_value(value: &str) -> Value {
+    pub(crate) fn parse_header_value(value: &str) -> Value {
         let is_json_like = (value.starts_with('{') && value.ends_with('}'))
             || (value.starts_with('[') && value.ends_with(']'));
 
@@ -435,8 +1316,50 @@ impl PyTeehistorianWriter {
             return Ok(());
         }
 
+        let header = self.encode_header()?;
+        self.header_len = header.len();
+        self.buffer.extend_from_slice(&header);
+        self.header_written = true;
+
+        Ok(())
+    }
+
+    /// Replace the header at the start of `buffer` with a fresh encoding
+    ///
+    /// Only possible until the header is flushed. The counting writer of
+    /// `estimate_size()` holds no data, so only its size changes.
+    fn rewrite_header(&mut self) -> PyResult<()> {
+        let header = self.encode_header()?;
+        if self.flushed == 0 {
+            self.buffer
+                .splice(..self.header_len, header.iter().copied());
+        } else {
+            self.flushed = self.flushed - self.header_len + header.len();
+        }
+        self.header_len = header.len();
+        Ok(())
+    }
+
+    /// Everything written so far, starting with the header
+    pub(crate) fn contents(&mut self) -> PyResult<Vec<u8>> {
+        if !self.header_written {
+            self.write_header()?;
+        }
+        match &mut self.sink {
+            Some(sink) => {
+                let mut data = sink.read_all()?;
+                data.extend_from_slice(&self.buffer);
+                Ok(data)
+            }
+            None => Ok(self.buffer.clone()),
+        }
+    }
+
+    /// Encode the UUID, JSON header and null terminator
+    fn encode_header(&mut self) -> PyResult<Vec<u8>> {
         // Add __teehistorian_py metadata with registered custom chunks
         self.populate_custom_chunk_metadata();
+        self.populate_extension_metadata();
 
         // The teehistorian UUID: 699db17b-8efb-34ff-b1d8-da6f60c15dd1
         // This is a version 3 UUID derived from the Teeworlds namespace
@@ -451,20 +1374,82 @@ impl PyTeehistorianWriter {
             TeehistorianParseError::Write(format!("Failed to serialize header: {}", e))
         })?;
 
+        let mut header = Vec::with_capacity(uuid_bytes.len() + header_str.len() + 1);
+
         // Step 1: Write the fixed UUID (16 bytes)
-        self.buffer.extend_from_slice(&uuid_bytes);
+        header.extend_from_slice(&uuid_bytes);
 
         // Step 2: Write the JSON header string
-        self.buffer.extend_from_slice(header_str.as_bytes());
+        header.extend_from_slice(header_str.as_bytes());
 
         // Step 3: Write the null terminator
-        self.buffer.push(0);
+        header.push(0);
 
-        self.header_written = true;
+        Ok(header)
+    }
+
+    /// UUID and name of an extension chunk that needs a registration
+    ///
+    /// None if the UUID is registered already. Extensions defined by DDNet
+    /// are known to every parser and need no registration, `Unknown` chunks
+    /// have no name to register.
+    fn new_extension(&self, chunk: &Bound<'_, PyAny>, data: &[u8]) -> Option<(Uuid, String)> {
+        let Some((TAG_EX, len)) = read_tag(data) else {
+            return None;
+        };
+        let uuid = Uuid::from_slice(data.get(len..len + 16)?).ok()?;
+        if self.extensions.iter().any(|(known, _)| *known == uuid)
+            || extensions::lookup(&uuid).is_some()
+        {
+            return None;
+        }
+        let name = chunk.getattr("handler_name").ok()?.extract().ok()?;
+        Some((uuid, name))
+    }
 
+    /// Register a custom extension UUID before any of its chunks is written
+    fn declare_extension(&mut self, uuid: &str, name: String) -> PyResult<()> {
+        let uuid = Uuid::parse_str(uuid).map_err(|e| {
+            TeehistorianParseError::Validation(format!("Invalid extension UUID {}: {}", uuid, e))
+        })?;
+        if extensions::lookup(&uuid).is_some() {
+            return Err(TeehistorianParseError::Validation(format!(
+                "{} is a DDNet extension and needs no registration",
+                uuid
+            ))
+            .into());
+        }
+        if !self.extensions.iter().any(|(known, _)| *known == uuid) {
+            self.extensions.push((uuid, name));
+        }
         Ok(())
     }
 
+    /// Register the extension UUIDs written so far in __teehistorian_py
+    ///
+    /// Parsers of this library register them again when reading the file,
+    /// so the chunks come back as `CustomChunk` with their name.
+    fn populate_extension_metadata(&mut self) {
+        if self.extensions.is_empty() {
+            return;
+        }
+
+        let meta = &mut self.header_data["__teehistorian_py"];
+        if !meta.is_object() {
+            *meta = json!({ "version": "0.1.0" });
+        }
+        let chunks = &mut meta["chunks"];
+        if !chunks.is_object() {
+            *chunks = json!({});
+        }
+        for (uuid, name) in &self.extensions {
+            let uuid = uuid.to_string();
+            if chunks.get(&uuid).is_none() {
+                chunks[uuid] = json!({ "name": name, "fields": {} });
+            }
+        }
+    }
+
     /// Populate __teehistorian_py metadata with registered custom chunks
     fn populate_custom_chunk_metadata(&mut self) {
         // Only include metadata if enabled
@@ -528,6 +1513,20 @@ impl PyTeehistorianWriter {
     }
 }
 
+impl Drop for PyTeehistorianWriter {
+    fn drop(&mut self) {
+        // Chunks still buffered would be lost otherwise, errors have no caller
+        match &self.sink {
+            Some(Sink::Spill { path, .. }) => {
+                let _ = std::fs::remove_file(path);
+            }
+            _ => {
+                let _ = self.flush();
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -544,17 +1543,16 @@ mod tests {
     fn test_header_operations() {
         let mut writer = PyTeehistorianWriter::new();
 
-        // Test setting and getting header fields
+        // Test setting and removing header fields
         writer
             .set_header("test_key".to_string(), "test_value".to_string())
             .unwrap();
-        assert_eq!(
-            writer.get_header("test_key".to_string()),
-            Some("test_value".to_string())
-        );
+        assert_eq!(writer.header_data["test_key"], json!("test_value"));
+        writer.remove_header("test_key").unwrap();
+        assert_eq!(writer.header_data.get("test_key"), None);
 
-        // Test getting non-existent field
-        assert_eq!(writer.get_header("non_existent".to_string()), None);
+        // Removing a non-existent field does nothing
+        writer.remove_header("non_existent").unwrap();
     }
 
     #[test]
@@ -563,7 +1561,7 @@ mod tests {
         writer.write_header().unwrap();
         assert!(!writer.is_empty());
 
-        writer.reset();
+        writer.reset().unwrap();
         assert!(writer.is_empty());
         assert!(!writer.header_written);
     }
@@ -0,0 +1,261 @@
+#!/usr/bin/env python3
+"""Test the whole-recording analysis classes and functions."""
+
+from pathlib import Path
+
+import pytest
+import teehistorian_py as th
+
+RECORDING = Path(__file__).parent / "recording.teehistorian"
+
+
+def test_player_tracker():
+    tracker = th.PlayerTracker(RECORDING.read_bytes())
+    assert tracker.client_ids == [0]
+    assert tracker.trajectory(0) == [(1, 100, 200), (7, 101, 199)]
+    assert tracker.positions_at(0) == {}
+    assert tracker.positions_at(6) == {0: (100, 200)}
+    # Dropped in the tick it last moved
+    assert tracker.positions_at(7) == {}
+    assert tracker.trajectory(1) == []
+
+
+def test_player_tracker_snapshots():
+    writer = th.TeehistorianWriter()
+    writer.write_join(0).write_join(1)
+    writer.write(th.PlayerNew(0, 10, 10), tick=1)
+    writer.write(th.PlayerNew(1, 50, 50), tick=1)
+    writer.write(th.PlayerNew(0, 12, 10), tick=3)
+    writer.write(th.PlayerNew(0, 12, 10), tick=4)
+    writer.write(th.PlayerOld(1), tick=5)
+    writer.finalize()
+    first = writer.getvalue()
+    writer = th.TeehistorianWriter()
+    writer.write_join(0)
+    writer.write(th.PlayerNew(0, 0, 0), tick=2)
+    writer.finalize()
+
+    tracker = th.PlayerTracker(first + writer.getvalue())
+    assert list(tracker.snapshots()) == [
+        (1, {0: (10, 10), 1: (50, 50)}),
+        (3, {0: (12, 10), 1: (50, 50)}),
+        # The characters of the first stream are gone with the second one
+        (5, {}),
+        (7, {0: (0, 0)}),
+    ]
+    assert tracker.ticks == [1, 3, 5, 7]
+    assert tracker.trajectory(0) == [(1, 10, 10), (3, 12, 10), (7, 0, 0)]
+    assert tracker.positions_at(100) == {0: (0, 0)}
+    assert tracker.kinematics(0) == [
+        (2, 0, 0, 0, 0),
+        (3, 2, 0, 2, 0),
+        (4, 0, 0, -2, 0),
+        # The second stream spawns the character again
+    ]
+    assert tracker.kinematics(1) == [(2, 0, 0, 0, 0), (3, 0, 0, 0, 0), (4, 0, 0, 0, 0)]
+    assert tracker.kinematics(5) == []
+
+    with pytest.raises(th.TeehistorianError):
+        th.PlayerTracker(b"not a recording")
+
+
+def test_extract_sessions():
+    sessions = th.extract_sessions(RECORDING.read_bytes())
+    assert [(s.cid, s.name, s.join_tick, s.drop_tick) for s in sessions] == [
+        (0, "Player", 0, 7)
+    ]
+    assert sessions[0].drop_reason == "quit"
+    assert sessions[0].duration == 7 / 50
+
+    writer = th.TeehistorianWriter()
+    writer.write_join(0).write_join(1)
+    writer.write(th.PlayerName(0, "first"), tick=0)
+    writer.write(th.DdnetVersion(1, "00" * 16, 19000, b"DDNet 19.0"), tick=0)
+    writer.write(th.Drop(0, "Timeout"), tick=100)
+    writer.write_join(0)
+    writer.write(th.PlayerName(0, "second"), tick=150)
+    writer.write(th.PlayerName(0, "renamed"), tick=200)
+    writer.finalize()
+
+    sessions = th.extract_sessions(writer.getvalue())
+    assert [(s.cid, s.name, s.join_tick, s.drop_tick) for s in sessions] == [
+        (0, "first", 0, 100),
+        (1, None, 0, None),
+        (0, "renamed", 100, None),
+    ]
+    assert [s.drop_reason for s in sessions] == ["Timeout", None, None]
+    assert sessions[1].ddnet_version == 19000
+    assert sessions[1].duration == sessions[2].duration + 2 == 4
+    assert repr(sessions[0]) == "PlayerSession(cid=0, name='first', join_tick=0, drop_tick=100)"
+    assert repr(sessions[1]) == "PlayerSession(cid=1, name=None, join_tick=0, drop_tick=None)"
+
+
+def test_input_stats():
+    writer = th.TeehistorianWriter()
+    writer.write_join(0).write_join(1)
+    inputs = [
+        th.PlayerInput(),
+        th.PlayerInput(direction=1, jump=1, target_x=3, target_y=4),
+        th.PlayerInput(direction=-1, fire=1, hook=1, target_x=3, target_y=4),
+        # Released fire and pressed it again within one input
+        th.PlayerInput(direction=-1, fire=3),
+    ]
+    for tick, player_input in enumerate(inputs, 1):
+        writer.write(th.InputNew(0, player_input.to_list()), tick=tick * 50)
+        if tick == 1:
+            writer.write(th.InputNew(1, th.PlayerInput(jump=1).to_list()), tick=60)
+    writer.write(th.Drop(0, "quit"), tick=250)
+    writer.finalize()
+
+    stats = th.input_stats(writer.getvalue())
+    assert list(stats) == [0, 1]
+    assert stats[0] == {
+        "inputs": 3,
+        "jumps": 1,
+        "hooks": 1,
+        "fires": 2,
+        "direction_changes": 2,
+        "mouse_distance": 5.0 + 5.0,
+        "active_seconds": 4.0,
+        "apm": 90.0,
+        "direction_changes_per_minute": 30.0,
+    }
+    # A single input changes nothing
+    assert stats[1]["inputs"] == 0
+    assert stats[1]["active_seconds"] == 3.8
+
+
+def test_input_stats_extreme_targets():
+    writer = th.TeehistorianWriter()
+    writer.write_join(0)
+    for tick, target in enumerate([2**31 - 1, -(2**31)], 1):
+        writer.write(th.InputNew(0, th.PlayerInput(target_x=target).to_list()), tick=tick)
+    writer.finalize()
+
+    stats = th.input_stats(writer.getvalue())
+    assert stats[0]["mouse_distance"] == 2**32 - 1
+
+
+def test_detect_anomalies():
+    chunks = [
+        # Teleported 1000 units in one tick
+        (1, th.PlayerNew(0, 0, 0)),
+        (2, th.PlayerNew(0, 1000, 0)),
+        (3, th.PlayerNew(0, 1010, 0)),
+        (5, th.InputNew(1, th.PlayerInput().to_list())),
+        (5, th.InputNew(2, th.PlayerInput().to_list())),
+    ]
+    # Jumped every 10 ticks
+    for tick in range(10, 130, 10):
+        chunks.append((tick, th.InputNew(1, th.PlayerInput(jump=1).to_list())))
+        chunks.append((tick + 5, th.InputNew(1, th.PlayerInput().to_list())))
+    # Changed direction every tick
+    for tick in range(201, 231):
+        direction = th.PlayerInput(direction=tick % 2 * 2 - 1)
+        chunks.append((tick, th.InputNew(2, direction.to_list())))
+    chunks.append((300, th.InputNew(2, th.PlayerInput().to_list())))
+
+    writer = th.TeehistorianWriter()
+    writer.write_join(0).write_join(1).write_join(2)
+    for tick, chunk in sorted(chunks, key=lambda c: (c[0], c[1].client_id)):
+        writer.write(chunk, tick=tick)
+    writer.write(th.Drop(1, "quit"), tick=300)
+    writer.finalize()
+
+    anomalies = th.detect_anomalies(writer.getvalue())
+    assert [(a.kind, a.cid, a.tick, a.end_tick) for a in anomalies] == [
+        ("teleport", 0, 1, 2),
+        ("periodic_input", 1, 10, 120),
+        ("input_rate", 2, 221, 230),
+    ]
+    assert anomalies[0].score == 1 - 128 / 2000
+    assert anomalies[1].details == "Pressed jump 12 times in a row exactly 10 ticks apart"
+    assert anomalies[2].details == "30 presses within one second"
+    assert all(0.5 < a.score < 1 for a in anomalies)
+    assert anomalies[0].details == "Moved 1000 units from (0, 0) to (1000, 0) in one tick"
+
+    relaxed = th.detect_anomalies(
+        writer.getvalue(), max_speed=2000, max_presses_per_second=50, min_periodic_presses=12
+    )
+    assert relaxed == []
+    with pytest.raises(th.TeehistorianError, match="positive"):
+        th.detect_anomalies(writer.getvalue(), max_speed=0)
+
+
+def test_detect_anomalies_after_idle_gap():
+    writer = th.TeehistorianWriter(delta_encode=True)
+    writer.write_join(0)
+    # Stood still for five ticks, then jumped 500 units twice in a row
+    for tick, x in [(1, 0), (6, 500), (7, 1000)]:
+        writer.write(th.PlayerNew(0, x, 0), tick=tick)
+    writer.finalize()
+
+    anomalies = th.detect_anomalies(writer.getvalue())
+    assert [(a.kind, a.tick, a.end_tick) for a in anomalies] == [
+        ("teleport", 5, 6),
+        ("teleport", 6, 7),
+    ]
+    assert anomalies[0].score == anomalies[1].score
+
+
+def test_extract_finishes():
+    writer = th.TeehistorianWriter()
+    writer.write_join(0).write_join(1).write_join(2)
+    writer.write(th.PlayerName(0, "solo"), tick=0)
+    writer.write(th.PlayerName(1, "left"), tick=0)
+    writer.write(th.PlayerTeam(1, 3), tick=10)
+    writer.write(th.PlayerTeam(2, 3), tick=10)
+    writer.write(th.PlayerFinish(0, 500), tick=600)
+    writer.write(th.TeamPractice(3, 1), tick=650)
+    writer.write(th.PlayerFinish(1, 650), tick=700)
+    writer.write(th.PlayerFinish(2, 650), tick=700)
+    writer.write(th.TeamFinish(3, 650), tick=700)
+    writer.finalize()
+
+    finishes = th.extract_finishes(writer.getvalue())
+    assert [(f.kind, f.cids, f.names, f.team, f.tick, f.practice) for f in finishes] == [
+        ("player", [0], ["solo"], 0, 600, False),
+        ("player", [1], ["left"], 3, 700, True),
+        ("player", [2], [None], 3, 700, True),
+        ("team", [1, 2], ["left", None], 3, 700, True),
+    ]
+    assert finishes[0].seconds == 10.0
+    assert finishes[0].start_tick == 100
+    assert finishes[3].time == 650
+    assert th.extract_finishes(RECORDING.read_bytes()) == []
+
+
+def test_verify_run():
+    writer = th.TeehistorianWriter()
+    writer.write_join(0).write_join(1)
+    writer.write(th.PlayerNew(0, 0, 0), tick=10)
+    writer.write(th.PlayerNew(1, 0, 0), tick=10)
+    writer.write(th.PlayerOld(1), tick=300)
+    writer.write(th.PlayerNew(1, 0, 0), tick=301)
+    writer.write(th.Join(2), tick=400)
+    writer.write(th.PlayerFinish(0, 500), tick=600)
+    writer.write(th.PlayerFinish(1, 450), tick=700)
+    writer.write(th.PlayerFinish(2, 1000), tick=800)
+    writer.finalize()
+    data = writer.getvalue()
+
+    result = th.verify_run(data, 0, 10.0)
+    assert result.verified
+    assert (result.recorded_time, result.start_tick, result.finish_tick) == (10.0, 100, 600)
+    assert th.verify_run(data, 0, 10.02).verified
+    assert th.verify_run(data, 0, 9.5).discrepancies == [
+        "Claimed 9.50 s, but the recorded time is 10.00 s (500 ticks at 50 ticks per second)"
+    ]
+    assert not th.verify_run(data, 0, 10.5, tolerance=0.4).verified
+    assert th.verify_run(data, 0, 10.5, tolerance=0.5).verified
+
+    assert th.verify_run(data, 1, 9.0).discrepancies == [
+        "Character was killed at tick 300 during the race"
+    ]
+    assert th.verify_run(data, 2, 20.0).discrepancies == [
+        "Race started at tick -200, before the recording",
+        "Client joined at tick 400, after the race started at tick -200",
+    ]
+    result = th.verify_run(data, 3, 10.0)
+    assert result.discrepancies == ["No finish of client 3"]
+    assert result.recorded_time is None
@@ -0,0 +1,109 @@
+#!/usr/bin/env python3
+"""
+Tests for parsing compressed teehistorian input.
+"""
+
+import gzip
+import tempfile
+from pathlib import Path
+
+try:
+    import pytest
+except ImportError:
+    # Fallback for running without pytest
+    import unittest as pytest
+
+import teehistorian_py as th
+
+FIXTURES = Path(__file__).parent
+
+
+def build_recording() -> bytes:
+    """Build a small uncompressed recording."""
+    writer = th.create(server_name="Compressed")
+    writer.write(th.Join(0))
+    writer.write(th.PlayerName(0, "Player"))
+    writer.write(th.TickSkip(5))
+    writer.write(th.Drop(0, "quit"))
+    writer.write(th.Eos())
+    return writer.getvalue()
+
+
+def chunk_types(parser) -> list:
+    return [chunk.chunk_type() for chunk in parser]
+
+
+# ============================================================================
+# Gzip Input Tests
+# ============================================================================
+
+
+class TestGzipInput:
+    """Test transparent gzip decompression."""
+
+    def test_gzip_bytes(self):
+        """Test gzip-compressed bytes parse like the raw data."""
+        data = build_recording()
+        parser = th.Teehistorian(gzip.compress(data))
+        assert parser.header() == th.Teehistorian(data).header()
+        assert chunk_types(parser) == chunk_types(th.Teehistorian(data))
+
+    def test_gzip_file(self):
+        """Test parse() accepts .teehistorian.gz files."""
+        data = build_recording()
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "recording.teehistorian.gz"
+            path.write_bytes(gzip.compress(data))
+            assert chunk_types(th.parse(path)) == chunk_types(th.Teehistorian(data))
+
+    def test_concatenated_gzip_members(self):
+        """Test multi-member gzip streams are decompressed completely."""
+        data = build_recording()
+        compressed = gzip.compress(data[:40]) + gzip.compress(data[40:])
+        assert chunk_types(th.Teehistorian(compressed)) == chunk_types(
+            th.Teehistorian(data)
+        )
+
+    def test_corrupt_gzip(self):
+        """Test corrupt gzip data raises a parse error."""
+        compressed = gzip.compress(build_recording())
+        with pytest.raises(th.TeehistorianError):
+            th.Teehistorian(compressed[:-12] + b"\x00" * 12)
+
+    def test_resume_gzip(self):
+        """Test a saved state can resume on the compressed data."""
+        compressed = gzip.compress(build_recording())
+        parser = th.Teehistorian(compressed)
+        next(parser)
+        next(parser)
+
+        resumed = th.Teehistorian.resume(compressed, parser.save_state())
+        assert chunk_types(resumed) == ["TickSkip", "Drop", "Eos"]
+
+
+# ============================================================================
+# Zstandard Input Tests
+# ============================================================================
+
+
+class TestZstdInput:
+    """Test transparent zstd decompression."""
+
+    def test_zstd_file(self):
+        """Test parse() accepts .teehistorian.zst files."""
+        raw = th.parse(FIXTURES / "recording.teehistorian")
+        compressed = th.parse(FIXTURES / "recording.teehistorian.zst")
+        assert compressed.header() == raw.header()
+        assert chunk_types(compressed) == chunk_types(raw)
+
+    def test_zstd_bytes(self):
+        """Test zstd-compressed bytes are detected by their magic."""
+        data = (FIXTURES / "recording.teehistorian.zst").read_bytes()
+        assert data.startswith(b"\x28\xb5\x2f\xfd")
+        assert chunk_types(th.Teehistorian(data))[-1] == "Eos"
+
+    def test_corrupt_zstd(self):
+        """Test truncated zstd data raises a parse error."""
+        data = (FIXTURES / "recording.teehistorian.zst").read_bytes()
+        with pytest.raises(th.TeehistorianError):
+            th.Teehistorian(data[:40])
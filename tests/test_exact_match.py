@@ -34,3 +34,53 @@ def test_roundtrip_exact_match():
     assert original_data == output_data, (
         f"Files differ: original={len(original_data)} bytes, output={len(output_data)} bytes"
     )
+
+
+def recorded(chunk, old, new):
+    """Chunk as a server would record it, with `old` in its encoding replaced.
+
+    `new` must have the same length, the chunk sizes are not updated.
+    """
+    data = bytes(chunk.write_to_buffer())
+    assert old in data
+    return th.RawChunk.from_bytes(data.replace(old, new))
+
+
+def test_roundtrip_recording():
+    data = Path("tests/recording.teehistorian").read_bytes()
+    assert th.roundtrip(data) == data
+
+
+def test_roundtrip_preserves_recorded_bytes():
+    writer = th.TeehistorianWriter()
+    writer.write_all([
+        th.Join(0),
+        recorded(th.PlayerName(0, "name"), b"name", b"n\xffme"),
+        th.InputNew(0, [1] * 10),
+        th.InputDiff(0, [0, 1] + [0] * 8),
+        th.InputNew(0, [2] * 10),
+        recorded(th.ConsoleCommand(0, 1, "say", ["hi"]), b"hi", b"\xffi"),
+        recorded(th.AuthLogin(0, 2, "admin"), b"admin", b"ad\xffin"),
+        recorded(th.AntiBot("event"), b"event", b"\x00\xffv\x01t"),
+        th.NetMessagePlayerInfo(0, "ClStartInfo", "name", "clan", 5, "santa", True, 1, 2),
+        th.Unknown(th.derive_uuid("test@example.org"), b"\x01\x02"),
+        th.Drop(0, b"\xffquit"),
+        th.Eos(),
+    ])
+    data = writer.getvalue()
+    assert th.roundtrip(data) == data
+    # A second recording appended to the first
+    assert th.roundtrip(data + data) == data + data
+
+
+def test_roundtrip_rejects_truncated_data():
+    data = th.create().write(th.Join(0)).write(th.PlayerNew(0, 100, 200)).getvalue()
+    with pytest.raises(AssertionError, match="middle of a chunk"):
+        th.roundtrip(data[:-1])
+
+
+def test_antibot_bytes():
+    chunk = th.AntiBot(b"\x00\xff")
+    assert chunk.data_bytes == b"\x00\xff"
+    assert chunk.data == "\x00�"
+    assert th.AntiBot("event").data_bytes == b"event"
@@ -0,0 +1,291 @@
+#!/usr/bin/env python3
+"""
+Tests for the typed view of the teehistorian header.
+"""
+
+import json
+from datetime import datetime, timedelta, timezone
+
+try:
+    import pytest
+except ImportError:
+    # Fallback for running without pytest
+    import unittest as pytest
+
+import teehistorian_py as th
+
+DDNET_HEADER = {
+    "map_name": "Kobra 4",
+    "map_sha256": "3c1e7ed0c6bd3c0a4a3e3ebd0c53a4b4a4c7f0a8f4d8e1a3c0e5e0fd9b1e6c2a",
+    "map_crc": "0a1b2c3d",
+    "server_version": "0.6.4, 18.0.3",
+    "start_time": "2024-05-01T18:30:00+0200",
+    "game_uuid": "5f1f4a2c-0d8e-4b7e-9a63-1c2d3e4f5a6b",
+}
+
+
+def build_recording(**headers: str) -> bytes:
+    writer = th.create(**headers)
+    writer.write(th.Eos())
+    return writer.getvalue()
+
+
+# ============================================================================
+# Typed Header Tests
+# ============================================================================
+
+
+class TestHeaderInfo:
+    """Test the typed fields of Teehistorian.header_info."""
+
+    def test_typed_fields(self):
+        """Test well-known fields are converted to proper types."""
+        info = th.Teehistorian(build_recording(**DDNET_HEADER)).header_info
+        assert isinstance(info, th.Header)
+        assert info.map_name == "Kobra 4"
+        assert info.map_sha256 == DDNET_HEADER["map_sha256"]
+        assert info.map_crc == 0x0A1B2C3D
+        assert info.server_version == "0.6.4, 18.0.3"
+        assert info.start_time == "2024-05-01T18:30:00+0200"
+        assert info.game_uuid == DDNET_HEADER["game_uuid"]
+
+    def test_missing_fields(self):
+        """Test fields the server did not write are None."""
+        info = th.Teehistorian(build_recording()).header_info
+        assert info.map_name is None
+        assert info.map_crc is None
+        assert info.start_time is None
+        assert info.server_version == "teehistorian-py 2.0.0"
+
+    def test_repr(self):
+        """Test the repr names the map."""
+        info = th.Teehistorian(build_recording(**DDNET_HEADER)).header_info
+        assert "Kobra 4" in repr(info)
+
+    def test_config(self):
+        """Test the embedded server config is exposed as a dict."""
+        data = build_recording(config='{"sv_motd": "Welcome", "sv_team": "1"}')
+        config = th.Teehistorian(data).header_info.config
+        assert config == {"sv_motd": "Welcome", "sv_team": "1"}
+
+    def test_config_missing(self):
+        """Test a header without config gives an empty dict."""
+        assert th.Teehistorian(build_recording()).header_info.config == {}
+
+
+class TestTuning:
+    """Test the physics tuning recorded in the header."""
+
+    def test_default_tuning(self):
+        """Test a header without tuning gives DDNet's defaults."""
+        tuning = th.Teehistorian(build_recording()).header_info.tuning
+        assert isinstance(tuning, th.Tuning)
+        assert tuning.is_default
+        assert tuning.gravity == 0.5
+        assert tuning.player_hooking == 1.0
+        assert tuning.changed == {}
+
+    def test_changed_tuning(self):
+        """Test fixed-point values written by the server are converted."""
+        data = build_recording(tuning='{"gravity": "25", "player_hooking": "0"}')
+        tuning = th.Teehistorian(data).header_info.tuning
+        assert not tuning.is_default
+        assert tuning.gravity == 0.25
+        assert tuning.player_hooking == 0.0
+        assert tuning.ground_jump_impulse == 13.2
+        assert tuning.changed == {"gravity": 0.25, "player_hooking": 0.0}
+        assert tuning.to_dict()["gravity"] == 0.25
+
+
+class TestHeaderJson:
+    """Test Teehistorian.header_json()."""
+
+    def test_header_json(self):
+        """Test the header is returned as a dict with nested objects."""
+        data = build_recording(map_name="Kobra 4", config='{"sv_motd": "Welcome"}')
+        header = th.Teehistorian(data).header_json()
+        assert header["map_name"] == "Kobra 4"
+        assert header["config"] == {"sv_motd": "Welcome"}
+        assert header["version"] == "2"
+
+    def test_malformed_header(self):
+        """Test a malformed header raises a descriptive error."""
+        magic = bytes.fromhex("699db17b8efb34ff b1d8da6f60c15dd1".replace(" ", ""))
+        data = magic + b'{"map_name": "Kobra 4",}\x00'
+        with pytest.raises(th.TeehistorianError, match="header JSON"):
+            th.Teehistorian(data).header_json()
+
+
+class TestHeaderComparison:
+    """Test Header.compatible_with() and Header.diff()."""
+
+    def test_same_setup(self):
+        """Test headers differing only in unrelated fields are compatible."""
+        ours = th.Teehistorian(build_recording(**DDNET_HEADER)).header_info
+        other = dict(DDNET_HEADER, start_time="2024-05-02T10:00:00+0200")
+        theirs = th.Teehistorian(build_recording(**other)).header_info
+        assert ours.compatible_with(theirs)
+        assert ours.diff(theirs) == {}
+
+    def test_different_setup(self):
+        """Test map hash, version and tuning differences are reported."""
+        ours = th.Teehistorian(build_recording(**DDNET_HEADER)).header_info
+        other = dict(
+            DDNET_HEADER,
+            map_crc="ffffffff",
+            server_version="0.6.4, 19.0",
+            tuning='{"gravity": "25"}',
+        )
+        theirs = th.Teehistorian(build_recording(**other)).header_info
+        assert not ours.compatible_with(theirs)
+        assert ours.diff(theirs) == {
+            "map_crc": ("0a1b2c3d", "ffffffff"),
+            "server_version": ("0.6.4, 18.0.3", "0.6.4, 19.0"),
+            "tuning.gravity": (0.5, 0.25),
+        }
+
+
+class TestPrng:
+    """Test the PRNG fields of the header."""
+
+    def test_prng_seed(self):
+        """Test the seed words are parsed from the PRNG description."""
+        description = "pcg-xsh-rr:0123456789abcdef:fedcba9876543210"
+        data = build_recording(prng_description=description, **DDNET_HEADER)
+        info = th.Teehistorian(data).header_info
+        assert info.prng_description == description
+        assert info.prng_seed == (0x0123456789ABCDEF, 0xFEDCBA9876543210)
+        assert info.game_uuid == DDNET_HEADER["game_uuid"]
+
+    def test_prng_missing(self):
+        """Test recordings without PRNG description."""
+        info = th.Teehistorian(build_recording()).header_info
+        assert info.prng_description is None
+        assert info.prng_seed is None
+
+
+class TestRewriteHeader:
+    """Test th.rewrite_header()."""
+
+    def test_replace_fields(self):
+        """Test fields are replaced and the chunks are kept byte for byte."""
+        writer = th.create(
+            server_name="Wrong", config='{"sv_motd": "hi", "sv_rcon_password": "x"}'
+        )
+        writer.write(th.Join(3))
+        writer.write(th.Eos())
+        data = writer.getvalue()
+
+        rewritten = th.rewrite_header(
+            data,
+            {"server_name": "KoG", "config": '{"sv_rcon_password": null}'},
+        )
+        parser = th.Teehistorian(rewritten)
+        header = parser.header_json()
+        assert header["server_name"] == "KoG"
+        assert header["config"] == {"sv_motd": "hi"}
+
+        old_body = data[th.Teehistorian(data).body_offset :]
+        assert rewritten[parser.body_offset :] == old_body
+
+    def test_remove_field(self):
+        """Test None removes a field."""
+        data = th.rewrite_header(build_recording(**DDNET_HEADER), {"game_uuid": None})
+        assert th.Teehistorian(data).header_info.game_uuid is None
+
+    def test_not_teehistorian(self):
+        """Test unrelated data is rejected."""
+        with pytest.raises(th.TeehistorianError, match="magic UUID"):
+            th.rewrite_header(b"not a recording", {})
+
+
+class TestHeaderValidate:
+    """Test Header.validate()."""
+
+    def test_conforming_header(self):
+        """Test a complete DDNet header has no violations."""
+        info = th.Teehistorian(build_recording(**DDNET_HEADER)).header_info
+        assert info.validate() == []
+
+    def test_violations(self):
+        """Test missing and malformed fields are reported."""
+        data = build_recording(
+            **dict(DDNET_HEADER, game_uuid="not-a-uuid", start_time="yesterday"),
+        )
+        data = th.rewrite_header(data, {"map_sha256": None})
+        violations = th.Teehistorian(data).header_info.validate()
+        assert len(violations) == 3
+        assert "Missing field 'map_sha256'" in violations
+        assert any("game_uuid" in v for v in violations)
+        assert any("start_time" in v for v in violations)
+
+
+class TestHeaderSpan:
+    """Test Teehistorian.header_span and Teehistorian.body_offset."""
+
+    def test_span(self):
+        """Test the span covers exactly the JSON header."""
+        data = build_recording(**DDNET_HEADER)
+        parser = th.Teehistorian(data)
+        start, end = parser.header_span
+        assert start == 16
+        assert data[start:end] == parser.header()
+        assert data[end] == 0
+        assert parser.body_offset == end + 1
+
+    def test_splice(self):
+        """Test a body can be spliced onto another header."""
+        data = build_recording(**DDNET_HEADER)
+        other = build_recording(map_name="Other")
+        parser = th.Teehistorian(other)
+        spliced = other[: parser.body_offset] + data[th.Teehistorian(data).body_offset :]
+        assert th.Teehistorian(spliced).header_info.map_name == "Other"
+        assert len(list(th.Teehistorian(spliced))) == 1
+
+
+class TestStartDatetime:
+    """Test Header.start_datetime and Header.tick_to_datetime()."""
+
+    def test_start_datetime(self):
+        """Test the start time is parsed with its UTC offset."""
+        info = th.Teehistorian(build_recording(**DDNET_HEADER)).header_info
+        start = info.start_datetime
+        assert start == datetime(
+            2024, 5, 1, 18, 30, tzinfo=timezone(timedelta(hours=2))
+        )
+        assert start.utcoffset() == timedelta(hours=2)
+
+    def test_tick_to_datetime(self):
+        """Test ticks are converted at 50 ticks per second."""
+        info = th.Teehistorian(build_recording(**DDNET_HEADER)).header_info
+        assert info.tick_to_datetime(0) == info.start_datetime
+        assert info.tick_to_datetime(75) == info.start_datetime + timedelta(
+            seconds=1.5
+        )
+
+    def test_unparseable_start_time(self):
+        """Test headers without a usable start time give None."""
+        info = th.Teehistorian(build_recording(start_time="yesterday")).header_info
+        assert info.start_datetime is None
+        assert info.tick_to_datetime(100) is None
+
+
+class TestUuidRegistrations:
+    """Test Teehistorian.uuid_registrations."""
+
+    def test_registrations_listed(self):
+        """Test chunk registrations in the header are exposed."""
+        uuid = "6bb8ba88-0f0b-382e-8dae-dbf4052b8b7d"
+        meta = {"version": "0.1.0", "chunks": {uuid: {"name": "Example", "fields": {}}}}
+        data = build_recording(__teehistorian_py=json.dumps(meta))
+
+        registrations = th.Teehistorian(data).uuid_registrations
+        assert len(registrations) == 1
+        assert isinstance(registrations[0], th.UuidRegistration)
+        assert (registrations[0].uuid, registrations[0].name) == (uuid, "Example")
+        assert registrations[0] == th.UuidRegistration(uuid, "Example")
+        assert "UuidRegistration" not in th.chunk_types()
+
+    def test_no_registrations(self):
+        """Test recordings without registrations give an empty list."""
+        assert th.Teehistorian(build_recording()).uuid_registrations == []
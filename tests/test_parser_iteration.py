@@ -0,0 +1,1355 @@
+#!/usr/bin/env python3
+"""
+Tests for the parser's native iteration helpers.
+
+Covers tick tracking and the filtered iterators that skip chunks in Rust.
+"""
+
+try:
+    import pytest
+except ImportError:
+    # Fallback for running without pytest
+    import unittest as pytest
+
+import pickle
+import tempfile
+import threading
+import time
+from pathlib import Path
+
+import teehistorian_py as th
+
+
+def build_recording() -> bytes:
+    """Build a small recording spanning ticks 1 to 12.
+
+    Tick layout:
+        1:  PlayerNew(0), PlayerNew(1)
+        2:  PlayerDiff(0), PlayerDiff(1)   (implicit tick)
+        10: TickSkip(7), PlayerDiff(1), Join(2)
+        11: PlayerDiff(0)                  (implicit tick)
+        12: TickSkip(0), ConsoleCommand(0)
+    """
+    writer = th.create()
+    writer.write(th.PlayerNew(0, 100, 200))
+    writer.write(th.PlayerNew(1, 300, 400))
+    writer.write(th.PlayerDiff(0, 1, 1))
+    writer.write(th.PlayerDiff(1, 2, 2))
+    writer.write(th.TickSkip(7))
+    writer.write(th.PlayerDiff(1, 3, 3))
+    writer.write(th.Join(2))
+    writer.write(th.PlayerDiff(0, 4, 4))
+    writer.write(th.TickSkip(0))
+    writer.write(th.ConsoleCommand(0, 0, "kill", []))
+    writer.write(th.Eos())
+    return writer.getvalue()
+
+
+# ============================================================================
+# Tick Tracking Tests
+# ============================================================================
+
+
+class TestTickTracking:
+    """Test that the parser derives ticks from the chunk stream."""
+
+    def test_tick_starts_at_zero(self):
+        """Test a fresh parser reports tick 0."""
+        parser = th.Teehistorian(build_recording())
+        assert parser.tick == 0
+
+    def test_ticks_follow_stream(self):
+        """Test ticks advance on TickSkip and on implicit player ticks."""
+        parser = th.Teehistorian(build_recording())
+        ticks = []
+        for chunk in parser:
+            ticks.append((chunk.chunk_type(), parser.tick))
+
+        assert ticks == [
+            ("PlayerNew", 1),
+            ("PlayerNew", 1),
+            ("PlayerDiff", 2),
+            ("PlayerDiff", 2),
+            ("TickSkip", 10),
+            ("PlayerDiff", 10),
+            ("Join", 10),
+            ("PlayerDiff", 11),
+            ("TickSkip", 12),
+            ("ConsoleCommand", 12),
+            ("Eos", 12),
+        ]
+
+    def test_iter_with_ticks(self):
+        """Test iter_with_ticks pairs every chunk with its tick."""
+        expected = []
+        parser = th.Teehistorian(build_recording())
+        for chunk in parser:
+            expected.append((parser.tick, chunk.chunk_type()))
+
+        parser = th.Teehistorian(build_recording())
+        pairs = [(tick, c.chunk_type()) for tick, c in parser.iter_with_ticks()]
+        assert pairs == expected
+        assert parser.tick == 12
+
+    def test_iter_with_ticks_shares_position(self):
+        """Test iter_with_ticks continues from the parser's position."""
+        parser = th.Teehistorian(build_recording())
+        for _ in range(4):
+            next(parser)
+        tick, chunk = next(parser.iter_with_ticks())
+        assert (tick, chunk.chunk_type()) == (10, "TickSkip")
+
+
+# ============================================================================
+# Peek Tests
+# ============================================================================
+
+
+class TestPeek:
+    """Test looking at the next chunk without consuming it."""
+
+    def test_peek_does_not_advance(self):
+        """Test peek returns the next chunk and leaves the position alone."""
+        parser = th.Teehistorian(build_recording())
+        for _ in range(4):
+            next(parser)
+
+        peeked = parser.peek()
+        assert isinstance(peeked, th.TickSkip)
+        assert parser.tick == 2
+        assert parser.chunk_count == 4
+        assert parser.peek().chunk_type() == "TickSkip"
+
+        chunk = next(parser)
+        assert chunk.chunk_type() == "TickSkip"
+        assert parser.tick == 10
+
+    def test_peek_respects_skip_inputs(self):
+        """Test peek skips chunks that iteration would skip."""
+        writer = th.create()
+        writer.write(th.InputNew(0, bytes(40)))
+        writer.write(th.Join(0))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue(), skip_inputs=True)
+        assert isinstance(parser.peek(), th.Join)
+        assert parser.chunk_count == 0
+        assert isinstance(next(parser), th.Join)
+
+    def test_peek_at_eof(self):
+        """Test peek returns None once the stream is exhausted."""
+        parser = th.Teehistorian(build_recording())
+        list(parser)
+        assert parser.peek() is None
+
+
+class TestReset:
+    """Test rewinding a parser."""
+
+    def test_reset_iterates_again(self):
+        """Test a reset parser yields the same chunks again."""
+        parser = th.Teehistorian(build_recording())
+        first = [c.chunk_type() for c in parser]
+        parser.reset()
+        assert parser.tick == 0
+        assert parser.chunk_count == 0
+        assert [c.chunk_type() for c in parser] == first
+        assert parser.tick == 12
+
+    def test_reset_keeps_registered_uuids(self):
+        """Test custom UUIDs survive a reset."""
+        parser = th.Teehistorian(build_recording())
+        parser.register_custom_uuid("12345678-1234-5678-1234-567812345678")
+        next(parser)
+        parser.reset()
+        assert [r.uuid for r in parser.get_registered_uuids()] == [
+            "12345678-1234-5678-1234-567812345678"
+        ]
+        assert isinstance(next(parser), th.PlayerNew)
+
+
+# ============================================================================
+# Raw Chunk Bytes Tests
+# ============================================================================
+
+
+class TestRaw:
+    """Test access to the encoded bytes of returned chunks."""
+
+    def test_raw_before_first_chunk(self):
+        """Test raw() is None until a chunk was returned."""
+        parser = th.Teehistorian(build_recording())
+        assert parser.raw() is None
+        parser.peek()
+        assert parser.raw() is None
+
+    def test_raw_matches_encoded_chunk(self):
+        """Test raw() returns what the writer produced for each chunk."""
+        parser = th.Teehistorian(build_recording())
+        for chunk in parser:
+            assert parser.raw() == chunk.write_to_buffer()
+        parser.reset()
+        assert parser.raw() is None
+
+    def test_raw_reassembles_data(self):
+        """Test the raw bytes of all chunks and batches cover the data."""
+        data = TestSegments.build_concatenated()
+        parser = th.Teehistorian(data)
+        rewritten = data[: 16 + len(parser.header()) + 1]
+        while batch := parser.next_batch(1):
+            assert len(batch) == 1
+            rewritten += parser.raw()
+        assert rewritten == data
+
+    def test_raw_with_filter(self):
+        """Test raw() follows the chunks a filtered iterator yields."""
+        parser = th.Teehistorian(build_recording())
+        for chunk in parser.filter([th.Join]):
+            assert parser.raw() == th.Join(2).write_to_buffer()
+
+
+# ============================================================================
+# Tick Range Iteration Tests
+# ============================================================================
+
+
+class TestIterTicks:
+    """Test iterating over a tick range."""
+
+    def test_iter_ticks_range(self):
+        """Test only chunks within the range are yielded."""
+        parser = th.Teehistorian(build_recording())
+        chunks = list(parser.iter_ticks(2, 10))
+        assert [c.chunk_type() for c in chunks] == [
+            "PlayerDiff",
+            "PlayerDiff",
+            "TickSkip",
+            "PlayerDiff",
+            "Join",
+        ]
+
+    def test_iter_ticks_leaves_rest_unread(self):
+        """Test the first chunk after the range stays on the parser."""
+        parser = th.Teehistorian(build_recording())
+        list(parser.iter_ticks(1, 2))
+        assert parser.tick == 2
+
+        chunk = next(parser)
+        assert isinstance(chunk, th.TickSkip)
+        assert parser.tick == 10
+
+    def test_iter_ticks_open_ended(self):
+        """Test omitting end iterates to the end of the stream."""
+        parser = th.Teehistorian(build_recording())
+        chunks = list(parser.iter_ticks(11))
+        assert [c.chunk_type() for c in chunks] == [
+            "PlayerDiff",
+            "TickSkip",
+            "ConsoleCommand",
+            "Eos",
+        ]
+
+    def test_iter_ticks_counts_skipped_chunks(self):
+        """Test skipped chunks still count as processed."""
+        parser = th.Teehistorian(build_recording())
+        list(parser.iter_ticks(12, 12))
+        assert parser.chunk_count == 11
+
+    def test_iter_ticks_past_end_of_stream(self):
+        """Test a range beyond the recording yields nothing."""
+        parser = th.Teehistorian(build_recording())
+        assert list(parser.iter_ticks(500, 600)) == []
+
+    def test_iter_ticks_rejects_inverted_range(self):
+        """Test end before start is rejected."""
+        parser = th.Teehistorian(build_recording())
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            parser.iter_ticks(10, 5)
+
+    def test_iter_ticks_returns_chunk_iterator(self):
+        """Test the returned object is a ChunkIterator."""
+        parser = th.Teehistorian(build_recording())
+        iterator = parser.iter_ticks(0)
+        assert isinstance(iterator, th.ChunkIterator)
+        assert iter(iterator) is iterator
+
+
+# ============================================================================
+# Player Filtering Tests
+# ============================================================================
+
+
+class TestIterPlayer:
+    """Test iterating over the chunks of selected players."""
+
+    def test_iter_player(self):
+        """Test only chunks for the given client are yielded."""
+        parser = th.Teehistorian(build_recording())
+        chunks = list(parser.iter_player(0))
+        assert [c.chunk_type() for c in chunks] == [
+            "PlayerNew",
+            "PlayerDiff",
+            "PlayerDiff",
+            "ConsoleCommand",
+        ]
+        assert all(c.client_id == 0 for c in chunks)
+
+    def test_iter_player_tracks_ticks(self):
+        """Test skipped chunks still advance the tick."""
+        parser = th.Teehistorian(build_recording())
+        ticks = [parser.tick for _ in parser.iter_player(0)]
+        assert ticks == [1, 2, 11, 12]
+
+    def test_iter_players(self):
+        """Test several client IDs can be selected at once."""
+        parser = th.Teehistorian(build_recording())
+        chunks = list(parser.iter_players([1, 2]))
+        assert [(c.chunk_type(), c.client_id) for c in chunks] == [
+            ("PlayerNew", 1),
+            ("PlayerDiff", 1),
+            ("PlayerDiff", 1),
+            ("Join", 2),
+        ]
+
+    def test_iter_player_unknown_client(self):
+        """Test a client ID that never appears yields nothing."""
+        parser = th.Teehistorian(build_recording())
+        assert list(parser.iter_player(42)) == []
+        assert parser.chunk_count == 11
+
+
+# ============================================================================
+# Early Termination Tests
+# ============================================================================
+
+
+class TestTakeUntil:
+    """Test skipping ahead to the first matching chunk."""
+
+    def test_take_until_chunk_type(self):
+        """Test the first chunk of a type is returned and consumed."""
+        parser = th.Teehistorian(build_recording())
+        chunk = parser.take_until(chunk_type=th.Join)
+        assert isinstance(chunk, th.Join)
+        assert parser.tick == 10
+        assert isinstance(next(parser), th.PlayerDiff)
+
+    def test_take_until_tick(self):
+        """Test the first chunk at or after a tick is returned."""
+        parser = th.Teehistorian(build_recording())
+        assert parser.take_until(tick=11).chunk_type() == "PlayerDiff"
+        assert parser.tick == 11
+
+    def test_take_until_both(self):
+        """Test both conditions must hold."""
+        parser = th.Teehistorian(build_recording())
+        chunk = parser.take_until(chunk_type="PlayerDiff", tick=10)
+        assert parser.tick == 10
+        assert chunk.client_id == 1
+        assert parser.take_until(chunk_type="PlayerDiff").client_id == 0
+        assert parser.take_until(chunk_type="PlayerDiff") is None
+
+    def test_take_until_needs_condition(self):
+        """Test calling without any condition is rejected."""
+        parser = th.Teehistorian(build_recording())
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            parser.take_until()
+
+
+# ============================================================================
+# Sampled Iteration Tests
+# ============================================================================
+
+
+class TestIterSampled:
+    """Test thinning out movement and input chunks."""
+
+    @staticmethod
+    def build_movement() -> bytes:
+        """Player 0 moves by (1, 2) on each of ticks 2 to 31."""
+        writer = th.create()
+        writer.write(th.PlayerNew(0, 100, 200))
+        writer.write(th.InputNew(0, [0] * 10))
+        for tick in range(30):
+            writer.write(th.PlayerDiff(0, 1, 2))
+            writer.write(th.InputDiff(0, [1] + [0] * 9))
+            if tick == 15:
+                writer.write(th.Join(1))
+        writer.write(th.Eos())
+        return writer.getvalue()
+
+    def test_sampled_diffs_sum_up(self):
+        """Test the yielded diffs add up to the full movement."""
+        parser = th.Teehistorian(self.build_movement())
+        chunks = list(parser.iter_sampled(every_n_ticks=10))
+        diffs = [c for c in chunks if isinstance(c, th.PlayerDiff)]
+        inputs = [c for c in chunks if isinstance(c, th.InputDiff)]
+
+        assert len(diffs) == len(inputs) == 4
+        assert sum(d.dx for d in diffs) == 29
+        assert sum(d.dy for d in diffs) == 58
+        assert sum(i.input[0] for i in inputs) == 29
+
+    def test_lifecycle_chunks_pass_through(self):
+        """Test chunks other than diffs are never dropped."""
+        data = self.build_movement()
+        sampled = [c.chunk_type() for c in th.Teehistorian(data).iter_sampled(10)]
+        for kept in ("PlayerNew", "InputNew", "Join", "Eos"):
+            assert kept in sampled
+
+    def test_stride_of_one_keeps_everything(self):
+        """Test a one-tick stride yields every chunk unchanged."""
+        data = self.build_movement()
+        full = [c.to_dict() for c in th.Teehistorian(data)]
+        assert [c.to_dict() for c in th.Teehistorian(data).iter_sampled(1)] == full
+
+    def test_invalid_stride(self):
+        """Test a non-positive stride is rejected."""
+        parser = th.Teehistorian(self.build_movement())
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            parser.iter_sampled(0)
+
+
+# ============================================================================
+# Chunk Type Filtering Tests
+# ============================================================================
+
+
+class TestFilterTypes:
+    """Test restricting iteration to selected chunk types."""
+
+    def test_filter_by_class(self):
+        """Test chunk classes select the chunks to yield."""
+        parser = th.Teehistorian(build_recording())
+        chunks = list(parser.filter(types=[th.TickSkip, th.Join]))
+        assert [c.chunk_type() for c in chunks] == ["TickSkip", "Join", "TickSkip"]
+
+    def test_filter_by_name(self):
+        """Test type names work as well as classes."""
+        parser = th.Teehistorian(build_recording())
+        chunks = list(parser.filter(["ConsoleCommand", th.Eos]))
+        assert [c.chunk_type() for c in chunks] == ["ConsoleCommand", "Eos"]
+
+    def test_filter_special_classes(self):
+        """Test classes exported without a Py prefix are recognized."""
+        parser = th.Teehistorian(build_recording())
+        assert list(parser.filter([th.Unknown, th.CustomChunk, th.RawChunk])) == []
+
+    def test_filter_player_info_messages(self):
+        """Test parsed player info messages are filtered by their own type."""
+        info = th.NetMessagePlayerInfo(0, "ClStartInfo", "Player", "Clan", 0, "default")
+        writer = th.create()
+        writer.write(th.NetMessage(1, b"\x00"))
+        writer.write(info)
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        chunks = list(parser.filter([th.NetMessagePlayerInfo]))
+        assert len(chunks) == 1
+        assert chunks[0].name == "Player"
+
+    def test_filter_rejects_unknown_type(self):
+        """Test unknown chunk type names are rejected."""
+        parser = th.Teehistorian(build_recording())
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            parser.filter(["NotAChunk"])
+
+
+# ============================================================================
+# Skip Inputs Tests
+# ============================================================================
+
+
+class TestSkipInputs:
+    """Test discarding input chunks while parsing."""
+
+    @staticmethod
+    def build_with_inputs() -> bytes:
+        writer = th.create()
+        writer.write(th.PlayerNew(0, 100, 200))
+        writer.write(th.InputNew(0, bytes(40)))
+        writer.write(th.InputDiff(0, bytes(40)))
+        writer.write(th.PlayerDiff(0, 1, 1))
+        writer.write(th.Eos())
+        return writer.getvalue()
+
+    def test_inputs_kept_by_default(self):
+        """Test input chunks are yielded without the flag."""
+        parser = th.Teehistorian(self.build_with_inputs())
+        types = [c.chunk_type() for c in parser]
+        assert "InputNew" in types
+        assert "InputDiff" in types
+
+    def test_skip_inputs(self):
+        """Test input chunks are dropped and still counted."""
+        parser = th.Teehistorian(self.build_with_inputs(), skip_inputs=True)
+        assert [c.chunk_type() for c in parser] == ["PlayerNew", "PlayerDiff", "Eos"]
+        assert parser.chunk_count == 5
+
+    def test_skip_inputs_batches(self):
+        """Test batches drop input chunks as well."""
+        parser = th.Teehistorian(self.build_with_inputs(), skip_inputs=True)
+        batch = parser.next_batch(3)
+        assert [c.chunk_type() for c in batch] == ["PlayerNew"]
+        assert parser.chunk_count == 3
+
+    def test_skip_inputs_combines_with_filters(self):
+        """Test filtered iterators also drop input chunks."""
+        parser = th.Teehistorian(self.build_with_inputs(), skip_inputs=True)
+        assert [c.chunk_type() for c in parser.iter_player(0)] == [
+            "PlayerNew",
+            "PlayerDiff",
+        ]
+
+
+class TestExcludeCategories:
+    """Test discarding whole chunk categories while parsing."""
+
+    def test_exclude_categories(self):
+        """Test every chunk of an excluded category is dropped."""
+        parser = th.Teehistorian(
+            build_recording(), exclude_categories=["PlayerState", "GameEvent"]
+        )
+        assert [c.chunk_type() for c in parser] == ["Join", "ConsoleCommand", "Eos"]
+        assert parser.chunk_count == 11
+
+    def test_excluded_chunks_still_advance_ticks(self):
+        """Test ticks are tracked across excluded chunks."""
+        parser = th.Teehistorian(build_recording(), exclude_categories=["PlayerState"])
+        ticks = [(c.chunk_type(), parser.tick) for c in parser]
+        assert ("Join", 10) in ticks
+        assert ("ConsoleCommand", 12) in ticks
+
+    def test_exclude_input_category(self):
+        """Test the Input category matches skip_inputs."""
+        data = TestSkipInputs.build_with_inputs()
+        by_category = th.Teehistorian(data, exclude_categories=["Input"])
+        by_flag = th.Teehistorian(data, skip_inputs=True)
+        assert [c.chunk_type() for c in by_category] == [
+            c.chunk_type() for c in by_flag
+        ]
+
+    def test_exclude_unknown_category(self):
+        """Test unknown category names are rejected."""
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            th.Teehistorian(build_recording(), exclude_categories=["Nope"])
+
+
+# ============================================================================
+# Summary Statistics Tests
+# ============================================================================
+
+
+class TestStats:
+    """Test whole-file statistics."""
+
+    def test_stats_counts(self):
+        """Test chunks are counted per type."""
+        stats = th.Teehistorian(build_recording()).stats()
+        assert stats.total_chunks == 11
+        assert stats.chunk_counts == {
+            "PlayerNew": 2,
+            "PlayerDiff": 4,
+            "TickSkip": 2,
+            "Join": 1,
+            "ConsoleCommand": 1,
+            "Eos": 1,
+        }
+
+    def test_stats_span(self):
+        """Test the tick span, players and duration."""
+        stats = th.Teehistorian(build_recording()).stats()
+        assert (stats.first_tick, stats.last_tick) == (1, 12)
+        assert stats.player_ids == [0, 1, 2]
+        assert stats.duration == 11 / 50
+
+    def test_stats_keep_position(self):
+        """Test stats neither depend on nor move the parser position."""
+        parser = th.Teehistorian(build_recording(), skip_inputs=True)
+        next(parser)
+        assert parser.stats().total_chunks == 11
+        assert parser.chunk_count == 1
+        assert isinstance(next(parser), th.PlayerNew)
+
+    def test_stats_empty_recording(self):
+        """Test a recording without chunks has no tick span."""
+        stats = th.Teehistorian(th.create().getvalue()).stats()
+        assert stats.total_chunks == 0
+        assert stats.first_tick is None
+        assert stats.duration == 0.0
+
+
+class TestCount:
+    """Test counting chunks of one type."""
+
+    def test_count_matches_stats(self):
+        """Test counts agree with a full scan for every type."""
+        parser = th.Teehistorian(build_recording())
+        for name, count in parser.stats().chunk_counts.items():
+            assert parser.count(name) == count
+
+    def test_count_by_class(self):
+        """Test chunk classes are accepted."""
+        parser = th.Teehistorian(build_recording())
+        assert parser.count(th.PlayerDiff) == 4
+        assert parser.count(th.Drop) == 0
+        assert parser.chunk_count == 0
+
+    def test_count_payload_dependent_types(self):
+        """Test net messages are told apart from parsed player info."""
+        info = th.NetMessagePlayerInfo(0, "ClStartInfo", "Player", "Clan", 0, "default")
+        writer = th.create()
+        writer.write(th.NetMessage(1, b"\x00"))
+        writer.write(info)
+        writer.write(th.PlayerReady(3))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        assert parser.count(th.NetMessage) == 1
+        assert parser.count(th.NetMessagePlayerInfo) == 1
+        assert parser.count("PlayerReady") == 1
+
+    def test_count_unknown_type(self):
+        """Test unknown chunk types are rejected."""
+        parser = th.Teehistorian(build_recording())
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            parser.count("NotAChunk")
+
+
+# ============================================================================
+# Save/Resume Tests
+# ============================================================================
+
+
+class TestSaveState:
+    """Test checkpointing and resuming a parser."""
+
+    def test_resume_continues_stream(self):
+        """Test a resumed parser yields the remaining chunks."""
+        data = build_recording()
+        parser = th.Teehistorian(data)
+        for _ in range(5):
+            next(parser)
+        state = parser.save_state()
+        remaining = [c.chunk_type() for c in parser]
+
+        resumed = th.Teehistorian.resume(data, state)
+        assert resumed.tick == 10
+        assert resumed.chunk_count == 5
+        assert [c.chunk_type() for c in resumed] == remaining
+
+    def test_resume_keeps_implicit_ticks(self):
+        """Test the tick bookkeeping survives a resume."""
+        data = build_recording()
+        parser = th.Teehistorian(data)
+        for _ in range(7):
+            next(parser)
+
+        resumed = th.Teehistorian.resume(data, parser.save_state())
+        next(resumed)
+        assert resumed.tick == 11
+
+    def test_state_serialization(self):
+        """Test states survive to_bytes and pickle."""
+        parser = th.Teehistorian(build_recording())
+        next(parser)
+        parser.register_custom_uuid("12345678-1234-5678-1234-567812345678")
+        state = parser.save_state()
+
+        assert th.ParserState.from_bytes(state.to_bytes()) == state
+        assert pickle.loads(pickle.dumps(state)) == state
+        assert state.registered_uuids == ["12345678-1234-5678-1234-567812345678"]
+
+    def test_resume_registers_uuids(self):
+        """Test registered UUIDs are restored on resume."""
+        data = build_recording()
+        parser = th.Teehistorian(data)
+        parser.register_custom_uuid("12345678-1234-5678-1234-567812345678")
+
+        resumed = th.Teehistorian.resume(data, parser.save_state())
+        assert [r.uuid for r in resumed.get_registered_uuids()] == [
+            "12345678-1234-5678-1234-567812345678"
+        ]
+
+    def test_resume_rejects_foreign_state(self):
+        """Test a state from different data is rejected."""
+        state = th.Teehistorian(build_recording()).save_state()
+        other = th.create(comment="a much longer header than before")
+        other.write(th.Eos())
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            th.Teehistorian.resume(other.getvalue(), state)
+
+    def test_resume_resolves_positions(self):
+        """Test resolved positions are part of the state."""
+        writer = th.create()
+        writer.write(th.PlayerNew(0, 100, 200))
+        writer.write(th.PlayerDiff(0, 5, -10))
+        writer.write(th.PlayerDiff(0, 5, -10))
+        writer.write(th.Eos())
+        data = writer.getvalue()
+
+        parser = th.Teehistorian(data, resolve_positions=True)
+        next(parser)
+        next(parser)
+        state = th.ParserState.from_bytes(parser.save_state().to_bytes())
+
+        resumed = th.Teehistorian.resume(data, state, resolve_positions=True)
+        chunk = next(resumed)
+        assert isinstance(chunk, th.PlayerPosition)
+        assert (chunk.x, chunk.y) == (110, 180)
+
+        resumed = th.Teehistorian.from_offset(data, state.offset, state, resolve_positions=True)
+        assert (next(resumed).x, next(resumed).chunk_type()) == (110, "Eos")
+
+        # Without the positions the diff cannot be resolved
+        state = th.Teehistorian(data).save_state()
+        with pytest.raises(th.TeehistorianError, match="resolve_positions"):
+            th.Teehistorian.resume(data, state, resolve_positions=True)
+
+    def test_resume_resolves_inputs(self):
+        """Test resolved inputs are part of the state."""
+        writer = th.create()
+        writer.write(th.InputNew(1, [1, 100, 50, 0, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.InputDiff(1, [-2, 10, 0, 1, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.InputDiff(1, [0, 10, -50, -1, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.Eos())
+        data = writer.getvalue()
+
+        parser = th.Teehistorian(data, resolve_inputs=True)
+        next(parser)
+        next(parser)
+        state = pickle.loads(pickle.dumps(parser.save_state()))
+
+        for resumed in (
+            th.Teehistorian.resume(data, state, resolve_inputs=True),
+            th.Teehistorian.from_offset(data, state.offset, state, resolve_inputs=True),
+        ):
+            chunk = next(resumed)
+            assert chunk.chunk_type() == "InputNew"
+            assert chunk.input == [-1, 120, 0, 0, 0, 0, 0, 0, 0, 0]
+
+        state = th.Teehistorian(data).save_state()
+        with pytest.raises(th.TeehistorianError, match="resolve_inputs"):
+            th.Teehistorian.resume(data, state, resolve_inputs=True)
+
+    def test_from_bytes_rejects_garbage(self):
+        """Test invalid serialized states are rejected."""
+        with pytest.raises((th.TeehistorianError, th.ValidationError)):
+            th.ParserState.from_bytes(b"garbage")
+
+
+class TestFromOffset:
+    """Test starting a parser at a known chunk offset."""
+
+    @staticmethod
+    def state_before(data: bytes, n: int) -> "th.ParserState":
+        parser = th.Teehistorian(data)
+        for _ in range(n):
+            next(parser)
+        return parser.save_state()
+
+    def test_from_offset(self):
+        """Test parsing starts at the given chunk."""
+        data = build_recording()
+        state = self.state_before(data, 4)
+
+        parser = th.Teehistorian.from_offset(data, state.offset)
+        assert [c.chunk_type() for c in parser][:2] == ["TickSkip", "PlayerDiff"]
+
+    def test_from_offset_counts_from_zero(self):
+        """Test ticks and counts start at zero without a state."""
+        data = build_recording()
+        parser = th.Teehistorian.from_offset(data, self.state_before(data, 4).offset)
+        next(parser)
+        assert parser.tick == 8
+        assert parser.chunk_count == 1
+
+    def test_from_offset_with_state(self):
+        """Test a state restores ticks and counts."""
+        data = build_recording()
+        state = self.state_before(data, 4)
+        parser = th.Teehistorian.from_offset(data, state.offset, state)
+        next(parser)
+        assert parser.tick == 10
+        assert parser.chunk_count == 5
+
+    def test_from_offset_rejects_bad_offsets(self):
+        """Test offsets outside the stream or inside a chunk are rejected."""
+        data = build_recording()
+        # The ConsoleCommand name starts three bytes into the chunk
+        inside = self.state_before(data, 9).offset + 3
+        for offset in (0, len(data) + 1, inside):
+            with pytest.raises((th.TeehistorianError, th.ValidationError)):
+                th.Teehistorian.from_offset(data, offset)
+
+
+# ============================================================================
+# Batch Decoding Tests
+# ============================================================================
+
+
+class TestNextBatch:
+    """Test reading chunks in batches."""
+
+    def test_batches_cover_stream(self):
+        """Test consecutive batches return every chunk once."""
+        parser = th.Teehistorian(build_recording())
+        sizes = []
+        types = []
+        while batch := parser.next_batch(4):
+            sizes.append(len(batch))
+            types.extend(c.chunk_type() for c in batch)
+
+        assert sizes == [4, 4, 3]
+        expected = [c.chunk_type() for c in th.Teehistorian(build_recording())]
+        assert types == expected
+
+    def test_batch_tracks_position(self):
+        """Test batches advance ticks and chunk counts like iteration."""
+        parser = th.Teehistorian(build_recording())
+        parser.next_batch(5)
+        assert parser.chunk_count == 5
+        assert parser.tick == 10
+        assert isinstance(next(parser), th.PlayerDiff)
+
+    def test_batch_at_eof(self):
+        """Test an exhausted parser returns an empty batch."""
+        parser = th.Teehistorian(build_recording())
+        list(parser)
+        assert parser.next_batch(10) == []
+
+
+# ============================================================================
+# Concatenated Stream Tests
+# ============================================================================
+
+
+class TestSegments:
+    """Test data holding several concatenated recordings."""
+
+    @staticmethod
+    def build_concatenated() -> bytes:
+        second = th.create(server_name="second")
+        second.write(th.PlayerNew(0, 1, 1))
+        second.write(th.Eos())
+        return build_recording() + second.getvalue()
+
+    def test_iteration_crosses_segments(self):
+        """Test iteration continues into the next stream after a marker."""
+        data = self.build_concatenated()
+        types = [c.chunk_type() for c in th.Teehistorian(data)]
+        assert types[10:] == ["Eos", "SegmentStart", "PlayerNew", "Eos"]
+
+    def test_segment_start_marker(self):
+        """Test the marker carries the header and offset of the new stream."""
+        data = self.build_concatenated()
+        marker = next(c for c in th.Teehistorian(data) if isinstance(c, th.SegmentStart))
+        assert marker.offset == len(build_recording())
+        assert '"server_name":"second"' in marker.header.replace(" ", "")
+
+    def test_ticks_continue_across_segments(self):
+        """Test ticks stay monotonic and the new stream opens a tick."""
+        parser = th.Teehistorian(self.build_concatenated())
+        list(parser)
+        assert parser.tick == 13
+
+    def test_segments_roundtrip(self):
+        """Test writing every chunk back reproduces the data."""
+        data = self.build_concatenated()
+        parser = th.Teehistorian(data)
+        # Magic UUID, JSON header and its NUL terminator
+        rewritten = data[: 16 + len(parser.header()) + 1]
+        for chunk in parser:
+            rewritten += chunk.write_to_buffer()
+        assert rewritten == data
+
+    def test_segments_in_batches_and_stats(self):
+        """Test batches, stats and count see the marker as well."""
+        data = self.build_concatenated()
+        parser = th.Teehistorian(data)
+        batched = []
+        while batch := parser.next_batch(4):
+            batched.extend(c.chunk_type() for c in batch)
+        assert batched == [c.chunk_type() for c in th.Teehistorian(data)]
+
+        parser = th.Teehistorian(data)
+        assert parser.stats().chunk_counts["SegmentStart"] == 1
+        assert parser.count(th.SegmentStart) == 1
+        assert parser.count(th.PlayerNew) == 3
+
+    def test_player_filters_skip_markers(self):
+        """Test filtered iterators only yield markers when asked for."""
+        data = self.build_concatenated()
+        assert all(
+            not isinstance(c, th.SegmentStart)
+            for c in th.Teehistorian(data).iter_player(0)
+        )
+        markers = list(th.Teehistorian(data).filter([th.SegmentStart]))
+        assert len(markers) == 1
+
+
+# ============================================================================
+# Follow Mode Tests
+# ============================================================================
+
+
+class TestFollow:
+    """Test following a recording that is still being written."""
+
+    @staticmethod
+    def follow_growing(data: bytes, cut: int) -> list:
+        """Follow a file holding data[:cut] while the rest is appended."""
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "live.teehistorian"
+            path.write_bytes(data[:cut])
+
+            def append_rest():
+                for i in range(cut, len(data), 7):
+                    time.sleep(0.005)
+                    with path.open("ab") as f:
+                        f.write(data[i : i + 7])
+
+            writer = threading.Thread(target=append_rest)
+            writer.start()
+            try:
+                return [c.chunk_type() for c in th.Teehistorian.follow(path, 0.001)]
+            finally:
+                writer.join()
+
+    def test_follow_complete_file(self):
+        """Test a finished recording is read up to its Eos."""
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "done.teehistorian"
+            path.write_bytes(build_recording())
+            live = th.Teehistorian.follow(path)
+            types = [c.chunk_type() for c in live]
+        assert types == [c.chunk_type() for c in th.Teehistorian(build_recording())]
+        assert live.tick == 12
+
+    def test_follow_waits_for_chunks(self):
+        """Test chunks appended during iteration are picked up."""
+        data = build_recording()
+        header_end = data.index(b"\x00", 16) + 1
+        expected = [c.chunk_type() for c in th.Teehistorian(data)]
+        assert self.follow_growing(data, header_end + 1) == expected
+
+    def test_follow_waits_for_header(self):
+        """Test following can start before the header is written."""
+        data = build_recording()
+        expected = [c.chunk_type() for c in th.Teehistorian(data)]
+        assert self.follow_growing(data, 0) == expected
+
+    def test_follow_parser_options(self):
+        """Test parser options are passed on to the followed recording."""
+        writer = th.create()
+        writer.write(th.PlayerNew(0, 100, 200))
+        writer.write(th.PlayerDiff(0, 5, -10))
+        writer.write(th.InputNew(0, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.InputDiff(0, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.Eos())
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "done.teehistorian"
+            path.write_bytes(writer.getvalue())
+            live = th.Teehistorian.follow(path, resolve_inputs=True, resolve_positions=True)
+            chunks = list(live)
+        assert [(c.x, c.y) for c in chunks[:2]] == [(100, 200), (105, 190)]
+        assert [c.input[0] for c in chunks[2:4]] == [1, 2]
+
+    def test_follow_rejects_other_files(self):
+        """Test a file that is not a recording raises instead of waiting."""
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "notes.txt"
+            path.write_bytes(b"not a teehistorian file")
+            with pytest.raises((th.TeehistorianError, th.ValidationError)):
+                next(th.Teehistorian.follow(path))
+            with pytest.raises(FileNotFoundError):
+                th.Teehistorian.follow(Path(tmp) / "missing.teehistorian")
+
+
+# ============================================================================
+# Truncated File Tests
+# ============================================================================
+
+
+class TestTruncated:
+    """Test recovering the complete prefix of truncated files."""
+
+    def test_complete_file_not_truncated(self):
+        """Test a complete file is not reported as truncated."""
+        parser = th.Teehistorian(build_recording())
+        list(parser)
+        assert parser.truncated is False
+        assert parser.last_complete_offset is None
+
+    def test_truncated_file_stops_cleanly(self):
+        """Test iteration stops before a partial chunk."""
+        data = build_recording()
+        # Cut into the ConsoleCommand chunk
+        truncated = data[:-6]
+        parser = th.Teehistorian(truncated)
+        types = [c.chunk_type() for c in parser]
+
+        assert types == [c.chunk_type() for c in th.Teehistorian(data)][:9]
+        assert parser.truncated is True
+        assert parser.last_complete_offset < len(truncated)
+
+    def test_complete_prefix_is_readable(self):
+        """Test the data up to last_complete_offset parses to the same chunks."""
+        truncated = build_recording()[:-6]
+        parser = th.Teehistorian(truncated)
+        chunks = [c.chunk_type() for c in parser]
+
+        prefix = th.Teehistorian(truncated[: parser.last_complete_offset])
+        assert [c.chunk_type() for c in prefix] == chunks
+        assert prefix.truncated is False
+
+    def test_truncated_batches(self):
+        """Test batches stop before the partial chunk as well."""
+        parser = th.Teehistorian(build_recording()[:-6])
+        assert len(parser.next_batch(100)) == 9
+        assert parser.next_batch(100) == []
+        assert parser.truncated is True
+
+
+# ============================================================================
+# Validation Report Tests
+# ============================================================================
+
+
+class TestValidate:
+    """Test whole-file validation reports."""
+
+    def test_valid_file(self):
+        """Test a well-formed recording passes every check."""
+        report = th.Teehistorian.validate(build_recording())
+        assert report.is_valid
+        assert report.header_ok
+        assert report.eos_present
+        assert report.ticks_monotonic
+        assert report.error_offset is None
+        assert report.unknown_uuids == []
+        assert report.chunk_counts["PlayerDiff"] == 4
+
+    def test_bad_header(self):
+        """Test a broken header is reported instead of raised."""
+        report = th.Teehistorian.validate(b"\x00" * 32)
+        assert not report.is_valid
+        assert not report.header_ok
+        assert report.header_error
+
+    def test_missing_eos(self):
+        """Test a recording without Eos is flagged."""
+        writer = th.create()
+        writer.write(th.Join(0))
+        report = th.Teehistorian.validate(writer.getvalue())
+        assert not report.eos_present
+        assert not report.is_valid
+
+    def test_truncated(self):
+        """Test the offset of a partial chunk is reported."""
+        data = build_recording()[:-6]
+        parser = th.Teehistorian(data)
+        list(parser)
+
+        report = th.Teehistorian.validate(data)
+        assert report.truncated
+        assert report.error_offset == parser.last_complete_offset
+
+    def test_unknown_uuids(self):
+        """Test extension chunks without a handler are listed."""
+        uuid = "12345678-1234-5678-1234-567812345678"
+        writer = th.create()
+        writer.write(th.Unknown(uuid, b"\x01"))
+        writer.write(th.Eos())
+        report = th.Teehistorian.validate(writer.getvalue())
+        assert report.unknown_uuids == [uuid]
+
+    def test_backwards_ticks(self):
+        """Test a negative tick skip breaks the monotonic tick check."""
+        writer = th.create()
+        writer.write(th.TickSkip(5))
+        writer.write(th.TickSkip(-10))
+        writer.write(th.Eos())
+        assert not th.Teehistorian.validate(writer.getvalue()).ticks_monotonic
+
+
+# ============================================================================
+# Parser Warning Tests
+# ============================================================================
+
+
+class TestWarnings:
+    """Test collecting non-fatal anomalies while parsing."""
+
+    UUID = "12345678-1234-5678-1234-567812345678"
+
+    @classmethod
+    def build_anomalies(cls) -> bytes:
+        writer = th.create()
+        writer.write(th.Unknown(cls.UUID, b"\x01"))
+        writer.write(th.Unknown(cls.UUID, b"\x02"))
+        writer.write(th.TickSkip(50 * 60 * 60 + 1))
+        writer.write(th.ConsoleCommand(0, 0, "say", ["x" * 70_000]))
+        writer.write(th.Eos())
+        return writer.getvalue()
+
+    def test_clean_file_has_no_warnings(self):
+        """Test an ordinary recording produces no warnings."""
+        parser = th.Teehistorian(build_recording())
+        list(parser)
+        assert parser.warnings == []
+
+    def test_warnings_collected(self):
+        """Test each anomaly is recorded once, with its location."""
+        parser = th.Teehistorian(self.build_anomalies())
+        chunks = list(parser)
+        kinds = [w.kind for w in parser.warnings]
+        assert kinds == ["unknown_uuid", "tick_jump", "oversized_chunk"]
+        assert self.UUID in parser.warnings[0].message
+        assert parser.warnings[2].tick == parser.tick
+        assert len(chunks) == 5
+
+    def test_warnings_in_batches(self):
+        """Test batch decoding records the same warnings."""
+        data = self.build_anomalies()
+        parser = th.Teehistorian(data)
+        list(parser)
+        batched = th.Teehistorian(data)
+        while batched.next_batch(2):
+            pass
+        assert [(w.kind, w.offset) for w in batched.warnings] == [
+            (w.kind, w.offset) for w in parser.warnings
+        ]
+
+    def test_peek_and_reset(self):
+        """Test peeking does not add warnings and reset clears them."""
+        parser = th.Teehistorian(self.build_anomalies())
+        parser.peek()
+        assert parser.warnings == []
+        next(parser)
+        assert len(parser.warnings) == 1
+        parser.reset()
+        assert parser.warnings == []
+
+    def test_emit_warnings(self):
+        """Test warnings can be bridged to Python's warnings module."""
+        import warnings
+
+        data = self.build_anomalies()
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            list(th.Teehistorian(data))
+            assert caught == []
+            list(th.Teehistorian(data, emit_warnings=True))
+        assert len(caught) == 3
+        assert all(issubclass(w.category, th.TeehistorianWarning) for w in caught)
+
+
+# ============================================================================
+# Custom UUID Tests
+# ============================================================================
+
+
+class TestCustomUuids:
+    """Test decoding of registered extension chunks."""
+
+    UUID = "12345678-1234-5678-1234-567812345678"
+
+    @classmethod
+    def build_custom(cls) -> bytes:
+        writer = th.create()
+        writer.write(th.Join(0))
+        writer.write(th.Unknown(cls.UUID, b"\x01\x02"))
+        writer.write(th.Eos())
+        return writer.getvalue()
+
+    def test_callback_decoder(self):
+        """Test a decoder callback replaces the CustomChunk."""
+        calls = []
+
+        def decode(uuid, payload):
+            calls.append((uuid, payload))
+            return {"checkpoint": payload[0]}
+
+        parser = th.Teehistorian(self.build_custom())
+        parser.register_custom_uuid(self.UUID, decode)
+        chunks = list(parser)
+        assert chunks[1] == {"checkpoint": 1}
+        assert calls == [(self.UUID, b"\x01\x02")]
+
+    def test_callback_errors_propagate(self):
+        """Test exceptions raised by a decoder reach the caller."""
+
+        def decode(uuid, payload):
+            raise KeyError("bad payload")
+
+        parser = th.Teehistorian(self.build_custom())
+        parser.register_custom_uuid(self.UUID, decode)
+        next(parser)
+        with pytest.raises(KeyError, match="bad payload"):
+            next(parser)
+
+    def test_field_decoder(self):
+        """Test payloads are decoded into the registered fields."""
+        writer = th.create()
+        # cid=3, cp=5, time=1500 as packed ints, then a string
+        writer.write(th.Unknown(self.UUID, b"\x03\x05\x9c\x17gold\x00"))
+        writer.write(th.Unknown(self.UUID, b"\x03"))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        parser.register_custom_uuid(
+            self.UUID,
+            name="kog-checkpoint",
+            fields=[("cid", "int"), ("cp", "int"), ("time", "int"), ("medal", "str")],
+        )
+        checkpoint, truncated, _ = parser
+        assert isinstance(checkpoint, th.CustomChunk)
+        assert checkpoint.handler_name == "kog-checkpoint"
+        assert (checkpoint.cid, checkpoint.cp, checkpoint.time) == (3, 5, 1500)
+        assert checkpoint.medal == "gold"
+        assert list(checkpoint.fields) == ["cid", "cp", "time", "medal"]
+        assert checkpoint.to_dict()["time"] == 1500
+
+        assert truncated.fields is None
+        with pytest.raises(AttributeError):
+            truncated.cid
+
+        assert repr(checkpoint).endswith(
+            "fields: {'cid': 3, 'cp': 5, 'time': 1500, 'medal': 'gold'} }"
+        )
+        assert repr(truncated).endswith("fields: None }")
+
+    def test_field_decoder_invalid(self):
+        """Test unknown formats and conflicting decoders are rejected."""
+        parser = th.Teehistorian(self.build_custom())
+        with pytest.raises(th.TeehistorianError, match="Unknown field format"):
+            parser.register_custom_uuid(self.UUID, fields=[("cid", "float")])
+        with pytest.raises(th.TeehistorianError, match="cannot be combined"):
+            parser.register_custom_uuid(
+                self.UUID, lambda uuid, payload: None, fields=[("cid", "int")]
+            )
+
+    def test_stateful_handler(self):
+        """Test handlers keep state across chunks and can derive events."""
+
+        class Splits(th.ExtensionHandler):
+            def __init__(self):
+                super().__init__()
+                self.last = None
+                self.resets = 0
+
+            def on_chunk(self, chunk, tick):
+                previous, self.last = self.last, chunk.time
+                if previous is None:
+                    return None
+                return ("split", chunk.cp, chunk.time - previous)
+
+            def reset(self):
+                self.last = None
+                self.resets += 1
+
+        writer = th.create()
+        writer.write(th.Unknown(self.UUID, b"\x01\x0a"))
+        writer.write(th.Unknown(self.UUID, b"\x02\x19"))
+        writer.write(th.Eos())
+
+        handler = Splits()
+        parser = th.Teehistorian(writer.getvalue())
+        parser.register_custom_uuid(
+            self.UUID, handler, fields=[("cp", "int"), ("time", "int")]
+        )
+        first = parser.peek()
+        assert isinstance(first, th.CustomChunk)
+        assert handler.last is None
+
+        chunks = list(parser)
+        assert isinstance(chunks[0], th.CustomChunk)
+        assert chunks[1] == ("split", 2, 15)
+
+        parser.reset()
+        assert handler.resets == 1
+        assert list(parser)[1] == ("split", 2, 15)
+
+    def test_decoder_must_be_callable(self):
+        """Test non-callable decoders are rejected."""
+        parser = th.Teehistorian(self.build_custom())
+        with pytest.raises(th.TeehistorianError, match="callable"):
+            parser.register_custom_uuid(self.UUID, "not a function")
+
+    def test_unregister_and_introspect(self):
+        """Test registered UUIDs are listed with their kind and can be removed."""
+        other = "87654321-4321-8765-4321-876543218765"
+        parser = th.Teehistorian(self.build_custom())
+        parser.register_custom_uuid(
+            self.UUID, name="checkpoint", fields=[("cp", "int")]
+        )
+        parser.register_custom_uuid(other, lambda uuid, payload: None)
+
+        registered = parser.get_registered_uuids()
+        assert [(r.uuid, r.name, r.kind) for r in registered] == [
+            (self.UUID, "checkpoint", "fields"),
+            (other, other, "callback"),
+        ]
+        assert isinstance(registered[0], th.RegisteredUuid)
+
+        assert parser.unregister_custom_uuid(self.UUID)
+        assert not parser.unregister_custom_uuid(self.UUID)
+        assert [r.uuid for r in parser.get_registered_uuids()] == [other]
+        assert isinstance(list(parser)[1], th.Unknown)
+
+    def test_unknown_uuid_policy(self):
+        """Test unregistered extension chunks can be skipped or rejected."""
+        data = self.build_custom()
+        chunks = list(th.Teehistorian(data, unknown_uuid_policy="yield"))
+        assert isinstance(chunks[1], th.Unknown)
+
+        chunks = list(th.Teehistorian(data, unknown_uuid_policy="skip"))
+        assert [c.chunk_type() for c in chunks] == ["Join", "Eos"]
+
+        parser = th.Teehistorian(data, unknown_uuid_policy="error")
+        assert isinstance(next(parser), th.Join)
+        with pytest.raises(th.TeehistorianError, match="Unknown extension UUID"):
+            next(parser)
+
+        parser = th.Teehistorian(data, unknown_uuid_policy="error")
+        parser.register_custom_uuid(self.UUID)
+        assert isinstance(list(parser)[1], th.CustomChunk)
+
+        with pytest.raises(th.TeehistorianError, match="Unknown UUID policy"):
+            th.Teehistorian(data, unknown_uuid_policy="ignore")
+
+    def test_unknown_uuid_policy_everywhere(self):
+        """Test every way of creating a parser accepts the policy."""
+        data = self.build_custom()
+        state = th.Teehistorian(data).save_state()
+        parsers = [
+            th.Teehistorian.resume(data, state, unknown_uuid_policy="skip"),
+            th.Teehistorian.from_offset(data, state.offset, unknown_uuid_policy="skip"),
+        ]
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "custom.teehistorian"
+            path.write_bytes(data)
+            parsers.append(th.parse(path, unknown_uuid_policy="skip"))
+            parsers.append(th.open(path, unknown_uuid_policy="skip"))
+            parsers.append(th.Teehistorian.follow(path, unknown_uuid_policy="skip"))
+            for parser in parsers:
+                assert [c.chunk_type() for c in parser] == ["Join", "Eos"]
+
+            with pytest.raises(th.TeehistorianError, match="Unknown UUID policy"):
+                th.Teehistorian.follow(path, unknown_uuid_policy="ignore")
+
+    def test_payload_accessors(self):
+        """Test CustomChunk payloads can be read with varint semantics."""
+        chunk = th.CustomChunk(self.UUID, b"\x03\x80\x01abc\x00", "checkpoint")
+        assert chunk.read_int() == (3, 1)
+        assert chunk.read_int(1) == (64, 3)
+        assert chunk.read_string(3) == ("abc", 7)
+        assert chunk.unpack(["int", "int", "str"]) == [3, 64, "abc"]
+
+        with pytest.raises(th.TeehistorianError, match="offset 7"):
+            chunk.read_int(7)
+        with pytest.raises(th.TeehistorianError, match="does not match"):
+            chunk.unpack(["int", "int"])
+        with pytest.raises(th.TeehistorianError, match="Unknown field format"):
+            chunk.unpack(["float"])
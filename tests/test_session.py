@@ -0,0 +1,74 @@
+#!/usr/bin/env python3
+"""Test generating recordings from game events with RecordingSession."""
+
+import pytest
+import teehistorian_py as th
+
+
+def parse(writer):
+    parser = th.Teehistorian(writer.getvalue())
+    return [(parser.tick, chunk) for chunk in parser]
+
+
+def test_session_writes_server_chunks():
+    session = th.RecordingSession(map_name="Kobra")
+    session.join(3).join(0, "tee")
+    session.spawn(3, 5, 5).spawn(0, 100, 200).input(0, direction=1)
+    session.tick()
+    session.move(0, 110, 190).move(3, 5, 5).input(0, jump=1).tick(50)
+    session.chat(0, "gg").leave(0, "quit")
+    assert session.current_tick == 52
+    writer = session.finish()
+
+    assert writer.get_header("map_name") == "Kobra"
+    chunks = [(tick, c.chunk_type()) for tick, c in parse(writer)]
+    assert chunks == [
+        (1, "PlayerNew"),
+        (1, "PlayerNew"),
+        (1, "Join"),
+        (1, "Join"),
+        (1, "PlayerName"),
+        (1, "InputNew"),
+        (2, "PlayerDiff"),
+        (2, "InputDiff"),
+        (52, "TickSkip"),
+        (52, "PlayerOld"),
+        (52, "NetMessage"),
+        (52, "Drop"),
+        (52, "Eos"),
+    ]
+    chunks = [c for _, c in parse(writer)]
+    assert chunks[0].client_id == 0
+    assert (chunks[6].dx, chunks[6].dy) == (10, -10)
+    assert chunks[7].player_input.jump == 1
+    assert chunks[7].player_input.direction == 0
+    assert chunks[10].decode().message == "gg"
+
+
+def test_session_rejects_impossible_events():
+    session = th.RecordingSession()
+    with pytest.raises(ValueError, match="did not join"):
+        session.spawn(0, 0, 0)
+    session.join(0)
+    with pytest.raises(ValueError, match="already joined"):
+        session.join(0)
+    with pytest.raises(ValueError, match="not spawned"):
+        session.move(0, 1, 1)
+    with pytest.raises(TypeError, match="shoot"):
+        session.input(0, shoot=1)
+    with pytest.raises(ValueError):
+        session.tick(0)
+    with pytest.raises(ValueError, match="protocol"):
+        session.join(1, protocol=8)
+    session.join(1, protocol=7)
+    chunks = th.Teehistorian(session.finish().getvalue())
+    types = [c.chunk_type() for c in chunks if c.chunk_type() != "TickSkip"]
+    assert types == ["Join", "JoinVer7", "Join", "Eos"]
+
+
+def test_session_context_manager():
+    writer = th.TeehistorianWriter(strict=True)
+    with th.RecordingSession(writer) as session:
+        session.join(0).spawn(0, 0, 0).tick().kill(0).write(th.PlayerReady(0))
+    types = [c.chunk_type() for _, c in parse(writer)]
+    assert types == ["PlayerNew", "Join", "PlayerOld", "PlayerReady", "Eos"]
@@ -5,6 +5,8 @@ Comprehensive test suite for teehistorian_py module.
 Uses pytest conventions and best practices for clear, maintainable tests.
 """
 
+import json
+import os
 import tempfile
 from pathlib import Path
 
@@ -36,27 +38,40 @@ class TestModuleImports:
         chunk_types = [
             "Join",
             "JoinVer6",
+            "JoinVer7",
             "Drop",
             "PlayerReady",
+            "PlayerRejoin",
             "PlayerNew",
             "PlayerOld",
             "PlayerTeam",
             "PlayerName",
             "PlayerDiff",
+            "PlayerPosition",
+            "PlayerFinish",
+            "PlayerSwap",
             "InputNew",
             "InputDiff",
             "NetMessage",
             "ConsoleCommand",
+            "AuthInit",
             "AuthLogin",
+            "AuthLogout",
             "DdnetVersion",
+            "DdnetVersionOld",
             "TickSkip",
+            "TeamSaveSuccess",
+            "TeamSaveFailure",
             "TeamLoadSuccess",
             "TeamLoadFailure",
+            "TeamPractice",
+            "TeamFinish",
             "AntiBot",
             "Eos",
+            "TestChunk",
             "Unknown",
             "CustomChunk",
-            "Generic",
+            "RawChunk",
         ]
         for chunk_type in chunk_types:
             assert hasattr(th, chunk_type), f"Missing chunk type: {chunk_type}"
@@ -68,6 +83,10 @@ class TestModuleImports:
         assert hasattr(th, "create")
         assert hasattr(th, "calculate_uuid")
         assert hasattr(th, "format_uuid_from_bytes")
+        assert hasattr(th, "make_chunk")
+        assert hasattr(th, "parse_chunk")
+        assert hasattr(th, "categories")
+        assert hasattr(th, "chunk_types")
 
     def test_exception_types_exported(self):
         """Test exception types are available."""
@@ -122,6 +141,86 @@ class TestChunkCreation:
         assert eos is not None
         assert "Eos" in repr(eos)
 
+    def test_make_chunk(self):
+        """Test chunks can be created from their type name."""
+        player = th.make_chunk("PlayerNew", client_id=3, x=100, y=200)
+        assert isinstance(player, th.PlayerNew)
+        assert (player.client_id, player.x, player.y) == (3, 100, 200)
+        assert isinstance(th.make_chunk("Eos"), th.Eos)
+
+        fields = th.TeamFinish(2, 1500).to_dict()
+        finish = th.make_chunk(fields.pop("type"), **fields)
+        assert (finish.team, finish.time) == (2, 1500)
+
+        with pytest.raises(th.TeehistorianError, match="Unknown chunk type"):
+            th.make_chunk("Nope")
+
+    def test_chunk_types(self):
+        """Test the registry maps every type name to its class."""
+        types = th.chunk_types()
+        assert types["Join"] is th.Join
+        assert types["RawChunk"] is th.RawChunk
+        assert types["PlayerNew"](3, 100, 200).chunk_type() == "PlayerNew"
+        assert sorted(types) == sorted(
+            name for names in th.categories().values() for name in names
+        )
+
+    def test_unknown_known_extension(self):
+        """Test unknown chunks with a DDNet extension UUID are recognized."""
+        name = "teehistorian-player-name@ddnet.org"
+        # Skip the extension tag, UUID and payload size
+        payload = th.PlayerName(3, "nameless tee").write_to_buffer()[18:]
+        unknown = th.Unknown(th.calculate_uuid(name), payload)
+        assert unknown.uuid_name == name
+        decoded = unknown.decode()
+        assert isinstance(decoded, th.PlayerName)
+        assert (decoded.client_id, decoded.name) == (3, "nameless tee")
+
+        assert th.Unknown(unknown.uuid, b"").decode() is None
+        other = th.Unknown(th.calculate_uuid("kog-one-login@kog.tw"), b"")
+        assert other.uuid_name is None
+        assert other.decode() is None
+
+    def test_category(self):
+        """Test chunks know their category."""
+        assert th.InputNew(0, [0] * 10).category == "Input"
+        assert th.Join(0).category == "PlayerLifecycle"
+        assert th.Eos().category == "Special"
+
+        categories = th.categories()
+        assert categories["Input"] == ["InputNew", "InputDiff"]
+        assert "Join" in categories["PlayerLifecycle"]
+        assert sum(len(names) for names in categories.values()) == len(
+            set().union(*categories.values())
+        )
+
+    def test_parse_chunk(self):
+        """Test single chunks are decoded from their encoding."""
+        chunks = [
+            th.PlayerNew(3, 100, -200),
+            th.Drop(2, "timeout"),
+            th.TeamFinish(1, 4200),
+            th.Eos(),
+        ]
+        for chunk in chunks:
+            parsed = th.parse_chunk(chunk.write_to_buffer())
+            assert parsed.to_dict() == chunk.to_dict()
+
+        uuid = th.calculate_uuid("kog-checkpoint@kog.tw")
+        data = th.Unknown(uuid, b"\x01\x02").write_to_buffer()
+        assert isinstance(th.parse_chunk(data), th.Unknown)
+        custom = th.parse_chunk(data, uuid_registry=[uuid])
+        assert isinstance(custom, th.CustomChunk)
+        assert custom.data == b"\x01\x02"
+
+    def test_parse_chunk_invalid(self):
+        """Test truncated or overlong data is rejected."""
+        data = th.PlayerNew(3, 100, -200).write_to_buffer()
+        with pytest.raises(th.TeehistorianError, match="truncated"):
+            th.parse_chunk(data[:-1])
+        with pytest.raises(th.TeehistorianError, match="after the end"):
+            th.parse_chunk(data + b"\x00")
+
 
 # ============================================================================
 # Parser Tests
@@ -162,6 +261,41 @@ class TestParser:
             th.open("nonexistent_file_xyz.teehistorian")
 
 
+# ============================================================================
+# Format Detection Tests
+# ============================================================================
+
+
+class TestFormatDetection:
+    """Test identifying teehistorian data and its format version."""
+
+    def test_detect_format(self):
+        """Test the version written by the writer is detected."""
+        data = th.create().getvalue()
+        assert th.detect_format(data) == 2
+        assert th.Teehistorian(data).format_version == 2
+
+    def test_detect_format_compressed(self):
+        """Test compressed data is detected after decompression."""
+        import gzip
+
+        assert th.detect_format(gzip.compress(th.create().getvalue())) == 2
+
+    def test_detect_format_without_version(self):
+        """Test a header without a version yields None."""
+        data = th.create().getvalue()
+        start = data.index(b'"version"')
+        end = data.index(b",", start) + 1
+        data = data[:start] + data[end:]
+        assert th.detect_format(data) is None
+        assert th.Teehistorian(data).format_version is None
+
+    def test_detect_format_rejects_other_files(self):
+        """Test non-teehistorian data raises a clear validation error."""
+        with pytest.raises(th.TeehistorianError, match="Not a teehistorian file"):
+            th.detect_format(b"\x89PNG\r\n\x1a\n" + b"\x00" * 32)
+
+
 # ============================================================================
 # Exception Tests
 # ============================================================================
@@ -384,6 +518,338 @@ class TestWriterChunks:
         writer.write(th.Drop(0, "quit"))
         assert writer.size > 0
 
+    def test_player_team_extension_chunk(self):
+        """Test PlayerTeam is written as DDNet's player-team extension chunk."""
+        writer = th.create()
+        writer.write(th.PlayerTeam(3, 5))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        chunk = next(parser)
+        assert isinstance(chunk, th.PlayerTeam)
+        assert (chunk.client_id, chunk.team) == (3, 5)
+        uuid = th.calculate_uuid("teehistorian-player-team@ddnet.tw")
+        assert bytes.fromhex(uuid.replace("-", "")) in parser.raw()
+
+    def test_player_ready_extension_chunk(self):
+        """Test PlayerReady is written as DDNet's player-ready extension chunk."""
+        writer = th.create()
+        writer.write(th.PlayerReady(2))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        chunk = next(parser)
+        assert isinstance(chunk, th.PlayerReady)
+        assert chunk.client_id == 2
+        uuid = th.calculate_uuid("teehistorian-player-ready@ddnet.tw")
+        assert bytes.fromhex(uuid.replace("-", "")) in parser.raw()
+
+    def test_auth_init_roundtrip(self):
+        """Test AuthInit is written and read back as DDNet's auth-init chunk."""
+        writer = th.create()
+        writer.write(th.AuthInit(1, 2, "moderator"))
+        writer.write(th.Eos())
+
+        chunk = next(th.Teehistorian(writer.getvalue()))
+        assert isinstance(chunk, th.AuthInit)
+        assert (chunk.client_id, chunk.level, chunk.auth_name) == (1, 2, "moderator")
+        assert chunk.chunk_type() == "AuthInit"
+
+    def test_auth_timeline(self):
+        """Test logins and logouts are read back in order."""
+        writer = th.create()
+        writer.write(th.AuthLogin(4, 3, "admin"))
+        writer.write(th.AuthLogout(4))
+        writer.write(th.Eos())
+
+        chunks = list(th.Teehistorian(writer.getvalue()))
+        assert [c.chunk_type() for c in chunks] == ["AuthLogin", "AuthLogout", "Eos"]
+        assert chunks[1].client_id == 4
+
+    def test_player_rejoin_roundtrip(self):
+        """Test PlayerRejoin is written as DDNet's rejoin extension chunk."""
+        writer = th.create()
+        writer.write(th.PlayerRejoin(7))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        chunk = next(parser)
+        assert isinstance(chunk, th.PlayerRejoin)
+        assert chunk.client_id == 7
+        uuid = th.calculate_uuid("teehistorian-rejoinver6@ddnet.org")
+        assert bytes.fromhex(uuid.replace("-", "")) in parser.raw()
+
+    def test_drop_reason_bytes(self):
+        """Test kick reasons that are not UTF-8 are kept byte for byte."""
+        writer = th.create()
+        writer.write(th.Drop(4, b"kicked \xff\xfe by mod"))
+        writer.write(th.Drop(5, "timeout"))
+        writer.write(th.Eos())
+
+        raw, text, _ = th.Teehistorian(writer.getvalue())
+        assert raw.reason_bytes == b"kicked \xff\xfe by mod"
+        assert raw.reason == "kicked \ufffd\ufffd by mod"
+        assert text.reason == "timeout"
+        assert text.reason_bytes == b"timeout"
+
+        # Written back unchanged
+        writer = th.create()
+        writer.write(raw)
+        writer.write(th.Eos())
+        assert next(th.Teehistorian(writer.getvalue())).reason_bytes == raw.reason_bytes
+
+    def test_player_input_fields(self):
+        """Test input integers are exposed under their DDNet names."""
+        inp = th.PlayerInput(direction=-1, target_x=100, target_y=-20, hook=1)
+        writer = th.create()
+        writer.write(th.InputNew(3, inp.to_list()))
+        writer.write(th.InputDiff(3, th.PlayerInput(fire=1).to_list()))
+        writer.write(th.Eos())
+
+        new, diff, _ = th.Teehistorian(writer.getvalue())
+        assert new.player_input == inp
+        assert new.input == [-1, 100, -20, 0, 0, 1, 0, 0, 0, 0]
+        assert diff.player_input.fire == 1
+        assert th.PlayerInput.FIELDS[6] == "player_flags"
+        assert th.PlayerInput.from_list(new.input).to_dict()["target_y"] == -20
+
+    def test_resolve_inputs(self):
+        """Test InputDiff chunks are returned as absolute inputs."""
+        writer = th.create()
+        writer.write(th.InputNew(1, [1, 100, 50, 0, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.InputDiff(1, [-2, 10, 0, 1, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.InputDiff(1, [0, 10, -50, -1, 0, 0, 0, 0, 0, 0]))
+        writer.write(th.Eos())
+        data = writer.getvalue()
+
+        chunks = list(th.Teehistorian(data, resolve_inputs=True))
+        assert [c.chunk_type() for c in chunks] == ["InputNew"] * 3 + ["Eos"]
+        assert chunks[1].input == [-1, 110, 50, 1, 0, 0, 0, 0, 0, 0]
+        assert chunks[2].input == [-1, 120, 0, 0, 0, 0, 0, 0, 0, 0]
+
+        # Without the mode the diffs are returned as written
+        assert th.Teehistorian(data).peek().chunk_type() == "InputNew"
+        assert list(th.Teehistorian(data))[1].chunk_type() == "InputDiff"
+
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "inputs.teehistorian"
+            path.write_bytes(data)
+            assert list(th.parse(path, resolve_inputs=True))[1].chunk_type() == "InputNew"
+            assert list(th.open(path, resolve_inputs=True))[1].chunk_type() == "InputNew"
+
+    def test_resolve_positions(self):
+        """Test movement chunks are returned as absolute positions."""
+        writer = th.create()
+        writer.write(th.PlayerNew(0, 100, 200))
+        writer.write(th.PlayerDiff(0, 5, -10))
+        writer.write(th.PlayerDiff(0, 5, -10))
+        writer.write(th.PlayerOld(0))
+        writer.write(th.Eos())
+
+        chunks = list(th.Teehistorian(writer.getvalue(), resolve_positions=True))
+        positions = [(c.tick, c.x, c.y) for c in chunks[:3]]
+        assert all(isinstance(c, th.PlayerPosition) for c in chunks[:3])
+        assert positions == [(1, 100, 200), (2, 105, 190), (3, 110, 180)]
+        assert chunks[3].chunk_type() == "PlayerOld"
+
+        # Written back as PlayerNew
+        writer = th.create()
+        writer.write(chunks[1])
+        writer.write(th.Eos())
+        chunk = next(th.Teehistorian(writer.getvalue()))
+        assert (chunk.chunk_type(), chunk.x, chunk.y) == ("PlayerNew", 105, 190)
+
+        with tempfile.TemporaryDirectory() as tmp:
+            path = Path(tmp) / "moves.teehistorian"
+            path.write_bytes(writer.getvalue())
+            assert isinstance(next(th.parse(path, resolve_positions=True)), th.PlayerPosition)
+            assert isinstance(next(th.open(path, resolve_positions=True)), th.PlayerPosition)
+
+    def test_antibot_decode(self):
+        """Test AntiBot payloads are split into event and parameters."""
+        writer = th.create()
+        writer.write(th.AntiBot("aimbot_suspect 3 score=0.92"))
+        writer.write(th.AntiBot("\x01\x02"))
+        writer.write(th.Eos())
+
+        chunks = list(th.Teehistorian(writer.getvalue()))
+        event = chunks[0].decode()
+        assert isinstance(event, th.AntibotEvent)
+        assert event.event == "aimbot_suspect"
+        assert event.args == ["3"]
+        assert event.params == {"score": "0.92"}
+        assert event.raw == b"aimbot_suspect 3 score=0.92"
+
+        fallback = chunks[1].decode()
+        assert fallback.event is None
+        assert fallback.args == [] and fallback.params == {}
+        assert fallback.raw == b"\x01\x02"
+
+    def test_net_message_decode(self):
+        """Test NetMessage payloads are decoded into typed game messages."""
+        writer = th.create()
+        writer.write(th.NetMessage(1, b"\x22\x00gg\x00"))
+        writer.write(th.NetMessage(1, b"\x2e\x02"))
+        writer.write(th.NetMessage(2, b"\x32kick\x003\x00spam\x00"))
+        writer.write(th.NetMessage(2, b"\x2c"))
+        writer.write(th.Eos())
+
+        messages = [c.decode() for c in list(th.Teehistorian(writer.getvalue()))[:4]]
+        chat, emoticon, vote, kill = messages
+        assert isinstance(chat, th.ChatMessage)
+        assert (chat.mode, chat.target, chat.message) == ("all", -1, "gg")
+        assert (emoticon.emoticon, emoticon.name) == (2, "hearts")
+        assert isinstance(vote, th.VoteCall)
+        assert (vote.vote_type, vote.value, vote.reason) == ("kick", "3", "spam")
+        assert kill.message_type() == "KillMessage"
+        assert th.NetMessage(0, b"\xff\xff").decode() is None
+
+    def test_net_message_decode_protocol(self):
+        """Test messages of 0.7 clients are decoded with the 0.7 protocol."""
+        say = bytes([48, 1, 0x40]) + b"hi\x00"
+        writer = th.create()
+        writer.write_join(3, 7)
+        writer.write(th.NetMessage(3, say))
+        writer.write(th.NetMessage(1, say))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        ver7, unknown = [c for c in parser if isinstance(c, th.NetMessage)]
+        assert ver7.protocol == "0.7"
+        assert ver7.decode().message == "hi"
+        # Without the protocol it reads as a 0.6 vote
+        assert unknown.protocol is None
+        assert unknown.decode().message_type() == "Vote"
+        assert unknown.decode(protocol="0.7").message == "hi"
+        with pytest.raises(th.TeehistorianError, match="Unknown protocol"):
+            unknown.decode(protocol="0.5")
+
+        # A resumed parser still knows the client's protocol
+        parser = th.Teehistorian(writer.getvalue())
+        while not isinstance(parser.peek(), th.NetMessage):
+            next(parser)
+        resumed = th.Teehistorian.resume(writer.getvalue(), parser.save_state())
+        assert next(resumed).protocol == "0.7"
+
+        # So do the callbacks of transform() and patch()
+        seen = []
+        th.transform(writer.getvalue(), map_fn=seen.append, map_types=[th.NetMessage])
+        th.patch(writer.getvalue(), seen.append, lambda c: c)
+        messages = [c for c in seen if isinstance(c, th.NetMessage)]
+        assert [c.protocol for c in messages] == ["0.7", None] * 2
+        assert messages[2].decode().message == "hi"
+
+    def test_type_scans_do_not_print(self, tmp_path):
+        """Test telling message types apart prints no libtw2 warnings."""
+        # A 0.6 message with a byte after its fields
+        writer = th.create()
+        writer.write_join(1)
+        writer.write(th.NetMessage(1, b"\x02hi\x00\x05"))
+        writer.write(th.Eos())
+        data = writer.getvalue()
+        parser = th.Teehistorian(data)
+
+        stdout = tmp_path / "stdout"
+        saved = os.dup(1)
+        with open(stdout, "wb") as out:
+            os.dup2(out.fileno(), 1)
+            try:
+                parser.stats()
+                parser.count(th.NetMessage)
+                list(parser.filter([th.Join]))
+                th.transform(data, drop=[th.NetMessagePlayerInfo])
+            finally:
+                os.dup2(saved, 1)
+                os.close(saved)
+        assert stdout.read_bytes() == b""
+
+    def test_finish_roundtrip(self):
+        """Test player and team finishes are written as DDNet's extension chunks."""
+        writer = th.create()
+        writer.write(th.PlayerFinish(3, 1523))
+        writer.write(th.TeamFinish(7, 2048))
+        writer.write(th.Eos())
+
+        data = writer.getvalue()
+        player, team, _ = th.Teehistorian(data)
+        assert isinstance(player, th.PlayerFinish)
+        assert (player.client_id, player.time) == (3, 1523)
+        assert isinstance(team, th.TeamFinish)
+        assert (team.team, team.time) == (7, 2048)
+        for name in ("player-finish", "team-finish"):
+            uuid = th.calculate_uuid(f"teehistorian-{name}@ddnet.org")
+            assert bytes.fromhex(uuid.replace("-", "")) in data
+
+    def test_team_save_roundtrip(self):
+        """Test the team save, practice and swap chunks keep their type."""
+        save_id = "5f1f4a2c-0d8e-4b7e-9a63-1c2d3e4f5a6b"
+        writer = th.create()
+        writer.write(th.TeamSaveSuccess(3, save_id, "save data"))
+        writer.write(th.TeamSaveFailure(4))
+        writer.write(th.TeamPractice(3, 1))
+        writer.write(th.PlayerSwap(1, 2))
+        writer.write(th.JoinVer7(5))
+        writer.write(th.TestChunk())
+        writer.write(th.Eos())
+
+        chunks = list(th.Teehistorian(writer.getvalue()))
+        assert [c.chunk_type() for c in chunks] == [
+            "TeamSaveSuccess",
+            "TeamSaveFailure",
+            "TeamPractice",
+            "PlayerSwap",
+            "JoinVer7",
+            "TestChunk",
+            "Eos",
+        ]
+        save, failure, practice, swap, join, _, _ = chunks
+        assert (save.team, save.save_id, save.save) == (3, save_id, "save data")
+        assert failure.team == 4
+        assert (practice.team, practice.practice) == (3, 1)
+        assert (swap.client_id1, swap.client_id2) == (1, 2)
+        assert join.client_id == 5
+
+    def test_map_changes_start_segments(self):
+        """Test the recordings of several maps can be told apart by their headers."""
+        data = b""
+        for map_name in ("Kobra 4", "Kobra 4", "Tutorial"):
+            writer = th.create(map_name=map_name)
+            writer.write(th.Join(0))
+            writer.write(th.Eos())
+            data += writer.getvalue()
+
+        parser = th.Teehistorian(data)
+        maps = [parser.header_json()["map_name"]]
+        for chunk in parser:
+            if isinstance(chunk, th.SegmentStart):
+                map_name = json.loads(chunk.header)["map_name"]
+                if map_name != maps[-1]:
+                    maps.append(map_name)
+        assert maps == ["Kobra 4", "Tutorial"]
+
+    def test_raw_chunk_roundtrip(self):
+        """Test raw chunks write back the exact bytes they were read from."""
+        writer = th.create()
+        writer.write(th.Join(3))
+        writer.write(th.PlayerNew(3, 100, -200))
+        writer.write(th.TeamPractice(1, 1))
+        writer.write(th.Eos())
+        data = writer.getvalue()
+
+        parser = th.Teehistorian(data)
+        copy = th.create()
+        raws = []
+        for _ in parser:
+            raws.append(th.RawChunk.from_bytes(parser.raw()))
+            copy.write(raws[-1])
+        assert [raw.type_id for raw in raws] == [-8, -3, -11, -1]
+        assert copy.getvalue()[parser.body_offset :] == data[parser.body_offset :]
+        assert raws[0].chunk_type() == "RawChunk"
+        assert raws[0].payload == b"\x03"
+
+        with pytest.raises(th.TeehistorianError, match="after the end"):
+            th.RawChunk.from_bytes(b"\x41\x03\x00")
+
     def test_write_server_chunks(self):
         """Test writing server event chunks."""
         writer = th.create()
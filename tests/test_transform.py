@@ -0,0 +1,483 @@
+#!/usr/bin/env python3
+"""Test rewriting recordings with the transform functions."""
+
+import gzip
+import re
+from pathlib import Path
+
+import pytest
+import teehistorian_py as th
+
+RECORDING = Path(__file__).parent / "recording.teehistorian"
+
+
+def ticks(data):
+    parser = th.Teehistorian(data)
+    return [(parser.tick, chunk.chunk_type()) for chunk in parser]
+
+
+def test_transform_identity():
+    data = RECORDING.read_bytes()
+    assert th.transform(data) == data
+    assert th.transform(Path(str(RECORDING) + ".zst").read_bytes()) == data
+
+
+def test_transform_keep_and_drop():
+    data = RECORDING.read_bytes()
+    dropped = th.transform(data, drop=[th.PlayerNew, "PlayerDiff"])
+    assert ticks(dropped) == [
+        (tick, name) for tick, name in ticks(data) if name not in ("PlayerNew", "PlayerDiff")
+    ]
+    kept = th.transform(data, keep=["Join", "Drop"])
+    assert [name for _, name in ticks(kept)] == ["Join", "TickSkip", "Drop", "Eos"]
+    assert th.Teehistorian.validate(kept).is_valid
+    with pytest.raises(th.TeehistorianError, match="Unknown chunk type"):
+        th.transform(data, keep=["Jion"])
+
+
+def test_transform_map_fn():
+    data = RECORDING.read_bytes()
+    seen = []
+
+    def map_fn(chunk):
+        seen.append(chunk.chunk_type())
+        if isinstance(chunk, th.PlayerName):
+            return None
+        return [chunk, th.PlayerReady(chunk.client_id)]
+
+    out = th.transform(data, map_fn=map_fn, map_types=[th.Join, th.PlayerName])
+    assert seen == ["Join", "PlayerName"]
+    assert [name for _, name in ticks(out)][:3] == ["Join", "PlayerReady", "PlayerNew"]
+    assert "PlayerName" not in [name for _, name in ticks(out)]
+
+
+def test_patch():
+    data = RECORDING.read_bytes()
+    assert th.patch(data, lambda chunk: False, lambda chunk: None) == data
+
+    def rename(chunk):
+        return th.PlayerName(chunk.client_id, "nameless tee")
+
+    patched = th.patch(data, lambda chunk: isinstance(chunk, th.PlayerName), rename)
+    assert [c.name for c in th.Teehistorian(patched) if isinstance(c, th.PlayerName)] == [
+        "nameless tee"
+    ]
+    assert ticks(patched) == ticks(data)
+    assert len(patched) == len(data) + len("nameless tee") - len("Player")
+
+    writer = th.TeehistorianWriter()
+    writer.write(th.Join(0))
+    writer.write(th.NetMessage.chat(0, "gg"))
+    writer.write(th.NetMessage.chat(0, "bad word"))
+    data = writer.getvalue()
+
+    def is_bad(chunk):
+        return isinstance(chunk, th.NetMessage) and chunk.decode().message == "bad word"
+
+    censored = th.patch(data, is_bad, lambda chunk: th.NetMessage.chat(0, "***"))
+    messages = [c for c in th.Teehistorian(censored) if c.chunk_type() == "NetMessage"]
+    assert [c.decode().message for c in messages] == ["gg", "***"]
+    removed = th.patch(data, is_bad, lambda chunk: None)
+    assert [c.chunk_type() for c in th.Teehistorian(removed)] == ["Join", "NetMessage"]
+
+
+def test_anonymize():
+    # ClStartInfo of 0.6, packed by hand: name, clan, country, skin and colors
+    start_info = b"\x28nameless tee\x00clan\x00\x40default\x00\x00\x00\x00"
+    uuid = "12345678-1234-5678-1234-567812345678"
+    writer = th.TeehistorianWriter().set_header("server_name", "My server (1.2.3.4)")
+    writer.write_join(0, 6, version=th.DdnetVersion(0, uuid, 16050, b"DDNet 16.5"))
+    writer.write(th.NetMessage(0, start_info))
+    writer.write(th.PlayerName(0, "nameless tee"))
+    writer.write(th.NetMessage.chat(0, "my ip is 192.168.0.9"))
+    writer.write(th.AuthLogin(0, 2, "admin"))
+    writer.write(th.ConsoleCommand(0, 0, "ban", ["192.168.0.9:8303", "10"]))
+    writer.write(th.ConsoleCommand(0, 0, "kick", ["nameless tee"]))
+    data = writer.getvalue()
+
+    out = th.anonymize(data)
+    parser = th.Teehistorian(out)
+    chunks = [c for c in parser if c.chunk_type() != "TickSkip"]
+    assert parser.header_json()["server_name"] == "My server (10.0.0.1)"
+    assert chunks[2].connection_id == "00000000-0000-0000-0000-000000000001"
+    assert (chunks[3].name, chunks[3].clan) == ("player-1", "")
+    assert chunks[4].name == "player-1"
+    assert chunks[5].decode().message == "my ip is 10.0.0.2"
+    assert chunks[6].auth_name == "account-1"
+    assert chunks[7].args == ["10.0.0.2:8303", "10"]
+    assert chunks[8].args == ["player-1"]
+
+    chunks = [c for c in th.Teehistorian(th.anonymize(data, ips=False, chat=True))]
+    assert chunks[-4].decode().message == ""
+    assert chunks[-2].args == ["192.168.0.9:8303", "10"]
+    kept = th.anonymize(data, names=False, ips=False, auth=False)
+    assert kept == data
+
+
+def test_slice_ticks():
+    writer = th.TeehistorianWriter(delta_encode=True)
+    writer.write_join(0).write_join(1, 6)
+    writer.write(th.PlayerName(0, "runner"))
+    writer.write(th.AuthLogin(1, 2, "admin"))
+    for tick in range(1, 40):
+        writer.write(th.PlayerNew(0, 2 * tick, 0), tick=tick)
+        writer.write(th.PlayerNew(1, 50, 50 + (tick > 25)), tick=tick)
+        writer.write(th.InputNew(0, [tick // 5] + [0] * 9), tick=tick)
+    writer.write(th.Drop(1, "quit"), tick=39)
+    writer.finalize()
+    data = writer.getvalue()
+
+    def resolved(data, start, end):
+        parser = th.Teehistorian(data, resolve_inputs=True, resolve_positions=True)
+        return [
+            (parser.tick, str(chunk))
+            for chunk in parser
+            if start <= parser.tick < end and chunk.chunk_type() not in ("TickSkip", "Eos")
+        ]
+
+    sliced = th.slice_ticks(data, 20, 30)
+    assert resolved(sliced, 0, 20) == []
+    assert resolved(sliced, 21, 30) == resolved(data, 21, 30)
+    first = [chunk for _, chunk in resolved(sliced, 20, 21)]
+    assert [chunk.split(" ")[0] for chunk in first] == [
+        "PyJoin",
+        "PyPlayerName",
+        "PyJoinVer6",
+        "PyJoin",
+        "PyAuthInit",
+        "PyPlayerPosition",
+        "PyPlayerPosition",
+        "PyInputNew",
+    ]
+    assert "x: 50, y: 50" in first[6]
+    chunks = list(th.Teehistorian(sliced))
+    assert chunks[-1].chunk_type() == "Eos"
+
+    # The input set at tick 20 does not change in the slice
+    inputs = [chunk for _, chunk in resolved(th.slice_ticks(data, 21, 24), 21, 22)]
+    assert inputs[-1].startswith("PyInputNew") and "input: [4, 0" in inputs[-1]
+
+    # Ranges without chunks still connect the clients and end with Eos
+    for start, end in [(100, None), (100, 200)]:
+        empty = th.slice_ticks(data, start, end)
+        assert [name for _, name in ticks(empty)] == [
+            "TickSkip",
+            "Join",
+            "PlayerName",
+            "PlayerNew",
+            "InputNew",
+            "Eos",
+        ]
+        assert th.Teehistorian.validate(empty).is_valid
+
+    assert th.slice_ticks(data, 0) == data
+    with pytest.raises(th.TeehistorianError, match="before start"):
+        th.slice_ticks(data, 30, 20)
+
+
+def test_split():
+    writer = th.TeehistorianWriter(delta_encode=True)
+    writer.write_join(0).write_join(1, 6)
+    writer.write(th.PlayerName(0, "runner"))
+    for tick in range(1, 40):
+        writer.write(th.PlayerNew(0, 2 * tick, 0), tick=tick)
+        writer.write(th.InputNew(0, [tick // 5] + [0] * 9), tick=tick)
+    writer.write(th.Drop(1, "quit"), tick=39)
+    writer.write(th.Join(1), tick=250)
+    writer.finalize()
+    data = writer.getvalue()
+
+    def positions(data):
+        parser = th.Teehistorian(data, resolve_positions=True)
+        return [str(c) for c in parser if isinstance(c, th.PlayerPosition)]
+
+    parts = th.split(data, 10)
+    assert [ticks(part)[0][0] for part in parts] == [0, 10, 20, 30, 250]
+    assert all(ticks(part)[-1][1] == "Eos" for part in parts)
+    assert sum((positions(part) for part in parts[:4]), []) == positions(data)
+    assert [name for _, name in ticks(parts[4])][:4] == [
+        "TickSkip",
+        "Join",
+        "PlayerName",
+        "PlayerNew",
+    ]
+    assert th.split(data, every_minutes=0.1) == [th.slice_ticks(data, 0)]
+    with pytest.raises(th.TeehistorianError, match="every_ticks or every_minutes"):
+        th.split(data)
+    with pytest.raises(th.TeehistorianError, match="at least one tick"):
+        th.split(data, 0)
+
+
+def test_merge():
+    first = th.TeehistorianWriter()
+    first.write_join(0).write_join(1).write_join(2)
+    first.write(th.PlayerNew(0, 10, 10), tick=5)
+    first.write(th.Drop(1, "quit"), tick=10)
+    first.finalize()
+    # Restarted with the clients 0 and 2 still connected
+    second = th.TeehistorianWriter()
+    second.write_join(2).write_join(0).write_join(1)
+    for cid in range(3):
+        second.write(th.PlayerNew(cid, cid, 0), tick=5)
+    second.write(th.Drop(0, "quit"), tick=6)
+    second.finalize()
+
+    merged = th.merge([first.getvalue(), second.getvalue()])
+    parser = th.Teehistorian(merged)
+    chunks = [(parser.tick, c) for c in parser if c.chunk_type() != "TickSkip"]
+    assert [(tick, c.chunk_type(), c.client_id) for tick, c in chunks[5:-1]] == [
+        (11, "PlayerOld", 0),
+        (11, "Drop", 0),
+        (11, "Drop", 2),
+        (12, "Join", 2),
+        (12, "Join", 0),
+        (12, "Join", 1),
+        (17, "PlayerNew", 0),
+        (17, "PlayerNew", 1),
+        (17, "PlayerNew", 2),
+        (18, "Drop", 0),
+    ]
+    assert chunks[6][1].reason == "server restart"
+    assert [c.chunk_type() for _, c in chunks].count("Eos") == 1
+    assert [c.x for _, c in chunks if isinstance(c, th.PlayerNew)] == [10, 0, 1, 2]
+    assert th.Teehistorian.validate(merged).is_valid
+    assert th.merge([first.getvalue()]) == first.getvalue()
+
+    other = th.TeehistorianWriter().set_header("map_sha256", "00" * 32)
+    with pytest.raises(th.TeehistorianError, match="Recording 2 is not compatible.*map_sha256"):
+        th.merge([first.getvalue(), other.getvalue()])
+
+
+def test_redact_chat():
+    writer = th.TeehistorianWriter().write_join(0, 6)
+    for text in ["gg", "you NOOB", "noobs are welcome", "call 555-0199"]:
+        writer.write(th.NetMessage.chat(0, text))
+    writer.write(th.PlayerName(0, "noob"))
+    data = writer.getvalue()
+
+    def messages(out):
+        return [c.decode().message for c in th.Teehistorian(out) if isinstance(c, th.NetMessage)]
+
+    redacted = th.redact_chat(data, ["noob"])
+    assert messages(redacted) == ["gg", "", "noobs are welcome", "call 555-0199"]
+    assert [c.name for c in th.Teehistorian(redacted) if isinstance(c, th.PlayerName)] == ["noob"]
+    assert messages(th.redact_chat(data, r"\d{3}-\d{4}", remove=True)) == [
+        "gg",
+        "you NOOB",
+        "noobs are welcome",
+    ]
+    assert messages(th.redact_chat(data, re.compile("^g")))[0] == ""
+    assert th.redact_chat(data, ["idiot"]) == data
+    with pytest.raises(th.TeehistorianError, match="No words"):
+        th.redact_chat(data, [])
+
+
+def test_extract_player():
+    writer = th.TeehistorianWriter(delta_encode=True)
+    writer.write_join(0, 6).write_join(1, 6)
+    writer.write(th.PlayerName(0, "other"))
+    writer.write(th.PlayerName(1, "runner"))
+    for tick in range(1, 5):
+        writer.write(th.PlayerNew(0, 0, tick), tick=tick)
+        writer.write(th.PlayerNew(1, tick, 0), tick=tick)
+    writer.write(th.TeamSaveFailure(0), tick=5)
+    writer.write(th.Drop(1, "quit"), tick=6)
+    writer.finalize()
+    data = writer.getvalue()
+
+    out = th.extract_player(data, "runner")
+    assert out == th.extract_player(data, 1)
+    parser = th.Teehistorian(out)
+    chunks = [(parser.tick, c) for c in parser if c.chunk_type() != "TickSkip"]
+    assert {c.client_id for _, c in chunks if hasattr(c, "client_id")} == {1}
+    assert [tick for tick, c in chunks if isinstance(c, th.PlayerDiff)] == [2, 3, 4]
+    assert [c.chunk_type() for _, c in chunks][-2:] == ["Drop", "Eos"]
+    assert chunks[-2][0] == 6
+
+    with pytest.raises(th.TeehistorianError, match="No client named"):
+        th.extract_player(data, "nobody")
+    with pytest.raises(th.TeehistorianError, match="No chunks of client 7"):
+        th.extract_player(data, 7)
+
+
+def test_diff():
+    def recording(delta_encode, last_x=3):
+        writer = th.TeehistorianWriter(delta_encode=delta_encode).write_join(0)
+        for tick, x in enumerate([1, 2, last_x], start=1):
+            writer.write(th.PlayerNew(0, x, 0), tick=tick)
+        writer.finalize()
+        return writer.getvalue()
+
+    result = th.diff(recording(False), recording(True))
+    assert result.identical
+    assert result.chunk_counts == {"PlayerDiff": 2, "PlayerNew": -2}
+    assert (result.first_index, result.offsets, result.chunks) == (None, None, None)
+
+    result = th.diff(recording(True), recording(True, last_x=7))
+    assert not result.identical
+    assert result.first_index == 3
+    assert result.ticks == (3, 3)
+    a, b = result.chunks
+    assert (a.dx, b.dx) == (1, 5)
+
+    other = th.TeehistorianWriter().set_header("map_name", "Kobra").write_join(0)
+    result = th.diff(recording(False), other.getvalue())
+    assert result.header["map_name"][1] == "Kobra"
+    assert result.chunk_counts == {"PlayerNew": -3, "Eos": -1}
+    assert result.first_index == 1 and result.offsets[1] is None
+
+
+def test_normalize():
+    def recording(delta_encode):
+        writer = th.TeehistorianWriter(delta_encode=delta_encode).write_join(0)
+        for tick, x in enumerate([1, 2, 2, 5], start=1):
+            writer.write(th.PlayerNew(0, x, 0), tick=tick)
+        writer.finalize()
+        return writer.getvalue()
+
+    full, delta = recording(False), recording(True)
+    assert full != delta
+    assert th.normalize(full) == th.normalize(delta) == delta
+    assert th.normalize(th.normalize(full)) == th.normalize(full)
+
+    expanded = th.normalize(delta, expand_diffs=True)
+    assert th.diff(expanded, delta).identical
+    assert [c.x for c in th.Teehistorian(expanded) if isinstance(c, th.PlayerNew)] == [1, 2, 5]
+    kept = th.normalize(full, recompress_diffs=False)
+    assert [c.chunk_type() for c in th.Teehistorian(kept)].count("PlayerNew") == 4
+
+
+def test_shift_ticks():
+    writer = th.TeehistorianWriter().write_join(0)
+    writer.write(th.PlayerNew(0, 1, 1), tick=3)
+    writer.write(th.Drop(0, "quit"), tick=8)
+    writer.finalize()
+    data = writer.getvalue()
+
+    shifted = th.shift_ticks(data, 100)
+    assert [entry for entry in ticks(shifted) if entry[1] != "TickSkip"] == [
+        (100, "Join"),
+        (103, "PlayerNew"),
+        (108, "Drop"),
+        (108, "Eos"),
+    ]
+    assert th.shift_ticks(shifted, -100) == data
+    with pytest.raises(th.TeehistorianError, match="would be moved to -1"):
+        th.shift_ticks(data, -1)
+
+
+def test_remap_cids():
+    writer = th.TeehistorianWriter().write_join(0).write_join(1)
+    writer.write(th.PlayerNew(0, 10, 0), tick=1)
+    writer.write(th.PlayerNew(1, 20, 0), tick=1)
+    writer.write(th.ConsoleCommand(1, 0, "kill", []), tick=2)
+    writer.finalize()
+    data = writer.getvalue()
+
+    parser = th.Teehistorian(th.remap_cids(data, {0: 5, 1: 2}))
+    chunks = [(parser.tick, c) for c in parser if c.chunk_type() != "TickSkip"]
+    assert [(tick, c.chunk_type(), c.client_id) for tick, c in chunks[:-1]] == [
+        (0, "Join", 5),
+        (0, "Join", 2),
+        (1, "PlayerNew", 2),
+        (1, "PlayerNew", 5),
+        (2, "ConsoleCommand", 2),
+    ]
+    assert th.remap_cids(data, {}) == data
+    with pytest.raises(th.TeehistorianError, match="both mapped to 3"):
+        th.remap_cids(data, {0: 3, 1: 3})
+    with pytest.raises(th.TeehistorianError, match="Client 1 keeps its ID"):
+        th.remap_cids(data, {0: 1})
+
+
+def test_strip():
+    data = RECORDING.read_bytes()
+    stripped = th.strip(data)
+    expected = [
+        entry
+        for entry in ticks(data)
+        if entry[1] not in ("InputNew", "InputDiff", "TickSkip")
+    ]
+    assert [entry for entry in ticks(stripped) if entry[1] != "TickSkip"] == expected
+
+    writer = th.TeehistorianWriter().write_join(0)
+    for tick in range(1, 10):
+        writer.write(th.InputNew(0, [tick] * 10), tick=tick)
+    writer.finalize()
+    inputs = writer.getvalue()
+    assert ticks(th.strip(inputs)) == [(0, "Join"), (9, "TickSkip"), (9, "Eos")]
+
+    events = th.strip(data, ["Input", "PlayerState", "Special"])
+    assert th.Teehistorian.validate(events).is_valid
+    assert th.strip(data, []) == data
+    with pytest.raises(th.TeehistorianError, match="Unknown chunk category: Inputs"):
+        th.strip(data, ["Inputs"])
+
+
+def test_repair():
+    writer = th.TeehistorianWriter().write_join(0)
+    writer.write(th.PlayerName(0, "nameless tee"), tick=5)
+    writer.finalize()
+    data = writer.getvalue()
+
+    fixed, report = th.repair(data)
+    assert fixed == data
+    assert not report.repaired
+
+    # Cut off in the middle of the name, before the Eos
+    cut = data[:-5]
+    fixed, report = th.repair(cut)
+    assert (report.truncated, report.eos_added) == (True, True)
+    assert report.dropped_bytes == len(cut) - len(fixed) + 1
+    assert report.error_offset == len(fixed) - 1
+    assert report.last_tick == 5
+    assert th.Teehistorian.validate(fixed).is_valid
+    assert [c.chunk_type() for c in th.Teehistorian(fixed)] == ["Join", "TickSkip", "Eos"]
+    assert report.decompress_error is None
+
+
+def test_repair_truncated_gzip():
+    writer = th.TeehistorianWriter().write_join(0)
+    for tick in range(1, 2000):
+        writer.write(th.PlayerNew(0, tick * 7919 % 10007, tick), tick=tick)
+    writer.finalize()
+    data = writer.getvalue()
+    archive = gzip.compress(data)
+
+    with pytest.raises(th.TeehistorianError, match="unexpected end of file"):
+        th.Teehistorian(archive[: len(archive) // 2])
+    fixed, report = th.repair(archive[: len(archive) // 2])
+    assert "Failed to decompress gzip data" in report.decompress_error
+    assert report.repaired and report.eos_added
+    assert report.kept_chunks > 100
+    assert data.startswith(fixed[:-1])
+    assert th.Teehistorian.validate(fixed).is_valid
+
+
+def test_recompress():
+    writer = th.TeehistorianWriter().write_join(0)
+    for tick, x in enumerate([1_000_000, 1_000_001, 1_000_001, 0], start=1):
+        writer.write(th.PlayerNew(0, x, 0), tick=tick)
+        writer.write(th.InputNew(0, [1] * 10), tick=tick)
+    writer.finalize()
+    data = writer.getvalue()
+
+    (small, report), (fast, _) = th.recompress(data), th.recompress(data, "fast")
+    assert len(small) < len(fast) < len(data)
+    assert th.recompress(data)[0] == small
+    assert (report.original_bytes, report.recompressed_bytes) == (len(data), len(small))
+    assert report.saved_bytes == len(data) - len(small) > 0
+
+    def values(data):
+        parser = th.Teehistorian(data, resolve_positions=True)
+        return [(parser.tick, c.chunk_type(), c.x) for c in parser if hasattr(c, "x")]
+
+    assert values(small) == values(fast) == values(data)[:2] + values(data)[3:]
+    types = [c.chunk_type() for c in th.Teehistorian(small)]
+    assert (types.count("PlayerNew"), types.count("PlayerDiff"), types.count("InputNew")) == (
+        2,
+        1,
+        1,
+    )
+    with pytest.raises(th.TeehistorianError, match="Unknown profile: tiny"):
+        th.recompress(data, "tiny")
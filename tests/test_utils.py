@@ -2,7 +2,8 @@
 """Tests for the utils module."""
 
 import pytest
-from teehistorian_py.utils import calculate_uuid, format_uuid_from_bytes
+import teehistorian_py as th
+from teehistorian_py.utils import calculate_uuid, derive_uuid, format_uuid_from_bytes
 
 
 class TestCalculateUuid:
@@ -75,6 +76,30 @@ class TestCalculateUuid:
         assert len(parts) == 5
 
 
+class TestDeriveUuid:
+    """Tests for derive_uuid function."""
+
+    def test_derive_uuid_matches_server(self):
+        """Test UUIDs match the ones the server computes."""
+        assert derive_uuid("teehistorian@ddnet.tw") == (
+            "699db17b-8efb-34ff-b1d8-da6f60c15dd1"
+        )
+        assert derive_uuid("teehistorian-player-name@ddnet.org") == (
+            "d016f9b9-4151-3b87-87e5-3a6087eb5f26"
+        )
+
+    def test_derive_uuid_names_extension(self):
+        """Test a chunk written with a derived UUID is recognized."""
+        unknown = th.Unknown(derive_uuid("teehistorian-player-name@ddnet.org"), b"")
+        assert unknown.uuid_name == "teehistorian-player-name@ddnet.org"
+
+    def test_calculate_uuid_is_derive_uuid(self):
+        """Test the older name gives the same UUIDs."""
+        assert calculate_uuid("kog-checkpoint@kog.tw") == derive_uuid(
+            "kog-checkpoint@kog.tw"
+        )
+
+
 class TestFormatUuidFromBytes:
     """Tests for format_uuid_from_bytes function."""
 
@@ -6,6 +6,8 @@ Tests creating new teehistorian files, writing chunks, and verifying
 that written files can be parsed back correctly.
 """
 
+import io
+import itertools
 import json
 import os
 import tempfile
@@ -228,6 +230,103 @@ class TestWriterFileOperations:
         assert len(data) > 0
         assert isinstance(data, bytes)
 
+    def test_streaming_to_path(self, tmp_path):
+        """Test a writer bound to a path flushes chunks to disk as it goes."""
+        path = tmp_path / "live.teehistorian"
+        chunks = [th.ConsoleCommand(0, 0, "say", ["x" * 100]) for _ in range(1000)]
+
+        memory = th.create(server_name="Stream")
+        memory.write_all(chunks)
+        memory.write(th.Eos())
+
+        with th.TeehistorianWriter(path=path) as writer:
+            writer.set_header("server_name", "Stream")
+            writer.write_all(chunks)
+            assert 0 < path.stat().st_size < writer.size
+            assert writer.getvalue() == memory.getvalue()[: writer.size]
+
+        assert path.read_bytes() == memory.getvalue()
+
+    def test_streaming_declared_extension(self, tmp_path):
+        """Test an extension declared up front is registered after a flush."""
+        path = tmp_path / "live.teehistorian"
+        uuid = th.derive_uuid("kog-checkpoint@kog.tw")
+
+        with th.TeehistorianWriter(path=path, extensions={uuid: "kog-checkpoint"}) as writer:
+            writer.write_all(th.PlayerDiff(0, 1, 1) for _ in range(30000))
+            assert path.stat().st_size > 0
+            writer.write(th.CustomChunk(uuid, b"\x01", "kog-checkpoint"))
+
+        chunks = list(th.parse(path))
+        assert isinstance(chunks[-2], th.CustomChunk)
+        assert chunks[-2].handler_name == "kog-checkpoint"
+        assert len(chunks) == 30002
+
+    def test_streaming_rejects_late_extension(self, tmp_path):
+        """Test an undeclared extension after a flush raises instead of being lost."""
+        from io import BytesIO
+
+        uuid = th.derive_uuid("kog-checkpoint@kog.tw")
+        checkpoint = th.CustomChunk(uuid, b"\x01", "kog-checkpoint")
+
+        stream = BytesIO()
+        with th.TeehistorianWriter(stream, buffer_size=0) as writer:
+            writer.write(th.Join(0))
+            with pytest.raises(th.TeehistorianError, match="was not declared"):
+                writer.write(checkpoint)
+        chunks = th.Teehistorian(stream.getvalue())
+        assert [c.chunk_type() for c in chunks] == ["Join", "Eos"]
+
+        stream = BytesIO()
+        with th.TeehistorianWriter(
+            stream, buffer_size=0, extensions={uuid: "kog-checkpoint"}
+        ) as writer:
+            writer.write(th.Join(0))
+            writer.write(checkpoint)
+        chunks = list(th.Teehistorian(stream.getvalue()))
+        assert isinstance(chunks[1], th.CustomChunk)
+        assert chunks[1].handler_name == "kog-checkpoint"
+
+        path = tmp_path / "live.teehistorian"
+        with th.TeehistorianWriter(path=path, buffer_size=0) as writer:
+            writer.write(th.Join(0))
+            with pytest.raises(th.TeehistorianError, match="was not declared"):
+                writer.write(checkpoint)
+
+        with pytest.raises(th.TeehistorianError, match="Invalid extension UUID"):
+            th.TeehistorianWriter(extensions={"nope": "kog-checkpoint"})
+
+    def test_streaming_to_file_object(self):
+        """Test every chunk reaches a file object as soon as it is written."""
+        from io import BytesIO
+
+        class Pipe(BytesIO):
+            flushes = 0
+
+            def flush(self):
+                self.flushes += 1
+
+        pipe = Pipe()
+        memory = th.create()
+        with th.TeehistorianWriter(pipe, buffer_size=0) as writer:
+            for cid in range(3):
+                writer.write(th.Join(cid))
+                memory.write(th.Join(cid))
+                assert pipe.getvalue() == memory.getvalue()
+            with pytest.raises(th.TeehistorianError, match="cannot be read back"):
+                writer.getvalue()
+        memory.write(th.Eos())
+
+        assert pipe.getvalue() == memory.getvalue()
+        assert pipe.flushes == 4
+
+    def test_streaming_rejects_file_and_path(self, tmp_path):
+        """Test a writer streams to either a file object or a path."""
+        from io import BytesIO
+
+        with pytest.raises(th.TeehistorianError, match="cannot be combined"):
+            th.TeehistorianWriter(BytesIO(), path=tmp_path / "out.teehistorian")
+
 
 class TestWriterHeaders:
     """Test setting and managing headers."""
@@ -333,6 +432,35 @@ class TestWriterStateManagement:
         finally:
             temp_path.unlink(missing_ok=True)
 
+    def test_finalize(self):
+        """Test finalize writes a single Eos and closes the writer."""
+        writer = th.create()
+        writer.write(th.Join(0))
+        writer.finalize()
+        writer.finalize()
+        types = [c.chunk_type() for c in th.Teehistorian(writer.getvalue())]
+        assert types == ["Join", "Eos"]
+        with pytest.raises(ValueError, match="closed"):
+            writer.write(th.Join(1))
+
+        # An Eos written by hand is not repeated
+        with th.create() as writer:
+            writer.write(th.Join(0)).write(th.Eos())
+        types = [c.chunk_type() for c in th.Teehistorian(writer.getvalue())]
+        assert types == ["Join", "Eos"]
+
+        # The Rust writer refuses chunks as well
+        raw = th.create()._writer
+        raw.write(th.Join(0))
+        raw.finalize()
+        assert raw.finished
+        with pytest.raises(th.TeehistorianError, match="finalized"):
+            raw.write(th.Join(1))
+
+        writer.reset()
+        assert not writer._closed
+        writer.write(th.Join(0))
+
 
 class TestWriterRoundtrip:
     """Test writing and reading back."""
@@ -424,6 +552,288 @@ class TestWriterRoundtrip:
         finally:
             temp_path.unlink(missing_ok=True)
 
+    def test_custom_chunk_uuid_registered(self):
+        """Test custom chunks register their UUID in the header."""
+        uuid = th.calculate_uuid("kog-checkpoint@kog.tw")
+        writer = th.create()
+        writer.write(th.Join(0))
+        writer.write(th.CustomChunk(uuid, b"\x01", "kog-checkpoint"))
+        writer.write(th.CustomChunk(uuid, b"\x02", "kog-checkpoint"))
+        writer.write(th.Unknown(th.calculate_uuid("other@kog.tw"), b""))
+        writer.write(th.PlayerName(0, "nameless tee"))
+        writer.write(th.Eos())
+
+        parser = th.Teehistorian(writer.getvalue())
+        registrations = parser.uuid_registrations
+        assert [(r.uuid, r.name) for r in registrations] == [(uuid, "kog-checkpoint")]
+        chunks = list(parser)
+        assert [c.chunk_type() for c in chunks] == [
+            "Join",
+            "CustomChunk",
+            "CustomChunk",
+            "Unknown",
+            "PlayerName",
+            "Eos",
+        ]
+        assert chunks[2].handler_name == "kog-checkpoint"
+        assert chunks[2].data == b"\x02"
+
+    def test_absolute_ticks(self):
+        """Test the writer inserts TickSkips for absolute ticks."""
+        writer = th.create()
+        writer.write(th.Join(0), tick=0)
+        writer.write(th.PlayerNew(0, 100, 200), tick=1)
+        writer.write(th.PlayerDiff(0, 5, 0), tick=2)
+        writer.write(th.PlayerDiff(0, 5, 0), tick=10)
+        writer.advance_to_tick(10).advance_to_tick(25)
+        assert writer.tick == 25
+        writer.write(th.Eos())
+
+        written = []
+        parser = th.Teehistorian(writer.getvalue())
+        for chunk in parser:
+            written.append((parser.tick, chunk.chunk_type()))
+        assert written == [
+            (0, "Join"),
+            (1, "PlayerNew"),
+            (2, "PlayerDiff"),
+            (10, "TickSkip"),
+            (10, "PlayerDiff"),
+            (25, "TickSkip"),
+            (25, "Eos"),
+        ]
+
+
+        writer = th.create().write(th.PlayerNew(0, 0, 0), tick=5)
+        with pytest.raises(th.TeehistorianError, match="Cannot go back"):
+            writer.write(th.Join(1), tick=3)
+        # A second position of client 0 can only go to the next tick
+        with pytest.raises(th.TeehistorianError, match="instead of 5"):
+            writer.write(th.PlayerDiff(0, 1, 1), tick=5)
+
+    def test_append(self, tmp_path):
+        """Test continuing a finished and an interrupted recording."""
+        uuid = th.derive_uuid("kog-checkpoint@kog.tw")
+        writer = th.create(server_name="Append")
+        writer.write(th.Join(0))
+        writer.write(th.CustomChunk(uuid, b"\x01", "kog-checkpoint"))
+        writer.write(th.PlayerNew(0, 0, 0), tick=5)
+        writer.finalize()
+        path = tmp_path / "finished.teehistorian"
+        writer.save(path)
+
+        appended = th.TeehistorianWriter.append(path, strict=True)
+        assert appended.tick == 5
+        assert appended.get_header("server_name") == "Append"
+        with pytest.raises(th.TeehistorianError, match="joined twice"):
+            appended.write(th.Join(0))
+        appended.write(th.PlayerDiff(0, 1, 1), tick=8)
+        appended.write(th.Drop(0, "quit"))
+        appended.finalize()
+
+        parser = th.Teehistorian(appended.getvalue())
+        chunks = [(parser.tick, c.chunk_type()) for c in parser]
+        assert chunks == [
+            (0, "Join"),
+            (0, "CustomChunk"),
+            (5, "TickSkip"),
+            (5, "PlayerNew"),
+            (8, "TickSkip"),
+            (8, "PlayerDiff"),
+            (8, "Drop"),
+            (8, "Eos"),
+        ]
+        assert [r.uuid for r in parser.uuid_registrations] == [uuid]
+
+        # An interrupted recording loses its incomplete last chunk
+        data = writer.getvalue()[: -len(th.Eos().write_to_buffer()) - 1]
+        appended = th.TeehistorianWriter.append(data)
+        assert appended.tick == 5
+        appended.finalize()
+        types = [c.chunk_type() for c in th.Teehistorian(appended.getvalue())]
+        assert types == ["Join", "CustomChunk", "TickSkip", "Eos"]
+
+    def test_spill_threshold(self):
+        """Test an in-memory writer moving to a temporary file."""
+        chunks = [th.Join(0)] + [th.PlayerNew(0, i, i) for i in range(1000)]
+        expected = th.TeehistorianWriter().write_all(chunks).getvalue()
+
+        writer = th.TeehistorianWriter(spill_threshold=1000).reserve(10**9)
+        writer.write_all(chunks[:10])
+        assert not writer.spilled
+        writer.write_all(chunks[10:])
+        assert writer.spilled
+        assert writer.size == len(expected)
+        assert writer.getvalue() == expected
+
+        with pytest.raises(th.TeehistorianError, match="in-memory"):
+            th.TeehistorianWriter(io.BytesIO(), spill_threshold=1000)
+
+    def test_estimate_size(self):
+        """Test estimating the size of chunks without writing them."""
+        chunks = [th.Join(0), th.PlayerNew(0, 100, 200), th.Drop(0, "quit")]
+        writer = th.TeehistorianWriter()
+        assert writer.estimate_size(chunks) == len(
+            th.TeehistorianWriter().write_all(chunks).getvalue()
+        )
+        assert writer.is_empty
+
+        writer.write_all(chunks[:1])
+        size = writer.size
+        assert writer.estimate_size(chunks[1:]) == len(writer.write_all(chunks[1:]).getvalue()) - size
+
+        # Registering a custom extension grows the header
+        custom = th.CustomChunk("12345678-1234-1234-1234-123456789abc", b"data", "test-ext")
+        before = writer.size
+        estimate = writer.estimate_size(iter([custom]))
+        writer.write(custom)
+        assert estimate == writer.size - before
+
+        strict = th.TeehistorianWriter(strict=True)
+        with pytest.raises(th.TeehistorianError, match="without joining"):
+            strict.estimate_size([th.Drop(0, "quit")])
+
+    def test_write_from(self):
+        """Test writing chunks pulled from a parser in batches."""
+
+        class Raw:
+            def write_to_buffer(self):
+                return th.PlayerReady(3).write_to_buffer()
+
+        data = Path("tests/recording.teehistorian").read_bytes()
+        expected = th.TeehistorianWriter()
+        expected.write_all(list(th.Teehistorian(data)) + [Raw()])
+
+        writer = th.TeehistorianWriter()
+        chunks = itertools.chain(th.Teehistorian(data), [Raw()])
+        count = writer.write_from(chunks, batch_size=7)
+        assert count == len(list(th.Teehistorian(data))) + 1
+        assert writer.getvalue() == expected.getvalue()
+        assert writer.write_from([]) == 0
+
+    def test_copy_header_from(self):
+        """Test keeping the header of a parsed recording."""
+        uuid = th.derive_uuid("kog-checkpoint@kog.tw")
+        original = th.create(server_name="Original", map_name="Kobra")
+        original.set_header("config", json.dumps({"sv_motd": "hi"}))
+        original.write(th.CustomChunk(uuid, b"\x01", "kog-checkpoint"))
+        data = original.getvalue()
+        parser = th.Teehistorian(data)
+
+        for source in [
+            parser,
+            parser.header_info,
+            parser.header_json(),
+            parser.get_header_str(),
+            parser.header(),
+        ]:
+            writer = th.TeehistorianWriter()
+            writer.copy_header_from(source).set_header("comment", "copy")
+            writer.write(th.Join(0))
+            copied = th.Teehistorian(writer.getvalue())
+            header = copied.header_json()
+            assert header["server_name"] == "Original"
+            assert header["config"] == {"sv_motd": "hi"}
+            assert header["comment"] == "copy"
+            assert [r.uuid for r in copied.uuid_registrations] == [uuid]
+
+        writer = th.TeehistorianWriter()
+        writer.write(th.Join(0))
+        with pytest.raises(th.TeehistorianError):
+            writer.copy_header_from(parser)
+
+    def test_strict_chunk_order(self):
+        """Test the strict writer rejects chunks in an impossible order."""
+        writer = th.TeehistorianWriter(strict=True)
+        with pytest.raises(th.TeehistorianError, match="dropped without joining"):
+            writer.write(th.Drop(0, "quit"))
+        writer.write(th.Join(0))
+        with pytest.raises(th.TeehistorianError, match="joined twice"):
+            writer.write(th.Join(0))
+        with pytest.raises(th.TeehistorianError, match="before its PlayerNew"):
+            writer.write(th.PlayerDiff(0, 5, 0))
+        writer.write(th.PlayerNew(0, 100, 200))
+        writer.write(th.PlayerDiff(0, 5, 0))
+        writer.write(th.Drop(0, "quit"))
+        writer.write(th.Eos())
+        with pytest.raises(th.TeehistorianError, match="after Eos"):
+            writer.write(th.Join(1))
+
+        # Rejected chunks are not written
+        chunks = list(th.Teehistorian(writer.getvalue()))
+        assert [c.chunk_type() for c in chunks] == [
+            "Join",
+            "PlayerNew",
+            "PlayerDiff",
+            "Drop",
+            "Eos",
+        ]
+
+        # Without strict mode the writer takes any order
+        th.TeehistorianWriter().write(th.PlayerDiff(0, 5, 0))
+
+    def test_write_join(self):
+        """Test writing joins of clients with different protocols."""
+        writer = th.TeehistorianWriter(strict=True)
+        writer.write_join(0, 7)
+        writer.write_join(1, 6, version=th.DdnetVersionOld(1, 16050), tick=3)
+        writer.write_join(2)
+        with pytest.raises(th.TeehistorianError, match="joined twice"):
+            writer.write_join(0, 6)
+        with pytest.raises(th.TeehistorianError, match="expected 6 or 7"):
+            writer.write_join(3, 8)
+        with pytest.raises(th.TeehistorianError, match="client 1"):
+            writer.write_join(3, version=th.DdnetVersionOld(1, 16050))
+        writer.write(th.JoinVer7(4))
+        with pytest.raises(th.TeehistorianError, match="missing"):
+            writer.write(th.Join(5))
+
+        data = th.TeehistorianWriter().write_join(0, 7).write_join(1, 6).getvalue()
+        assert "__teehistorian_py" not in th.Teehistorian(data).header_json()
+        chunks = [c for c in th.Teehistorian(writer.getvalue()) if c.chunk_type() != "TickSkip"]
+        assert [c.chunk_type() for c in chunks] == [
+            "JoinVer7",
+            "Join",
+            "JoinVer6",
+            "Join",
+            "DdnetVersionOld",
+            "Join",
+            "JoinVer7",
+        ]
+
+    def test_delta_encode(self):
+        """Test absolute positions and inputs being written as diffs."""
+        writer = th.TeehistorianWriter(delta_encode=True)
+        writer.write(th.Join(0))
+        writer.write(th.PlayerNew(0, 100, 200), tick=1)
+        writer.write(th.InputNew(0, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]), tick=1)
+        writer.write(th.PlayerNew(0, 105, 200), tick=2)
+        writer.write(th.InputNew(0, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]), tick=2)
+        writer.write(th.PlayerPosition(0, 3, 105, 200), tick=3)
+        writer.write(th.InputNew(0, [-1, 0, 0, 0, 1, 0, 0, 0, 0, 0]), tick=3)
+        writer.write(th.PlayerDiff(0, 0, -10), tick=4)
+        writer.write(th.PlayerNew(0, 105, 180), tick=5)
+        writer.write(th.Drop(0, "quit"), tick=5)
+        writer.write(th.Join(0), tick=6)
+        writer.write(th.InputNew(0, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]), tick=6)
+
+        chunks = [c for c in th.Teehistorian(writer.getvalue()) if c.chunk_type() != "TickSkip"]
+        assert [c.chunk_type() for c in chunks] == [
+            "Join",
+            "PlayerNew",
+            "InputNew",
+            "PlayerDiff",
+            "InputDiff",
+            "PlayerDiff",
+            "PlayerDiff",
+            "Drop",
+            "Join",
+            "InputNew",
+        ]
+        assert (chunks[3].dx, chunks[3].dy) == (5, 0)
+        assert chunks[4].input == [-2, 0, 0, 0, 1, 0, 0, 0, 0, 0]
+        assert (chunks[6].dx, chunks[6].dy) == (0, -10)
+
 
 class TestWriterSizeConsistency:
     """Test that file sizes are consistent in roundtrip."""
@@ -683,6 +1093,32 @@ class TestWriterJSONHeaders:
         assert header["config"]["sv_motd"] == "Welcome to server"
         assert header["config"]["sv_name"] == "Test Server"
 
+    def test_typed_header_values(self):
+        """Test that non-string header values keep their JSON type."""
+        writer = th.TeehistorianWriter()
+        writer.set_header("map_size", 299932)
+        writer.set_header("ratio", 0.5)
+        writer.set_header("flags", [True, None, "1"])
+        writer.update_headers({"config": {"sv_motd": "{not json}", "limits": {"clients": 64}}})
+        writer.set_header("comment", "gone")
+        writer.remove_header("comment").remove_header("missing")
+        assert writer.get_header("config")["limits"] == {"clients": 64}
+        assert writer.get_header("comment") is None
+        with pytest.raises(th.TeehistorianError):
+            writer.set_header("bad", {1: "int key"})
+        with pytest.raises(th.TeehistorianError):
+            writer.set_header("bad", float("nan"))
+        writer.write(th.Eos())
+
+        header = th.Teehistorian(writer.getvalue()).header_json()
+        assert header["map_size"] == 299932
+        assert header["ratio"] == 0.5
+        assert header["flags"] == [True, None, "1"]
+        assert header["config"] == {"sv_motd": "{not json}", "limits": {"clients": 64}}
+        assert "comment" not in header
+        with pytest.raises(th.TeehistorianError, match="after writing"):
+            writer.remove_header("map_size")
+
     def test_json_tuning_parsing(self):
         """Test that JSON tuning data is parsed as an object."""
         writer = th.TeehistorianWriter()
@@ -52,6 +52,20 @@ def main() -> int:
         dest="map_file",
         help="Path to local .map file (default: download from S3)",
     )
+    parser.add_argument(
+        "-s", "--start",
+        type=int,
+        help="First tick to convert (default: start of the recording)",
+    )
+    parser.add_argument(
+        "-e", "--end",
+        type=int,
+        help="Tick to stop at (default: end of the recording)",
+    )
+    parser.add_argument(
+        "-p", "--player",
+        help="Only convert this player, by client ID or name",
+    )
     parser.add_argument(
         "-v", "--verbose",
         action="store_true",
@@ -60,12 +74,19 @@ def main() -> int:
 
     args = parser.parse_args()
 
+    player = args.player
+    if player is not None and player.isdigit():
+        player = int(player)
+
     try:
         demo_path = convert_to_demo(
             args.source,
             output=args.output,
             output_dir=args.output_dir,
             map_file=args.map_file,
+            start=args.start,
+            end=args.end,
+            player=player,
             verbose=args.verbose,
             prompt_func=_prompt_yes_no,
         )
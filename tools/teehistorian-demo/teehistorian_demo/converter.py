@@ -10,6 +10,7 @@ import tempfile
 from pathlib import Path
 from typing import Callable, Optional, Union
 
+import teehistorian_py as th
 from teehistorian_header import parse_header
 from teehistorian_downloader import download as download_teehistorian
 from teehistorian_map import download_map
@@ -131,6 +132,9 @@ def convert_to_demo(
     output: Optional[Union[str, Path]] = None,
     output_dir: Optional[Union[str, Path]] = None,
     map_file: Optional[Union[str, Path]] = None,
+    start: Optional[int] = None,
+    end: Optional[int] = None,
+    player: Optional[Union[int, str]] = None,
     verbose: bool = False,
     prompt_func: Optional[Callable[[str], bool]] = None,
 ) -> Path:
@@ -138,9 +142,13 @@ def convert_to_demo(
 
     Args:
         source: UUID to download, or path to local .teehistorian file.
-        output: Output .demo file path. If not specified, uses {game_uuid}.demo.
+        output: Output .demo file path. If not specified, uses {game_uuid}.demo,
+                with the tick range and player appended for a part of the game.
         output_dir: Directory for output file. Defaults to current directory.
         map_file: Path to local .map file. If not specified, downloads from S3.
+        start: First tick to convert. Defaults to the start of the recording.
+        end: Tick to stop at. Defaults to the end of the recording.
+        player: Only convert this player, as client ID or name.
         verbose: Print verbose output.
         prompt_func: Function to prompt user for map installation.
                      Takes a message, returns True/False.
@@ -174,6 +182,17 @@ def convert_to_demo(
             if not th_file.exists():
                 raise NotFoundError(f"Teehistorian file not found: {th_file}")
 
+        # Step 1b: Cut out the part to convert
+        if start is not None or end is not None or player is not None:
+            th_file = _cut(
+                th_file,
+                temp_path / "part.teehistorian",
+                start=start,
+                end=end,
+                player=player,
+                verbose=verbose,
+            )
+
         # Step 2: Parse header to get map info and game_uuid
         header = parse_header(th_file)
         game_uuid = header.get("game_uuid", "unknown")
@@ -221,7 +240,7 @@ def convert_to_demo(
             demo_path = Path(output)
         else:
             out_dir = Path(output_dir) if output_dir else Path.cwd()
-            demo_path = out_dir / f"{game_uuid}.demo"
+            demo_path = out_dir / f"{game_uuid}{_part_suffix(start, end, player)}.demo"
 
         demo_path.parent.mkdir(parents=True, exist_ok=True)
 
@@ -258,6 +277,46 @@ def convert_to_demo(
         return demo_path
 
 
+def _cut(
+    th_file: Path,
+    part_file: Path,
+    *,
+    start: Optional[int],
+    end: Optional[int],
+    player: Optional[Union[int, str]],
+    verbose: bool = False,
+) -> Path:
+    """Write the ticks and player to convert to part_file."""
+    data = th_file.read_bytes()
+    try:
+        if start is not None or end is not None:
+            if verbose:
+                print(f"Cutting ticks {start or 0} to {'end' if end is None else end}")
+            data = th.slice_ticks(data, start or 0, end)
+        if player is not None:
+            if verbose:
+                print(f"Extracting player: {player}")
+            data = th.extract_player(data, player)
+    except th.TeehistorianError as e:
+        raise ConversionError(f"Could not cut recording: {e}") from e
+    part_file.write_bytes(data)
+    return part_file
+
+
+def _part_suffix(
+    start: Optional[int],
+    end: Optional[int],
+    player: Optional[Union[int, str]],
+) -> str:
+    """Suffix of the default file name for a part of a game."""
+    suffix = ""
+    if start is not None or end is not None:
+        suffix += f"_{start or 0}-{'' if end is None else end}"
+    if player is not None:
+        suffix += f"_{player}"
+    return suffix
+
+
 def _is_uuid(s: str) -> bool:
     """Check if string looks like a UUID."""
     import re
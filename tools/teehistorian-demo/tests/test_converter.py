@@ -0,0 +1,86 @@
+"""Tests for converting part of a recording with --start, --end and --player."""
+
+import sys
+from unittest import mock
+
+import pytest
+
+import teehistorian_py as th
+from teehistorian_demo import __main__ as cli
+from teehistorian_demo.converter import ConversionError, _cut, _part_suffix
+
+
+def write_recording(tmp_path):
+    writer = th.TeehistorianWriter(delta_encode=True)
+    writer.write_join(0).write_join(1)
+    writer.write(th.PlayerName(0, "other"))
+    writer.write(th.PlayerName(1, "runner"))
+    for tick in range(1, 20):
+        writer.write(th.PlayerNew(0, 0, tick), tick=tick)
+        writer.write(th.PlayerNew(1, tick, 0), tick=tick)
+    writer.finalize()
+
+    path = tmp_path / "game.teehistorian"
+    path.write_bytes(writer.getvalue())
+    return path
+
+
+def moves(data):
+    parser = th.Teehistorian(data, resolve_positions=True)
+    return [(parser.tick, c.client_id) for c in parser if isinstance(c, th.PlayerPosition)]
+
+
+def test_cut_ticks(tmp_path):
+    recording = write_recording(tmp_path)
+    part = _cut(recording, tmp_path / "part.teehistorian", start=5, end=10, player=None)
+    data = part.read_bytes()
+    assert th.Teehistorian.validate(data).is_valid
+    assert {tick for tick, _ in moves(data)} == set(range(5, 10))
+
+    # Without an end the part runs to the end of the recording
+    part = _cut(recording, tmp_path / "part.teehistorian", start=15, end=None, player=None)
+    assert {tick for tick, _ in moves(part.read_bytes())} == set(range(15, 20))
+
+
+def test_cut_player(tmp_path):
+    recording = write_recording(tmp_path)
+    part = _cut(recording, tmp_path / "part.teehistorian", start=None, end=None, player="runner")
+    data = part.read_bytes()
+    assert data == th.extract_player(recording.read_bytes(), 1)
+    assert {cid for _, cid in moves(data)} == {1}
+
+    part = _cut(recording, tmp_path / "part.teehistorian", start=5, end=10, player=0)
+    assert moves(part.read_bytes()) == [(tick, 0) for tick in range(5, 10)]
+
+
+def test_cut_unknown_player(tmp_path):
+    recording = write_recording(tmp_path)
+    with pytest.raises(ConversionError, match="No client named"):
+        _cut(recording, tmp_path / "part.teehistorian", start=None, end=None, player="nobody")
+
+
+def test_part_suffix():
+    assert _part_suffix(None, None, None) == ""
+    assert _part_suffix(5, 10, None) == "_5-10"
+    assert _part_suffix(None, 10, None) == "_0-10"
+    assert _part_suffix(5, None, None) == "_5-"
+    assert _part_suffix(None, None, "runner") == "_runner"
+    assert _part_suffix(5, 10, 3) == "_5-10_3"
+
+
+def test_cli_options():
+    cases = [
+        ([], (None, None, None)),
+        (["--start", "5", "--end", "10"], (5, 10, None)),
+        (["-s", "5", "-p", "3"], (5, None, 3)),
+        (["--player", "runner"], (None, None, "runner")),
+    ]
+    for args, expected in cases:
+        argv = ["teehistorian-demo", "game.teehistorian", *args]
+        with mock.patch.object(sys, "argv", argv), mock.patch.object(
+            cli, "convert_to_demo", return_value="game.demo"
+        ) as convert:
+            assert cli.main() == 0
+
+        options = convert.call_args.kwargs
+        assert (options["start"], options["end"], options["player"]) == expected